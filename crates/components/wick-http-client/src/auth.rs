@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use reqwest::header::HeaderValue;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use url::Url;
+use wick_config::config::components::HttpClientAuth;
+use wick_config::config::TemplateConfig;
+use wick_packet::RuntimeConfig;
+
+use crate::error::Error;
+
+/// How long before a token expires that it is considered stale and fetched again.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// Produces the Authorization header for each request.
+#[derive(Debug)]
+pub(crate) enum Auth {
+  /// A header that never changes, for bearer and basic authentication.
+  Static(HeaderValue),
+  /// A token fetched with the OAuth2 client credentials grant.
+  OAuth2(OAuth2),
+}
+
+pub(crate) struct OAuth2 {
+  token_url: Url,
+  client_id: String,
+  client_secret: String,
+  scopes: Vec<String>,
+  token: Mutex<Option<CachedToken>>,
+}
+
+impl std::fmt::Debug for OAuth2 {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("OAuth2")
+      .field("token_url", &self.token_url)
+      .field("client_id", &self.client_id)
+      .field("scopes", &self.scopes)
+      .finish_non_exhaustive()
+  }
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+  header: HeaderValue,
+  expires: Option<Instant>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+  access_token: String,
+  #[serde(default)]
+  expires_in: Option<u64>,
+}
+
+impl Auth {
+  pub(crate) fn new(config: &HttpClientAuth, root_config: Option<&RuntimeConfig>) -> Result<Self, Error> {
+    let auth = match config {
+      HttpClientAuth::Bearer(v) => Self::Static(bearer(&render(v.token(), root_config)?)?),
+      HttpClientAuth::Basic(v) => {
+        let username = render(v.username(), root_config)?;
+        let password = v.password().map(|p| render(p, root_config)).transpose()?;
+        let credentials = format!("{}:{}", username, password.unwrap_or_default());
+        let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+        Self::Static(header(&format!("Basic {}", encoded))?)
+      }
+      HttpClientAuth::OAuth2(v) => Self::OAuth2(OAuth2 {
+        token_url: Url::parse(v.token_url())
+          .map_err(|e| Error::Validation(format!("invalid token_url {}: {}", v.token_url(), e)))?,
+        client_id: render(v.client_id(), root_config)?,
+        client_secret: render(v.client_secret(), root_config)?,
+        scopes: v.scopes().to_vec(),
+        token: Mutex::new(None),
+      }),
+    };
+    Ok(auth)
+  }
+
+  /// The Authorization header to send, fetching a new token if the cached one is missing or stale.
+  pub(crate) async fn header(&self, client: &reqwest::Client) -> Result<HeaderValue, Error> {
+    match self {
+      Auth::Static(header) => Ok(header.clone()),
+      Auth::OAuth2(oauth) => oauth.header(client).await,
+    }
+  }
+
+  /// Forget a token the server rejected. Returns whether there was a token that can be fetched again.
+  pub(crate) async fn invalidate(&self) -> bool {
+    match self {
+      Auth::Static(_) => false,
+      Auth::OAuth2(oauth) => oauth.token.lock().await.take().is_some(),
+    }
+  }
+}
+
+impl OAuth2 {
+  async fn header(&self, client: &reqwest::Client) -> Result<HeaderValue, Error> {
+    // Holding the lock while fetching keeps concurrent requests from fetching a token each.
+    let mut token = self.token.lock().await;
+    if let Some(cached) = token.as_ref() {
+      if cached.expires.map_or(true, |expires| Instant::now() < expires) {
+        return Ok(cached.header.clone());
+      }
+    }
+    let fetched = self.fetch(client).await?;
+    let header = fetched.header.clone();
+    *token = Some(fetched);
+    Ok(header)
+  }
+
+  async fn fetch(&self, client: &reqwest::Client) -> Result<CachedToken, Error> {
+    let mut form = vec![
+      ("grant_type", "client_credentials".to_owned()),
+      ("client_id", self.client_id.clone()),
+      ("client_secret", self.client_secret.clone()),
+    ];
+    if !self.scopes.is_empty() {
+      form.push(("scope", self.scopes.join(" ")));
+    }
+    let response = client
+      .post(self.token_url.clone())
+      .form(&form)
+      .send()
+      .await
+      .and_then(|r| r.error_for_status())
+      .map_err(|e| Error::Auth(e.to_string()))?;
+    let token: TokenResponse = response.json().await.map_err(|e| Error::Auth(e.to_string()))?;
+    debug!(expires_in = ?token.expires_in, "http:client:oauth2:token");
+
+    Ok(CachedToken {
+      header: bearer(&token.access_token)?,
+      expires: token
+        .expires_in
+        .map(|secs| Instant::now() + Duration::from_secs(secs).saturating_sub(EXPIRY_MARGIN)),
+    })
+  }
+}
+
+fn render(template: &TemplateConfig<String>, root_config: Option<&RuntimeConfig>) -> Result<String, Error> {
+  template
+    .render(None, root_config, None)
+    .map_err(|e| Error::Validation(format!("could not render auth configuration: {}", e)))
+}
+
+fn bearer(token: &str) -> Result<HeaderValue, Error> {
+  header(&format!("Bearer {}", token))
+}
+
+fn header(value: &str) -> Result<HeaderValue, Error> {
+  let mut header = HeaderValue::from_str(value).map_err(|_| Error::InvalidHeader("authorization".to_owned()))?;
+  header.set_sensitive(true);
+  Ok(header)
+}
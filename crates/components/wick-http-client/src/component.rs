@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::anyhow;
 use flow_component::{BoxFuture, Component, ComponentError, RuntimeCallback};
@@ -14,13 +15,17 @@ use wick_config::config::components::{
   HttpClientComponentConfig,
   HttpClientOperationDefinition,
   OperationConfig,
+  Pagination,
 };
 use wick_config::config::{Codec, HttpMethod, LiquidJsonConfig, Metadata, UrlResource};
 use wick_config::{ConfigValidation, Resolver};
 use wick_interface_types::{ComponentSignature, OperationSignatures};
 use wick_packet::{Base64Bytes, FluxChannel, Invocation, Observer, Packet, PacketSender, PacketStream, RuntimeConfig};
 
+use crate::auth::Auth;
 use crate::error::Error;
+use crate::retry::{self, Retry};
+use crate::{pagination, stream};
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
 #[derive(Debug, Clone)]
@@ -32,6 +37,7 @@ pub struct HttpClientComponent {
   root_config: Option<RuntimeConfig>,
   path_templates: HashMap<String, Arc<(String, String)>>,
   client: reqwest::Client,
+  auth: Option<Arc<Auth>>,
 }
 
 impl HttpClientComponent {
@@ -66,15 +72,24 @@ impl HttpClientComponent {
       );
     }
 
+    let auth = config
+      .auth()
+      .map(|auth| Auth::new(auth, root_config.as_ref()))
+      .transpose()?
+      .map(Arc::new);
+
+    let client = ClientBuilder::new()
+      .connect_timeout(Duration::from_millis(config.connect_timeout()))
+      .user_agent(config.user_agent().map_or(APP_USER_AGENT, String::as_str))
+      .build()
+      .map_err(|e| anyhow!("Could not build HTTP client: {}", e))?;
+
     Ok(Self {
       signature: sig,
       base: url,
       path_templates,
-      client: ClientBuilder::new()
-        .connect_timeout(std::time::Duration::from_secs(5))
-        .user_agent(APP_USER_AGENT)
-        .build()
-        .unwrap(),
+      client,
+      auth,
       root_config,
       config,
     })
@@ -96,6 +111,18 @@ impl Component for HttpClientComponent {
       .as_ref()
       .and_then(|op| self.path_templates.get(op.name()).cloned());
     let client = self.client.clone();
+    let auth = self.auth.clone();
+    // Operation settings take precedence over the component's.
+    let retry = opdef
+      .as_ref()
+      .and_then(|op| op.retry())
+      .or(config.retry())
+      .map(Retry::new);
+    let timeout = opdef
+      .as_ref()
+      .and_then(|op| op.timeout())
+      .or(config.timeout())
+      .map(Duration::from_millis);
 
     Box::pin(async move {
       let (tx, rx) = invocation.make_response();
@@ -110,6 +137,9 @@ impl Component for HttpClientComponent {
         path_template,
         baseurl,
         client,
+        retry,
+        auth,
+        timeout,
       );
       tokio::spawn(async move {
         if let Err(e) = fut.await {
@@ -141,6 +171,9 @@ async fn handle(
   path_template: Option<Arc<(String, String)>>,
   baseurl: Url,
   client: reqwest::Client,
+  retry: Option<Retry>,
+  auth: Option<Arc<Auth>>,
+  timeout: Option<Duration>,
 ) -> anyhow::Result<()> {
   if baseurl.cannot_be_a_base() {
     return Err(Error::InvalidBaseUrl(baseurl).into());
@@ -155,11 +188,16 @@ async fn handle(
   let input_list: Vec<_> = opdef.inputs().iter().map(|i| i.name.clone()).collect();
   let (invocation, stream) = invocation.split();
   let mut inputs = wick_packet::StreamMap::from_stream(stream, input_list);
+  // A streamed input becomes the request body, so it is kept out of the sets used to render the rest of the request.
+  let mut request_stream = opdef.request_stream().map(|port| inputs.take(port)).transpose()?;
+  let only_streamed = request_stream.is_some() && inputs.keys().next().is_none();
   let mut handles = Vec::new();
 
   'outer: loop {
     let inputs = match inputs.next_set().await {
       Ok(Some(inputs)) => inputs,
+      // There are no other inputs to wait for when the streamed input is the only one.
+      Ok(None) if only_streamed => HashMap::new(),
       Ok(None) => break 'outer,
       Err(e) => {
         let _ = tx.error(wick_packet::Error::component_error(e.to_string()));
@@ -167,7 +205,7 @@ async fn handle(
       }
     };
 
-    if inputs.values().all(|v| v.is_done()) {
+    if !inputs.is_empty() && inputs.values().all(|v| v.is_done()) {
       break 'outer;
     }
     let inputs: Map<String, Value> = inputs
@@ -227,10 +265,16 @@ async fn handle(
       HttpMethod::Post => Request::new(Method::POST, request_url),
       HttpMethod::Put => Request::new(Method::PUT, request_url),
       HttpMethod::Delete => Request::new(Method::DELETE, request_url),
+      HttpMethod::Patch => Request::new(Method::PATCH, request_url),
+      HttpMethod::Head => Request::new(Method::HEAD, request_url),
+      HttpMethod::Options => Request::new(Method::OPTIONS, request_url),
+      HttpMethod::Trace => Request::new(Method::TRACE, request_url),
     };
 
     let request_builder = RequestBuilder::from_parts(client.clone(), request);
-    let mut request_builder = if let Some(body) = body {
+    let mut request_builder = if let Some(packets) = request_stream.take() {
+      request_builder.body(stream::request_body(codec, packets))
+    } else if let Some(body) = body {
       match codec {
         Codec::Json => request_builder.json(&body),
         Codec::Raw => {
@@ -259,11 +303,35 @@ async fn handle(
     }
 
     let (client, request) = request_builder.build_split();
-    let request = request.unwrap();
+    let mut request = request.unwrap();
+    *request.timeout_mut() = timeout;
+    if opdef.request_stream().is_some() && !request.headers().contains_key(CONTENT_TYPE) {
+      if let Some(content_type) = stream::content_type(codec) {
+        request.headers_mut().insert(CONTENT_TYPE, content_type);
+      }
+    }
 
     invocation.trace(|| debug!(request=?request, "http:client:request"));
 
-    let response = match client.execute(request).await {
+    if let Some(pagination) = opdef.pagination() {
+      if let Err(e) = paginate(
+        &client,
+        request,
+        pagination,
+        retry.as_ref(),
+        auth.as_deref(),
+        &tx,
+        &invocation.span,
+      )
+      .await
+      {
+        let _ = tx.error(wick_packet::Error::component_error(e.to_string()));
+        break 'outer;
+      }
+      continue;
+    }
+
+    let response = match retry::send(&client, request, retry.as_ref(), auth.as_deref()).await {
       Ok(r) => r,
       Err(e) => {
         let _ = tx.error(wick_packet::Error::component_error(e.to_string()));
@@ -292,12 +360,16 @@ async fn handle(
     invocation.trace(|| debug!(response = ?our_response, "http:client:response"));
 
     let _ = tx.send(Packet::encode("response", our_response));
-    handles.push(tokio::spawn(output_task(
-      invocation.span.clone(),
-      codec,
-      Box::pin(body_stream),
-      tx.clone(),
-    )));
+    let task = match opdef.response_stream() {
+      Some(mode) => stream::output_task(invocation.span.clone(), *mode, Box::pin(body_stream), tx.clone()),
+      None => output_task(invocation.span.clone(), codec, Box::pin(body_stream), tx.clone()),
+    };
+    handles.push(tokio::spawn(task));
+
+    // A streamed input is consumed by the first request, so there is nothing left to send.
+    if opdef.request_stream().is_some() {
+      break 'outer;
+    }
   }
   let _ = tx.send(Packet::done("response"));
   let _ = futures::future::join_all(handles).await;
@@ -306,6 +378,63 @@ async fn handle(
   Ok(())
 }
 
+/// Requests every page of a paginated response, sending each page's `response` and each of its items as a separate
+/// `body` packet.
+async fn paginate(
+  client: &reqwest::Client,
+  mut request: Request,
+  pagination: &Pagination,
+  retry: Option<&Retry>,
+  auth: Option<&Auth>,
+  tx: &PacketSender,
+  span: &Span,
+) -> Result<(), Error> {
+  let mut pages = 0;
+  let mut visited = pagination::Visited::new(request.url());
+  loop {
+    let url = request.url().clone();
+    let next_request = request.try_clone();
+    let response = retry::send(client, request, retry, auth).await?;
+    let status = response.status();
+    let headers = response.headers().clone();
+    span.in_scope(|| debug!(%url, %status, page = pages + 1, "http:client:page"));
+
+    let (our_response, body_stream) = crate::conversions::to_wick_response(response)?;
+    let _ = tx.send(Packet::encode("response", our_response));
+    if !status.is_success() {
+      return Err(Error::Pagination(format!("{} responded with {}", url, status)));
+    }
+
+    let bytes: Vec<Base64Bytes> = body_stream
+      .try_collect()
+      .await
+      .map_err(|e| Error::Request(e.to_string()))?;
+    let bytes = bytes.concat();
+    let page: Value = if bytes.is_empty() {
+      Value::Null
+    } else {
+      serde_json::from_slice(&bytes).map_err(|e| Error::Pagination(format!("page is not valid JSON: {}", e)))?
+    };
+
+    let next = pagination::next_page(pagination, &url, &headers, &page)?;
+    for item in pagination::items(pagination, page) {
+      let _ = tx.send(Packet::encode("body", item));
+    }
+
+    pages += 1;
+    let Some(next) = next else {
+      break;
+    };
+    if pagination.max_pages() > 0 && pages >= pagination.max_pages() {
+      break;
+    }
+    visited.next(&next)?;
+    request = next_request.ok_or_else(|| Error::Request("request body can not be resent".to_owned()))?;
+    *request.url_mut() = next;
+  }
+  Ok(())
+}
+
 fn output_task(
   span: Span,
   codec: Codec,
@@ -324,11 +453,16 @@ fn output_task(
         };
         let bytes = bytes.concat();
 
-        let json: Value = match serde_json::from_slice(&bytes) {
-          Ok(r) => r,
-          Err(e) => {
-            let _ = tx.error(wick_packet::Error::component_error(e.to_string()));
-            return;
+        // Responses to HEAD requests (and 204s) have no body to decode.
+        let json: Value = if bytes.is_empty() {
+          Value::Null
+        } else {
+          match serde_json::from_slice(&bytes) {
+            Ok(r) => r,
+            Err(e) => {
+              let _ = tx.error(wick_packet::Error::component_error(e.to_string()));
+              return;
+            }
           }
         };
         span.in_scope(|| trace!(%json, "http:client:response_body"));
@@ -377,7 +511,30 @@ impl ConfigValidation for HttpClientComponent {
   }
 }
 
-fn validate(_config: &HttpClientComponentConfig, _resolver: &Resolver) -> Result<(), Error> {
+fn validate(config: &HttpClientComponentConfig, _resolver: &Resolver) -> Result<(), Error> {
+  for op in config.operations() {
+    if let Some(port) = op.request_stream() {
+      if !op.inputs().iter().any(|i| &i.name == port) {
+        return Err(Error::Validation(format!(
+          "operation {} streams input {} which it does not define",
+          op.name(),
+          port
+        )));
+      }
+      if op.body().is_some() {
+        return Err(Error::Validation(format!(
+          "operation {} can not stream a request body and render one too",
+          op.name()
+        )));
+      }
+    }
+    if op.pagination().is_some() && (op.request_stream().is_some() || op.response_stream().is_some()) {
+      return Err(Error::Validation(format!(
+        "operation {} can not paginate a streamed request or response",
+        op.name()
+      )));
+    }
+  }
   Ok(())
 }
 
@@ -496,6 +653,228 @@ mod test {
     Ok(())
   }
 
+  #[test]
+  fn test_validate_streaming() -> Result<()> {
+    let resolver = AppConfiguration::default().resolver();
+    let op = |name: &str| {
+      HttpClientOperationDefinition::new_post(name, "upload", vec![Field::new("data", Type::Bytes)], None, None)
+    };
+    let config = |op: HttpClientOperationDefinition| {
+      let mut config = HttpClientComponentConfigBuilder::default()
+        .resource("base")
+        .build()
+        .unwrap();
+      config.operations_mut().push(op);
+      config
+    };
+
+    let valid = config(op("upload").request_stream("data".to_owned()).build()?);
+    assert_eq!(validate(&valid, &resolver), Ok(()));
+
+    let missing = config(op("upload").request_stream("other".to_owned()).build()?);
+    assert!(validate(&missing, &resolver).is_err());
+
+    let with_body = config(
+      op("upload")
+        .request_stream("data".to_owned())
+        .body(Some(json!({"a": 1}).into()))
+        .build()?,
+    );
+    assert!(validate(&with_body, &resolver).is_err());
+
+    let paginated = config(
+      op("upload")
+        .response_stream(wick_config::config::components::ResponseStream::Ndjson)
+        .pagination(wick_config::config::components::PaginationBuilder::default().build()?)
+        .build()?,
+    );
+    assert!(validate(&paginated, &resolver).is_err());
+    Ok(())
+  }
+
+  mod local_server {
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Response};
+    use serde_json::json;
+    use wick_config::config::components::{
+      HttpClientAuth,
+      OAuth2ClientCredentialsBuilder,
+      PaginationBuilder,
+      PaginationMode,
+      ResponseStream,
+      RetryPolicyBuilder,
+    };
+    use wick_config::config::TemplateConfig;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Counts {
+      tokens: AtomicUsize,
+      flaky: AtomicUsize,
+    }
+
+    fn respond(counts: &Counts, req: &hyper::Request<Body>) -> Response<Body> {
+      let json = |status: u16, body: Value| {
+        Response::builder()
+          .status(status)
+          .header("content-type", "application/json")
+          .header("retry-after", "0")
+          .body(Body::from(body.to_string()))
+          .unwrap()
+      };
+      if req.uri().path() == "/token" {
+        counts.tokens.fetch_add(1, Ordering::SeqCst);
+        return json(200, json!({"access_token": "abc", "expires_in": 3600}));
+      }
+      if req.headers().get("authorization").map(|v| v.as_bytes()) != Some(b"Bearer abc") {
+        return json(401, Value::Null);
+      }
+      match (req.uri().path(), req.uri().query()) {
+        ("/flaky", _) if counts.flaky.fetch_add(1, Ordering::SeqCst) < 2 => json(503, Value::Null),
+        ("/flaky", _) => json(200, json!({"ok": true})),
+        ("/items", Some("cursor=p2")) => json(200, json!({"data": [3], "next": null})),
+        ("/items", _) => json(200, json!({"data": [1, 2], "next": "p2"})),
+        ("/events", _) => Response::builder()
+          .header("content-type", "text/event-stream")
+          .body(Body::from(": hello\n\nevent: update\ndata: 1\n\ndata: 2\nid: 7\n\n"))
+          .unwrap(),
+        _ => json(404, Value::Null),
+      }
+    }
+
+    fn start_server(counts: Arc<Counts>) -> Url {
+      let make_svc = make_service_fn(move |_| {
+        let counts = counts.clone();
+        async move {
+          Ok::<_, Infallible>(service_fn(move |req: hyper::Request<Body>| {
+            // Streams the request body straight back.
+            let response = if req.uri().path() == "/echo" {
+              Response::new(req.into_body())
+            } else {
+              respond(&counts, &req)
+            };
+            async move { Ok::<_, Infallible>(response) }
+          }))
+        }
+      });
+      let server = hyper::Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_svc);
+      let url = Url::parse(&format!("http://{}/", server.local_addr())).unwrap();
+      tokio::spawn(server);
+      url
+    }
+
+    fn component(base: &Url) -> Result<HttpClientComponent> {
+      let auth = OAuth2ClientCredentialsBuilder::default()
+        .token_url(base.join("token")?.to_string())
+        .client_id(TemplateConfig::new_value("id".to_owned()))
+        .client_secret(TemplateConfig::new_value("secret".to_owned()))
+        .build()?;
+      let mut config = HttpClientComponentConfigBuilder::default()
+        .resource("base")
+        .codec(Codec::Json)
+        .auth(HttpClientAuth::OAuth2(auth))
+        .build()?;
+      config.operations_mut().push(
+        HttpClientOperationDefinition::new_get("flaky", "flaky", vec![Field::new("input", Type::String)], None)
+          .retry(RetryPolicyBuilder::default().build()?)
+          .build()?,
+      );
+      config.operations_mut().push(
+        HttpClientOperationDefinition::new_get("items", "items", vec![Field::new("input", Type::String)], None)
+          .pagination(
+            PaginationBuilder::default()
+              .mode(PaginationMode::Cursor)
+              .items(Some("data".to_owned()))
+              .cursor(Some("next".to_owned()))
+              .build()?,
+          )
+          .build()?,
+      );
+      config.operations_mut().push(
+        HttpClientOperationDefinition::new_post("echo", "echo", vec![Field::new("data", Type::String)], None, None)
+          .codec(Codec::Text)
+          .request_stream("data".to_owned())
+          .response_stream(ResponseStream::Lines)
+          .build()?,
+      );
+      config.operations_mut().push(
+        HttpClientOperationDefinition::new_get("events", "events", vec![Field::new("input", Type::String)], None)
+          .response_stream(ResponseStream::Sse)
+          .build()?,
+      );
+
+      let mut app_config = AppConfiguration::default();
+      app_config.add_resource("base", ResourceDefinition::Url(UrlResource::new(base.clone())));
+      HttpClientComponent::new(config, None, None, &app_config.resolver())
+    }
+
+    async fn invoke(comp: &HttpClientComponent, op: &str, packets: PacketStream) -> Result<Vec<Packet>> {
+      let invocation = Invocation::test(op, Entity::local(op), packets, Default::default())?;
+      let packets = comp
+        .handle(invocation, Default::default(), panic_callback())
+        .await?
+        .filter(|p| futures::future::ready(p.as_ref().map_or(true, |p| p.has_data())))
+        .collect::<Vec<_>>()
+        .await;
+      Ok(packets.into_iter().collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn bodies(packets: &[Packet]) -> Vec<Value> {
+      packets
+        .iter()
+        .filter(|p| p.port() == "body")
+        .map(|p| p.clone().decode_value().unwrap())
+        .collect()
+    }
+
+    #[test_logger::test(tokio::test)]
+    async fn test_retry_auth_and_pagination() -> Result<()> {
+      let counts = Arc::new(Counts::default());
+      let base = start_server(counts.clone());
+      let comp = component(&base)?;
+
+      let packets = invoke(&comp, "flaky", packet_stream!(("input", "x"))).await?;
+      assert_eq!(bodies(&packets), vec![json!({"ok": true})]);
+      assert_eq!(counts.flaky.load(Ordering::SeqCst), 3);
+
+      let packets = invoke(&comp, "items", packet_stream!(("input", "x"))).await?;
+      assert_eq!(bodies(&packets), vec![json!(1), json!(2), json!(3)]);
+      assert_eq!(packets.iter().filter(|p| p.port() == "response").count(), 2);
+
+      // The token is fetched once and reused for every request.
+      assert_eq!(counts.tokens.load(Ordering::SeqCst), 1);
+      Ok(())
+    }
+
+    #[test_logger::test(tokio::test)]
+    async fn test_streaming() -> Result<()> {
+      let base = start_server(Arc::new(Counts::default()));
+      let comp = component(&base)?;
+
+      let packets = invoke(
+        &comp,
+        "echo",
+        packet_stream!(("data", "first\nsec"), ("data", "ond\n"), ("data", "third")),
+      )
+      .await?;
+      assert_eq!(bodies(&packets), vec![json!("first"), json!("second"), json!("third")]);
+
+      let packets = invoke(&comp, "events", packet_stream!(("input", "x"))).await?;
+      assert_eq!(
+        bodies(&packets),
+        vec![
+          json!({"event": "update", "data": "1", "id": null}),
+          json!({"event": "message", "data": "2", "id": "7"}),
+        ]
+      );
+      Ok(())
+    }
+  }
+
   mod integration_test {
     use serde_json::json;
     use wick_interface_http::types::{HttpResponse, HttpVersion};
@@ -17,4 +17,13 @@ pub enum Error {
 
   #[error("Invalid baseurl: {0}")]
   InvalidBaseUrl(Url),
+
+  #[error("Failed to authenticate: {0}")]
+  Auth(String),
+
+  #[error("Request failed: {0}")]
+  Request(String),
+
+  #[error("Failed to follow pagination: {0}")]
+  Pagination(String),
 }
@@ -111,9 +111,13 @@
 // !!END_LINTS
 // Add exceptions here
 #![allow(missing_docs)]
+mod auth;
 mod component;
 mod conversions;
 mod error;
+mod pagination;
+mod retry;
+mod stream;
 
 #[macro_use]
 extern crate tracing;
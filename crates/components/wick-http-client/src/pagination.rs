@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+
+use reqwest::header::{HeaderMap, LINK};
+use serde_json::Value;
+use url::Url;
+use wick_config::config::components::{Pagination, PaginationMode};
+
+use crate::error::Error;
+
+/// The URL of the page after `current`, if there is one.
+pub(crate) fn next_page(
+  pagination: &Pagination,
+  current: &Url,
+  headers: &HeaderMap,
+  body: &Value,
+) -> Result<Option<Url>, Error> {
+  let next = match pagination.mode() {
+    PaginationMode::Link => headers
+      .get_all(LINK)
+      .iter()
+      .filter_map(|v| v.to_str().ok())
+      .find_map(next_link)
+      .map(|link| current.join(link))
+      .transpose()
+      .map_err(|e| Error::Pagination(format!("invalid next link: {}", e)))?,
+    PaginationMode::Cursor => {
+      let path = pagination
+        .cursor()
+        .ok_or_else(|| Error::Pagination("cursor pagination requires a cursor path".to_owned()))?;
+      let cursor = match lookup(body, path) {
+        Some(Value::String(s)) if !s.is_empty() => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => return Ok(None),
+      };
+      match Url::parse(&cursor) {
+        Ok(url) if url.has_host() => Some(url),
+        _ => Some(with_query_param(current, pagination.cursor_param(), &cursor)),
+      }
+    }
+  };
+
+  // Requests carry credentials, so never follow a page onto another origin.
+  match next {
+    Some(next) if next.origin() != current.origin() => Err(Error::Pagination(format!(
+      "refusing to follow next page to a different origin: {}",
+      next
+    ))),
+    next => Ok(next),
+  }
+}
+
+/// The pages requested so far, so a server that links back to one of them can't keep pagination going forever.
+#[derive(Debug)]
+pub(crate) struct Visited(HashSet<Url>);
+
+impl Visited {
+  pub(crate) fn new(first: &Url) -> Self {
+    Self(HashSet::from([first.clone()]))
+  }
+
+  /// Record the next page to request, failing if it was requested before.
+  pub(crate) fn next(&mut self, url: &Url) -> Result<(), Error> {
+    if self.0.insert(url.clone()) {
+      Ok(())
+    } else {
+      Err(Error::Pagination(format!("next page {} was already requested", url)))
+    }
+  }
+}
+
+/// The items in a page, or the whole page if no item path is configured.
+pub(crate) fn items(pagination: &Pagination, page: Value) -> Vec<Value> {
+  let Some(path) = pagination.items() else {
+    return vec![page];
+  };
+  match lookup(&page, path) {
+    Some(Value::Array(items)) => items.clone(),
+    Some(Value::Null) | None => Vec::new(),
+    Some(item) => vec![item.clone()],
+  }
+}
+
+/// Finds the `rel="next"` target in a `Link` header (RFC 8288).
+fn next_link(header: &str) -> Option<&str> {
+  header.split(',').find_map(|link| {
+    let mut parts = link.split(';');
+    let target = parts.next()?.trim().strip_prefix('<')?.strip_suffix('>')?;
+    parts
+      .filter_map(|param| param.trim().split_once('='))
+      .any(|(name, value)| {
+        name.trim().eq_ignore_ascii_case("rel")
+          && value
+            .trim()
+            .trim_matches('"')
+            .split_ascii_whitespace()
+            .any(|rel| rel.eq_ignore_ascii_case("next"))
+      })
+      .then_some(target)
+  })
+}
+
+/// Looks up a dot-separated path in a JSON value. Numeric segments index into arrays.
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+  path
+    .split('.')
+    .filter(|segment| !segment.is_empty())
+    .try_fold(value, |value, segment| match value {
+      Value::Object(map) => map.get(segment),
+      Value::Array(list) => list.get(segment.parse::<usize>().ok()?),
+      _ => None,
+    })
+}
+
+fn with_query_param(url: &Url, name: &str, value: &str) -> Url {
+  let mut url = url.clone();
+  let pairs: Vec<(String, String)> = url
+    .query_pairs()
+    .filter(|(k, _)| k != name)
+    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+    .collect();
+  url
+    .query_pairs_mut()
+    .clear()
+    .extend_pairs(pairs)
+    .append_pair(name, value);
+  url
+}
+
+#[cfg(test)]
+mod test {
+  use reqwest::header::HeaderValue;
+  use serde_json::json;
+  use wick_config::config::components::PaginationBuilder;
+
+  use super::*;
+
+  #[test]
+  fn test_next_link() {
+    assert_eq!(
+      next_link(r#"<https://api.test/items?page=1>; rel="prev", <https://api.test/items?page=3>; rel="next""#),
+      Some("https://api.test/items?page=3")
+    );
+    assert_eq!(next_link(r#"</items?page=2>; rel="next last""#), Some("/items?page=2"));
+    assert_eq!(next_link("</items?page=2>; rel=next"), Some("/items?page=2"));
+    assert_eq!(next_link(r#"</items?page=1>; rel="prev""#), None);
+  }
+
+  #[test]
+  fn test_link_pagination() {
+    let pagination = PaginationBuilder::default().build().unwrap();
+    let current = Url::parse("https://api.test/v1/items?page=1").unwrap();
+    let mut headers = HeaderMap::new();
+    assert_eq!(next_page(&pagination, &current, &headers, &Value::Null), Ok(None));
+
+    headers.insert(LINK, HeaderValue::from_static(r#"</v1/items?page=2>; rel="next""#));
+    assert_eq!(
+      next_page(&pagination, &current, &headers, &Value::Null).unwrap(),
+      Some(Url::parse("https://api.test/v1/items?page=2").unwrap())
+    );
+
+    headers.insert(LINK, HeaderValue::from_static(r#"<https://evil.test/>; rel="next""#));
+    assert!(next_page(&pagination, &current, &headers, &Value::Null).is_err());
+  }
+
+  #[test]
+  fn test_cursor_pagination() {
+    let pagination = PaginationBuilder::default()
+      .mode(PaginationMode::Cursor)
+      .cursor(Some("meta.next".to_owned()))
+      .build()
+      .unwrap();
+    let current = Url::parse("https://api.test/items?limit=2&cursor=a").unwrap();
+    let headers = HeaderMap::new();
+
+    let next = next_page(&pagination, &current, &headers, &json!({"meta": {"next": "b c"}})).unwrap();
+    assert_eq!(next.unwrap().as_str(), "https://api.test/items?limit=2&cursor=b+c");
+
+    let next = next_page(
+      &pagination,
+      &current,
+      &headers,
+      &json!({"meta": {"next": "https://api.test/items?after=9"}}),
+    )
+    .unwrap();
+    assert_eq!(next.unwrap().as_str(), "https://api.test/items?after=9");
+
+    assert_eq!(
+      next_page(&pagination, &current, &headers, &json!({"meta": {"next": null}})),
+      Ok(None)
+    );
+    assert_eq!(
+      next_page(&pagination, &current, &headers, &json!({"meta": {"next": ""}})),
+      Ok(None)
+    );
+  }
+
+  #[test]
+  fn test_visited() {
+    let first = Url::parse("https://api.test/items").unwrap();
+    let second = Url::parse("https://api.test/items?cursor=b").unwrap();
+    let mut visited = Visited::new(&first);
+    assert_eq!(visited.next(&second), Ok(()));
+    // A server that returns the same cursor again, or links back to the first page, is a loop.
+    assert!(visited.next(&second).is_err());
+    assert!(visited.next(&first).is_err());
+  }
+
+  #[test]
+  fn test_items() {
+    let pagination = PaginationBuilder::default()
+      .items(Some("data.results".to_owned()))
+      .build()
+      .unwrap();
+    assert_eq!(
+      items(&pagination, json!({"data": {"results": [1, 2]}})),
+      vec![json!(1), json!(2)]
+    );
+    assert_eq!(items(&pagination, json!({"data": {}})), Vec::<Value>::new());
+
+    let pagination = PaginationBuilder::default().build().unwrap();
+    assert_eq!(items(&pagination, json!({"a": 1})), vec![json!({"a": 1})]);
+    assert_eq!(lookup(&json!({"a": [{"b": 1}]}), "a.0.b"), Some(&json!(1)));
+  }
+}
@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use reqwest::header::{HeaderValue, AUTHORIZATION, RETRY_AFTER};
+use reqwest::{Method, Request, Response, StatusCode};
+use wick_config::config::components::RetryPolicy;
+use wick_config::config::BackoffConfig;
+
+use crate::auth::Auth;
+use crate::error::Error;
+
+/// The statuses retried when a policy doesn't list any.
+const DEFAULT_STATUSES: [u16; 4] = [429, 502, 503, 504];
+
+/// The longest a `Retry-After` header can delay a retry when the backoff has no `max_delay`.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub(crate) struct Retry {
+  retries: u16,
+  backoff: BackoffConfig,
+  statuses: Vec<u16>,
+}
+
+impl Retry {
+  pub(crate) fn new(policy: &RetryPolicy) -> Self {
+    let statuses = if policy.statuses().is_empty() {
+      DEFAULT_STATUSES.to_vec()
+    } else {
+      policy.statuses().to_vec()
+    };
+    Self {
+      retries: policy.retries(),
+      backoff: *policy.backoff(),
+      statuses,
+    }
+  }
+
+  fn retries_status(&self, status: StatusCode) -> bool {
+    self.statuses.contains(&status.as_u16())
+  }
+
+  /// How long to wait before the passed retry attempt (starting at 1). A `Retry-After` header is
+  /// capped at the backoff's `max_delay`, or [MAX_RETRY_AFTER] without one.
+  fn delay(&self, attempt: u16, retry_after: Option<&HeaderValue>) -> Duration {
+    retry_after.and_then(parse_retry_after).map_or_else(
+      || self.backoff.delay_for(attempt),
+      |delay| {
+        let max = match self.backoff.max_delay() {
+          0 => MAX_RETRY_AFTER,
+          max => Duration::from_millis(max),
+        };
+        delay.min(max)
+      },
+    )
+  }
+}
+
+/// Whether a request can be sent again without changing the result. Only these requests are retried.
+const fn is_idempotent(method: &Method) -> bool {
+  matches!(
+    *method,
+    Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE | Method::PUT | Method::DELETE
+  )
+}
+
+/// Parses a `Retry-After` header given in seconds. HTTP dates fall back to the configured backoff.
+fn parse_retry_after(value: &HeaderValue) -> Option<Duration> {
+  value.to_str().ok()?.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// Sends a request, authenticating it and retrying it according to the passed policy.
+pub(crate) async fn send(
+  client: &reqwest::Client,
+  request: Request,
+  retry: Option<&Retry>,
+  auth: Option<&Auth>,
+) -> Result<Response, Error> {
+  // Streamed bodies can't be cloned, so those requests are sent exactly once.
+  if request.try_clone().is_none() {
+    let mut request = request;
+    if let Some(auth) = auth {
+      request.headers_mut().insert(AUTHORIZATION, auth.header(client).await?);
+    }
+    return client.execute(request).await.map_err(|e| Error::Request(e.to_string()));
+  }
+
+  let retry = retry.filter(|_| is_idempotent(request.method()));
+  let mut attempt = 0;
+  let mut reauthenticated = false;
+
+  loop {
+    let mut next = request
+      .try_clone()
+      .ok_or_else(|| Error::Request("request body can not be resent".to_owned()))?;
+    if let Some(auth) = auth {
+      next.headers_mut().insert(AUTHORIZATION, auth.header(client).await?);
+    }
+
+    let retry_after = match client.execute(next).await {
+      Ok(response) => {
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED && !reauthenticated {
+          if let Some(auth) = auth {
+            if auth.invalidate().await {
+              debug!("http:client:reauthenticate");
+              reauthenticated = true;
+              continue;
+            }
+          }
+        }
+        match retry {
+          Some(retry) if attempt < retry.retries && retry.retries_status(status) => {
+            response.headers().get(RETRY_AFTER).cloned()
+          }
+          _ => return Ok(response),
+        }
+      }
+      Err(e) => match retry {
+        Some(retry) if attempt < retry.retries && (e.is_connect() || e.is_timeout()) => None,
+        _ => return Err(Error::Request(e.to_string())),
+      },
+    };
+
+    attempt += 1;
+    let delay = retry.map_or(Duration::ZERO, |r| r.delay(attempt, retry_after.as_ref()));
+    debug!(attempt, delay_ms = delay.as_millis(), "http:client:retry");
+    tokio::time::sleep(delay).await;
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use wick_config::config::components::RetryPolicyBuilder;
+  use wick_config::config::{BackoffConfigBuilder, BackoffStrategy};
+
+  use super::*;
+
+  #[test]
+  fn test_statuses() {
+    let retry = Retry::new(&RetryPolicyBuilder::default().build().unwrap());
+    assert!(retry.retries_status(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(retry.retries_status(StatusCode::TOO_MANY_REQUESTS));
+    assert!(!retry.retries_status(StatusCode::INTERNAL_SERVER_ERROR));
+
+    let retry = Retry::new(&RetryPolicyBuilder::default().statuses([500]).build().unwrap());
+    assert!(retry.retries_status(StatusCode::INTERNAL_SERVER_ERROR));
+    assert!(!retry.retries_status(StatusCode::SERVICE_UNAVAILABLE));
+  }
+
+  #[test]
+  fn test_idempotent() {
+    assert!(is_idempotent(&Method::GET));
+    assert!(is_idempotent(&Method::PUT));
+    assert!(is_idempotent(&Method::DELETE));
+    assert!(!is_idempotent(&Method::POST));
+    assert!(!is_idempotent(&Method::PATCH));
+  }
+
+  #[test]
+  fn test_delay() {
+    let backoff = BackoffConfigBuilder::default()
+      .strategy(BackoffStrategy::Exponential)
+      .delay(100_u64)
+      .build()
+      .unwrap();
+    let retry = Retry::new(&RetryPolicyBuilder::default().backoff(backoff).build().unwrap());
+    assert_eq!(retry.delay(1, None), Duration::from_millis(100));
+    assert_eq!(retry.delay(3, None), Duration::from_millis(400));
+    assert_eq!(
+      retry.delay(3, Some(&HeaderValue::from_static("2"))),
+      Duration::from_secs(2)
+    );
+    assert_eq!(
+      retry.delay(1, Some(&HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"))),
+      Duration::from_millis(100)
+    );
+  }
+
+  #[test]
+  fn test_retry_after_limit() {
+    let retry = Retry::new(&RetryPolicyBuilder::default().build().unwrap());
+    assert_eq!(
+      retry.delay(1, Some(&HeaderValue::from_static("86400"))),
+      MAX_RETRY_AFTER
+    );
+
+    let backoff = BackoffConfigBuilder::default().max_delay(5000_u64).build().unwrap();
+    let retry = Retry::new(&RetryPolicyBuilder::default().backoff(backoff).build().unwrap());
+    assert_eq!(
+      retry.delay(1, Some(&HeaderValue::from_static("86400"))),
+      Duration::from_secs(5)
+    );
+    assert_eq!(
+      retry.delay(1, Some(&HeaderValue::from_static("2"))),
+      Duration::from_secs(2)
+    );
+  }
+}
@@ -0,0 +1,294 @@
+use flow_component::BoxFuture;
+use futures::{Stream, StreamExt};
+use reqwest::header::HeaderValue;
+use serde_json::{json, Value};
+use tracing::Span;
+use wick_config::config::components::ResponseStream;
+use wick_config::config::Codec;
+use wick_packet::{Base64Bytes, Observer, Packet, PacketSender, PacketStream};
+
+use crate::error::Error;
+
+/// The number of request body chunks buffered ahead of the connection.
+const REQUEST_BUFFER: usize = 16;
+
+/// Sends the packets of a streamed input as a chunked request body, encoding each packet with the operation's codec.
+pub(crate) fn request_body(codec: Codec, mut packets: PacketStream) -> reqwest::Body {
+  // Request bodies must be `Sync` and packet streams aren't, so chunks are forwarded through a channel. The bounded
+  // channel also keeps a fast upstream from buffering more than the connection can send.
+  let (tx, rx) = tokio::sync::mpsc::channel(REQUEST_BUFFER);
+  tokio::spawn(async move {
+    while let Some(packet) = packets.next().await {
+      let chunk = match packet {
+        Ok(p) if p.is_done() => break,
+        Ok(p) if p.is_error() => Err(Error::Request(p.unwrap_err().msg().to_owned())),
+        Ok(p) if !p.has_data() => continue,
+        Ok(p) => encode_chunk(codec, p),
+        Err(e) => Err(Error::Request(e.to_string())),
+      };
+      let failed = chunk.is_err();
+      if tx.send(chunk).await.is_err() || failed {
+        break;
+      }
+    }
+  });
+  let chunks = futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|chunk| (chunk, rx)) });
+  reqwest::Body::wrap_stream(chunks)
+}
+
+/// The Content-Type of a streamed body, unless the operation sets its own.
+pub(crate) const fn content_type(codec: Codec) -> Option<HeaderValue> {
+  match codec {
+    Codec::Json => Some(HeaderValue::from_static("application/x-ndjson")),
+    Codec::Text => Some(HeaderValue::from_static("text/plain; charset=utf-8")),
+    Codec::Raw | Codec::FormData => None,
+  }
+}
+
+fn encode_chunk(codec: Codec, packet: Packet) -> Result<bytes::Bytes, Error> {
+  let chunk = match codec {
+    Codec::Raw => packet.decode::<Base64Bytes>().map(Into::into),
+    Codec::Text => packet.decode::<String>().map(Into::into),
+    // Each value is sent on its own line, as newline-delimited JSON.
+    Codec::Json => packet.decode::<Value>().map(|v| format!("{}\n", v).into()),
+    Codec::FormData => return Err(Error::Validation("form data can not be streamed".to_owned())),
+  };
+  chunk.map_err(|e| Error::Request(e.to_string()))
+}
+
+/// Emits a response body as it arrives, splitting it into `body` packets according to the passed mode.
+pub(crate) fn output_task(
+  span: Span,
+  mode: ResponseStream,
+  mut body_stream: std::pin::Pin<Box<impl Stream<Item = Result<Base64Bytes, reqwest::Error>> + Send + 'static>>,
+  tx: PacketSender,
+) -> BoxFuture<'static, ()> {
+  let task = async move {
+    let mut decoder = Decoder::new(mode);
+    let _ = tx.send(Packet::open_bracket("body"));
+    while let Some(chunk) = body_stream.next().await {
+      let chunk = match chunk {
+        Ok(chunk) => chunk,
+        Err(e) => {
+          let _ = tx.error(wick_packet::Error::component_error(e.to_string()));
+          return;
+        }
+      };
+      span.in_scope(|| trace!(len = chunk.len(), "http:client:response_chunk"));
+      for packet in decoder.push(chunk) {
+        let _ = tx.send(packet);
+      }
+    }
+    for packet in decoder.finish() {
+      let _ = tx.send(packet);
+    }
+    let _ = tx.send(Packet::close_bracket("body"));
+  };
+  Box::pin(task)
+}
+
+/// Splits a response body into packets as its chunks arrive.
+#[derive(Debug)]
+struct Decoder {
+  mode: ResponseStream,
+  lines: Lines,
+  event: Event,
+}
+
+impl Decoder {
+  fn new(mode: ResponseStream) -> Self {
+    Self {
+      mode,
+      lines: Lines::default(),
+      event: Event::default(),
+    }
+  }
+
+  fn push(&mut self, chunk: Base64Bytes) -> Vec<Packet> {
+    if self.mode == ResponseStream::Chunks {
+      return vec![Packet::encode("body", chunk)];
+    }
+    let lines = self.lines.push(&chunk);
+    lines.into_iter().filter_map(|line| self.line(line)).collect()
+  }
+
+  /// Emits whatever is left after the last newline. An incomplete server-sent event is discarded.
+  fn finish(mut self) -> Vec<Packet> {
+    match self.mode {
+      ResponseStream::Lines | ResponseStream::Ndjson => {
+        let rest = std::mem::take(&mut self.lines).finish();
+        rest.and_then(|line| self.line(line)).into_iter().collect()
+      }
+      ResponseStream::Chunks | ResponseStream::Sse => Vec::new(),
+    }
+  }
+
+  fn line(&mut self, line: Vec<u8>) -> Option<Packet> {
+    let line = match String::from_utf8(line) {
+      Ok(line) => line,
+      Err(e) => return Some(Packet::err("body", format!("response line is not valid UTF-8: {}", e))),
+    };
+    match self.mode {
+      ResponseStream::Chunks => unreachable!("chunks are not split into lines"),
+      ResponseStream::Lines => Some(Packet::encode("body", line)),
+      // Blank lines are allowed between values.
+      ResponseStream::Ndjson if line.trim().is_empty() => None,
+      ResponseStream::Ndjson => Some(match serde_json::from_str::<Value>(&line) {
+        Ok(value) => Packet::encode("body", value),
+        Err(e) => Packet::err("body", format!("response line is not valid JSON: {}", e)),
+      }),
+      ResponseStream::Sse => self.event.line(&line).map(|event| Packet::encode("body", event)),
+    }
+  }
+}
+
+/// Buffers bytes until a full line has been received.
+#[derive(Debug, Default)]
+struct Lines {
+  buffer: Vec<u8>,
+}
+
+impl Lines {
+  /// The lines completed by `chunk`, without their line endings. Anything after the last newline is kept for the next
+  /// chunk.
+  fn push(&mut self, chunk: &[u8]) -> Vec<Vec<u8>> {
+    self.buffer.extend_from_slice(chunk);
+    let Some(end) = self.buffer.iter().rposition(|b| *b == b'\n') else {
+      return Vec::new();
+    };
+    let rest = self.buffer.split_off(end + 1);
+    let complete = std::mem::replace(&mut self.buffer, rest);
+    complete[..end]
+      .split(|b| *b == b'\n')
+      .map(|line| line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+      .collect()
+  }
+
+  /// The final line if the body didn't end with a newline.
+  fn finish(self) -> Option<Vec<u8>> {
+    (!self.buffer.is_empty()).then_some(self.buffer)
+  }
+}
+
+/// A server-sent event being assembled from the lines of an event stream.
+#[derive(Debug, Default)]
+struct Event {
+  event: Option<String>,
+  data: Vec<String>,
+  id: Option<String>,
+}
+
+impl Event {
+  /// Applies a line of the event stream, returning the event if the line completes it.
+  fn line(&mut self, line: &str) -> Option<Value> {
+    if line.is_empty() {
+      return self.dispatch();
+    }
+    if line.starts_with(':') {
+      return None;
+    }
+    let (field, value) = line.split_once(':').map_or((line, ""), |(field, value)| {
+      (field, value.strip_prefix(' ').unwrap_or(value))
+    });
+    match field {
+      "event" => self.event = Some(value.to_owned()),
+      "data" => self.data.push(value.to_owned()),
+      "id" => self.id = Some(value.to_owned()),
+      _ => {}
+    }
+    None
+  }
+
+  fn dispatch(&mut self) -> Option<Value> {
+    let event = std::mem::take(self);
+    if event.data.is_empty() {
+      return None;
+    }
+    Some(json!({
+      "event": event.event.unwrap_or_else(|| "message".to_owned()),
+      "data": event.data.join("\n"),
+      "id": event.id,
+    }))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn decode(mode: ResponseStream, chunks: &[&str]) -> Vec<Result<Value, String>> {
+    let mut decoder = Decoder::new(mode);
+    let mut packets: Vec<Packet> = chunks
+      .iter()
+      .flat_map(|chunk| decoder.push(Base64Bytes::new(chunk.as_bytes().to_vec())))
+      .collect();
+    packets.extend(decoder.finish());
+    packets
+      .into_iter()
+      .map(|p| {
+        if p.is_error() {
+          Err(p.unwrap_err().msg().to_owned())
+        } else {
+          Ok(p.decode_value().unwrap())
+        }
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_lines() {
+    let mut lines = Lines::default();
+    assert_eq!(lines.push(b"one\r\ntw"), vec![b"one".to_vec()]);
+    assert_eq!(lines.push(b"o\n\nthr"), vec![b"two".to_vec(), b"".to_vec()]);
+    assert_eq!(lines.finish(), Some(b"thr".to_vec()));
+
+    assert_eq!(
+      decode(ResponseStream::Lines, &["a\nb", "\nc"]),
+      vec![Ok(json!("a")), Ok(json!("b")), Ok(json!("c"))]
+    );
+  }
+
+  #[test]
+  fn test_ndjson() {
+    let values = decode(ResponseStream::Ndjson, &["{\"a\":1}\n{\"a\"", ":2}\n\nnope\n{\"a\":3}"]);
+    assert_eq!(values.len(), 4);
+    assert_eq!(values[0], Ok(json!({"a": 1})));
+    assert_eq!(values[1], Ok(json!({"a": 2})));
+    assert!(values[2].is_err());
+    assert_eq!(values[3], Ok(json!({"a": 3})));
+  }
+
+  #[test]
+  fn test_sse() {
+    let values = decode(
+      ResponseStream::Sse,
+      &[
+        ": keep-alive\n\ndata: first\n",
+        "\nevent: update\nid: 2\ndata: {\"a\":1}\ndata:second line\r\n\r\n",
+        "data: incomplete",
+      ],
+    );
+    assert_eq!(
+      values,
+      vec![
+        Ok(json!({"event": "message", "data": "first", "id": null})),
+        Ok(json!({"event": "update", "data": "{\"a\":1}\nsecond line", "id": "2"})),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_encode_chunk() {
+    let chunk = encode_chunk(Codec::Json, Packet::encode("body", json!({"a": 1}))).unwrap();
+    assert_eq!(chunk.as_ref(), b"{\"a\":1}\n");
+    let chunk = encode_chunk(Codec::Text, Packet::encode("body", "hello")).unwrap();
+    assert_eq!(chunk.as_ref(), b"hello");
+    let chunk = encode_chunk(
+      Codec::Raw,
+      Packet::encode("body", Base64Bytes::new(b"\x00\x01".to_vec())),
+    )
+    .unwrap();
+    assert_eq!(chunk.as_ref(), b"\x00\x01");
+    assert!(encode_chunk(Codec::FormData, Packet::encode("body", "a")).is_err());
+  }
+}
@@ -3,9 +3,10 @@ pub(crate) mod sql_wrapper;
 use futures::stream::BoxStream;
 use serde_json::Value;
 use url::Url;
+use wick_config::config::components::{ComponentConfig, OperationConfig, SqlComponentConfig, SqlOperationDefinition};
 use wick_config::config::{ErrorBehavior, Metadata, UrlResource};
 use wick_config::Resolver;
-use wick_interface_types::{component, ComponentSignature, Field, OperationSignature, Type};
+use wick_interface_types::{component, ComponentSignature, Field, OperationSignature, Type, TypeDefinition};
 use wick_packet::{Packet, TypeWrapper};
 
 use self::sql_wrapper::ConvertedType;
@@ -17,6 +18,7 @@ pub(crate) fn gen_signature(
   name: &str,
   operations: Vec<OperationSignature>,
   config: &[Field],
+  types: &[TypeDefinition],
   metadata: &Option<Metadata>,
 ) -> Result<ComponentSignature> {
   let mut sig = component! {
@@ -25,16 +27,31 @@ pub(crate) fn gen_signature(
     operations: operations,
   };
   sig.config = config.to_vec();
+  sig.types = types.to_vec();
 
-  // NOTE: remove this must change when db components support customized outputs.
-  sig.operations.iter_mut().for_each(|op| {
-    if !op.outputs.iter().any(|f| f.name == "output") {
-      op.outputs.push(Field::new("output", Type::Object));
-    }
-  });
   Ok(sig)
 }
 
+/// Exec operations only produce the number of affected rows, so they can have at most one output named "output".
+pub(crate) fn validate_outputs(config: &SqlComponentConfig) -> Result<()> {
+  let bad_ops: Vec<_> = config
+    .operations()
+    .iter()
+    .filter(|op| {
+      let outputs = op.outputs();
+      matches!(op, SqlOperationDefinition::Exec(_))
+        && (outputs.len() > 1 || outputs.len() == 1 && outputs[0].name() != "output")
+    })
+    .map(|op| op.name().to_owned())
+    .collect();
+
+  if !bad_ops.is_empty() {
+    return Err(Error::InvalidOutput(bad_ops));
+  }
+
+  Ok(())
+}
+
 pub(crate) fn convert_url_resource(resolver: &Resolver, id: &str) -> Result<Url> {
   let addr = resolver(id).and_then(|r| r.try_resource())?;
 
@@ -151,6 +168,8 @@ pub(crate) trait ClientConnection: Send + Sync {
     'a: 'b;
 
   async fn exec(&mut self, stmt: String, bound_args: Vec<ConvertedType>) -> Result<u64>;
+  /// Execute statements as a unit, in a transaction of their own or in a savepoint of the invocation's transaction.
+  async fn exec_batch(&mut self, stmts: Vec<(String, Vec<ConvertedType>)>, behavior: ErrorBehavior) -> Result<u64>;
   async fn finish(&mut self, behavior: ErrorBehavior) -> Result<()>;
   async fn handle_error(&mut self, e: Error, behavior: ErrorBehavior) -> Result<()>;
   async fn start(&mut self, behavior: ErrorBehavior) -> Result<()>;
@@ -180,6 +199,14 @@ impl<'conn> Connection<'conn> {
     self.0.exec(stmt, bound_args).await
   }
 
+  pub(crate) async fn exec_batch(
+    &mut self,
+    stmts: Vec<(String, Vec<ConvertedType>)>,
+    behavior: ErrorBehavior,
+  ) -> Result<u64> {
+    self.0.exec_batch(stmts, behavior).await
+  }
+
   pub(crate) async fn handle_error(&mut self, e: Error, behavior: ErrorBehavior) -> Result<()> {
     self.0.handle_error(e, behavior).await
   }
@@ -188,9 +215,7 @@ impl<'conn> Connection<'conn> {
     self.0.start(behavior).await
   }
 
-  #[allow(clippy::unused_async)]
-  pub(crate) async fn finish(&mut self) -> Result<()> {
-    // todo
-    Ok(())
+  pub(crate) async fn finish(&mut self, behavior: ErrorBehavior) -> Result<()> {
+    self.0.finish(behavior).await
   }
 }
@@ -7,58 +7,82 @@ use flow_component::{BoxFuture, Component, ComponentError, RuntimeCallback};
 use futures::StreamExt;
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
+use serde_json::Value;
 use tracing::Span;
 use url::Url;
-use wick_config::config::components::{ComponentConfig, OperationConfig, SqlComponentConfig, SqlOperationDefinition};
+use wick_config::config::components::{
+  ComponentConfig,
+  OperationConfig,
+  SqlBatchConfig,
+  SqlComponentConfig,
+  SqlOperationDefinition,
+};
 use wick_config::config::{ErrorBehavior, Metadata};
 use wick_config::Resolver;
-use wick_interface_types::{ComponentSignature, Field, OperationSignatures, Type};
+use wick_interface_types::{ComponentSignature, Field, OperationSignatures, Type, TypeDefinition};
 use wick_packet::{Invocation, Observer, Packet, PacketSender, PacketStream, RuntimeConfig};
 
 use crate::common::{Connection, DatabaseProvider};
 use crate::{common, Error};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum DbKind {
+pub(crate) enum DbKind {
   Mssql,
   Postgres,
+  Mysql,
   Sqlite,
 }
+
+impl DbKind {
+  /// The placeholder for the positional argument at `index` (starting at 1).
+  pub(crate) fn placeholder(self, index: usize) -> String {
+    match self {
+      DbKind::Mssql => format!("@p{}", index),
+      DbKind::Postgres | DbKind::Sqlite => format!("${}", index),
+      DbKind::Mysql => "?".to_owned(),
+    }
+  }
+}
+
 #[derive(Clone)]
-struct Client {
+pub(crate) struct Client {
   inner: Arc<dyn DatabaseProvider + Send + Sync>,
+  kind: DbKind,
 }
 
 impl Client {
-  async fn new(
+  pub(crate) async fn new(
     url: &Url,
     config: &mut SqlComponentConfig,
     _metadata: Option<Metadata>,
     _root_config: Option<RuntimeConfig>, // TODO use this
     resolver: &Resolver,
   ) -> Result<Self, Error> {
-    let client: Arc<dyn DatabaseProvider + Send + Sync> = match url.scheme() {
-      "mssql" => {
-        normalize_operations(config.operations_mut(), DbKind::Mssql);
-        Arc::new(crate::mssql_tiberius::AzureSqlComponent::new(config.clone(), resolver).await?)
-      }
-      "postgres" => {
-        normalize_operations(config.operations_mut(), DbKind::Postgres);
-        Arc::new(crate::sqlx::SqlXComponent::new(config.clone(), resolver).await?)
-      }
-      "file" | "sqlite" => {
-        normalize_operations(config.operations_mut(), DbKind::Sqlite);
+    let kind = match url.scheme() {
+      "mssql" => DbKind::Mssql,
+      "postgres" => DbKind::Postgres,
+      "mysql" => DbKind::Mysql,
+      "file" | "sqlite" => DbKind::Sqlite,
+      _ => return Err(Error::InvalidScheme(url.scheme().to_owned())),
+    };
+    normalize_operations(config.operations_mut(), kind);
+    let client: Arc<dyn DatabaseProvider + Send + Sync> = match kind {
+      DbKind::Mssql => Arc::new(crate::mssql_tiberius::AzureSqlComponent::new(config.clone(), resolver).await?),
+      DbKind::Postgres | DbKind::Mysql | DbKind::Sqlite => {
         Arc::new(crate::sqlx::SqlXComponent::new(config.clone(), resolver).await?)
       }
-      _ => return Err(Error::InvalidScheme(url.scheme().to_owned())),
     };
 
-    Ok(Self { inner: client })
+    Ok(Self { inner: client, kind })
   }
 
   fn inner(&self) -> &Arc<dyn DatabaseProvider + Sync + Send> {
     &self.inner
   }
+
+  pub(crate) const fn kind(&self) -> DbKind {
+    self.kind
+  }
 }
 
 #[async_trait::async_trait]
@@ -83,6 +107,7 @@ pub struct SqlComponent {
   signature: Arc<ComponentSignature>,
   url: Url,
   config: SqlComponentConfig,
+  types: Arc<Vec<TypeDefinition>>,
   root_config: Option<RuntimeConfig>,
 }
 
@@ -103,26 +128,32 @@ impl SqlComponent {
     mut config: SqlComponentConfig,
     root_config: Option<RuntimeConfig>,
     metadata: Option<Metadata>,
+    types: Vec<TypeDefinition>,
     resolver: &Resolver,
   ) -> Result<Self, ComponentError> {
-    validate(&config, resolver)?;
+    validate(&config, &types, resolver)?;
     let sig = common::gen_signature(
       "wick/component/sql",
       config.operation_signatures(),
       config.config(),
+      &types,
       &metadata,
     )?;
 
     let url = common::convert_url_resource(resolver, config.resource())?;
 
-    validate(&config, resolver)?;
     let provider = Client::new(&url, &mut config, metadata, root_config.clone(), resolver).await?;
 
+    if let Some(migrations) = config.migrations() {
+      crate::migrations::apply(&provider, migrations).await?;
+    }
+
     Ok(Self {
       provider,
       signature: Arc::new(sig),
       url,
       root_config,
+      types: Arc::new(types),
       config,
     })
   }
@@ -136,6 +167,7 @@ impl Component for SqlComponent {
     _callback: Arc<RuntimeCallback>,
   ) -> BoxFuture<Result<PacketStream, ComponentError>> {
     let client = self.provider.clone();
+    let types = self.types.clone();
     let opdef = self
       .config
       .get_operation(invocation.target().operation_id())
@@ -150,6 +182,8 @@ impl Component for SqlComponent {
       let (invocation, stream) = invocation.split();
       let input_streams = wick_packet::split_stream(stream, input_names);
       let (tx, rx) = invocation.make_response();
+      let outputs = RowOutput::new(&opdef, types);
+      let batch = Batch::new(&opdef, client.kind());
       tokio::spawn(async move {
         let start = SystemTime::now();
         let span = invocation.span.clone();
@@ -159,13 +193,26 @@ impl Component for SqlComponent {
           });
           return;
         };
-        if let Err(e) = handle_call(&mut connection, opdef, input_streams, tx.clone(), &stmt, span).await {
+        if let Err(e) = handle_call(
+          &mut connection,
+          opdef,
+          &outputs,
+          batch,
+          input_streams,
+          tx.clone(),
+          &stmt,
+          span,
+        )
+        .await
+        {
           invocation.trace(|| {
             error!(error = %e, "error handling sql operation");
           });
           let _ = tx.error(wick_packet::Error::component_error(e.to_string()));
         }
-        let _ = tx.send(Packet::done("output"));
+        for port in outputs.ports() {
+          let _ = tx.send(Packet::done(port));
+        }
         let duration = SystemTime::now().duration_since(start).unwrap();
         invocation.trace(|| {
           debug!(?duration, target=%invocation.target,"mssql operation complete");
@@ -181,27 +228,117 @@ impl Component for SqlComponent {
   }
 }
 
-fn validate(config: &SqlComponentConfig, _resolver: &Resolver) -> Result<(), Error> {
-  let bad_ops: Vec<_> = config
-    .operations()
-    .iter()
-    .filter(|op| {
-      let outputs = op.outputs();
-      outputs.len() > 1 || outputs.len() == 1 && outputs[0] != Field::new("output", Type::Object)
-    })
-    .map(|op| op.name().to_owned())
-    .collect();
-
-  if !bad_ops.is_empty() {
-    return Err(Error::InvalidOutput(bad_ops));
+fn validate(config: &SqlComponentConfig, types: &[TypeDefinition], _resolver: &Resolver) -> Result<(), Error> {
+  common::validate_outputs(config)?;
+
+  for op in config.operations() {
+    for output in op.outputs().iter() {
+      if let Some(name) = unknown_type(output.ty(), types) {
+        return Err(Error::UnknownOutputType(
+          op.name().to_owned(),
+          output.name().to_owned(),
+          name.to_owned(),
+        ));
+      }
+    }
   }
 
   Ok(())
 }
 
+/// Returns the first named type referenced by `ty` that isn't in `types`.
+fn unknown_type<'a>(ty: &'a Type, types: &[TypeDefinition]) -> Option<&'a str> {
+  match ty {
+    Type::Named(name) => (!types.iter().any(|t| t.name() == name)).then_some(name.as_str()),
+    Type::List { ty } | Type::Optional { ty } | Type::Map { value: ty, .. } => unknown_type(ty, types),
+    Type::AnonymousStruct(fields) => fields.iter().find_map(|f| unknown_type(f.ty(), types)),
+    _ => None,
+  }
+}
+
+/// How the rows returned by an operation are sent to its output ports.
+#[derive(Debug, Clone)]
+enum OutputShape {
+  /// Each row is sent whole on a single port.
+  Row(Field),
+  /// Each column is sent on the port with the same name.
+  Columns(Vec<Field>),
+}
+
+#[derive(Debug, Clone)]
+struct RowOutput {
+  shape: OutputShape,
+  types: Arc<Vec<TypeDefinition>>,
+}
+
+impl RowOutput {
+  fn new(opdef: &SqlOperationDefinition, types: Arc<Vec<TypeDefinition>>) -> Self {
+    let mut outputs = opdef.outputs().into_owned();
+    let shape = match opdef {
+      SqlOperationDefinition::Exec(_) => OutputShape::Row(Field::new("output", Type::U32)),
+      SqlOperationDefinition::Query(_) if outputs.is_empty() => OutputShape::Row(Field::new("output", Type::Object)),
+      SqlOperationDefinition::Query(_) if outputs.len() == 1 && is_row_type(outputs[0].ty(), &types) => {
+        OutputShape::Row(outputs.remove(0))
+      }
+      SqlOperationDefinition::Query(_) => OutputShape::Columns(outputs),
+    };
+    Self { shape, types }
+  }
+
+  fn ports(&self) -> Vec<&str> {
+    match &self.shape {
+      OutputShape::Row(field) => vec![field.name()],
+      OutputShape::Columns(fields) => fields.iter().map(|f| f.name()).collect(),
+    }
+  }
+
+  fn coerce(&self, field: &Field, value: Value) -> Result<Value, Error> {
+    field
+      .ty()
+      .coerce_value(value, &self.types)
+      .map_err(|v| Error::OutputCoercion(field.name().to_owned(), field.ty().clone(), v))
+  }
+
+  /// Coerce a row into the declared output types and send it downstream.
+  fn send_row(&self, tx: &PacketSender, row: Value) -> Result<(), Error> {
+    match &self.shape {
+      OutputShape::Row(field) => {
+        let _ = tx.send(Packet::encode(field.name(), self.coerce(field, row)?));
+      }
+      OutputShape::Columns(fields) => {
+        let Value::Object(mut columns) = row else {
+          return Err(Error::Fetch(format!("expected a row of columns, got {}", row)));
+        };
+        let values = fields
+          .iter()
+          .map(|field| self.coerce(field, columns.remove(field.name()).unwrap_or_default()))
+          .collect::<Result<Vec<_>, _>>()?;
+        for (field, value) in fields.iter().zip(values) {
+          let _ = tx.send(Packet::encode(field.name(), value));
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Whether a value of this type holds a whole row rather than a single column.
+fn is_row_type(ty: &Type, types: &[TypeDefinition]) -> bool {
+  match ty {
+    Type::Object | Type::Map { .. } | Type::AnonymousStruct(_) => true,
+    Type::Named(name) => types
+      .iter()
+      .any(|t| matches!(t, TypeDefinition::Struct(def) if &def.name == name)),
+    _ => false,
+  }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn handle_call<'a, 'b, 'c>(
   connection: &'a mut Connection<'c>,
   opdef: SqlOperationDefinition,
+  outputs: &RowOutput,
+  batch: Option<Batch>,
   input_streams: Vec<PacketStream>,
   tx: PacketSender,
   stmt: &'b str,
@@ -214,21 +351,24 @@ where
 
   connection.start(error_behavior).await?;
 
-  let result = handle_stream(connection, opdef, input_streams, tx, stmt, span.clone()).await;
+  let result = handle_stream(connection, opdef, outputs, batch, input_streams, tx, stmt, span.clone()).await;
   if let Err(e) = result {
     span.in_scope(|| error!(error = %e, "error in sql operation"));
     let err = Error::OperationFailed(e.to_string());
     connection.handle_error(e, error_behavior).await?;
     return Err(err);
   }
-  connection.finish().await?;
+  connection.finish(error_behavior).await?;
 
   Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_stream<'a, 'b, 'c>(
   connection: &'a mut Connection<'c>,
   opdef: SqlOperationDefinition,
+  outputs: &RowOutput,
+  mut batch: Option<Batch>,
   mut input_streams: Vec<PacketStream>,
   tx: PacketSender,
   stmt: &'b str,
@@ -241,8 +381,19 @@ where
   'outer: loop {
     let mut incoming_packets = Vec::new();
 
-    for input in &mut input_streams {
-      let packet = input.next().await;
+    for (i, input) in input_streams.iter_mut().enumerate() {
+      // Don't wait for the next row past the pending batch's window.
+      let deadline = batch.as_ref().and_then(|b| b.deadline).filter(|_| i == 0);
+      let packet = match deadline {
+        Some(deadline) => match tokio::time::timeout_at(deadline, input.next()).await {
+          Ok(packet) => packet,
+          Err(_) => {
+            flush_batch(connection, &tx, &opdef, outputs, &mut batch, stmt, &span).await?;
+            continue 'outer;
+          }
+        },
+        None => input.next().await,
+      };
 
       incoming_packets.push(packet);
     }
@@ -268,17 +419,37 @@ where
         break 'outer;
       }
       if packet.is_open_bracket() || packet.is_close_bracket() {
-        let _ = tx.send(packet.set_port("output"));
+        flush_batch(connection, &tx, &opdef, outputs, &mut batch, stmt, &span).await?;
+        for port in outputs.ports() {
+          let _ = tx.send(packet.clone().set_port(port));
+        }
         continue 'outer;
       }
       let ty = fields.iter().find(|f| f.name() == packet.port()).unwrap().ty().clone();
       type_wrappers.push((ty, packet));
     }
 
+    if let Some(pending) = &mut batch {
+      pending.push(type_wrappers);
+      if pending.is_full() {
+        flush_batch(connection, &tx, &opdef, outputs, &mut batch, stmt, &span).await?;
+      }
+      continue 'outer;
+    }
+
     let start = SystemTime::now();
     let result = match &opdef {
       SqlOperationDefinition::Query(_) => {
-        query(connection, tx.clone(), opdef.clone(), type_wrappers, stmt, span.clone()).await
+        query(
+          connection,
+          tx.clone(),
+          opdef.clone(),
+          outputs,
+          type_wrappers,
+          stmt,
+          span.clone(),
+        )
+        .await
       }
       SqlOperationDefinition::Exec(_) => {
         exec(connection, tx.clone(), opdef.clone(), type_wrappers, stmt, span.clone()).await
@@ -288,18 +459,33 @@ where
 
     span.in_scope(|| debug!(μs = duration.as_micros(), "executed query"));
 
-    if let Err(e) = result {
-      if opdef.on_error() == ErrorBehavior::Ignore {
-        let _ = tx.send(Packet::err("output", e.to_string()));
-      } else {
-        return Err(Error::ErrorInStream(e.to_string()));
-      }
-    };
+    handle_result(&opdef, outputs, &tx, result)?;
 
     if opdef.inputs().len() == 0 {
       break 'outer;
     }
   }
+  flush_batch(connection, &tx, &opdef, outputs, &mut batch, stmt, &span).await?;
+
+  Ok(())
+}
+
+/// Report a failed statement downstream when errors are ignored, otherwise pass it up to abort the operation.
+fn handle_result<T>(
+  opdef: &SqlOperationDefinition,
+  outputs: &RowOutput,
+  tx: &PacketSender,
+  result: Result<T, Error>,
+) -> Result<(), Error> {
+  if let Err(e) = result {
+    if opdef.on_error() == ErrorBehavior::Ignore {
+      for port in outputs.ports() {
+        let _ = tx.send(Packet::err(port, e.to_string()));
+      }
+    } else {
+      return Err(Error::ErrorInStream(e.to_string()));
+    }
+  }
   Ok(())
 }
 
@@ -307,6 +493,7 @@ async fn query<'a, 'b, 'c>(
   client: &'a mut Connection<'c>,
   tx: PacketSender,
   def: SqlOperationDefinition,
+  outputs: &RowOutput,
   args: Vec<(Type, Packet)>,
   stmt: &'b str,
   _span: Span,
@@ -321,10 +508,16 @@ where
   let mut rows = client.query(stmt, bound_args).await?;
 
   while let Some(row) = rows.next().await {
-    let _ = match row {
-      Ok(row) => tx.send(Packet::encode("output", row)),
-      Err(e) => tx.send(Packet::err("output", e.to_string())),
-    };
+    match row.and_then(|row| outputs.send_row(&tx, row)) {
+      Ok(()) => {}
+      // Let the caller abort the transaction unless errors are ignored.
+      Err(e) if def.on_error() != ErrorBehavior::Ignore => return Err(e),
+      Err(e) => {
+        for port in outputs.ports() {
+          let _ = tx.send(Packet::err(port, e.to_string()));
+        }
+      }
+    }
   }
 
   let duration = SystemTime::now().duration_since(start).unwrap();
@@ -346,18 +539,221 @@ where
   let start = SystemTime::now();
 
   let bound_args = common::bind_args(def.arguments(), &args)?;
-  let packet = match connection.exec(stmt.to_owned(), bound_args).await {
-    Ok(num) => Packet::encode("output", num),
-    Err(err) => Packet::err("output", err.to_string()),
+  let rows = connection.exec(stmt.to_owned(), bound_args).await?;
+
+  let _ = tx.send(Packet::encode("output", rows));
+
+  let duration = SystemTime::now().duration_since(start).unwrap();
+
+  Ok(duration)
+}
+
+/// Input rows buffered by an exec operation that runs in batches.
+struct Batch {
+  config: SqlBatchConfig,
+  db: DbKind,
+  rows: Vec<Vec<(Type, Packet)>>,
+  deadline: Option<tokio::time::Instant>,
+}
+
+impl Batch {
+  fn new(opdef: &SqlOperationDefinition, db: DbKind) -> Option<Self> {
+    match opdef {
+      SqlOperationDefinition::Exec(op) if !op.inputs().is_empty() => op.batch().map(|config| Self {
+        config: *config,
+        db,
+        rows: Vec::new(),
+        deadline: None,
+      }),
+      _ => None,
+    }
+  }
+
+  fn push(&mut self, row: Vec<(Type, Packet)>) {
+    if self.rows.is_empty() {
+      self.deadline = self
+        .config
+        .window_duration()
+        .map(|window| tokio::time::Instant::now() + window);
+    }
+    self.rows.push(row);
+  }
+
+  fn is_full(&self) -> bool {
+    self.rows.len() >= self.config.size().max(1) as usize
+  }
+
+  fn take(&mut self) -> Vec<Vec<(Type, Packet)>> {
+    self.deadline = None;
+    std::mem::take(&mut self.rows)
+  }
+}
+
+async fn flush_batch(
+  connection: &mut Connection<'_>,
+  tx: &PacketSender,
+  opdef: &SqlOperationDefinition,
+  outputs: &RowOutput,
+  batch: &mut Option<Batch>,
+  stmt: &str,
+  span: &Span,
+) -> Result<(), Error> {
+  let Some(batch) = batch else {
+    return Ok(());
   };
+  if batch.rows.is_empty() {
+    return Ok(());
+  }
+  let db = batch.db;
+  let rows = batch.take();
+  let num_rows = rows.len();
 
-  let _ = tx.send(packet);
+  let result = exec_batch(connection, tx, opdef, rows, db, stmt).await;
+  if let Ok(duration) = &result {
+    span.in_scope(|| debug!(rows = num_rows, μs = duration.as_micros(), "executed batch"));
+  }
+
+  handle_result(opdef, outputs, tx, result)
+}
+
+/// Run a batch of input rows, sending the total number of affected rows downstream. Simple `INSERT ... VALUES`
+/// statements are expanded to insert many rows per statement, anything else runs once per row. The batch is applied
+/// as a whole or not at all.
+async fn exec_batch(
+  connection: &mut Connection<'_>,
+  tx: &PacketSender,
+  def: &SqlOperationDefinition,
+  rows: Vec<Vec<(Type, Packet)>>,
+  db: DbKind,
+  stmt: &str,
+) -> Result<Duration, Error> {
+  let start = SystemTime::now();
+
+  let bound_args = rows
+    .iter()
+    .map(|row| common::bind_args(def.arguments(), row))
+    .collect::<Result<Vec<_>, _>>()?;
+  // Spread arguments can bind a different number of values per row, which the expanded statement can't express.
+  let argc = bound_args.first().map_or(0, |args| args.len());
+  let insert = MultiRowInsert::parse(stmt, db).filter(|_| bound_args.iter().all(|args| args.len() == argc));
+
+  let stmts = match insert {
+    Some(insert) => {
+      let mut bound_args = bound_args.into_iter().peekable();
+      let mut stmts = Vec::new();
+      while bound_args.peek().is_some() {
+        let chunk: Vec<_> = bound_args.by_ref().take(insert.max_rows(argc)).collect();
+        stmts.push((
+          insert.statement(chunk.len(), argc),
+          chunk.into_iter().flatten().collect(),
+        ));
+      }
+      stmts
+    }
+    None => bound_args.into_iter().map(|args| (stmt.to_owned(), args)).collect(),
+  };
+  let affected = connection.exec_batch(stmts, def.on_error()).await?;
+
+  let _ = tx.send(Packet::encode("output", affected));
 
   let duration = SystemTime::now().duration_since(start).unwrap();
 
   Ok(duration)
 }
 
+static INSERT_VALUES: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)^\s*insert\b.*?\bvalues\s*\(").unwrap());
+static MSSQL_ARGS: Lazy<Regex> = Lazy::new(|| Regex::new(r"@p(?<id>\d+)\b").unwrap());
+
+/// An `INSERT` statement with a single `VALUES` row that can be repeated to insert many rows at once.
+#[derive(Debug)]
+struct MultiRowInsert<'a> {
+  db: DbKind,
+  head: &'a str,
+  row: &'a str,
+  tail: &'a str,
+}
+
+impl<'a> MultiRowInsert<'a> {
+  fn parse(stmt: &'a str, db: DbKind) -> Option<Self> {
+    let open = INSERT_VALUES.find(stmt)?.end() - 1;
+    let close = open + closing_paren(&stmt[open..])?;
+    let tail = stmt[close + 1..].trim_end().trim_end_matches(';');
+    // Already a multi-row insert or more than one statement.
+    if tail.trim_start().starts_with(',') || tail.contains(';') {
+      return None;
+    }
+    let head = &stmt[..open];
+    // Arguments outside the row (e.g. in `ON CONFLICT` or `RETURNING`) would be bound once for every row.
+    if has_args(head, db) || has_args(tail, db) {
+      return None;
+    }
+    Some(Self {
+      db,
+      head,
+      row: &stmt[open..=close],
+      tail,
+    })
+  }
+
+  /// The number of rows that fit in one statement without exceeding the database's parameter limits.
+  fn max_rows(&self, argc: usize) -> usize {
+    let (max_args, max_rows) = match self.db {
+      DbKind::Postgres | DbKind::Mysql => (65535, usize::MAX),
+      DbKind::Sqlite => (32766, usize::MAX),
+      // sp_executesql takes two of SQL Server's 2100 parameters for itself.
+      DbKind::Mssql => (2098, 1000),
+    };
+    (max_args / argc.max(1)).clamp(1, max_rows)
+  }
+
+  fn statement(&self, rows: usize, argc: usize) -> String {
+    let values: Vec<_> = (0..rows).map(|i| self.row_at(i * argc)).collect();
+    format!("{}{}{}", self.head, values.join(", "), self.tail)
+  }
+
+  /// The row with its positional arguments shifted by `offset`.
+  fn row_at(&self, offset: usize) -> Cow<'a, str> {
+    let renumber = |prefix: &'static str| {
+      move |cap: &Captures| format!("{}{}", prefix, offset + cap["id"].parse::<usize>().unwrap())
+    };
+    match self.db {
+      // MySQL binds `?` in order so every row is the same.
+      DbKind::Mysql => Cow::Borrowed(self.row),
+      DbKind::Mssql => MSSQL_ARGS.replace_all(self.row, renumber("@p")),
+      DbKind::Postgres | DbKind::Sqlite => POSITIONAL_ARGS.replace_all(self.row, renumber("$")),
+    }
+  }
+}
+
+/// Whether `sql` refers to any positional arguments.
+fn has_args(sql: &str, db: DbKind) -> bool {
+  match db {
+    DbKind::Mysql => sql.contains('?'),
+    DbKind::Mssql => MSSQL_ARGS.is_match(sql),
+    DbKind::Postgres | DbKind::Sqlite => POSITIONAL_ARGS.is_match(sql),
+  }
+}
+
+/// The index of the parenthesis that closes the one `sql` starts with, ignoring any in quoted strings.
+fn closing_paren(sql: &str) -> Option<usize> {
+  let mut depth = 0;
+  let mut quoted = false;
+  for (i, c) in sql.char_indices() {
+    match c {
+      '\'' => quoted = !quoted,
+      '(' if !quoted => depth += 1,
+      ')' if !quoted => {
+        depth -= 1;
+        if depth == 0 {
+          return Some(i);
+        }
+      }
+      _ => {}
+    }
+  }
+  None
+}
+
 static POSITIONAL_ARGS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$(?<id>\d+)\b").unwrap());
 static WICK_ID_ARGS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$\{(?<id>\w+)\}").unwrap());
 
@@ -365,18 +761,22 @@ fn normalize_operations(ops: &mut Vec<SqlOperationDefinition>, db: DbKind) {
   for operations in ops {
     match operations {
       wick_config::config::components::SqlOperationDefinition::Query(ref mut op) => {
-        let (mut query, args) = normalize_inline_ids(op.query(), op.arguments().to_vec());
+        let (mut query, mut args) = normalize_inline_ids(op.query(), op.arguments().to_vec());
         if db == DbKind::Mssql {
           query = normalize_mssql_query(query);
+        } else if db == DbKind::Mysql {
+          (query, args) = normalize_mysql_query(query, args);
         }
         let query = query.to_string();
         op.set_query(query);
         op.set_arguments(args);
       }
       wick_config::config::components::SqlOperationDefinition::Exec(ref mut op) => {
-        let (mut query, args) = normalize_inline_ids(op.exec(), op.arguments().to_vec());
+        let (mut query, mut args) = normalize_inline_ids(op.exec(), op.arguments().to_vec());
         if db == DbKind::Mssql {
           query = normalize_mssql_query(query);
+        } else if db == DbKind::Mysql {
+          (query, args) = normalize_mysql_query(query, args);
         }
         let query = query.to_string();
         op.set_exec(query);
@@ -429,12 +829,413 @@ fn normalize_mssql_query(original: Cow<str>) -> Cow<str> {
   }
 }
 
+// This translates `$1..$n` to MySQL's `?` placeholders. MySQL binds arguments in the order placeholders appear
+// so the argument list is rebuilt to match, repeating arguments that are referenced more than once.
+fn normalize_mysql_query(original: Cow<str>, args: Vec<String>) -> (Cow<str>, Vec<String>) {
+  if original.contains('$') {
+    let mut ordered_args = Vec::new();
+    let normalized = POSITIONAL_ARGS.replace_all(&original, |cap: &Captures| {
+      let id: usize = cap.name("id").unwrap().as_str().parse().unwrap();
+      // Leave references to missing arguments intact so they surface as a query error rather than a bad binding.
+      id.checked_sub(1).and_then(|i| args.get(i)).map_or_else(
+        || cap[0].to_owned(),
+        |arg| {
+          ordered_args.push(arg.clone());
+          "?".to_owned()
+        },
+      )
+    });
+    debug!(%original,%normalized, "sql:mysql:normalized query");
+    (Cow::Owned(normalized.to_string()), ordered_args)
+  } else {
+    (original, args)
+  }
+}
+
 #[cfg(test)]
 mod test {
   use anyhow::Result;
+  use flow_component::panic_callback;
+  use serde_json::json;
+  use wick_config::config::components::{
+    SqlBatchConfigBuilder,
+    SqlComponentConfigBuilder,
+    SqlExecOperationDefinitionBuilder,
+    SqlQueryOperationDefinitionBuilder,
+  };
+  use wick_config::config::ResourceDefinition;
+  use wick_interface_types::StructDefinition;
+  use wick_packet::packet_stream;
 
   use super::*;
 
+  /// Insert three rows into a fresh SQLite database where the last one violates the primary key, returning the
+  /// number of rows left in the table.
+  async fn insert_with_failure(name: &str, on_error: ErrorBehavior) -> Result<i64> {
+    let path = std::env::temp_dir().join(format!("wick-sql-{}-{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    std::fs::File::create(&path)?;
+    let pool = sqlx::SqlitePool::connect(&path.to_string_lossy()).await?;
+    sqlx::query("create table items (id integer primary key, name text not null)")
+      .execute(&pool)
+      .await?;
+
+    let mut config = SqlComponentConfigBuilder::default()
+      .resource("db")
+      .tls(false)
+      .build()
+      .unwrap();
+    let op = SqlExecOperationDefinitionBuilder::default()
+      .name("insert")
+      .exec("insert into items (id, name) values ($1, $2);")
+      .inputs([Field::new("id", Type::I32), Field::new("name", Type::String)])
+      .arguments(["id".to_owned(), "name".to_owned()])
+      .on_error(on_error)
+      .build()
+      .unwrap();
+    config.operations_mut().push(SqlOperationDefinition::Exec(op));
+    let mut app_config = wick_config::config::AppConfiguration::default();
+    app_config.add_resource(
+      "db",
+      ResourceDefinition::Url(format!("file://{}", path.display()).try_into().unwrap()),
+    );
+    let component = SqlComponent::new(config, None, None, Vec::new(), &app_config.resolver()).await?;
+
+    let input = packet_stream!(
+      ("id", 1_i32),
+      ("name", "first"),
+      ("id", 2_i32),
+      ("name", "second"),
+      ("id", 1_i32),
+      ("name", "duplicate")
+    );
+    let inv = Invocation::test("sqlite", "wick://__local__/insert", input, None)?;
+    let response = component.handle(inv, Default::default(), panic_callback()).await?;
+    let packets: Vec<_> = response.collect().await;
+    assert!(packets.iter().any(|p| p.as_ref().map_or(true, |p| p.is_error())));
+
+    let (count,): (i64,) = sqlx::query_as("select count(*) from items").fetch_one(&pool).await?;
+    pool.close().await;
+    std::fs::remove_file(&path)?;
+    Ok(count)
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_sqlite_exec_rollback() -> Result<()> {
+    assert_eq!(insert_with_failure("rollback", ErrorBehavior::Rollback).await?, 0);
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_sqlite_exec_commit() -> Result<()> {
+    assert_eq!(insert_with_failure("commit", ErrorBehavior::Commit).await?, 2);
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_sqlite_exec_ignore() -> Result<()> {
+    assert_eq!(insert_with_failure("ignore", ErrorBehavior::Ignore).await?, 2);
+    Ok(())
+  }
+
+  /// Execute a statement in batches against a fresh SQLite database, seeded by `setup`, returning the emitted packets
+  /// and the rows left in the table.
+  async fn exec_batched(
+    name: &str,
+    setup: Option<&str>,
+    exec: &str,
+    batch: SqlBatchConfig,
+    input: PacketStream,
+  ) -> Result<(Vec<Packet>, Vec<(i32, String)>)> {
+    let path = std::env::temp_dir().join(format!("wick-sql-{}-{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    std::fs::File::create(&path)?;
+    let pool = sqlx::SqlitePool::connect(&path.to_string_lossy()).await?;
+    sqlx::query("create table items (id integer primary key, name text not null)")
+      .execute(&pool)
+      .await?;
+    if let Some(setup) = setup {
+      sqlx::query(setup).execute(&pool).await?;
+    }
+
+    let mut config = SqlComponentConfigBuilder::default()
+      .resource("db")
+      .tls(false)
+      .build()
+      .unwrap();
+    let op = SqlExecOperationDefinitionBuilder::default()
+      .name("insert")
+      .exec(exec)
+      .inputs([Field::new("id", Type::I32), Field::new("name", Type::String)])
+      .arguments(["id".to_owned(), "name".to_owned()])
+      .batch(batch)
+      .build()
+      .unwrap();
+    config.operations_mut().push(SqlOperationDefinition::Exec(op));
+    let mut app_config = wick_config::config::AppConfiguration::default();
+    app_config.add_resource(
+      "db",
+      ResourceDefinition::Url(format!("file://{}", path.display()).try_into().unwrap()),
+    );
+    let component = SqlComponent::new(config, None, None, Vec::new(), &app_config.resolver()).await?;
+
+    let inv = Invocation::test("sqlite", "wick://__local__/insert", input, None)?;
+    let response = component.handle(inv, Default::default(), panic_callback()).await?;
+    let packets = response
+      .collect::<Vec<_>>()
+      .await
+      .into_iter()
+      .collect::<Result<_, _>>()?;
+
+    let rows = sqlx::query_as("select id, name from items order by id")
+      .fetch_all(&pool)
+      .await?;
+    pool.close().await;
+    std::fs::remove_file(&path)?;
+    Ok((packets, rows))
+  }
+
+  fn items(ids: std::ops::Range<i32>) -> PacketStream {
+    let packets: Vec<_> = ids
+      .flat_map(|id| [Packet::encode("id", id), Packet::encode("name", format!("item {}", id))])
+      .chain([Packet::done("id"), Packet::done("name")])
+      .map(Ok)
+      .collect();
+    PacketStream::new(Box::new(futures::stream::iter(packets)))
+  }
+
+  #[rstest::rstest]
+  #[case("batch-insert", "insert into items (id, name) values ($1, $2);")]
+  #[case("batch-select", "insert or replace into items (id, name) select $1, $2;")]
+  #[test_logger::test(tokio::test)]
+  async fn test_sqlite_exec_batch(#[case] name: &str, #[case] exec: &str) -> Result<()> {
+    let batch = SqlBatchConfigBuilder::default().size(2_u32).build()?;
+    let (packets, rows) = exec_batched(name, None, exec, batch, items(0..5)).await?;
+
+    assert_eq!(rows.len(), 5);
+    assert_eq!(
+      packets,
+      vec![
+        Packet::encode("output", 2),
+        Packet::encode("output", 2),
+        Packet::encode("output", 1),
+        Packet::done("output")
+      ]
+    );
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_sqlite_exec_batch_window() -> Result<()> {
+    let batch = SqlBatchConfigBuilder::default().size(100_u32).window(50_u64).build()?;
+    // The second row arrives well after the first batch's window has closed.
+    let input = items(0..2).then(|packet| async move {
+      if matches!(&packet, Ok(p) if p.port() == "id" && p.clone().decode::<i32>().ok() == Some(1)) {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+      }
+      packet
+    });
+    let input = PacketStream::new(Box::new(input.boxed()));
+    let (packets, rows) = exec_batched(
+      "window",
+      None,
+      "insert into items (id, name) values ($1, $2);",
+      batch,
+      input,
+    )
+    .await?;
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+      packets,
+      vec![
+        Packet::encode("output", 1),
+        Packet::encode("output", 1),
+        Packet::done("output")
+      ]
+    );
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_sqlite_exec_batch_atomic() -> Result<()> {
+    let batch = SqlBatchConfigBuilder::default().size(3_u32).build()?;
+    let setup = "insert into items (id, name) values (0, 'item 0'), (1, 'item 1'), (2, 'item 2'), (5, 'item 5')";
+    // The last update collides with an existing id. Errors are ignored by default, but the batch is still undone.
+    let (packets, rows) = exec_batched(
+      "batch-atomic",
+      Some(setup),
+      "update items set id = $1 + 3 where name = $2;",
+      batch,
+      items(0..3),
+    )
+    .await?;
+
+    assert_eq!(packets.len(), 2);
+    assert!(packets[0].is_error());
+    assert_eq!(packets[1], Packet::done("output"));
+    assert_eq!(rows.into_iter().map(|(id, _)| id).collect::<Vec<_>>(), vec![0, 1, 2, 5]);
+    Ok(())
+  }
+
+  #[rstest::rstest]
+  #[case(
+    DbKind::Postgres,
+    "insert into items (id, name) values ($1, $2);",
+    "insert into items (id, name) values ($1, $2), ($3, $4), ($5, $6)"
+  )]
+  #[case(
+    DbKind::Sqlite,
+    "INSERT INTO items VALUES ($1, lower($2)) ON CONFLICT DO NOTHING",
+    "INSERT INTO items VALUES ($1, lower($2)), ($3, lower($4)), ($5, lower($6)) ON CONFLICT DO NOTHING"
+  )]
+  #[case(
+    DbKind::Sqlite,
+    "insert into items values ($2, ')'||$1)",
+    "insert into items values ($2, ')'||$1), ($4, ')'||$3), ($6, ')'||$5)"
+  )]
+  #[case(
+    DbKind::Mssql,
+    "insert into items values (@p1, @p2)",
+    "insert into items values (@p1, @p2), (@p3, @p4), (@p5, @p6)"
+  )]
+  #[case(
+    DbKind::Mysql,
+    "insert into items values (?, ?)",
+    "insert into items values (?, ?), (?, ?), (?, ?)"
+  )]
+  fn test_multi_row_insert(#[case] db: DbKind, #[case] stmt: &str, #[case] expected: &str) {
+    let insert = MultiRowInsert::parse(stmt, db).unwrap();
+    assert_eq!(insert.statement(3, 2), expected);
+  }
+
+  #[rstest::rstest]
+  #[case("update items set name = $2 where id = $1")]
+  #[case("insert into items select * from other")]
+  #[case("insert into items values ($1, $2), ($3, $4)")]
+  #[case("insert into items values ($1, $2); delete from items")]
+  #[case("insert into items values ($1, $2) on conflict (id) do update set name = $2")]
+  #[case("insert into items values ($1, $2) returning id, $1")]
+  fn test_multi_row_insert_unsupported(#[case] stmt: &str) {
+    assert!(MultiRowInsert::parse(stmt, DbKind::Postgres).is_none());
+  }
+
+  #[test]
+  fn test_multi_row_insert_limits() {
+    let insert = MultiRowInsert::parse("insert into items values (@p1)", DbKind::Mssql).unwrap();
+    assert_eq!(insert.max_rows(1), 1000);
+    assert_eq!(insert.max_rows(3), 699);
+    let insert = MultiRowInsert::parse("insert into items values ($1, $2)", DbKind::Postgres).unwrap();
+    assert_eq!(insert.max_rows(2), 32767);
+  }
+
+  /// Run a query against a fresh SQLite database holding a single user, returning the emitted packets.
+  async fn query_users(name: &str, outputs: Vec<Field>, types: Vec<TypeDefinition>) -> Result<Vec<Packet>> {
+    let path = std::env::temp_dir().join(format!("wick-sql-{}-{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    std::fs::File::create(&path)?;
+    let pool = sqlx::SqlitePool::connect(&path.to_string_lossy()).await?;
+    sqlx::query("create table users (id integer primary key, name text not null, active boolean not null)")
+      .execute(&pool)
+      .await?;
+    sqlx::query("insert into users (id, name, active) values (1, 'first', 1)")
+      .execute(&pool)
+      .await?;
+
+    let mut config = SqlComponentConfigBuilder::default()
+      .resource("db")
+      .tls(false)
+      .build()
+      .unwrap();
+    let op = SqlQueryOperationDefinitionBuilder::default()
+      .name("users")
+      .query("select id, name, active from users where id = $1;")
+      .inputs([Field::new("id", Type::I32)])
+      .outputs(outputs)
+      .arguments(["id".to_owned()])
+      .build()
+      .unwrap();
+    config.operations_mut().push(SqlOperationDefinition::Query(op));
+    let mut app_config = wick_config::config::AppConfiguration::default();
+    app_config.add_resource(
+      "db",
+      ResourceDefinition::Url(format!("file://{}", path.display()).try_into().unwrap()),
+    );
+    let component = SqlComponent::new(config, None, None, types, &app_config.resolver()).await?;
+
+    let inv = Invocation::test("sqlite", "wick://__local__/users", packet_stream!(("id", 1_i32)), None)?;
+    let response = component.handle(inv, Default::default(), panic_callback()).await?;
+    let packets = response
+      .collect::<Vec<_>>()
+      .await
+      .into_iter()
+      .collect::<Result<_, _>>()?;
+
+    pool.close().await;
+    std::fs::remove_file(&path)?;
+    Ok(packets)
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_sqlite_named_output() -> Result<()> {
+    let user = StructDefinition::new(
+      "User",
+      vec![
+        Field::new("id", Type::U32),
+        Field::new("name", Type::String),
+        Field::new("active", Type::Bool),
+      ],
+      None,
+    );
+    let packets = query_users(
+      "named",
+      vec![Field::new("user", Type::Named("User".to_owned()))],
+      vec![TypeDefinition::Struct(user)],
+    )
+    .await?;
+
+    assert_eq!(
+      packets,
+      vec![
+        Packet::encode("user", json!({"id":1, "name":"first", "active":true})),
+        Packet::done("user")
+      ]
+    );
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_sqlite_column_outputs() -> Result<()> {
+    let packets = query_users(
+      "columns",
+      vec![Field::new("name", Type::String), Field::new("active", Type::Bool)],
+      Vec::new(),
+    )
+    .await?;
+
+    assert_eq!(
+      packets,
+      vec![
+        Packet::encode("name", "first"),
+        Packet::encode("active", true),
+        Packet::done("name"),
+        Packet::done("active")
+      ]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_unknown_output_type() {
+    let types = [TypeDefinition::Struct(StructDefinition::new("User", Vec::new(), None))];
+    let list = Type::List {
+      ty: Box::new(Type::Named("Missing".to_owned())),
+    };
+
+    assert_eq!(unknown_type(&Type::Named("User".to_owned()), &types), None);
+    assert_eq!(unknown_type(&list, &types), Some("Missing"));
+  }
+
   #[test]
   fn test_mssql_query_normalization() -> Result<()> {
     let query = "select id,name from users where id=$1;";
@@ -445,6 +1246,28 @@ mod test {
     Ok(())
   }
 
+  #[rstest::rstest]
+  #[case("select id,name from users where id=$1;",["id"],"select id,name from users where id=?;",["id"])]
+  #[case("select id,name from users where email=$2 and id=$1;",["id","email"],"select id,name from users where email=? and id=?;",["email","id"])]
+  #[case("select id,name from users where id=$1 or parent=$1;",["id"],"select id,name from users where id=? or parent=?;",["id","id"])]
+  #[case("select id,name from users;",["id"],"select id,name from users;",["id"])]
+  fn test_mysql_query_normalization<const K: usize, const U: usize>(
+    #[case] orig_query: &str,
+    #[case] orig_args: [&str; K],
+    #[case] expected_query: &str,
+    #[case] expected_args: [&str; U],
+  ) -> Result<()> {
+    let (actual, actual_args) = normalize_mysql_query(
+      Cow::Borrowed(orig_query),
+      orig_args.iter().copied().map(|s| s.to_owned()).collect(),
+    );
+    let expected_args = expected_args.iter().map(|s| s.to_owned()).collect::<Vec<_>>();
+    assert_eq!(actual, expected_query);
+    assert_eq!(actual_args, expected_args);
+
+    Ok(())
+  }
+
   #[rstest::rstest]
   #[case("select id,name from users where id=${id};",[],"select id,name from users where id=$1;",["id"])]
   #[case("select id,name from users where email=$1, id=${id};",["email"],"select id,name from users where email=$1, id=$2;",["email","id"])]
@@ -517,7 +1340,7 @@ mod integration_test {
       ),
     );
 
-    let component = SqlComponent::new(config, None, None, &app_config.resolver()).await?;
+    let component = SqlComponent::new(config, None, None, Vec::new(), &app_config.resolver()).await?;
 
     Ok(component)
   }
@@ -573,7 +1396,7 @@ mod integration_test {
       ),
     );
 
-    let component = SqlComponent::new(config, None, None, &app_config.resolver()).await?;
+    let component = SqlComponent::new(config, None, None, Vec::new(), &app_config.resolver()).await?;
 
     Ok(component)
   }
@@ -596,6 +1419,62 @@ mod integration_test {
     Ok(())
   }
 
+  async fn init_mysql_component() -> Result<SqlComponent> {
+    let docker_host = std::env::var("TEST_HOST").unwrap();
+    let db_host = docker_host.split(':').next().unwrap();
+    let password = std::env::var("TEST_PASSWORD").unwrap();
+    let port = std::env::var("MYSQL_PORT").unwrap();
+    let user = "root";
+    let db_name = "wick_test";
+
+    let mut config = SqlComponentConfigBuilder::default()
+      .resource("db")
+      .tls(false)
+      .build()
+      .unwrap();
+    let op = SqlQueryOperationDefinitionBuilder::default()
+      .name("test")
+      .query("select id,name from users where id=$1 and id=${input};")
+      .inputs([Field::new("input", Type::I32)])
+      .outputs([Field::new("output", Type::Object)])
+      .arguments(["input".to_owned()])
+      .build()
+      .unwrap();
+
+    config.operations_mut().push(SqlOperationDefinition::Query(op));
+    let mut app_config = wick_config::config::AppConfiguration::default();
+    app_config.add_resource(
+      "db",
+      ResourceDefinition::Url(
+        format!("mysql://{}:{}@{}:{}/{}", user, password, db_host, port, db_name)
+          .try_into()
+          .unwrap(),
+      ),
+    );
+
+    let component = SqlComponent::new(config, None, None, Vec::new(), &app_config.resolver()).await?;
+
+    Ok(component)
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_mysql_basic() -> Result<()> {
+    let db = init_mysql_component().await?;
+    let input = packet_stream!(("input", 1_i32));
+    let inv = Invocation::test("mysql", "wick://__local__/test", input, None)?;
+    let response = db.handle(inv, Default::default(), panic_callback()).await.unwrap();
+    let packets: Vec<_> = response.collect().await;
+
+    assert_eq!(
+      packets,
+      vec![
+        Ok(Packet::encode("output", json!({"id":1_i32, "name":"Test User"}))),
+        Ok(Packet::done("output"))
+      ]
+    );
+    Ok(())
+  }
+
   async fn init_sqlite_component() -> Result<SqlComponent> {
     let db = std::env::var("SQLITE_DB").unwrap();
 
@@ -620,7 +1499,7 @@ mod integration_test {
       ResourceDefinition::Url(format!("file://{}", db).try_into().unwrap()),
     );
 
-    let component = SqlComponent::new(config, None, None, &app_config.resolver()).await?;
+    let component = SqlComponent::new(config, None, None, Vec::new(), &app_config.resolver()).await?;
 
     Ok(component)
   }
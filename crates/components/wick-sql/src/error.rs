@@ -1,13 +1,20 @@
 use wick_config::error::ManifestError;
+use wick_interface_types::Type;
 use wick_packet::TypeWrapper;
 
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
 #[non_exhaustive]
 pub enum Error {
-  #[error("Invalid output for operations {}. At this time postgres operations can have at most one output named 'output' of type 'object'", .0.join(", "))]
+  #[error("Invalid output for exec operations {}. Exec operations can have at most one output named 'output'", .0.join(", "))]
   InvalidOutput(Vec<String>),
 
+  #[error("Output '{1}' of operation '{0}' references unknown type '{2}'")]
+  UnknownOutputType(String, String, String),
+
+  #[error("Could not coerce value '{2}' into type {1} for output '{0}'")]
+  OutputCoercion(String, Type, serde_json::Value),
+
   #[error("Failed to fetch result of query: {0}")]
   Fetch(String),
 
@@ -31,6 +38,9 @@ pub enum Error {
   #[error("Failed to connect to Postgres Server: {0}")]
   PostgresConnect(String),
 
+  #[error("Failed to connect to MySQL Server: {0}")]
+  MysqlConnect(String),
+
   #[error("Failed to open to Sqlite DB: {0}")]
   SqliteConnect(String),
 
@@ -90,6 +100,27 @@ pub enum Error {
 
   #[error("Got a row with no data")]
   NoRow,
+
+  #[error("No migrations configured for this component")]
+  NoMigrations,
+
+  #[error("Could not read migrations from '{0}': {1}")]
+  MigrationRead(String, String),
+
+  #[error("Invalid migration file name '{0}', expected '<version>_<name>.sql'")]
+  InvalidMigration(String),
+
+  #[error("Found more than one migration with version {0}")]
+  DuplicateMigration(i64),
+
+  #[error("Migration {0} ({1}) has changed since it was applied")]
+  MigrationChanged(i64, String),
+
+  #[error("Migration {0} ({1}) was applied but is missing from the migrations directory")]
+  MigrationMissing(i64, String),
+
+  #[error("Migration {0} ({1}) failed: {2}")]
+  MigrationFailed(i64, String, String),
 }
 
 #[derive(thiserror::Error, Debug, Copy, Clone)]
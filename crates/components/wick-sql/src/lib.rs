@@ -121,11 +121,13 @@ pub use error::Error;
 pub(crate) mod common;
 
 mod component;
+mod migrations;
 
 pub(crate) mod mssql_tiberius;
 pub(crate) mod sqlx;
 
 pub use self::component::SqlComponent;
+pub use self::migrations::{migrate, migration_status, MigrationState, MigrationStatus};
 
 #[macro_use]
 extern crate tracing;
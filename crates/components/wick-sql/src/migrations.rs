@@ -0,0 +1,589 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use futures::StreamExt;
+use serde_json::Value;
+use wick_config::config::components::SqlComponentConfig;
+use wick_config::config::ErrorBehavior;
+use wick_config::{AssetReference, Resolver};
+
+use crate::common::sql_wrapper::ConvertedType;
+use crate::common::{Connection, DatabaseProvider};
+use crate::component::{Client, DbKind};
+use crate::{common, Error};
+
+type Result<T> = std::result::Result<T, Error>;
+
+const MIGRATIONS_TABLE: &str = "_wick_migrations";
+
+/// The state of a single migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum MigrationState {
+  /// The migration has been applied to the database.
+  Applied,
+  /// The migration has not been applied yet.
+  Pending,
+  /// The migration was applied but its file has changed since.
+  Modified,
+  /// The migration was applied but its file no longer exists.
+  Missing,
+}
+
+impl std::fmt::Display for MigrationState {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      MigrationState::Applied => f.pad("applied"),
+      MigrationState::Pending => f.pad("pending"),
+      MigrationState::Modified => f.pad("modified"),
+      MigrationState::Missing => f.pad("missing"),
+    }
+  }
+}
+
+/// A migration's version, name, and whether it has been applied.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[non_exhaustive]
+pub struct MigrationStatus {
+  /// The version from the migration's file name.
+  pub version: i64,
+  /// The name from the migration's file name.
+  pub name: String,
+  /// The state of the migration in the database.
+  pub state: MigrationState,
+}
+
+/// Apply a SQL component's pending migrations, returning the state of every migration.
+pub async fn migrate(mut config: SqlComponentConfig, resolver: &Resolver) -> Result<Vec<MigrationStatus>> {
+  let dir = config.migrations().cloned().ok_or(Error::NoMigrations)?;
+  let url = common::convert_url_resource(resolver, config.resource())?;
+  let client = Client::new(&url, &mut config, None, None, resolver).await?;
+  apply(&client, &dir).await
+}
+
+/// Report the state of a SQL component's migrations without applying any.
+pub async fn migration_status(mut config: SqlComponentConfig, resolver: &Resolver) -> Result<Vec<MigrationStatus>> {
+  let dir = config.migrations().cloned().ok_or(Error::NoMigrations)?;
+  let url = common::convert_url_resource(resolver, config.resource())?;
+  let client = Client::new(&url, &mut config, None, None, resolver).await?;
+  status(&client, &dir).await
+}
+
+/// The state of the migrations in `dir`.
+pub(crate) async fn status(client: &Client, dir: &AssetReference) -> Result<Vec<MigrationStatus>> {
+  let migrations = load(dir)?;
+  let mut connection = client.get_connection().await?;
+  // A database without the tracking table has had no migrations applied, and reporting that shouldn't create it.
+  let applied = if table_exists(&mut connection, client.kind()).await? {
+    applied(&mut connection).await?
+  } else {
+    BTreeMap::new()
+  };
+  Ok(statuses(&migrations, &applied))
+}
+
+/// Apply any pending migrations from `dir`, failing if an applied migration has changed or gone missing.
+pub(crate) async fn apply(client: &Client, dir: &AssetReference) -> Result<Vec<MigrationStatus>> {
+  let migrations = load(dir)?;
+  let mut connection = client.get_connection().await?;
+  create_table(&mut connection, client.kind()).await?;
+  let applied = applied(&mut connection).await?;
+  let mut statuses = statuses(&migrations, &applied);
+
+  if let Some(status) = statuses.iter().find(|s| s.state == MigrationState::Modified) {
+    return Err(Error::MigrationChanged(status.version, status.name.clone()));
+  }
+  if let Some(status) = statuses.iter().find(|s| s.state == MigrationState::Missing) {
+    return Err(Error::MigrationMissing(status.version, status.name.clone()));
+  }
+
+  for (migration, status) in migrations.iter().zip(statuses.iter_mut()) {
+    if status.state != MigrationState::Pending {
+      continue;
+    }
+    info!(version = migration.version, name = %migration.name, "applying sql migration");
+    run(&mut connection, client.kind(), migration)
+      .await
+      .map_err(|e| Error::MigrationFailed(migration.version, migration.name.clone(), e.to_string()))?;
+    status.state = MigrationState::Applied;
+  }
+
+  Ok(statuses)
+}
+
+/// A versioned migration loaded from a `<version>_<name>.sql` file.
+#[derive(Debug, Clone, PartialEq)]
+struct Migration {
+  version: i64,
+  name: String,
+  sql: String,
+  checksum: String,
+}
+
+/// Load the migrations in a directory, ordered by version. Files without a `.sql` extension are ignored.
+fn load(dir: &AssetReference) -> Result<Vec<Migration>> {
+  let dir = dir
+    .path()
+    .map_err(|e| Error::MigrationRead(dir.location().to_owned(), e.to_string()))?;
+  load_dir(&dir)
+}
+
+fn load_dir(dir: &Path) -> Result<Vec<Migration>> {
+  let read_err = |e: std::io::Error| Error::MigrationRead(dir.to_string_lossy().to_string(), e.to_string());
+
+  let mut migrations: BTreeMap<i64, Migration> = BTreeMap::new();
+  for entry in std::fs::read_dir(dir).map_err(read_err)? {
+    let path = entry.map_err(read_err)?.path();
+    if !path.is_file() || path.extension().map_or(true, |ext| ext != "sql") {
+      continue;
+    }
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let (version, name) = parse_file_name(&file_name).ok_or_else(|| Error::InvalidMigration(file_name.clone()))?;
+    let sql = std::fs::read_to_string(&path).map_err(read_err)?;
+    let migration = Migration {
+      version,
+      name: name.to_owned(),
+      checksum: sha256::digest(sql.as_str()),
+      sql,
+    };
+    if migrations.insert(version, migration).is_some() {
+      return Err(Error::DuplicateMigration(version));
+    }
+  }
+
+  Ok(migrations.into_values().collect())
+}
+
+/// Split a `<version>_<name>.sql` file name into its version and name.
+fn parse_file_name(file_name: &str) -> Option<(i64, &str)> {
+  let (version, name) = file_name.strip_suffix(".sql")?.split_once('_')?;
+  if name.is_empty() || !version.chars().all(|c| c.is_ascii_digit()) {
+    return None;
+  }
+  Some((version.parse().ok()?, name))
+}
+
+/// Combine the migrations on disk with those recorded in the database.
+fn statuses(migrations: &[Migration], applied: &BTreeMap<i64, (String, String)>) -> Vec<MigrationStatus> {
+  let mut statuses: Vec<_> = migrations
+    .iter()
+    .map(|migration| MigrationStatus {
+      version: migration.version,
+      name: migration.name.clone(),
+      state: match applied.get(&migration.version) {
+        Some((_, checksum)) if checksum == &migration.checksum => MigrationState::Applied,
+        Some(_) => MigrationState::Modified,
+        None => MigrationState::Pending,
+      },
+    })
+    .collect();
+  statuses.extend(
+    applied
+      .iter()
+      .filter(|(version, _)| !migrations.iter().any(|m| m.version == **version))
+      .map(|(version, (name, _))| MigrationStatus {
+        version: *version,
+        name: name.clone(),
+        state: MigrationState::Missing,
+      }),
+  );
+  statuses.sort_by_key(|s| s.version);
+  statuses
+}
+
+async fn create_table(connection: &mut Connection<'_>, db: DbKind) -> Result<()> {
+  let stmt = match db {
+    DbKind::Postgres => format!(
+      "CREATE TABLE IF NOT EXISTS {} (version BIGINT PRIMARY KEY, name TEXT NOT NULL, checksum TEXT NOT NULL, applied_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+      MIGRATIONS_TABLE
+    ),
+    DbKind::Sqlite => format!(
+      "CREATE TABLE IF NOT EXISTS {} (version INTEGER PRIMARY KEY, name TEXT NOT NULL, checksum TEXT NOT NULL, applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+      MIGRATIONS_TABLE
+    ),
+    DbKind::Mysql => format!(
+      "CREATE TABLE IF NOT EXISTS {} (version BIGINT PRIMARY KEY, name VARCHAR(255) NOT NULL, checksum CHAR(64) NOT NULL, applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+      MIGRATIONS_TABLE
+    ),
+    DbKind::Mssql => format!(
+      "IF OBJECT_ID(N'{0}', N'U') IS NULL CREATE TABLE {0} (version BIGINT PRIMARY KEY, name NVARCHAR(255) NOT NULL, checksum CHAR(64) NOT NULL, applied_at DATETIME2 NOT NULL DEFAULT SYSUTCDATETIME())",
+      MIGRATIONS_TABLE
+    ),
+  };
+  connection.exec(stmt, Vec::new()).await?;
+  Ok(())
+}
+
+/// Whether the migrations table exists.
+async fn table_exists(connection: &mut Connection<'_>, db: DbKind) -> Result<bool> {
+  let stmt = match db {
+    DbKind::Postgres => format!(
+      "SELECT COUNT(*) AS tables FROM information_schema.tables WHERE table_schema = current_schema() AND table_name = '{}'",
+      MIGRATIONS_TABLE
+    ),
+    DbKind::Sqlite => format!(
+      "SELECT COUNT(*) AS tables FROM sqlite_master WHERE type = 'table' AND name = '{}'",
+      MIGRATIONS_TABLE
+    ),
+    DbKind::Mysql => format!(
+      "SELECT COUNT(*) AS tables FROM information_schema.tables WHERE table_schema = DATABASE() AND table_name = '{}'",
+      MIGRATIONS_TABLE
+    ),
+    DbKind::Mssql => format!(
+      "SELECT COUNT(*) AS tables FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_NAME = '{}'",
+      MIGRATIONS_TABLE
+    ),
+  };
+  let rows: Vec<_> = connection.query(&stmt, Vec::new()).await?.collect().await;
+  let row = rows.into_iter().next().transpose()?;
+  Ok(
+    row
+      .and_then(|row| row.get("tables").and_then(Value::as_i64))
+      .unwrap_or_default()
+      > 0,
+  )
+}
+
+/// Take a lock that's held until the current transaction ends, so concurrent runs apply migrations one at a time.
+async fn lock(connection: &mut Connection<'_>, db: DbKind) -> Result<()> {
+  match db {
+    DbKind::Postgres => {
+      let stmt = format!("SELECT pg_advisory_xact_lock(hashtext('{}'))", MIGRATIONS_TABLE);
+      connection.exec(stmt, Vec::new()).await?;
+    }
+    // SQLite allows one writer at a time, so any write holds the database until the transaction ends.
+    DbKind::Sqlite => {
+      let stmt = format!("DELETE FROM {} WHERE 0", MIGRATIONS_TABLE);
+      connection.exec(stmt, Vec::new()).await?;
+    }
+    // MySQL's named locks belong to the connection, see [unlock].
+    DbKind::Mysql => {
+      let stmt = format!("SELECT GET_LOCK('{}', -1)", MIGRATIONS_TABLE);
+      connection.exec(stmt, Vec::new()).await?;
+    }
+    DbKind::Mssql => {
+      let stmt = format!(
+        "DECLARE @result INT; EXEC @result = sp_getapplock @Resource = '{}', @LockMode = 'Exclusive', @LockOwner = 'Transaction'; SELECT @result AS result",
+        MIGRATIONS_TABLE
+      );
+      let _: Vec<_> = connection.query(&stmt, Vec::new()).await?.collect().await;
+    }
+  }
+  Ok(())
+}
+
+/// Release a lock taken by [lock] that doesn't end with its transaction.
+async fn unlock(connection: &mut Connection<'_>, db: DbKind) -> Result<()> {
+  if db == DbKind::Mysql {
+    let stmt = format!("SELECT RELEASE_LOCK('{}')", MIGRATIONS_TABLE);
+    connection.exec(stmt, Vec::new()).await?;
+  }
+  Ok(())
+}
+
+/// The version, name, and checksum of every migration recorded in the database.
+async fn applied(connection: &mut Connection<'_>) -> Result<BTreeMap<i64, (String, String)>> {
+  let stmt = format!(
+    "SELECT version, name, checksum FROM {} ORDER BY version",
+    MIGRATIONS_TABLE
+  );
+  let rows: Vec<_> = connection.query(&stmt, Vec::new()).await?.collect().await;
+
+  let mut applied = BTreeMap::new();
+  for row in rows {
+    let row = row?;
+    let field = |name: &str| row.get(name).cloned().unwrap_or(Value::Null);
+    let (Some(version), Value::String(name), Value::String(checksum)) =
+      (field("version").as_i64(), field("name"), field("checksum"))
+    else {
+      return Err(Error::Fetch(format!("invalid row in {}: {}", MIGRATIONS_TABLE, row)));
+    };
+    applied.insert(version, (name, checksum));
+  }
+  Ok(applied)
+}
+
+/// Run a migration and record it in a single transaction, unless a concurrent run applied it first.
+async fn run(connection: &mut Connection<'_>, db: DbKind, migration: &Migration) -> Result<()> {
+  connection.start(ErrorBehavior::Rollback).await?;
+  let result = async {
+    lock(connection, db).await?;
+    if applied(connection).await?.contains_key(&migration.version) {
+      debug!(version = migration.version, "sql migration already applied");
+      return Ok(0);
+    }
+    for stmt in split_statements(&migration.sql) {
+      connection.exec(stmt.to_owned(), Vec::new()).await?;
+    }
+    let record = format!(
+      "INSERT INTO {} (version, name, checksum) VALUES ({}, {}, {})",
+      MIGRATIONS_TABLE,
+      db.placeholder(1),
+      db.placeholder(2),
+      db.placeholder(3)
+    );
+    let args = vec![
+      ConvertedType::I64(Some(migration.version)),
+      ConvertedType::String(Some(migration.name.clone())),
+      ConvertedType::String(Some(migration.checksum.clone())),
+    ];
+    connection.exec(record, args).await
+  }
+  .await;
+  let result = match (result, unlock(connection, db).await) {
+    (Ok(_), Err(e)) => Err(e),
+    (result, _) => result,
+  };
+
+  match result {
+    Ok(_) => connection.finish(ErrorBehavior::Rollback).await,
+    Err(e) => {
+      let err = Error::Failed(e.to_string());
+      connection.handle_error(e, ErrorBehavior::Rollback).await?;
+      Err(err)
+    }
+  }
+}
+
+/// Split a script into its statements on `;`, ignoring any within quotes, comments, or Postgres dollar-quoted strings.
+fn split_statements(sql: &str) -> Vec<&str> {
+  let mut statements = Vec::new();
+  let mut start = 0;
+  let mut has_code = false;
+  let mut i = 0;
+
+  while i < sql.len() {
+    let rest = &sql[i..];
+    let c = rest.chars().next().unwrap_or_default();
+    let skip = match c {
+      '\'' | '"' | '`' => rest[1..].find(c).map_or(rest.len(), |end| end + 2),
+      '-' if rest.starts_with("--") => rest.find('\n').unwrap_or(rest.len()),
+      '/' if rest.starts_with("/*") => rest[2..].find("*/").map_or(rest.len(), |end| end + 4),
+      '$' => match rest[1..].find('$') {
+        Some(end) if rest[1..=end].chars().all(|c| c.is_alphanumeric() || c == '_') => {
+          let tag = &rest[..end + 2];
+          rest[tag.len()..]
+            .find(tag)
+            .map_or(rest.len(), |close| tag.len() + close + tag.len())
+        }
+        _ => 1,
+      },
+      ';' => {
+        if has_code {
+          statements.push(sql[start..i].trim());
+        }
+        start = i + 1;
+        has_code = false;
+        i += 1;
+        continue;
+      }
+      c => c.len_utf8(),
+    };
+    if !(c.is_whitespace() || rest.starts_with("--") || rest.starts_with("/*")) {
+      has_code = true;
+    }
+    i += skip;
+  }
+  if has_code {
+    statements.push(sql[start..].trim());
+  }
+
+  statements
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use wick_config::config::components::SqlComponentConfigBuilder;
+  use wick_config::config::ResourceDefinition;
+
+  use super::*;
+
+  #[rstest::rstest]
+  #[case("0001_create_users.sql", Some((1, "create_users")))]
+  #[case("20230901120000_add_index.sql", Some((20230901120000, "add_index")))]
+  #[case("0001.sql", None)]
+  #[case("v1_create_users.sql", None)]
+  #[case("0001_create_users.txt", None)]
+  fn test_parse_file_name(#[case] file_name: &str, #[case] expected: Option<(i64, &str)>) {
+    assert_eq!(parse_file_name(file_name), expected);
+  }
+
+  #[rstest::rstest]
+  #[case("create table a (id int);", vec!["create table a (id int)"])]
+  #[case("create table a (id int); create table b (id int)", vec!["create table a (id int)", "create table b (id int)"])]
+  #[case("insert into a values ('x;y'); -- done;\n", vec!["insert into a values ('x;y')"])]
+  #[case("/* a; b */ select 1;\n\n", vec!["/* a; b */ select 1"])]
+  #[case(
+    "create function f() returns int as $body$ select 1; $body$ language sql; select f()",
+    vec!["create function f() returns int as $body$ select 1; $body$ language sql", "select f()"]
+  )]
+  #[case("select $1; select $2", vec!["select $1", "select $2"])]
+  fn test_split_statements(#[case] sql: &str, #[case] expected: Vec<&str>) {
+    assert_eq!(split_statements(sql), expected);
+  }
+
+  fn state(version: i64, name: &str, state: MigrationState) -> MigrationStatus {
+    MigrationStatus {
+      version,
+      name: name.to_owned(),
+      state,
+    }
+  }
+
+  fn write_migrations(name: &str, files: &[(&str, &str)]) -> Result<std::path::PathBuf> {
+    let dir = std::env::temp_dir().join(format!("wick-sql-migrations-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+    for (file, sql) in files {
+      std::fs::write(dir.join(file), sql)?;
+    }
+    Ok(dir)
+  }
+
+  async fn sqlite_client(db: &Path, migrations: &Path) -> Result<(Client, AssetReference)> {
+    let dir = AssetReference::new(migrations.to_string_lossy());
+    let mut config = SqlComponentConfigBuilder::default()
+      .resource("db")
+      .tls(false)
+      .migrations(Some(dir.clone()))
+      .build()
+      .unwrap();
+    let mut app_config = wick_config::config::AppConfiguration::default();
+    app_config.add_resource(
+      "db",
+      ResourceDefinition::Url(format!("file://{}", db.display()).try_into().unwrap()),
+    );
+    let url = common::convert_url_resource(&app_config.resolver(), "db")?;
+    let client = Client::new(&url, &mut config, None, None, &app_config.resolver()).await?;
+    Ok((client, dir))
+  }
+
+  #[test]
+  fn test_load_migrations() -> Result<()> {
+    let dir = write_migrations(
+      "load",
+      &[
+        ("0002_add_email.sql", "alter table users add column email text;"),
+        ("0001_create_users.sql", "create table users (id integer primary key);"),
+        ("README.md", "not a migration"),
+      ],
+    )?;
+    let migrations = load_dir(&dir)?;
+    assert_eq!(
+      migrations
+        .iter()
+        .map(|m| (m.version, m.name.as_str()))
+        .collect::<Vec<_>>(),
+      vec![(1, "create_users"), (2, "add_email")]
+    );
+
+    std::fs::write(dir.join("1_duplicate.sql"), "select 1;")?;
+    assert!(matches!(load_dir(&dir), Err(Error::DuplicateMigration(1))));
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_sqlite_migrations() -> Result<()> {
+    let db = std::env::temp_dir().join(format!("wick-sql-migrate-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&db);
+    std::fs::File::create(&db)?;
+    let dir = write_migrations(
+      "apply",
+      &[(
+        "0001_create_users.sql",
+        "create table users (id integer primary key, name text not null);\ninsert into users (id, name) values (1, 'first');",
+      )],
+    )?;
+    let (client, migrations) = sqlite_client(&db, &dir).await?;
+
+    // Checking the status doesn't touch the database.
+    let statuses = status(&client, &migrations).await?;
+    assert_eq!(statuses, vec![state(1, "create_users", MigrationState::Pending)]);
+    let pool = sqlx::SqlitePool::connect(&db.to_string_lossy()).await?;
+    let (tables,): (i64,) = sqlx::query_as("select count(*) from sqlite_master")
+      .fetch_one(&pool)
+      .await?;
+    assert_eq!(tables, 0);
+    pool.close().await;
+
+    let statuses = apply(&client, &migrations).await?;
+    assert_eq!(statuses, vec![state(1, "create_users", MigrationState::Applied)]);
+
+    // Applying again is a no-op, new migrations are applied in order.
+    std::fs::write(
+      dir.join("0002_add_email.sql"),
+      "alter table users add column email text;",
+    )?;
+    let statuses = apply(&client, &migrations).await?;
+    assert_eq!(
+      statuses,
+      vec![
+        state(1, "create_users", MigrationState::Applied),
+        state(2, "add_email", MigrationState::Applied)
+      ]
+    );
+
+    // A failed migration is rolled back and not recorded.
+    std::fs::write(
+      dir.join("0003_broken.sql"),
+      "create table other (id integer);\nnot sql;",
+    )?;
+    assert!(matches!(
+      apply(&client, &migrations).await,
+      Err(Error::MigrationFailed(3, _, _))
+    ));
+
+    // Changing an applied migration is an error.
+    std::fs::remove_file(dir.join("0003_broken.sql"))?;
+    std::fs::write(dir.join("0001_create_users.sql"), "create table users (id integer);")?;
+    assert!(matches!(
+      apply(&client, &migrations).await,
+      Err(Error::MigrationChanged(1, _))
+    ));
+
+    let pool = sqlx::SqlitePool::connect(&db.to_string_lossy()).await?;
+    let (count,): (i64,) = sqlx::query_as("select count(*) from users").fetch_one(&pool).await?;
+    assert_eq!(count, 1);
+    let (tables,): (i64,) = sqlx::query_as("select count(*) from sqlite_master where name = 'other'")
+      .fetch_one(&pool)
+      .await?;
+    assert_eq!(tables, 0);
+    pool.close().await;
+
+    std::fs::remove_file(&db)?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_concurrent_migrations() -> Result<()> {
+    let db = std::env::temp_dir().join(format!("wick-sql-migrate-concurrent-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&db);
+    std::fs::File::create(&db)?;
+    // Neither migration can run twice.
+    let dir = write_migrations(
+      "concurrent",
+      &[
+        ("0001_create_users.sql", "create table users (id integer primary key);"),
+        ("0002_add_email.sql", "alter table users add column email text;"),
+      ],
+    )?;
+    let (first, migrations) = sqlite_client(&db, &dir).await?;
+    let (second, _) = sqlite_client(&db, &dir).await?;
+
+    let (a, b) = tokio::join!(apply(&first, &migrations), apply(&second, &migrations));
+    let expected = vec![
+      state(1, "create_users", MigrationState::Applied),
+      state(2, "add_email", MigrationState::Applied),
+    ];
+    assert_eq!(a?, expected);
+    assert_eq!(b?, expected);
+
+    std::fs::remove_file(&db)?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+  }
+}
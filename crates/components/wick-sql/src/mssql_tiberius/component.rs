@@ -11,7 +11,6 @@ use url::Url;
 use wick_config::config::components::{ComponentConfig, OperationConfig, SqlComponentConfig};
 use wick_config::config::ErrorBehavior;
 use wick_config::{ConfigValidation, Resolver};
-use wick_interface_types::{Field, Type};
 
 use super::sql_wrapper::FromSqlWrapper;
 use crate::common::sql_wrapper::ConvertedType;
@@ -41,8 +40,13 @@ impl Context {
 
 #[async_trait::async_trait]
 impl<'a> ClientConnection for PooledConnection<'a, ConnectionManager> {
-  async fn finish(&mut self, _behavior: ErrorBehavior) -> Result<(), Error> {
-    // todo
+  async fn finish(&mut self, behavior: ErrorBehavior) -> Result<(), Error> {
+    match behavior {
+      ErrorBehavior::Commit | ErrorBehavior::Rollback => {
+        self.simple_query("COMMIT").await.map_err(|_| Error::TxCommit)?;
+      }
+      _ => {}
+    }
     Ok(())
   }
 
@@ -64,7 +68,7 @@ impl<'a> ClientConnection for PooledConnection<'a, ConnectionManager> {
       }
       ErrorBehavior::Rollback => {
         error!(error=%e, on_error=?behavior, "error in sql operation, rolling back transaction");
-        self.simple_query("ROLLBACK").await.map_err(|_| Error::TxCommit)?;
+        self.simple_query("ROLLBACK").await.map_err(|_| Error::TxRollback)?;
       }
       _ => {}
     }
@@ -85,6 +89,33 @@ impl<'a> ClientConnection for PooledConnection<'a, ConnectionManager> {
       .map(|r| r.rows_affected()[0])
   }
 
+  async fn exec_batch(
+    &mut self,
+    stmts: Vec<(String, Vec<ConvertedType>)>,
+    behavior: ErrorBehavior,
+  ) -> Result<u64, Error> {
+    // Operations that don't ignore errors already run in a transaction, so a savepoint is enough.
+    let (begin, commit, rollback) = match behavior {
+      ErrorBehavior::Ignore => ("BEGIN TRAN", Some("COMMIT"), "ROLLBACK"),
+      ErrorBehavior::Commit | ErrorBehavior::Rollback => ("SAVE TRAN wick_batch", None, "ROLLBACK TRAN wick_batch"),
+    };
+    self.simple_query(begin).await.map_err(|_| Error::TxStart)?;
+    let mut affected = 0;
+    for (stmt, args) in stmts {
+      match self.exec(stmt, args).await {
+        Ok(rows) => affected += rows,
+        Err(e) => {
+          self.simple_query(rollback).await.map_err(|_| Error::TxRollback)?;
+          return Err(e);
+        }
+      }
+    }
+    if let Some(commit) = commit {
+      self.simple_query(commit).await.map_err(|_| Error::TxCommit)?;
+    }
+    Ok(affected)
+  }
+
   async fn query<'b, 'c>(
     &'b mut self,
     stmt: &'b str,
@@ -171,21 +202,7 @@ impl ConfigValidation for AzureSqlComponent {
 }
 
 fn validate(config: &SqlComponentConfig, _resolver: &Resolver) -> Result<(), Error> {
-  let bad_ops: Vec<_> = config
-    .operations()
-    .iter()
-    .filter(|op| {
-      let outputs = op.outputs();
-      outputs.len() > 1 || outputs.len() == 1 && outputs[0] != Field::new("output", Type::Object)
-    })
-    .map(|op| op.name().to_owned())
-    .collect();
-
-  if !bad_ops.is_empty() {
-    return Err(Error::InvalidOutput(bad_ops));
-  }
-
-  Ok(())
+  common::validate_outputs(config)
 }
 
 async fn init_client(config: &SqlComponentConfig, addr: Url) -> Result<Pool<ConnectionManager>, Error> {
@@ -217,8 +234,8 @@ mod test {
   use anyhow::Result;
   use wick_config::config::components::{
     SqlComponentConfigBuilder,
+    SqlExecOperationDefinitionBuilder,
     SqlOperationDefinition,
-    SqlQueryOperationDefinitionBuilder,
   };
   use wick_config::config::{ResourceDefinition, TcpPort};
   use wick_interface_types::{Field, Type};
@@ -238,16 +255,16 @@ mod test {
       .tls(false)
       .build()
       .unwrap();
-    let op = SqlQueryOperationDefinitionBuilder::default()
+    let op = SqlExecOperationDefinitionBuilder::default()
       .name("test")
-      .query("select * from users where user_id = $1;")
+      .exec("delete from users where user_id = $1;")
       .inputs([Field::new("input", Type::I32)])
-      .outputs([Field::new("output", Type::String)])
+      .outputs([Field::new("output", Type::U32), Field::new("deleted", Type::Bool)])
       .arguments(["input".to_owned()])
       .build()
       .unwrap();
 
-    config.operations_mut().push(SqlOperationDefinition::Query(op));
+    config.operations_mut().push(SqlOperationDefinition::Exec(op));
     let mut app_config = wick_config::config::AppConfiguration::default();
     app_config.add_resource("db", ResourceDefinition::TcpPort(TcpPort::new("0.0.0.0", 11111)));
 
@@ -4,24 +4,31 @@ use flow_component::ComponentError;
 use futures::stream::BoxStream;
 use futures::StreamExt;
 use serde_json::Value;
-use sqlx::{PgPool, SqlitePool};
+use sqlx::{Acquire, MySql, MySqlPool, PgPool, Postgres, Sqlite, SqlitePool, Transaction};
+use sync_wrapper::SyncWrapper;
 use url::Url;
 use wick_config::config::components::{ComponentConfig, OperationConfig, SqlComponentConfig};
 use wick_config::config::ErrorBehavior;
 use wick_config::{ConfigValidation, Resolver};
-use wick_interface_types::{Field, Type};
 
 use crate::common::sql_wrapper::ConvertedType;
 use crate::common::{ClientConnection, Connection, DatabaseProvider};
-use crate::sqlx::{postgres, sqlite};
+use crate::sqlx::{mysql, postgres, sqlite};
 use crate::{common, Error};
 
 #[derive(Debug, Clone)]
 enum CtxPool {
   Postgres(PgPool),
+  MySql(MySqlPool),
   SqlLite(SqlitePool),
 }
 
+fn to_value<T: serde::Serialize>(row: Result<T, sqlx::Error>) -> Result<Value, Error> {
+  row
+    .map(|el| serde_json::to_value(el).unwrap_or(Value::Null))
+    .map_err(|e| Error::Fetch(e.to_string()))
+}
+
 impl CtxPool {
   fn run_query<'a, 'b>(&'a self, querystr: &'b str, args: Vec<ConvertedType>) -> BoxStream<'a, Result<Value, Error>>
   where
@@ -30,21 +37,19 @@ impl CtxPool {
     match self {
       CtxPool::Postgres(c) => {
         let query = postgres::make_query(querystr, args);
-        let stream = query.fetch(c).map(|res| res.map(postgres::SerMapRow::from)).map(|res| {
-          res
-            .map(|el| serde_json::to_value(el).unwrap_or(Value::Null))
-            .map_err(|e| Error::Fetch(e.to_string()))
-        });
+        let stream = query.fetch(c).map(|res| to_value(res.map(postgres::SerMapRow::from)));
+
+        stream.boxed()
+      }
+      CtxPool::MySql(c) => {
+        let query = mysql::make_query(querystr, args);
+        let stream = query.fetch(c).map(|res| to_value(res.map(mysql::SerMapRow::from)));
 
         stream.boxed()
       }
       CtxPool::SqlLite(c) => {
         let query = sqlite::make_query(querystr, args);
-        let stream = query.fetch(c).map(|res| res.map(sqlite::SerMapRow::from)).map(|res| {
-          res
-            .map(|el| serde_json::to_value(el).unwrap_or(Value::Null))
-            .map_err(|e| Error::Fetch(e.to_string()))
-        });
+        let stream = query.fetch(c).map(|res| to_value(res.map(sqlite::SerMapRow::from)));
 
         stream.boxed()
       }
@@ -60,6 +65,10 @@ impl CtxPool {
         let query = postgres::make_query(query, args);
         query.execute(c).await.map(|r| r.rows_affected())
       }
+      CtxPool::MySql(c) => {
+        let query = mysql::make_query(query, args);
+        query.execute(c).await.map(|r| r.rows_affected())
+      }
       CtxPool::SqlLite(c) => {
         let query = sqlite::make_query(query, args);
         query.execute(c).await.map(|r| r.rows_affected())
@@ -67,6 +76,131 @@ impl CtxPool {
     };
     result.map_err(|e| Error::Exec(e.to_string()))
   }
+
+  async fn begin(&self) -> Result<CtxTransaction<'static>, Error> {
+    let tx = match self {
+      CtxPool::Postgres(c) => c.begin().await.map(CtxTransaction::Postgres),
+      CtxPool::MySql(c) => c.begin().await.map(CtxTransaction::MySql),
+      CtxPool::SqlLite(c) => c.begin().await.map(CtxTransaction::SqlLite),
+    };
+    tx.map_err(|e| {
+      error!(error = %e, "could not start transaction");
+      Error::TxStart
+    })
+  }
+}
+
+/// A transaction that holds a single connection from the pool for the duration of an invocation, or a savepoint
+/// within one.
+enum CtxTransaction<'c> {
+  Postgres(Transaction<'c, Postgres>),
+  MySql(Transaction<'c, MySql>),
+  SqlLite(Transaction<'c, Sqlite>),
+}
+
+impl<'c> CtxTransaction<'c> {
+  /// Start a transaction nested in this one, which runs as a savepoint.
+  async fn begin(&mut self) -> Result<CtxTransaction<'_>, Error> {
+    let tx = match self {
+      CtxTransaction::Postgres(tx) => Acquire::begin(&mut *tx).await.map(CtxTransaction::Postgres),
+      CtxTransaction::MySql(tx) => Acquire::begin(&mut *tx).await.map(CtxTransaction::MySql),
+      CtxTransaction::SqlLite(tx) => Acquire::begin(&mut *tx).await.map(CtxTransaction::SqlLite),
+    };
+    tx.map_err(|e| {
+      error!(error = %e, "could not start savepoint");
+      Error::TxStart
+    })
+  }
+
+  fn run_query<'a, 'b>(&'a mut self, querystr: &'b str, args: Vec<ConvertedType>) -> BoxStream<'a, Result<Value, Error>>
+  where
+    'b: 'a,
+  {
+    match self {
+      CtxTransaction::Postgres(tx) => {
+        let query = postgres::make_query(querystr, args);
+        let stream = query
+          .fetch(&mut **tx)
+          .map(|res| to_value(res.map(postgres::SerMapRow::from)));
+
+        stream.boxed()
+      }
+      CtxTransaction::MySql(tx) => {
+        let query = mysql::make_query(querystr, args);
+        let stream = query
+          .fetch(&mut **tx)
+          .map(|res| to_value(res.map(mysql::SerMapRow::from)));
+
+        stream.boxed()
+      }
+      CtxTransaction::SqlLite(tx) => {
+        let query = sqlite::make_query(querystr, args);
+        let stream = query
+          .fetch(&mut **tx)
+          .map(|res| to_value(res.map(sqlite::SerMapRow::from)));
+
+        stream.boxed()
+      }
+    }
+  }
+
+  async fn run_exec(&mut self, query: &str, args: Vec<ConvertedType>) -> Result<u64, Error> {
+    let result = match self {
+      CtxTransaction::Postgres(tx) => {
+        let query = postgres::make_query(query, args);
+        query.execute(&mut **tx).await.map(|r| r.rows_affected())
+      }
+      CtxTransaction::MySql(tx) => {
+        let query = mysql::make_query(query, args);
+        query.execute(&mut **tx).await.map(|r| r.rows_affected())
+      }
+      CtxTransaction::SqlLite(tx) => {
+        let query = sqlite::make_query(query, args);
+        query.execute(&mut **tx).await.map(|r| r.rows_affected())
+      }
+    };
+    result.map_err(|e| Error::Exec(e.to_string()))
+  }
+
+  async fn commit(self) -> Result<(), Error> {
+    let result = match self {
+      CtxTransaction::Postgres(tx) => tx.commit().await,
+      CtxTransaction::MySql(tx) => tx.commit().await,
+      CtxTransaction::SqlLite(tx) => tx.commit().await,
+    };
+    result.map_err(|e| {
+      error!(error = %e, "could not commit transaction");
+      Error::TxCommit
+    })
+  }
+
+  async fn rollback(self) -> Result<(), Error> {
+    let result = match self {
+      CtxTransaction::Postgres(tx) => tx.rollback().await,
+      CtxTransaction::MySql(tx) => tx.rollback().await,
+      CtxTransaction::SqlLite(tx) => tx.rollback().await,
+    };
+    result.map_err(|e| {
+      error!(error = %e, "could not roll back transaction");
+      Error::TxRollback
+    })
+  }
+}
+
+/// A connection for a single invocation, which runs within a transaction unless errors are ignored.
+struct CtxConnection {
+  pool: CtxPool,
+  // SQLite connections aren't `Sync`, so the transaction is only reachable through `&mut self`.
+  tx: SyncWrapper<Option<CtxTransaction<'static>>>,
+}
+
+impl CtxConnection {
+  const fn new(pool: CtxPool) -> Self {
+    Self {
+      pool,
+      tx: SyncWrapper::new(None),
+    }
+  }
 }
 
 #[derive(Clone)]
@@ -118,29 +252,74 @@ impl DatabaseProvider for SqlXComponent {
   where
     'a: 'b,
   {
-    Ok(Connection::new(Box::new(self.context.db.clone())))
+    Ok(Connection::new(Box::new(CtxConnection::new(self.context.db.clone()))))
   }
 }
 
 #[async_trait::async_trait]
-impl ClientConnection for CtxPool {
+impl ClientConnection for CtxConnection {
   async fn finish(&mut self, _behavior: ErrorBehavior) -> Result<(), Error> {
-    // todo
+    if let Some(tx) = self.tx.get_mut().take() {
+      tx.commit().await?;
+    }
     Ok(())
   }
 
-  async fn start(&mut self, _behavior: ErrorBehavior) -> Result<(), Error> {
-    // todo
+  async fn start(&mut self, behavior: ErrorBehavior) -> Result<(), Error> {
+    match behavior {
+      ErrorBehavior::Commit | ErrorBehavior::Rollback => {
+        *self.tx.get_mut() = Some(self.pool.begin().await?);
+      }
+      ErrorBehavior::Ignore => {}
+    }
     Ok(())
   }
 
-  async fn handle_error(&mut self, _e: Error, _behavior: ErrorBehavior) -> Result<(), Error> {
-    // todo
+  async fn handle_error(&mut self, e: Error, behavior: ErrorBehavior) -> Result<(), Error> {
+    let Some(tx) = self.tx.get_mut().take() else {
+      return Ok(());
+    };
+    match behavior {
+      ErrorBehavior::Commit => {
+        error!(error=%e, on_error=?behavior, "error in sql operation, committing transaction");
+        tx.commit().await?;
+      }
+      ErrorBehavior::Rollback | ErrorBehavior::Ignore => {
+        error!(error=%e, on_error=?behavior, "error in sql operation, rolling back transaction");
+        tx.rollback().await?;
+      }
+    }
     Ok(())
   }
 
   async fn exec(&mut self, stmt: String, bound_args: Vec<ConvertedType>) -> Result<u64, Error> {
-    self.run_exec(&stmt, bound_args).await
+    match self.tx.get_mut() {
+      Some(tx) => tx.run_exec(&stmt, bound_args).await,
+      None => self.pool.run_exec(&stmt, bound_args).await,
+    }
+  }
+
+  async fn exec_batch(
+    &mut self,
+    stmts: Vec<(String, Vec<ConvertedType>)>,
+    _behavior: ErrorBehavior,
+  ) -> Result<u64, Error> {
+    let mut batch = match self.tx.get_mut() {
+      Some(tx) => tx.begin().await?,
+      None => self.pool.begin().await?,
+    };
+    let mut affected = 0;
+    for (stmt, args) in stmts {
+      match batch.run_exec(&stmt, args).await {
+        Ok(rows) => affected += rows,
+        Err(e) => {
+          batch.rollback().await?;
+          return Err(e);
+        }
+      }
+    }
+    batch.commit().await?;
+    Ok(affected)
   }
 
   async fn query<'a, 'b>(
@@ -151,8 +330,11 @@ impl ClientConnection for CtxPool {
   where
     'a: 'b,
   {
-    let stream = self.run_query(stmt.as_ref(), bound_args);
-    Ok(stream.boxed())
+    let stream = match self.tx.get_mut() {
+      Some(tx) => tx.run_query(stmt, bound_args),
+      None => self.pool.run_query(stmt, bound_args),
+    };
+    Ok(stream)
   }
 }
 
@@ -164,21 +346,7 @@ impl ConfigValidation for SqlXComponent {
 }
 
 fn validate(config: &SqlComponentConfig, _resolver: &Resolver) -> Result<(), Error> {
-  let bad_ops: Vec<_> = config
-    .operations()
-    .iter()
-    .filter(|op| {
-      let outputs = op.outputs();
-      outputs.len() > 1 || outputs.len() == 1 && outputs[0] != Field::new("output", Type::Object)
-    })
-    .map(|op| op.name().to_owned())
-    .collect();
-
-  if !bad_ops.is_empty() {
-    return Err(Error::InvalidOutput(bad_ops));
-  }
-
-  Ok(())
+  common::validate_outputs(config)
 }
 
 async fn init_client(config: &SqlComponentConfig, addr: &Url) -> Result<CtxPool, Error> {
@@ -203,7 +371,7 @@ async fn init_client(config: &SqlComponentConfig, addr: &Url) -> Result<CtxPool,
       }
       CtxPool::SqlLite(sqlite::connect(config, None).await?)
     }
-    "mysql" => unimplemented!("MySql is not supported yet"),
+    "mysql" => CtxPool::MySql(mysql::connect(config, addr).await?),
     "mssql" => unreachable!(),
     s => return Err(Error::InvalidScheme(s.to_owned())),
   };
@@ -223,8 +391,8 @@ mod test {
   use anyhow::Result;
   use wick_config::config::components::{
     SqlComponentConfigBuilder,
+    SqlExecOperationDefinitionBuilder,
     SqlOperationDefinition,
-    SqlQueryOperationDefinitionBuilder,
   };
   use wick_config::config::{ResourceDefinition, TcpPort};
   use wick_interface_types::{Field, Type};
@@ -244,16 +412,16 @@ mod test {
       .tls(false)
       .build()
       .unwrap();
-    let op = SqlQueryOperationDefinitionBuilder::default()
+    let op = SqlExecOperationDefinitionBuilder::default()
       .name("test")
-      .query("select * from users where user_id = $1;")
+      .exec("delete from users where user_id = $1;")
       .inputs([Field::new("input", Type::I32)])
-      .outputs([Field::new("output", Type::String)])
+      .outputs([Field::new("output", Type::U32), Field::new("deleted", Type::Bool)])
       .arguments(["input".to_owned()])
       .build()
       .unwrap();
 
-    config.operations_mut().push(SqlOperationDefinition::Query(op));
+    config.operations_mut().push(SqlOperationDefinition::Exec(op));
     let mut app_config = wick_config::config::AppConfiguration::default();
     app_config.add_resource("db", ResourceDefinition::TcpPort(TcpPort::new("0.0.0.0", 11111)));
 
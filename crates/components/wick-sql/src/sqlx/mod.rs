@@ -1,4 +1,5 @@
 mod component;
+pub(crate) mod mysql;
 pub(crate) mod postgres;
 pub(crate) mod sqlite;
 
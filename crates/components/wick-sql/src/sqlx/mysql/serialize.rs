@@ -0,0 +1,217 @@
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use sqlx::mysql::{MySqlRow, MySqlValueRef};
+use sqlx::{Column, Decode, MySql, Row, TypeInfo, ValueRef};
+
+/// Can be used with serialize_with
+pub(crate) fn serialize_valueref<S>(value: &MySqlValueRef, s: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  if value.is_null() {
+    return s.serialize_none();
+  }
+
+  let value = value.clone();
+  let info = value.type_info();
+  let name = info.name();
+  match name {
+    "BOOLEAN" => {
+      let v: bool = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_bool(v)
+    }
+    "TINYINT" => {
+      let v: i8 = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_i8(v)
+    }
+    "SMALLINT" => {
+      let v: i16 = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_i16(v)
+    }
+    "INT" | "MEDIUMINT" => {
+      let v: i32 = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_i32(v)
+    }
+    "BIGINT" => {
+      let v: i64 = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_i64(v)
+    }
+    "TINYINT UNSIGNED" => {
+      let v: u8 = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_u8(v)
+    }
+    "SMALLINT UNSIGNED" | "YEAR" => {
+      let v: u16 = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_u16(v)
+    }
+    "INT UNSIGNED" | "MEDIUMINT UNSIGNED" => {
+      let v: u32 = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_u32(v)
+    }
+    "BIGINT UNSIGNED" => {
+      let v: u64 = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_u64(v)
+    }
+    "FLOAT" => {
+      let v: f32 = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_f32(v)
+    }
+    "DOUBLE" => {
+      let v: f64 = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_f64(v)
+    }
+    "DECIMAL" => {
+      // DECIMAL values are sent as strings in both the text and binary protocols.
+      let v: String = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      let v: f64 = v.parse().map_err(serde::ser::Error::custom)?;
+      s.serialize_f64(v)
+    }
+    "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" | "SET" => {
+      let v: String = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_str(&v)
+    }
+    "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" => {
+      let v: Vec<u8> = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_some(&v)
+    }
+    "JSON" => {
+      let v: Value = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_some(&v)
+    }
+    "DATETIME" | "TIMESTAMP" => {
+      let v: wick_packet::DateTime = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_str(&v.to_rfc3339())
+    }
+    "DATE" => {
+      let v: chrono::NaiveDate = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_str(&v.to_string())
+    }
+    "TIME" => {
+      let v: chrono::NaiveTime = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_str(&v.to_string())
+    }
+    _ => {
+      let v: String = Decode::<MySql>::decode(value).map_err(serde::ser::Error::custom)?;
+      s.serialize_str(&v)
+    }
+  }
+}
+
+/// Can be used with serialize_with
+pub(crate) fn serialize_row_as_vec<S>(x: &MySqlRow, s: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  let cols = x.columns();
+  let mut seq = s.serialize_seq(Some(cols.len()))?;
+  for c in cols {
+    let c: MySqlValueRef = x.try_get_raw(c.ordinal()).unwrap();
+    let c = SerMySqlValueRef(c);
+    seq.serialize_element(&c)?;
+  }
+  seq.end()
+}
+
+/// Can be used with serialize_with
+pub(crate) fn serialize_row_as_map<S>(x: &MySqlRow, s: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  let cols = x.columns();
+  let mut map = s.serialize_map(Some(cols.len()))?;
+  for col in cols {
+    let c: MySqlValueRef = x.try_get_raw(col.ordinal()).unwrap();
+    let c = SerMySqlValueRef(c);
+    map.serialize_entry(col.name(), &c)?;
+  }
+  map.end()
+}
+
+#[derive(Serialize)]
+pub(crate) struct SerVecMySqlRow(#[serde(serialize_with = "serialize_row_as_vec")] MySqlRow);
+
+#[derive(Serialize)]
+pub(crate) struct SerMapRow(#[serde(serialize_with = "serialize_row_as_map")] MySqlRow);
+
+impl From<MySqlRow> for SerMapRow {
+  fn from(row: MySqlRow) -> Self {
+    SerMapRow(row)
+  }
+}
+
+/// SerMySqlValueRef::from(mysql_value_ref) will make your value serialize as its closest serde type.
+#[derive(Serialize)]
+pub(crate) struct SerMySqlValueRef<'r>(#[serde(serialize_with = "serialize_valueref")] MySqlValueRef<'r>);
+
+impl From<MySqlRow> for SerVecMySqlRow {
+  fn from(row: MySqlRow) -> Self {
+    SerVecMySqlRow(row)
+  }
+}
+
+#[cfg(test)]
+mod integration_test {
+  use anyhow::Result;
+  use sqlx::{Connection, Executor, MySqlConnection};
+
+  use super::*;
+
+  fn read_row(row: &MySqlRow) -> Vec<Value> {
+    let columns = row.columns();
+    let mut result: Vec<Value> = Vec::with_capacity(columns.len());
+    for c in columns {
+      let value = row.try_get_raw(c.ordinal()).unwrap();
+      let value = SerMySqlValueRef(value);
+      let value = serde_json::to_value(&value).unwrap();
+      result.push(value);
+    }
+    result
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_mysql_types() -> Result<()> {
+    let docker_host = std::env::var("TEST_HOST").unwrap();
+    let db_host = docker_host.split(':').next().unwrap();
+    let password = std::env::var("TEST_PASSWORD").unwrap();
+    let port = std::env::var("MYSQL_PORT").unwrap();
+    let conn_string = format!("mysql://root:{}@{}:{}/wick_test", password, db_host, port);
+    info!("connection string {}", conn_string);
+
+    let mut conn = MySqlConnection::connect(&conn_string).await.unwrap();
+    let row = conn.fetch_one("SELECT NOW()").await.unwrap();
+    let row = read_row(&row);
+    chrono::DateTime::parse_from_rfc3339(row[0].as_str().unwrap()).unwrap();
+
+    let row = conn.fetch_one("select 3.3").await.unwrap();
+    let row = read_row(&row);
+    assert_eq!(row[0].as_f64().unwrap(), 3.3);
+
+    let row = conn.fetch_one("select cast(3.3 as decimal(19,4))").await.unwrap();
+    let row = read_row(&row);
+    assert_eq!(row[0].as_f64().unwrap(), 3.3);
+
+    let row = conn.fetch_one("select cast(42 as unsigned)").await.unwrap();
+    let row = read_row(&row);
+    assert_eq!(row[0].as_u64().unwrap(), 42);
+
+    let row = conn.fetch_one("select cast('{\"a\":1}' as json)").await.unwrap();
+    let row = read_row(&row);
+    assert_eq!(row[0], serde_json::json!({"a":1}));
+
+    let row = conn.fetch_one("select null").await.unwrap();
+    let row = read_row(&row);
+    assert_eq!(row[0], Value::Null);
+
+    let row = conn.fetch_one("select 1 as foo, 'hello' as bar").await.unwrap();
+    let row = SerMapRow::from(row);
+    let row = serde_json::to_string(&row).unwrap();
+    assert_eq!(row, r#"{"foo":1,"bar":"hello"}"#);
+
+    let row = conn.fetch_one("select 1 as foo, 'hello' as bar").await.unwrap();
+    let row = SerVecMySqlRow::from(row);
+    let row = serde_json::to_string(&row).unwrap();
+    assert_eq!(row, r#"[1,"hello"]"#);
+    Ok(())
+  }
+}
@@ -8,12 +8,20 @@ use crate::common::sql_wrapper::ConvertedType;
 use crate::Error;
 
 pub(crate) async fn connect(_config: &SqlComponentConfig, addr: Option<&str>) -> Result<SqlitePool, Error> {
-  let addr = addr.unwrap_or(":memory:");
-  debug!(%addr, "connecting to sqlite");
+  debug!(addr = addr.unwrap_or(":memory:"), "connecting to sqlite");
 
-  let pool = SqlitePoolOptions::new()
-    .max_connections(5)
-    .connect(addr)
+  let options = match addr {
+    Some(_) => SqlitePoolOptions::new().max_connections(5),
+    // Every connection to an in-memory database gets its own, empty database that is dropped along with it, so keep
+    // a single connection open for the life of the pool.
+    None => SqlitePoolOptions::new()
+      .max_connections(1)
+      .min_connections(1)
+      .idle_timeout(None)
+      .max_lifetime(None),
+  };
+  let pool = options
+    .connect(addr.unwrap_or(":memory:"))
     .await
     .map_err(|e| Error::SqliteConnect(e.to_string()))?;
   Ok(pool)
@@ -454,6 +454,12 @@ impl InstancePort {
     }
   }
 
+  /// Whether this is the reserved error port ([crate::parse::ERROR_PORT]) or a path into it.
+  #[must_use]
+  pub fn is_error(&self) -> bool {
+    self.name() == Some(crate::parse::ERROR_PORT)
+  }
+
   /// Convert the [InstancePort] to an Option<String> representing the (optional) parseable value.
   #[must_use]
   pub fn to_option_string(&self) -> Option<String> {
@@ -17,3 +17,5 @@ pub const SENDER_ID: &str = "core::sender";
 pub const CORE_ID: &str = "core";
 /// The name of SENDER's output port.
 pub const SENDER_PORT: &str = "output";
+/// The reserved name of the port an operation instance routes its errors to when the operation doesn't declare it.
+pub const ERROR_PORT: &str = "error";
@@ -283,6 +283,7 @@ mod tests {
   #[case("foo.hey", InstancePort::path("foo", vec!["hey".to_owned()]))]
   #[case("foo.hey.0.this", InstancePort::path("foo", vec!["hey".to_owned(),"0".to_owned(),"this".to_owned()]))]
   #[case("input.\"Raw String Field #\"", InstancePort::path("input", vec!["Raw String Field #".to_owned()]))]
+  #[case("error.message", InstancePort::path("error", vec!["message".to_owned()]))]
   fn test_instance_port(#[case] input: &'static str, #[case] expected: InstancePort) -> Result<()> {
     let (i, actual) = instance_port(input)?;
 
@@ -301,6 +302,7 @@ mod tests {
   #[case("<> -> ref1.port", ((InstTgt::Default, "port"),(InstTgt::named("ref1"), "port")))]
   #[case("<> -> test::reverse[A].input",((InstTgt::Default, "input"),(InstTgt::path("test::reverse","A"), "input")))]
   #[case("<>.anything -> drop",((InstTgt::Default, "anything"),(InstTgt::Null(None), InstancePort::None)))]
+  #[case("fetch.error -> handler.input",((InstTgt::named("fetch"), "error"),(InstTgt::named("handler"), "input")))]
   fn connection_parts(
     #[case] input: &'static str,
     #[case] expected: ((InstTgt, impl Into<InstancePort>), (InstTgt, impl Into<InstancePort>)),
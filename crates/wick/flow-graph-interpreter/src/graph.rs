@@ -18,6 +18,7 @@ use serde_json::Value;
 use types::*;
 use wick_config::config::components::{ComponentConfig, OperationConfig};
 use wick_config::config::{ComponentImplementation, ExecutionSettings, FlowOperation};
+use wick_interface_types::OperationSignature;
 use wick_packet::RuntimeConfig;
 
 use self::helpers::{ensure_added, ParseHelper};
@@ -39,6 +40,12 @@ impl From<&NodeReference> for Reference {
   }
 }
 
+/// Whether the named output is the operation's reserved error port, i.e. it's named [flow_graph::ERROR_PORT] and the
+/// operation doesn't declare an output of its own by that name.
+pub(crate) fn is_error_port(signature: &OperationSignature, port: &str) -> bool {
+  port == flow_graph::ERROR_PORT && !signature.outputs.iter().any(|p| p.name == port)
+}
+
 impl Reference {
   pub(crate) fn name(&self) -> &str {
     self.0.name()
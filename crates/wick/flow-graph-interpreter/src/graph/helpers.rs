@@ -95,11 +95,17 @@ pub(super) fn decorate(
 ) -> Result<(), GraphError> {
   if component == components::core::CoreComponent::ID {
     match operation {
+      components::core::batch::Op::ID => components::core::batch::Op::decorate(node),
       components::core::pluck::Op::ID => components::core::pluck::Op::decorate(node),
       components::core::collect::Op::ID => components::core::collect::Op::decorate(node),
+      components::core::expression::Op::ID => components::core::expression::Op::decorate(node),
+      components::core::filter::Op::ID => components::core::filter::Op::decorate(node),
+      components::core::map::Op::ID => components::core::map::Op::decorate(node),
       components::core::merge::Op::ID => components::core::merge::Op::decorate(node),
+      components::core::reduce::Op::ID => components::core::reduce::Op::decorate(node),
       components::core::sender::Op::ID => components::core::sender::Op::decorate(node),
       components::core::switch::Op::ID => components::core::switch::Op::decorate(node),
+      components::core::window::Op::ID => components::core::window::Op::decorate(node),
       _ => {
         panic!("unhandled core component operation: {}", operation);
       }
@@ -1,4 +1,5 @@
 pub(crate) mod channel;
+pub(crate) mod circuit_breaker;
 pub(crate) mod components;
 pub(crate) mod error;
 pub(crate) mod event_loop;
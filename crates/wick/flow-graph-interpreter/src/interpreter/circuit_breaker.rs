@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use parking_lot::Mutex;
+use wick_config::config::CircuitBreakerConfig;
+
+/// The circuits of every component that has a circuit breaker, keyed by namespace.
+///
+/// Circuits live as long as the interpreter so failures are counted across invocations.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct CircuitBreakers {
+  circuits: Arc<Mutex<HashMap<String, Circuit>>>,
+}
+
+#[derive(Debug, Default)]
+struct Circuit {
+  state: State,
+  failures: u32,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum State {
+  /// Calls go through.
+  #[default]
+  Closed,
+  /// Calls fail immediately until the reset time passes.
+  Open(Instant),
+  /// A single trial call is in flight. Its result closes or reopens the circuit.
+  HalfOpen,
+}
+
+impl CircuitBreakers {
+  /// A permit for a call to the passed component, if the call may go through. Lets one trial call through once an open
+  /// circuit's reset time has passed.
+  pub(crate) fn allow(&self, namespace: &str, config: &CircuitBreakerConfig) -> Option<Permit> {
+    let mut circuits = self.circuits.lock();
+    let circuit = circuits.entry(namespace.to_owned()).or_default();
+    match circuit.state {
+      State::Closed => {}
+      State::HalfOpen => return None,
+      State::Open(since) => {
+        if since.elapsed() < config.reset_duration() {
+          return None;
+        }
+        circuit.state = State::HalfOpen;
+      }
+    }
+    Some(Permit {
+      breakers: self.clone(),
+      namespace: namespace.to_owned(),
+      config: *config,
+      recorded: false,
+    })
+  }
+
+  fn record(&self, namespace: &str, config: &CircuitBreakerConfig, success: bool) {
+    let mut circuits = self.circuits.lock();
+    let circuit = circuits.entry(namespace.to_owned()).or_default();
+    if success {
+      circuit.failures = 0;
+      circuit.state = State::Closed;
+      return;
+    }
+    circuit.failures = circuit.failures.saturating_add(1);
+    if circuit.state == State::HalfOpen || circuit.failures >= config.failures() {
+      warn!(component = namespace, failures = circuit.failures, "circuit open");
+      circuit.state = State::Open(Instant::now());
+    }
+  }
+}
+
+/// A call that [CircuitBreakers::allow] let through.
+///
+/// A permit dropped before its result is recorded counts as a failure, so a call that ends early can't leave the
+/// circuit half-open.
+#[must_use]
+#[derive(Debug)]
+pub(crate) struct Permit {
+  breakers: CircuitBreakers,
+  namespace: String,
+  config: CircuitBreakerConfig,
+  recorded: bool,
+}
+
+impl Permit {
+  /// Record the result of the call.
+  pub(crate) fn record(mut self, success: bool) {
+    self.recorded = true;
+    self.breakers.record(&self.namespace, &self.config, success);
+  }
+}
+
+impl Drop for Permit {
+  fn drop(&mut self) {
+    if !self.recorded {
+      self.breakers.record(&self.namespace, &self.config, false);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::time::Duration;
+
+  use anyhow::Result;
+  use wick_config::config::CircuitBreakerConfigBuilder;
+
+  use super::*;
+
+  #[test]
+  fn test_circuit() -> Result<()> {
+    let config = CircuitBreakerConfigBuilder::default()
+      .failures(2_u32)
+      .reset_after(20_u64)
+      .build()?;
+    let breakers = CircuitBreakers::default();
+
+    breakers.allow("flaky", &config).unwrap().record(false);
+    breakers.allow("flaky", &config).unwrap().record(false);
+    assert!(breakers.allow("flaky", &config).is_none());
+    breakers.allow("other", &config).unwrap().record(true);
+
+    std::thread::sleep(Duration::from_millis(30));
+    // One trial call is let through once the circuit resets...
+    let trial = breakers.allow("flaky", &config).unwrap();
+    assert!(breakers.allow("flaky", &config).is_none());
+    // ...and its failure opens the circuit again.
+    trial.record(false);
+    assert!(breakers.allow("flaky", &config).is_none());
+
+    std::thread::sleep(Duration::from_millis(30));
+    breakers.allow("flaky", &config).unwrap().record(true);
+    breakers.allow("flaky", &config).unwrap().record(true);
+    breakers.allow("flaky", &config).unwrap().record(true);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_abandoned_trial() -> Result<()> {
+    let config = CircuitBreakerConfigBuilder::default()
+      .failures(1_u32)
+      .reset_after(20_u64)
+      .build()?;
+    let breakers = CircuitBreakers::default();
+
+    breakers.allow("flaky", &config).unwrap().record(false);
+    std::thread::sleep(Duration::from_millis(30));
+
+    // A trial that errors out before recording its result reopens the circuit instead of leaving it half-open.
+    let trial = breakers.allow("flaky", &config).unwrap();
+    drop(trial);
+    assert!(breakers.allow("flaky", &config).is_none());
+
+    std::thread::sleep(Duration::from_millis(30));
+    breakers.allow("flaky", &config).unwrap().record(true);
+    breakers.allow("flaky", &config).unwrap().record(true);
+
+    Ok(())
+  }
+}
@@ -14,6 +14,7 @@ use wick_interface_types::{ComponentSignature, OperationSignature};
 
 use self::core::CoreComponent;
 use self::internal::InternalComponent;
+use super::circuit_breaker::CircuitBreakers;
 use crate::error::InterpreterError;
 use crate::graph::types::Network;
 use crate::SharedHandler;
@@ -24,6 +25,7 @@ pub(crate) type ComponentMap = HashMap<String, ComponentSignature>;
 #[must_use]
 pub struct HandlerMap {
   components: HashMap<String, NamespaceHandler>,
+  circuits: CircuitBreakers,
 }
 
 impl Default for HandlerMap {
@@ -36,6 +38,7 @@ impl HandlerMap {
   pub fn new(components: Vec<NamespaceHandler>) -> Result<Self, InterpreterError> {
     let mut map = Self {
       components: Default::default(),
+      circuits: Default::default(),
     };
     for component in components {
       map.add(component)?;
@@ -70,6 +73,10 @@ impl HandlerMap {
       .collect::<HashMap<String, ComponentSignature>>()
   }
 
+  pub(crate) const fn circuits(&self) -> &CircuitBreakers {
+    &self.circuits
+  }
+
   #[must_use]
   pub fn get(&self, namespace: &str) -> Option<&NamespaceHandler> {
     self.components.get(namespace)
@@ -6,13 +6,29 @@ use crate::graph::types::Network;
 use crate::interpreter::components::dyn_component_id;
 use crate::{BoxFuture, HandlerMap};
 
+pub(crate) mod batch;
 pub(crate) mod collect;
+pub(crate) mod expression;
+pub(crate) mod filter;
+pub(crate) mod map;
 pub(crate) mod merge;
 pub(crate) mod pluck;
+pub(crate) mod reduce;
 pub(crate) mod sender;
 pub(crate) mod switch;
+pub(crate) mod window;
 
-pub(crate) static DYNAMIC_OPERATIONS: &[&str] = &[collect::Op::ID, merge::Op::ID, switch::Op::ID];
+pub(crate) static DYNAMIC_OPERATIONS: &[&str] = &[
+  batch::Op::ID,
+  collect::Op::ID,
+  expression::Op::ID,
+  filter::Op::ID,
+  map::Op::ID,
+  merge::Op::ID,
+  reduce::Op::ID,
+  switch::Op::ID,
+  window::Op::ID,
+];
 
 #[derive(Debug)]
 pub(crate) struct CoreComponent {
@@ -22,6 +38,12 @@ pub(crate) struct CoreComponent {
   merge: merge::Op,
   switch: switch::Op,
   collect: collect::Op,
+  map: map::Op,
+  batch: batch::Op,
+  window: window::Op,
+  reduce: reduce::Op,
+  filter: filter::Op,
+  expression: expression::Op,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -50,6 +72,12 @@ enum DynamicOperation {
   Merge,
   Switch,
   Collect,
+  Map,
+  Batch,
+  Window,
+  Reduce,
+  Filter,
+  Expression,
 }
 
 impl std::fmt::Display for DynamicOperation {
@@ -58,6 +86,12 @@ impl std::fmt::Display for DynamicOperation {
       DynamicOperation::Merge => f.write_str("merge"),
       DynamicOperation::Switch => f.write_str("switch"),
       DynamicOperation::Collect => f.write_str("collect"),
+      DynamicOperation::Map => f.write_str("map"),
+      DynamicOperation::Batch => f.write_str("batch"),
+      DynamicOperation::Window => f.write_str("window"),
+      DynamicOperation::Reduce => f.write_str("reduce"),
+      DynamicOperation::Filter => f.write_str("filter"),
+      DynamicOperation::Expression => f.write_str("expression"),
     }
   }
 }
@@ -65,7 +99,7 @@ impl std::fmt::Display for DynamicOperation {
 impl CoreComponent {
   pub(crate) const ID: &str = "core";
 
-  #[allow(clippy::cognitive_complexity)]
+  #[allow(clippy::cognitive_complexity, clippy::too_many_lines)]
   pub(crate) fn new(graph: &Network, handlers: &HandlerMap) -> Result<Self, OpInitError> {
     let mut this = Self {
       signature: ComponentSignature::new_named(Self::ID).set_version("0.0.0"),
@@ -74,6 +108,12 @@ impl CoreComponent {
       merge: merge::Op::new(),
       switch: switch::Op::new(),
       collect: collect::Op::new(),
+      map: map::Op::new(),
+      batch: batch::Op::new(),
+      window: window::Op::new(),
+      reduce: reduce::Op::new(),
+      filter: filter::Op::new(),
+      expression: expression::Op::new(),
     };
 
     this.signature = this.signature.add_operation(this.pluck.get_signature(None).clone());
@@ -91,6 +131,12 @@ impl CoreComponent {
           merge::Op::ID => DynamicOperation::Merge,
           switch::Op::ID => DynamicOperation::Switch,
           collect::Op::ID => DynamicOperation::Collect,
+          map::Op::ID => DynamicOperation::Map,
+          batch::Op::ID => DynamicOperation::Batch,
+          window::Op::ID => DynamicOperation::Window,
+          reduce::Op::ID => DynamicOperation::Reduce,
+          filter::Op::ID => DynamicOperation::Filter,
+          expression::Op::ID => DynamicOperation::Expression,
           _ => continue,
         };
 
@@ -136,6 +182,70 @@ impl CoreComponent {
             }
             Err(e) => Err(OpInitError::new(e, op)),
           },
+          DynamicOperation::Map => match map::Op::decode_config(config) {
+            Ok(config) => {
+              let id = dyn_component_id(map::Op::ID, schematic.name(), operation.id());
+              debug!(%id,%op,"adding type signature for dynamic component");
+              map::Op::gen_signature(id, schematic, graph, handlers, config)
+                .map(|op_sig| this.signature.operations.push(op_sig))
+                .map_err(|e| OpInitError::new(e, op))
+            }
+            Err(e) => Err(OpInitError::new(e, op)),
+          },
+          DynamicOperation::Batch => match batch::Op::decode_config(config) {
+            Ok(config) => {
+              let id = dyn_component_id(batch::Op::ID, schematic.name(), operation.id());
+              debug!(%id,%op,"adding type signature for dynamic component");
+              let op_sig = batch::Op::gen_signature(&id, config);
+
+              this.signature.operations.push(op_sig);
+              Ok(())
+            }
+            Err(e) => Err(OpInitError::new(e, op)),
+          },
+          DynamicOperation::Window => match window::Op::decode_config(config) {
+            Ok(config) => {
+              let id = dyn_component_id(window::Op::ID, schematic.name(), operation.id());
+              debug!(%id,%op,"adding type signature for dynamic component");
+              let op_sig = window::Op::gen_signature(&id, config);
+
+              this.signature.operations.push(op_sig);
+              Ok(())
+            }
+            Err(e) => Err(OpInitError::new(e, op)),
+          },
+          DynamicOperation::Reduce => match reduce::Op::decode_config(config) {
+            Ok(config) => {
+              let id = dyn_component_id(reduce::Op::ID, schematic.name(), operation.id());
+              debug!(%id,%op,"adding type signature for dynamic component");
+              reduce::Op::gen_signature(id, schematic, graph, handlers, config)
+                .map(|op_sig| this.signature.operations.push(op_sig))
+                .map_err(|e| OpInitError::new(e, op))
+            }
+            Err(e) => Err(OpInitError::new(e, op)),
+          },
+          DynamicOperation::Filter => match filter::Op::decode_config(config) {
+            Ok(config) => {
+              let id = dyn_component_id(filter::Op::ID, schematic.name(), operation.id());
+              debug!(%id,%op,"adding type signature for dynamic component");
+              let op_sig = filter::Op::gen_signature(&id, config);
+
+              this.signature.operations.push(op_sig);
+              Ok(())
+            }
+            Err(e) => Err(OpInitError::new(e, op)),
+          },
+          DynamicOperation::Expression => match expression::Op::decode_config(config) {
+            Ok(config) => {
+              let id = dyn_component_id(expression::Op::ID, schematic.name(), operation.id());
+              debug!(%id,%op,"adding type signature for dynamic component");
+              let op_sig = expression::Op::gen_signature(&id, config);
+
+              this.signature.operations.push(op_sig);
+              Ok(())
+            }
+            Err(e) => Err(OpInitError::new(e, op)),
+          },
         };
         if let Err(error) = result {
           error!(%error, "Failed to add dynamic signature");
@@ -174,6 +284,12 @@ impl Component for CoreComponent {
         merge::Op::ID => core_op! {merge::Op, invocation, self.merge, callback, data},
         switch::Op::ID => core_op! {switch::Op, invocation, self.switch, callback, data},
         collect::Op::ID => core_op! {collect::Op, invocation, self.collect, callback, data},
+        map::Op::ID => core_op! {map::Op, invocation, self.map, callback, data},
+        batch::Op::ID => core_op! {batch::Op, invocation, self.batch, callback, data},
+        window::Op::ID => core_op! {window::Op, invocation, self.window, callback, data},
+        reduce::Op::ID => core_op! {reduce::Op, invocation, self.reduce, callback, data},
+        filter::Op::ID => core_op! {filter::Op, invocation, self.filter, callback, data},
+        expression::Op::ID => core_op! {expression::Op, invocation, self.expression, callback, data},
         _ => {
           panic!("Core operation {} not handled.", invocation.target().operation_id());
         }
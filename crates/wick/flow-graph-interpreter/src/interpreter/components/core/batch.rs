@@ -0,0 +1,262 @@
+use std::time::Duration;
+
+use anyhow::anyhow;
+use flow_component::{ComponentError, Context, Operation, RenderConfiguration};
+use futures::{FutureExt, StreamExt};
+use serde_json::Value;
+use tokio::time::Instant;
+use wasmrs_rx::Observer;
+use wick_interface_types::{OperationSignature, Type};
+use wick_packet::{Invocation, Packet, PacketStream, RuntimeConfig};
+
+use crate::BoxFuture;
+pub(crate) struct Op {}
+
+const INPUT: &str = "input";
+const OUTPUT: &str = "output";
+
+impl std::fmt::Debug for Op {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct(Op::ID).finish()
+  }
+}
+
+impl crate::graph::NodeDecorator for Op {
+  fn decorate(node: &mut crate::graph::types::Node) -> Result<(), String> {
+    node.add_input(INPUT);
+    node.add_output(OUTPUT);
+    Ok(())
+  }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub(crate) struct Config {
+  /// The number of elements that fill a batch.
+  size: Option<usize>,
+  /// The number of milliseconds after its first element that a batch is sent, full or not.
+  duration: Option<u64>,
+  /// The type of the elements.
+  #[serde(rename = "type")]
+  ty: Type,
+}
+
+fn gen_signature(id: &str, config: Config) -> OperationSignature {
+  OperationSignature::new_named(id)
+    .add_input(INPUT, config.ty.clone())
+    .add_output(
+      OUTPUT,
+      Type::List {
+        ty: Box::new(config.ty),
+      },
+    )
+}
+
+impl Op {
+  pub(crate) const fn new() -> Self {
+    Self {}
+  }
+
+  pub(crate) fn gen_signature(id: &str, config: Config) -> OperationSignature {
+    gen_signature(id, config)
+  }
+}
+
+impl Operation for Op {
+  const ID: &'static str = "batch";
+  type Config = Config;
+
+  fn handle(
+    &self,
+    invocation: Invocation,
+    context: Context<Self::Config>,
+  ) -> BoxFuture<Result<PacketStream, ComponentError>> {
+    let (tx, rx) = invocation.make_response();
+    let config = context.config;
+
+    tokio::spawn(async move {
+      let mut stream = invocation.into_stream();
+      let duration = config.duration.map(Duration::from_millis);
+      let mut batch: Vec<Value> = Vec::new();
+      let mut deadline: Option<Instant> = None;
+
+      loop {
+        let next = match deadline {
+          Some(at) => {
+            tokio::select! {
+              next = stream.next() => next,
+              _ = tokio::time::sleep_until(at) => {
+                let _ = tx.send(Packet::encode(OUTPUT, std::mem::take(&mut batch)));
+                deadline = None;
+                continue;
+              }
+            }
+          }
+          None => stream.next().await,
+        };
+        let Some(next) = next else {
+          break;
+        };
+        let packet = match next {
+          Ok(packet) => packet,
+          Err(e) => {
+            let _ = tx.send_result(Err(e));
+            continue;
+          }
+        };
+        if packet.port() != INPUT || packet.is_signal() {
+          continue;
+        }
+        if packet.is_error() {
+          let _ = tx.send(packet.set_port(OUTPUT));
+          continue;
+        }
+        match packet.decode_value() {
+          Ok(value) => batch.push(value),
+          Err(e) => {
+            let _ = tx.send(Packet::err(OUTPUT, e.to_string()));
+            continue;
+          }
+        }
+        if config.size.map_or(false, |size| batch.len() >= size) {
+          let _ = tx.send(Packet::encode(OUTPUT, std::mem::take(&mut batch)));
+        }
+        deadline = match (batch.len(), duration) {
+          (0, _) | (_, None) => None,
+          (1, Some(duration)) => Some(Instant::now() + duration),
+          _ => deadline,
+        };
+      }
+
+      if !batch.is_empty() {
+        let _ = tx.send(Packet::encode(OUTPUT, batch));
+      }
+      let _ = tx.send(Packet::done(OUTPUT));
+    });
+
+    async move { Ok(rx) }.boxed()
+  }
+
+  fn get_signature(&self, _config: Option<&Self::Config>) -> &OperationSignature {
+    panic!("{} operation has a dynamic signature", Self::ID);
+  }
+
+  fn input_names(&self, _config: &Self::Config) -> Vec<String> {
+    vec![INPUT.to_owned()]
+  }
+}
+
+impl RenderConfiguration for Op {
+  type Config = Config;
+  type ConfigSource = RuntimeConfig;
+
+  fn decode_config(data: Option<Self::ConfigSource>) -> Result<Self::Config, ComponentError> {
+    let config =
+      data.ok_or_else(|| anyhow!("Batch component requires configuration, please specify configuration."))?;
+
+    let size: Option<usize> = config.has("size").then(|| config.coerce_key("size")).transpose()?;
+    let duration: Option<u64> = config
+      .has("duration")
+      .then(|| config.coerce_key("duration"))
+      .transpose()?;
+
+    if size.is_none() && duration.is_none() {
+      return Err(anyhow!("Batch component requires a size, a duration, or both"));
+    }
+    if size == Some(0) || duration == Some(0) {
+      return Err(anyhow!("Batch size and duration must be at least 1"));
+    }
+
+    Ok(Self::Config {
+      size,
+      duration,
+      ty: if config.has("type") {
+        config.coerce_key("type")
+      } else {
+        Ok(Type::Object)
+      }?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use flow_component::panic_callback;
+  use serde_json::json;
+  use wick_packet::{Entity, InherentData};
+
+  use super::*;
+
+  async fn run(config: Value, input: PacketStream) -> Result<Vec<Packet>> {
+    let config = Op::decode_config(Some(RuntimeConfig::from_value(config)?))?;
+    let inv = Invocation::test(file!(), Entity::test("batch"), input, None)?;
+    let packets = Op::new()
+      .handle(
+        inv,
+        Context::new(config, &InherentData::unsafe_default(), panic_callback()),
+      )
+      .await?
+      .collect::<Vec<_>>()
+      .await
+      .into_iter()
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(packets)
+  }
+
+  #[tokio::test]
+  async fn test_size() -> Result<()> {
+    let packets = run(
+      json!({"size": 2}),
+      vec![
+        Packet::encode(INPUT, 1),
+        Packet::encode(INPUT, 2),
+        Packet::encode(INPUT, 3),
+        Packet::done(INPUT),
+      ]
+      .into(),
+    )
+    .await?;
+
+    assert_eq!(
+      packets,
+      vec![
+        Packet::encode(OUTPUT, vec![1, 2]),
+        Packet::encode(OUTPUT, vec![3]),
+        Packet::done(OUTPUT)
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_duration() -> Result<()> {
+    let input = futures::stream::iter([(0, 1), (0, 2), (100, 3), (0, 4)])
+      .then(|(delay, value)| async move {
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        Ok(Packet::encode(INPUT, value))
+      })
+      .chain(futures::stream::iter([Ok(Packet::done(INPUT))]));
+    let packets = run(json!({"duration": 50, "size": 10}), PacketStream::new(input.boxed())).await?;
+
+    assert_eq!(
+      packets,
+      vec![
+        Packet::encode(OUTPUT, vec![1, 2]),
+        Packet::encode(OUTPUT, vec![3, 4]),
+        Packet::done(OUTPUT)
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_config() -> Result<()> {
+    assert!(Op::decode_config(Some(RuntimeConfig::from_value(json!({}))?)).is_err());
+    assert!(Op::decode_config(Some(RuntimeConfig::from_value(json!({"size": 0}))?)).is_err());
+    assert!(Op::decode_config(Some(RuntimeConfig::from_value(json!({"duration": 10}))?)).is_ok());
+
+    Ok(())
+  }
+}
@@ -0,0 +1,370 @@
+use anyhow::anyhow;
+use flow_component::{ComponentError, Context, Operation, RenderConfiguration};
+use futures::{FutureExt, StreamExt};
+use jaq_core::{parse, Ctx, Definitions, RcIter, Val};
+use liquid_json::LiquidJson;
+use serde_json::{Map, Value};
+use wasmrs_rx::Observer;
+use wick_interface_types::{OperationSignature, Type};
+use wick_packet::{Invocation, Packet, PacketStream, RuntimeConfig};
+
+use crate::BoxFuture;
+
+pub(crate) struct Op {}
+
+const INPUT: &str = "input";
+const OUTPUT: &str = "output";
+
+/// The languages an [Expression] can be written in.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Language {
+  /// A liquid expression, like the ones inside `{{ }}`, with each variable in scope by name.
+  Liquid,
+  /// A jq filter, with each variable in scope as `$name` and the last one as `.`.
+  Jq,
+}
+
+impl Default for Language {
+  fn default() -> Self {
+    Self::Liquid
+  }
+}
+
+/// A small expression evaluated in-process against packet data.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub(crate) struct Expression {
+  language: Language,
+  source: String,
+  /// The names of the variables the expression is evaluated with.
+  vars: Vec<String>,
+}
+
+impl Expression {
+  /// Creates an expression over the named variables, checking that jq filters parse.
+  pub(crate) fn new(language: Language, source: String, vars: &[&str]) -> Result<Self, ComponentError> {
+    let expression = Self {
+      language,
+      source,
+      vars: vars.iter().map(|v| (*v).to_owned()).collect(),
+    };
+    expression.compile()?;
+    Ok(expression)
+  }
+
+  /// Reads an expression from the `key` and `language` fields of an operation's configuration.
+  pub(crate) fn from_config(config: &RuntimeConfig, key: &str, vars: &[&str]) -> Result<Self, ComponentError> {
+    let language = if config.has("language") {
+      config.coerce_key("language")?
+    } else {
+      Language::default()
+    };
+    Self::new(language, config.coerce_key(key)?, vars)
+  }
+
+  pub(crate) fn compile(&self) -> Result<Compiled, ComponentError> {
+    match self.language {
+      Language::Liquid => Ok(Compiled::Liquid(self.clone())),
+      Language::Jq => {
+        let (main, errors) = parse::parse(&self.source, parse::main());
+        let main = match main {
+          Some(main) if errors.is_empty() => main,
+          _ => {
+            let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+            return Err(anyhow!(
+              "Could not parse jq filter '{}': {}",
+              self.source,
+              errors.join(", ")
+            ));
+          }
+        };
+        let mut errors = Vec::new();
+        let filter = Definitions::core().finish(main, self.vars.clone(), &mut errors);
+        if !errors.is_empty() {
+          let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+          return Err(anyhow!("Invalid jq filter '{}': {}", self.source, errors.join(", ")));
+        }
+        Ok(Compiled::Jq(self.source.clone(), filter))
+      }
+    }
+  }
+}
+
+/// An [Expression] ready to be evaluated.
+pub(crate) enum Compiled {
+  Liquid(Expression),
+  Jq(String, jaq_core::Filter),
+}
+
+impl Compiled {
+  /// Evaluates the expression with a value for each of its variables, in order, and returns everything it produced.
+  pub(crate) fn evaluate(&self, values: &[&Value]) -> Result<Vec<Value>, ComponentError> {
+    match self {
+      Compiled::Liquid(expr) => {
+        let template = LiquidJson::new(Value::String(format!("{{{{ {} | output }}}}", expr.source)));
+        let value = template
+          .render(&liquid_vars(&expr.vars, values))
+          .map_err(|e| anyhow!("Could not evaluate expression '{}': {}", expr.source, e))?;
+        Ok(vec![value])
+      }
+      Compiled::Jq(source, filter) => {
+        let inputs = RcIter::new(std::iter::empty());
+        let vars = values.iter().map(|v| Val::from((*v).clone()));
+        let this = values.last().map_or(Val::Null, |v| Val::from((*v).clone()));
+        filter
+          .run(Ctx::new(vars, &inputs), this)
+          .map(|result| {
+            result
+              .map(Into::into)
+              .map_err(|e| anyhow!("Could not evaluate jq filter '{}': {}", source, e))
+          })
+          .collect()
+      }
+    }
+  }
+
+  /// Evaluates the expression as a condition. Liquid conditions follow the rules of `{% if %}` and jq conditions are
+  /// true when their first result is neither `false` nor `null`.
+  pub(crate) fn test(&self, values: &[&Value]) -> Result<bool, ComponentError> {
+    match self {
+      Compiled::Liquid(expr) => {
+        let template = format!("{{% if {} %}}true{{% endif %}}", expr.source);
+        let result = liquid_json::render_string(&template, &liquid_vars(&expr.vars, values))
+          .map_err(|e| anyhow!("Could not evaluate condition '{}': {}", expr.source, e))?;
+        Ok(result == "true")
+      }
+      Compiled::Jq(..) => Ok(
+        self
+          .evaluate(values)?
+          .first()
+          .map_or(false, |v| !matches!(v, Value::Null | Value::Bool(false))),
+      ),
+    }
+  }
+}
+
+fn liquid_vars(names: &[String], values: &[&Value]) -> Value {
+  Value::Object(
+    names
+      .iter()
+      .zip(values)
+      .map(|(name, value)| (name.clone(), (*value).clone()))
+      .collect::<Map<_, _>>(),
+  )
+}
+
+impl std::fmt::Debug for Op {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct(Op::ID).finish()
+  }
+}
+
+impl crate::graph::NodeDecorator for Op {
+  fn decorate(node: &mut crate::graph::types::Node) -> Result<(), String> {
+    node.add_input(INPUT);
+    node.add_output(OUTPUT);
+    Ok(())
+  }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub(crate) struct Config {
+  /// The expression evaluated for each element.
+  expression: Expression,
+  /// The type of the values the expression produces.
+  #[serde(rename = "type")]
+  ty: Type,
+}
+
+fn gen_signature(id: &str, config: Config) -> OperationSignature {
+  OperationSignature::new_named(id)
+    .add_input(INPUT, Type::Object)
+    .add_output(OUTPUT, config.ty)
+}
+
+impl Op {
+  pub(crate) const fn new() -> Self {
+    Self {}
+  }
+
+  pub(crate) fn gen_signature(id: &str, config: Config) -> OperationSignature {
+    gen_signature(id, config)
+  }
+}
+
+impl Operation for Op {
+  const ID: &'static str = "expression";
+  type Config = Config;
+
+  fn handle(
+    &self,
+    invocation: Invocation,
+    context: Context<Self::Config>,
+  ) -> BoxFuture<Result<PacketStream, ComponentError>> {
+    let (tx, rx) = invocation.make_response();
+    let expression = context.config.expression.compile();
+
+    tokio::spawn(async move {
+      let mut stream = invocation.into_stream();
+      let expression = match expression {
+        Ok(expression) => expression,
+        Err(e) => {
+          let _ = tx.send(Packet::err(OUTPUT, e.to_string()));
+          let _ = tx.send(Packet::done(OUTPUT));
+          return;
+        }
+      };
+
+      while let Some(next) = stream.next().await {
+        let packet = match next {
+          Ok(packet) => packet,
+          Err(e) => {
+            let _ = tx.send_result(Err(e));
+            continue;
+          }
+        };
+        if packet.port() != INPUT || packet.is_done() {
+          continue;
+        }
+        if packet.is_error() || packet.is_bracket() {
+          let _ = tx.send(packet.set_port(OUTPUT));
+          continue;
+        }
+        let results = packet
+          .decode_value()
+          .map_err(|e| anyhow!(e))
+          .and_then(|value| expression.evaluate(&[&value]));
+        match results {
+          Ok(values) => {
+            for value in values {
+              let _ = tx.send(Packet::encode(OUTPUT, value));
+            }
+          }
+          Err(e) => {
+            let _ = tx.send(Packet::err(OUTPUT, e.to_string()));
+          }
+        }
+      }
+      let _ = tx.send(Packet::done(OUTPUT));
+    });
+
+    async move { Ok(rx) }.boxed()
+  }
+
+  fn get_signature(&self, _config: Option<&Self::Config>) -> &OperationSignature {
+    panic!("{} operation has a dynamic signature", Self::ID);
+  }
+
+  fn input_names(&self, _config: &Self::Config) -> Vec<String> {
+    vec![INPUT.to_owned()]
+  }
+}
+
+impl RenderConfiguration for Op {
+  type Config = Config;
+  type ConfigSource = RuntimeConfig;
+
+  fn decode_config(data: Option<Self::ConfigSource>) -> Result<Self::Config, ComponentError> {
+    let config =
+      data.ok_or_else(|| anyhow!("Expression component requires configuration, please specify configuration."))?;
+
+    Ok(Self::Config {
+      expression: Expression::from_config(&config, "expression", &[INPUT])?,
+      ty: if config.has("type") {
+        config.coerce_key("type")
+      } else {
+        Ok(Type::Object)
+      }?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use flow_component::panic_callback;
+  use serde_json::json;
+  use wick_packet::{Entity, InherentData};
+
+  use super::*;
+
+  fn eval(language: Language, source: &str, value: &Value) -> Result<Vec<Value>> {
+    Expression::new(language, source.to_owned(), &[INPUT])?
+      .compile()?
+      .evaluate(&[value])
+  }
+
+  fn test(language: Language, source: &str, value: &Value) -> Result<bool> {
+    Expression::new(language, source.to_owned(), &[INPUT])?
+      .compile()?
+      .test(&[value])
+  }
+
+  #[test]
+  fn test_liquid() -> Result<()> {
+    assert_eq!(
+      eval(Language::Liquid, "input.name | upcase", &json!({"name": "wick"}))?,
+      vec![json!("WICK")]
+    );
+    assert_eq!(eval(Language::Liquid, "input | plus: 1", &json!(41))?, vec![json!(42)]);
+    assert!(test(Language::Liquid, "input.age > 17", &json!({"age": 18}))?);
+    assert!(!test(Language::Liquid, "input.age > 17", &json!({"age": 17}))?);
+    assert!(!test(Language::Liquid, "input.missing", &json!({}))?);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_jq() -> Result<()> {
+    assert_eq!(
+      eval(
+        Language::Jq,
+        "{name: .first, count: ($input.items | length)}",
+        &json!({"first": "a", "items": [1, 2]})
+      )?,
+      vec![json!({"name": "a", "count": 2})]
+    );
+    assert_eq!(eval(Language::Jq, ".[]", &json!([1, 2]))?, vec![json!(1), json!(2)]);
+    assert!(test(Language::Jq, ".age > 17", &json!({"age": 18}))?);
+    assert!(!test(Language::Jq, ".missing", &json!({}))?);
+    assert!(Expression::new(Language::Jq, ".[".to_owned(), &[INPUT]).is_err());
+    assert!(Expression::new(Language::Jq, "$nope".to_owned(), &[INPUT]).is_err());
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_op() -> Result<()> {
+    let config = Op::decode_config(Some(RuntimeConfig::from_value(
+      json!({"expression": ".value * 2", "language": "jq"}),
+    )?))?;
+    let inv = Invocation::test(
+      file!(),
+      Entity::test("expression"),
+      vec![
+        Packet::encode(INPUT, json!({"value": 1})),
+        Packet::encode(INPUT, json!({})),
+        Packet::encode(INPUT, json!({"value": 3})),
+        Packet::done(INPUT),
+      ],
+      None,
+    )?;
+    let packets = Op::new()
+      .handle(
+        inv,
+        Context::new(config, &InherentData::unsafe_default(), panic_callback()),
+      )
+      .await?
+      .collect::<Vec<_>>()
+      .await
+      .into_iter()
+      .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(packets[0], Packet::encode(OUTPUT, 2));
+    assert!(packets[1].is_error());
+    assert_eq!(packets[2], Packet::encode(OUTPUT, 6));
+    assert_eq!(packets[3], Packet::done(OUTPUT));
+
+    Ok(())
+  }
+}
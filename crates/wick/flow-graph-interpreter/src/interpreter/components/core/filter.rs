@@ -0,0 +1,202 @@
+use anyhow::anyhow;
+use flow_component::{ComponentError, Context, Operation, RenderConfiguration};
+use futures::{FutureExt, StreamExt};
+use wasmrs_rx::Observer;
+use wick_interface_types::{OperationSignature, Type};
+use wick_packet::{Invocation, Packet, PacketStream, RuntimeConfig};
+
+use super::expression::Expression;
+use crate::BoxFuture;
+
+pub(crate) struct Op {}
+
+const INPUT: &str = "input";
+const OUTPUT: &str = "output";
+
+impl std::fmt::Debug for Op {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct(Op::ID).finish()
+  }
+}
+
+impl crate::graph::NodeDecorator for Op {
+  fn decorate(node: &mut crate::graph::types::Node) -> Result<(), String> {
+    node.add_input(INPUT);
+    node.add_output(OUTPUT);
+    Ok(())
+  }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub(crate) struct Config {
+  /// The condition an element has to meet to be passed on.
+  condition: Expression,
+  /// The type of the elements.
+  #[serde(rename = "type")]
+  ty: Type,
+}
+
+fn gen_signature(id: &str, config: Config) -> OperationSignature {
+  OperationSignature::new_named(id)
+    .add_input(INPUT, config.ty.clone())
+    .add_output(OUTPUT, config.ty)
+}
+
+impl Op {
+  pub(crate) const fn new() -> Self {
+    Self {}
+  }
+
+  pub(crate) fn gen_signature(id: &str, config: Config) -> OperationSignature {
+    gen_signature(id, config)
+  }
+}
+
+impl Operation for Op {
+  const ID: &'static str = "filter";
+  type Config = Config;
+
+  fn handle(
+    &self,
+    invocation: Invocation,
+    context: Context<Self::Config>,
+  ) -> BoxFuture<Result<PacketStream, ComponentError>> {
+    let (tx, rx) = invocation.make_response();
+    let condition = context.config.condition.compile();
+
+    tokio::spawn(async move {
+      let mut stream = invocation.into_stream();
+      let condition = match condition {
+        Ok(condition) => condition,
+        Err(e) => {
+          let _ = tx.send(Packet::err(OUTPUT, e.to_string()));
+          let _ = tx.send(Packet::done(OUTPUT));
+          return;
+        }
+      };
+
+      while let Some(next) = stream.next().await {
+        let packet = match next {
+          Ok(packet) => packet,
+          Err(e) => {
+            let _ = tx.send_result(Err(e));
+            continue;
+          }
+        };
+        if packet.port() != INPUT || packet.is_done() {
+          continue;
+        }
+        if packet.is_error() || packet.is_bracket() {
+          let _ = tx.send(packet.set_port(OUTPUT));
+          continue;
+        }
+        let keep = packet
+          .clone()
+          .decode_value()
+          .map_err(|e| anyhow!(e))
+          .and_then(|value| condition.test(&[&value]));
+        match keep {
+          Ok(true) => {
+            let _ = tx.send(packet.set_port(OUTPUT));
+          }
+          Ok(false) => {}
+          Err(e) => {
+            let _ = tx.send(Packet::err(OUTPUT, e.to_string()));
+          }
+        }
+      }
+      let _ = tx.send(Packet::done(OUTPUT));
+    });
+
+    async move { Ok(rx) }.boxed()
+  }
+
+  fn get_signature(&self, _config: Option<&Self::Config>) -> &OperationSignature {
+    panic!("{} operation has a dynamic signature", Self::ID);
+  }
+
+  fn input_names(&self, _config: &Self::Config) -> Vec<String> {
+    vec![INPUT.to_owned()]
+  }
+}
+
+impl RenderConfiguration for Op {
+  type Config = Config;
+  type ConfigSource = RuntimeConfig;
+
+  fn decode_config(data: Option<Self::ConfigSource>) -> Result<Self::Config, ComponentError> {
+    let config =
+      data.ok_or_else(|| anyhow!("Filter component requires configuration, please specify configuration."))?;
+
+    Ok(Self::Config {
+      condition: Expression::from_config(&config, "condition", &[INPUT])?,
+      ty: if config.has("type") {
+        config.coerce_key("type")
+      } else {
+        Ok(Type::Object)
+      }?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use flow_component::panic_callback;
+  use serde_json::{json, Value};
+  use wick_packet::{Entity, InherentData};
+
+  use super::*;
+
+  async fn run(config: Value, input: &[Value]) -> Result<Vec<Packet>> {
+    let config = Op::decode_config(Some(RuntimeConfig::from_value(config)?))?;
+    let mut packets: Vec<_> = input.iter().map(|v| Packet::encode(INPUT, v)).collect();
+    packets.push(Packet::done(INPUT));
+    let inv = Invocation::test(file!(), Entity::test("filter"), packets, None)?;
+    let packets = Op::new()
+      .handle(
+        inv,
+        Context::new(config, &InherentData::unsafe_default(), panic_callback()),
+      )
+      .await?
+      .collect::<Vec<_>>()
+      .await
+      .into_iter()
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(packets)
+  }
+
+  #[tokio::test]
+  async fn test_liquid() -> Result<()> {
+    let packets = run(
+      json!({"condition": "input > 2"}),
+      &[json!(1), json!(3), json!(2), json!(4)],
+    )
+    .await?;
+    assert_eq!(
+      packets,
+      vec![
+        Packet::encode(OUTPUT, 3),
+        Packet::encode(OUTPUT, 4),
+        Packet::done(OUTPUT)
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_jq() -> Result<()> {
+    let packets = run(
+      json!({"condition": ".tags | has(0)", "language": "jq"}),
+      &[json!({"tags": []}), json!({"tags": ["a"]})],
+    )
+    .await?;
+    assert_eq!(
+      packets,
+      vec![Packet::encode(OUTPUT, json!({"tags": ["a"]})), Packet::done(OUTPUT)]
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use flow_component::{ComponentError, Context, Operation, RenderConfiguration, RuntimeCallback};
+use futures::{FutureExt, StreamExt};
+use seeded_random::{Random, Seed};
+use serde_json::Value;
+use wasmrs_rx::Observer;
+use wick_interface_types::{Field, OperationSignature, Type};
+use wick_packet::{
+  ComponentReference,
+  Entity,
+  InherentData,
+  Invocation,
+  InvocationData,
+  Packet,
+  PacketStream,
+  RuntimeConfig,
+};
+
+use super::switch::get_op_signature;
+use crate::graph::types::{Network, Schematic};
+use crate::utils::path_to_entity;
+use crate::{BoxFuture, HandlerMap};
+
+pub(crate) struct Op {}
+
+const DEFAULT_INPUT: &str = "input";
+const DEFAULT_CONCURRENCY: usize = 1;
+
+impl std::fmt::Debug for Op {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct(Op::ID).finish()
+  }
+}
+
+impl crate::graph::NodeDecorator for Op {
+  fn decorate(node: &mut crate::graph::types::Node) -> Result<(), String> {
+    let Ok(config) = node.data().config.render(&InherentData::unsafe_default()) else {
+      return Err(format!("Could not render config for {}", Op::ID));
+    };
+    let config = match Op::decode_config(config) {
+      Ok(c) => c,
+      Err(e) => {
+        return Err(e.to_string());
+      }
+    };
+    node.add_input(&config.input);
+    for field in config.outputs {
+      node.add_output(field.name());
+    }
+    Ok(())
+  }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub(crate) struct Config {
+  /// The input each element is sent on, both to `core::map` and to the target operation.
+  input: String,
+  /// The outputs of the target operation that are passed on.
+  outputs: Vec<Field>,
+  /// The operation invoked for each element.
+  #[serde(rename = "do")]
+  map_do: String,
+  /// The configuration passed to the target operation.
+  with: Option<RuntimeConfig>,
+  /// The maximum number of elements processed at once.
+  concurrency: usize,
+  /// Whether the output keeps the order of the elements or follows the order the invocations complete in.
+  ordered: bool,
+  /// Whether each output is gathered into a single list instead of being streamed.
+  collect: bool,
+}
+
+fn gen_signature(
+  id: String,
+  parent_schematic: &Schematic,
+  graph: &Network,
+  handlers: &HandlerMap,
+  config: Config,
+) -> Result<OperationSignature, ComponentError> {
+  let target = get_op_signature(&config.map_do, parent_schematic, graph, handlers)
+    .ok_or_else(|| anyhow!("Invalid map configuration: operation '{}' not found", config.map_do))?;
+
+  if !target.inputs.iter().any(|field| field.name == config.input) {
+    return Err(anyhow!(
+      "Invalid map configuration: operation '{}' has no input named '{}'",
+      config.map_do,
+      config.input
+    ));
+  }
+
+  let mut signature = OperationSignature::new_named(id).add_input(&config.input, Type::Object);
+  for field in config.outputs {
+    if !target.outputs.iter().any(|output| output.name == field.name) {
+      return Err(anyhow!(
+        "Invalid map configuration: operation '{}' has no output named '{}'",
+        config.map_do,
+        field.name
+      ));
+    }
+    let ty = if config.collect {
+      Type::List { ty: Box::new(field.ty) }
+    } else {
+      field.ty
+    };
+    signature = signature.add_output(field.name, ty);
+  }
+
+  Ok(signature)
+}
+
+impl Op {
+  pub(crate) const fn new() -> Self {
+    Self {}
+  }
+
+  pub(crate) fn gen_signature(
+    id: String,
+    parent_schematic: &Schematic,
+    graph: &Network,
+    handlers: &HandlerMap,
+    config: Config,
+  ) -> Result<OperationSignature, ComponentError> {
+    gen_signature(id, parent_schematic, graph, handlers, config)
+  }
+}
+
+type Results = Vec<Result<Packet, wick_packet::Error>>;
+
+/// Turns a packet sent to `core::map` into the work it represents: one invocation per element, or the packets to pass
+/// straight on.
+fn split_packet(config: &Config, packet: Result<Packet, wick_packet::Error>) -> Vec<Work> {
+  let packet = match packet {
+    Ok(packet) => packet,
+    Err(e) => return vec![Work::Done(vec![Err(e)])],
+  };
+  if packet.port() != config.input || packet.is_signal() {
+    return Vec::new();
+  }
+  if packet.is_error() {
+    let outputs = config
+      .outputs
+      .iter()
+      .map(|output| Ok(packet.clone().set_port(output.name())))
+      .collect();
+    return vec![Work::Done(outputs)];
+  }
+  match packet.decode_value() {
+    Ok(Value::Array(elements)) => elements.into_iter().map(Work::Element).collect(),
+    Ok(element) => vec![Work::Element(element)],
+    Err(e) => vec![Work::Done(errors(&config.outputs, &e.to_string()))],
+  }
+}
+
+enum Work {
+  Element(Value),
+  Done(Results),
+}
+
+fn errors(outputs: &[Field], msg: &str) -> Results {
+  outputs
+    .iter()
+    .map(|output| Ok(Packet::err(output.name(), msg)))
+    .collect()
+}
+
+/// Invokes the target operation with a single element and returns the packets it sent on the mapped outputs.
+async fn map_element(
+  target: Entity,
+  invocation: Arc<InvocationData>,
+  inherent: InherentData,
+  callback: Arc<RuntimeCallback>,
+  config: Arc<Config>,
+  element: Value,
+) -> Results {
+  let compref = ComponentReference::new(invocation.target().clone(), target.clone());
+  let stream: PacketStream = vec![Packet::encode(&config.input, element), Packet::done(&config.input)].into();
+  let span = info_span!(parent:&invocation.span,"map:element",otel.name=format!("map:{}",target));
+
+  match callback(
+    compref,
+    target.operation_id().to_owned(),
+    stream,
+    inherent,
+    config.with.clone(),
+    &span,
+  )
+  .await
+  {
+    Ok(stream) => {
+      stream
+        .filter(|packet| {
+          let keep = packet.as_ref().map_or(true, |packet| {
+            !packet.is_done() && config.outputs.iter().any(|output| output.name == packet.port())
+          });
+          futures::future::ready(keep)
+        })
+        .collect()
+        .await
+    }
+    Err(e) => {
+      span.in_scope(|| warn!(error = %e, "map:element:error"));
+      errors(&config.outputs, &e.to_string())
+    }
+  }
+}
+
+impl Operation for Op {
+  const ID: &'static str = "map";
+  type Config = Config;
+
+  fn handle(
+    &self,
+    invocation: Invocation,
+    context: Context<Self::Config>,
+  ) -> BoxFuture<Result<PacketStream, ComponentError>> {
+    let (tx, rx) = invocation.make_response();
+    let config = context.config;
+    let callback = context.callback;
+
+    tokio::spawn(async move {
+      let (invocation, stream) = invocation.split();
+      let invocation = Arc::new(invocation);
+      let rng = Random::from_seed(Seed::unsafe_new(invocation.seed()));
+      let target = path_to_entity(&config.map_do);
+
+      let work = {
+        let config = config.clone();
+        stream.flat_map(move |packet| futures::stream::iter(split_packet(&config, packet)))
+      };
+      let tasks = work.map(|work| -> BoxFuture<'static, Results> {
+        match work {
+          Work::Element(element) => map_element(
+            target.clone(),
+            invocation.clone(),
+            InherentData::new(rng.gen(), invocation.timestamp()),
+            callback.clone(),
+            config.clone(),
+            element,
+          )
+          .boxed(),
+          Work::Done(results) => futures::future::ready(results).boxed(),
+        }
+      });
+      let mut results = if config.ordered {
+        tasks.buffered(config.concurrency).boxed()
+      } else {
+        tasks.buffer_unordered(config.concurrency).boxed()
+      };
+
+      let mut lists: HashMap<String, Vec<Value>> = HashMap::new();
+      while let Some(results) = results.next().await {
+        for result in results {
+          match result {
+            Ok(packet) if config.collect && packet.has_data() => {
+              let port = packet.port().to_owned();
+              match packet.decode_value() {
+                Ok(value) => lists.entry(port).or_default().push(value),
+                Err(e) => {
+                  let _ = tx.send(Packet::err(port, e.to_string()));
+                }
+              }
+            }
+            Ok(packet) if config.collect && packet.is_bracket() => {}
+            result => {
+              let _ = tx.send_result(result);
+            }
+          }
+        }
+      }
+
+      for output in &config.outputs {
+        if config.collect {
+          let list = lists.remove(output.name()).unwrap_or_default();
+          let _ = tx.send(Packet::encode(output.name(), list));
+        }
+        let _ = tx.send(Packet::done(output.name()));
+      }
+    });
+
+    async move { Ok(rx) }.boxed()
+  }
+
+  fn get_signature(&self, _config: Option<&Self::Config>) -> &OperationSignature {
+    panic!("{} operation has a dynamic signature", Self::ID);
+  }
+
+  fn input_names(&self, config: &Self::Config) -> Vec<String> {
+    vec![config.input.clone()]
+  }
+}
+
+impl RenderConfiguration for Op {
+  type Config = Config;
+  type ConfigSource = RuntimeConfig;
+
+  fn decode_config(data: Option<Self::ConfigSource>) -> Result<Self::Config, ComponentError> {
+    let config = data.ok_or_else(|| anyhow!("Map component requires configuration, please specify configuration."))?;
+
+    let concurrency = if config.has("concurrency") {
+      config.coerce_key("concurrency")?
+    } else {
+      DEFAULT_CONCURRENCY
+    };
+    if concurrency == 0 {
+      return Err(anyhow!("Map concurrency must be at least 1"));
+    }
+
+    if !config.has("outputs") {
+      return Err(anyhow!(
+        "Map component requires the outputs to pass on from its operation"
+      ));
+    }
+    let outputs: Vec<Field> = config.coerce_key("outputs")?;
+    if outputs.is_empty() {
+      return Err(anyhow!("Map component requires at least one output"));
+    }
+
+    Ok(Self::Config {
+      input: if config.has("input") {
+        config.coerce_key("input")
+      } else {
+        Ok(DEFAULT_INPUT.to_owned())
+      }?,
+      outputs,
+      map_do: config.coerce_key("do")?,
+      with: config.has("with").then(|| config.coerce_key("with")).transpose()?,
+      concurrency,
+      ordered: if config.has("ordered") {
+        config.coerce_key("ordered")
+      } else {
+        Ok(true)
+      }?,
+      collect: if config.has("collect") {
+        config.coerce_key("collect")
+      } else {
+        Ok(false)
+      }?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  use anyhow::Result;
+  use serde_json::{json, Value};
+  use tokio::sync::{watch, Barrier};
+  use wick_packet::{Entity, InherentData};
+
+  use super::*;
+
+  /// A callback that doubles its input once `concurrency` calls are running at once, tracking how many calls overlap.
+  /// With `finish_from`, calls finish in descending order of their input, starting with that value.
+  fn doubler(concurrency: usize, finish_from: Option<u64>, max: Arc<AtomicUsize>) -> Arc<RuntimeCallback> {
+    let running = Arc::new(AtomicUsize::new(0));
+    let barrier = Arc::new(Barrier::new(concurrency));
+    let turn = Arc::new(watch::channel(finish_from.unwrap_or_default()).0);
+    Arc::new(move |_compref, _op, mut stream, _inherent, _config, _span| {
+      let running = running.clone();
+      let max = max.clone();
+      let barrier = barrier.clone();
+      let turn = turn.clone();
+      let mut next = turn.subscribe();
+      async move {
+        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+        max.fetch_max(now, Ordering::SeqCst);
+        let value: u64 = stream.next().await.unwrap()?.decode()?;
+        barrier.wait().await;
+        if finish_from.is_some() {
+          next.wait_for(|turn| *turn == value).await?;
+          turn.send_replace(value - 1);
+        }
+        running.fetch_sub(1, Ordering::SeqCst);
+        let packets: PacketStream = vec![Packet::encode("output", value * 2), Packet::done("output")].into();
+        Ok(packets)
+      }
+      .boxed()
+    })
+  }
+
+  async fn run(config: Value, input: Vec<Packet>, finish_from: Option<u64>) -> Result<(Vec<Packet>, usize)> {
+    let max = Arc::new(AtomicUsize::new(0));
+    let config = Op::decode_config(Some(RuntimeConfig::from_value(config)?))?;
+    let callback = doubler(config.concurrency, finish_from, max.clone());
+    let inv = Invocation::test(file!(), Entity::test("map"), input, None)?;
+    let packets = Op::new()
+      .handle(inv, Context::new(config, &InherentData::unsafe_default(), callback))
+      .await?
+      .collect::<Vec<_>>()
+      .await
+      .into_iter()
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok((packets, max.load(Ordering::SeqCst)))
+  }
+
+  fn values(packets: &[Packet]) -> Result<Vec<u64>> {
+    Ok(
+      packets
+        .iter()
+        .filter(|p| p.has_data())
+        .map(|p| p.clone().decode())
+        .collect::<Result<_, _>>()?,
+    )
+  }
+
+  #[tokio::test]
+  async fn test_ordered() -> Result<()> {
+    let (packets, max) = run(
+      json!({"do": "test::double", "outputs": [{"name": "output", "type": "u64"}], "concurrency": 2}),
+      vec![
+        Packet::encode("input", json!([1, 2, 3])),
+        Packet::encode("input", 4),
+        Packet::done("input"),
+      ],
+      None,
+    )
+    .await?;
+
+    assert_eq!(values(&packets)?, vec![2, 4, 6, 8]);
+    assert_eq!(packets.last().unwrap(), &Packet::done("output"));
+    assert_eq!(max, 2);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_unordered() -> Result<()> {
+    let (packets, max) = run(
+      json!({"do": "test::double", "outputs": [{"name": "output", "type": "u64"}], "concurrency": 4, "ordered": false}),
+      vec![Packet::encode("input", json!([1, 2, 3, 4])), Packet::done("input")],
+      Some(4),
+    )
+    .await?;
+
+    // Later elements finish first, so they come out first.
+    assert_eq!(values(&packets)?, vec![8, 6, 4, 2]);
+    assert_eq!(max, 4);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_collect() -> Result<()> {
+    let (packets, _) = run(
+      json!({"do": "test::double", "outputs": [{"name": "output", "type": "u64"}], "concurrency": 3, "collect": true}),
+      vec![Packet::encode("input", json!([1, 2, 3])), Packet::done("input")],
+      None,
+    )
+    .await?;
+
+    assert_eq!(
+      packets,
+      vec![Packet::encode("output", vec![2, 4, 6]), Packet::done("output")]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_requires_outputs() {
+    let config = |value| Op::decode_config(Some(RuntimeConfig::from_value(value).unwrap()));
+    assert!(config(json!({"do": "test::double"})).is_err());
+    assert!(config(json!({"do": "test::double", "outputs": []})).is_err());
+    assert!(config(json!({"do": "test::double", "outputs": [{"name": "output", "type": "u64"}]})).is_ok());
+  }
+}
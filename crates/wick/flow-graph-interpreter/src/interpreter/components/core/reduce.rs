@@ -0,0 +1,422 @@
+use anyhow::anyhow;
+use flow_component::{ComponentError, Context, Operation, RenderConfiguration, RuntimeCallback};
+use futures::{FutureExt, StreamExt};
+use seeded_random::{Random, Seed};
+use serde_json::Value;
+use wasmrs_rx::Observer;
+use wick_interface_types::{OperationSignature, Type};
+use wick_packet::{
+  ComponentReference,
+  Entity,
+  InherentData,
+  Invocation,
+  InvocationData,
+  Packet,
+  PacketStream,
+  RuntimeConfig,
+};
+
+use super::expression::{Compiled, Expression};
+use super::switch::get_op_signature;
+use crate::graph::types::{Network, Schematic};
+use crate::utils::path_to_entity;
+use crate::{BoxFuture, HandlerMap};
+
+pub(crate) struct Op {}
+
+const INPUT: &str = "input";
+const OUTPUT: &str = "output";
+/// The input of the target operation, or the variable in the expression, that holds the value reduced so far.
+const ACCUMULATOR: &str = "accumulator";
+
+impl std::fmt::Debug for Op {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct(Op::ID).finish()
+  }
+}
+
+impl crate::graph::NodeDecorator for Op {
+  fn decorate(node: &mut crate::graph::types::Node) -> Result<(), String> {
+    node.add_input(INPUT);
+    node.add_output(OUTPUT);
+    Ok(())
+  }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub(crate) enum Reducer {
+  /// An operation invoked with the accumulator and each element.
+  Operation(String),
+  /// An expression evaluated with the accumulator and each element.
+  Expression(Expression),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub(crate) struct Config {
+  /// How each element is combined with the accumulator.
+  reducer: Reducer,
+  /// The starting value of the accumulator. The first element is used when there is none.
+  initial: Option<Value>,
+  /// The configuration passed to the target operation.
+  with: Option<RuntimeConfig>,
+  /// The type of the reduced value, when it isn't taken from the target operation.
+  #[serde(rename = "type")]
+  ty: Type,
+}
+
+fn gen_signature(
+  id: String,
+  parent_schematic: &Schematic,
+  graph: &Network,
+  handlers: &HandlerMap,
+  config: Config,
+) -> Result<OperationSignature, ComponentError> {
+  let ty = match &config.reducer {
+    Reducer::Operation(path) => {
+      let target = get_op_signature(path, parent_schematic, graph, handlers)
+        .ok_or_else(|| anyhow!("Invalid reduce configuration: operation '{}' not found", path))?;
+
+      for input in [ACCUMULATOR, INPUT] {
+        if !target.inputs.iter().any(|field| field.name == input) {
+          return Err(anyhow!(
+            "Invalid reduce configuration: operation '{}' has no input named '{}'",
+            path,
+            input
+          ));
+        }
+      }
+      let output = target
+        .outputs
+        .iter()
+        .find(|field| field.name == OUTPUT)
+        .ok_or_else(|| {
+          anyhow!(
+            "Invalid reduce configuration: operation '{}' has no output named '{}'",
+            path,
+            OUTPUT
+          )
+        })?;
+      output.ty.clone()
+    }
+    Reducer::Expression(_) => config.ty,
+  };
+
+  Ok(
+    OperationSignature::new_named(id)
+      .add_input(INPUT, Type::Object)
+      .add_output(OUTPUT, ty),
+  )
+}
+
+impl Op {
+  pub(crate) const fn new() -> Self {
+    Self {}
+  }
+
+  pub(crate) fn gen_signature(
+    id: String,
+    parent_schematic: &Schematic,
+    graph: &Network,
+    handlers: &HandlerMap,
+    config: Config,
+  ) -> Result<OperationSignature, ComponentError> {
+    gen_signature(id, parent_schematic, graph, handlers, config)
+  }
+}
+
+/// Invokes the target operation with the accumulator and a single element and returns the value it sent on `output`.
+async fn invoke(
+  target: &Entity,
+  invocation: &InvocationData,
+  inherent: InherentData,
+  callback: &RuntimeCallback,
+  config: &Config,
+  accumulator: Value,
+  input: Value,
+) -> Result<Value, ComponentError> {
+  let compref = ComponentReference::new(invocation.target().clone(), target.clone());
+  let stream: PacketStream = vec![
+    Packet::encode(ACCUMULATOR, accumulator),
+    Packet::done(ACCUMULATOR),
+    Packet::encode(INPUT, input),
+    Packet::done(INPUT),
+  ]
+  .into();
+  let span = info_span!(parent:&invocation.span,"reduce:element",otel.name=format!("reduce:{}",target));
+
+  let mut stream = callback(
+    compref,
+    target.operation_id().to_owned(),
+    stream,
+    inherent,
+    config.with.clone(),
+    &span,
+  )
+  .await?;
+
+  while let Some(packet) = stream.next().await {
+    let packet = packet?;
+    if packet.port() != OUTPUT {
+      continue;
+    }
+    if packet.is_error() {
+      return Err(anyhow!(packet.unwrap_err().msg().to_owned()));
+    }
+    if packet.has_data() {
+      return Ok(packet.decode_value()?);
+    }
+  }
+  Err(anyhow!("Operation '{}' did not send a value on '{}'", target, OUTPUT))
+}
+
+/// What [Reducer] turns into for the length of an invocation.
+enum Step {
+  Evaluate(Compiled),
+  Invoke(Entity),
+}
+
+impl Operation for Op {
+  const ID: &'static str = "reduce";
+  type Config = Config;
+
+  fn handle(
+    &self,
+    invocation: Invocation,
+    context: Context<Self::Config>,
+  ) -> BoxFuture<Result<PacketStream, ComponentError>> {
+    let (tx, rx) = invocation.make_response();
+    let config = context.config;
+    let callback = context.callback;
+
+    tokio::spawn(async move {
+      let (invocation, mut stream) = invocation.split();
+      let rng = Random::from_seed(Seed::unsafe_new(invocation.seed()));
+      let mut accumulator = config.initial.clone();
+      let mut failed = false;
+      let step = match &config.reducer {
+        Reducer::Expression(expression) => match expression.compile() {
+          Ok(expression) => Step::Evaluate(expression),
+          Err(e) => {
+            let _ = tx.send(Packet::err(OUTPUT, e.to_string()));
+            let _ = tx.send(Packet::done(OUTPUT));
+            return;
+          }
+        },
+        Reducer::Operation(path) => Step::Invoke(path_to_entity(path)),
+      };
+
+      while let Some(next) = stream.next().await {
+        let packet = match next {
+          Ok(packet) => packet,
+          Err(e) => {
+            let _ = tx.send_result(Err(e));
+            continue;
+          }
+        };
+        if failed || packet.port() != INPUT || packet.is_signal() {
+          continue;
+        }
+        if packet.is_error() {
+          let _ = tx.send(packet.set_port(OUTPUT));
+          continue;
+        }
+        let result = match (packet.decode_value(), accumulator.take()) {
+          (Err(e), acc) => {
+            accumulator = acc;
+            let _ = tx.send(Packet::err(OUTPUT, e.to_string()));
+            continue;
+          }
+          (Ok(input), None) => Ok(input),
+          (Ok(input), Some(acc)) => match &step {
+            Step::Evaluate(expression) => expression.evaluate(&[&acc, &input]).and_then(|values| {
+              values
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow!("Reduce expression did not produce a value"))
+            }),
+            Step::Invoke(target) => {
+              invoke(
+                target,
+                &invocation,
+                InherentData::new(rng.gen(), invocation.timestamp()),
+                callback.as_ref(),
+                &config,
+                acc,
+                input,
+              )
+              .await
+            }
+          },
+        };
+        match result {
+          Ok(value) => accumulator = Some(value),
+          Err(e) => {
+            // Later elements can't be reduced without the value this one would have produced.
+            failed = true;
+            let _ = tx.send(Packet::err(OUTPUT, e.to_string()));
+          }
+        }
+      }
+
+      if let Some(value) = accumulator {
+        let _ = tx.send(Packet::encode(OUTPUT, value));
+      }
+      let _ = tx.send(Packet::done(OUTPUT));
+    });
+
+    async move { Ok(rx) }.boxed()
+  }
+
+  fn get_signature(&self, _config: Option<&Self::Config>) -> &OperationSignature {
+    panic!("{} operation has a dynamic signature", Self::ID);
+  }
+
+  fn input_names(&self, _config: &Self::Config) -> Vec<String> {
+    vec![INPUT.to_owned()]
+  }
+}
+
+impl RenderConfiguration for Op {
+  type Config = Config;
+  type ConfigSource = RuntimeConfig;
+
+  fn decode_config(data: Option<Self::ConfigSource>) -> Result<Self::Config, ComponentError> {
+    let config =
+      data.ok_or_else(|| anyhow!("Reduce component requires configuration, please specify configuration."))?;
+
+    let reducer = match (config.has("do"), config.has("expression")) {
+      (true, false) => Reducer::Operation(config.coerce_key("do")?),
+      (false, true) => Reducer::Expression(Expression::from_config(&config, "expression", &[ACCUMULATOR, INPUT])?),
+      _ => return Err(anyhow!("Reduce component requires exactly one of 'do' or 'expression'")),
+    };
+
+    Ok(Self::Config {
+      reducer,
+      initial: config
+        .has("initial")
+        .then(|| config.coerce_key("initial"))
+        .transpose()?,
+      with: config.has("with").then(|| config.coerce_key("with")).transpose()?,
+      ty: if config.has("type") {
+        config.coerce_key("type")
+      } else {
+        Ok(Type::Object)
+      }?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::sync::Arc;
+
+  use anyhow::Result;
+  use flow_component::panic_callback;
+  use serde_json::json;
+  use wick_packet::Entity;
+
+  use super::*;
+
+  async fn run(config: Value, callback: Arc<RuntimeCallback>, input: Vec<Packet>) -> Result<Vec<Packet>> {
+    let config = Op::decode_config(Some(RuntimeConfig::from_value(config)?))?;
+    let inv = Invocation::test(file!(), Entity::test("reduce"), input, None)?;
+    let packets = Op::new()
+      .handle(inv, Context::new(config, &InherentData::unsafe_default(), callback))
+      .await?
+      .collect::<Vec<_>>()
+      .await
+      .into_iter()
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(packets)
+  }
+
+  fn numbers(values: &[u64]) -> Vec<Packet> {
+    let mut packets: Vec<_> = values.iter().map(|v| Packet::encode(INPUT, v)).collect();
+    packets.push(Packet::done(INPUT));
+    packets
+  }
+
+  /// A callback that adds its two inputs together.
+  fn adder() -> Arc<RuntimeCallback> {
+    Arc::new(|_compref, _op, stream, _inherent, _config, _span| {
+      async move {
+        let packets = stream.collect::<Vec<_>>().await;
+        let mut sum = 0;
+        for packet in packets {
+          let packet = packet?;
+          if packet.has_data() {
+            sum += packet.decode::<u64>()?;
+          }
+        }
+        let packets: PacketStream = vec![Packet::encode(OUTPUT, sum), Packet::done(OUTPUT)].into();
+        Ok(packets)
+      }
+      .boxed()
+    })
+  }
+
+  #[tokio::test]
+  async fn test_expression() -> Result<()> {
+    let packets = run(
+      json!({"expression": "accumulator | plus: input"}),
+      panic_callback(),
+      numbers(&[1, 2, 3, 4]),
+    )
+    .await?;
+    assert_eq!(packets, vec![Packet::encode(OUTPUT, 10), Packet::done(OUTPUT)]);
+
+    let packets = run(
+      json!({"expression": "accumulator | append: input", "initial": ""}),
+      panic_callback(),
+      vec![
+        Packet::encode(INPUT, "a"),
+        Packet::encode(INPUT, "b"),
+        Packet::done(INPUT),
+      ],
+    )
+    .await?;
+    assert_eq!(packets, vec![Packet::encode(OUTPUT, "ab"), Packet::done(OUTPUT)]);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_jq() -> Result<()> {
+    let packets = run(
+      json!({"expression": "$accumulator + [.]", "language": "jq", "initial": []}),
+      panic_callback(),
+      numbers(&[1, 2]),
+    )
+    .await?;
+    assert_eq!(packets, vec![Packet::encode(OUTPUT, vec![1, 2]), Packet::done(OUTPUT)]);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_operation() -> Result<()> {
+    let packets = run(json!({"do": "test::add", "initial": 100}), adder(), numbers(&[1, 2, 3])).await?;
+    assert_eq!(packets, vec![Packet::encode(OUTPUT, 106), Packet::done(OUTPUT)]);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_empty() -> Result<()> {
+    let packets = run(json!({"expression": "input"}), panic_callback(), numbers(&[])).await?;
+    assert_eq!(packets, vec![Packet::done(OUTPUT)]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_config() -> Result<()> {
+    assert!(Op::decode_config(Some(RuntimeConfig::from_value(json!({}))?)).is_err());
+    assert!(Op::decode_config(Some(RuntimeConfig::from_value(
+      json!({"do": "test::add", "expression": "input"})
+    )?))
+    .is_err());
+
+    Ok(())
+  }
+}
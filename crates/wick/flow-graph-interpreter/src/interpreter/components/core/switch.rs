@@ -81,7 +81,7 @@ pub(crate) struct SwitchCase {
 }
 
 #[allow(clippy::option_if_let_else)]
-fn get_op_signature(
+pub(super) fn get_op_signature(
   op_path: &str,
   parent_schematic: &Schematic,
   graph: &Network,
@@ -0,0 +1,239 @@
+use std::collections::VecDeque;
+
+use anyhow::anyhow;
+use flow_component::{ComponentError, Context, Operation, RenderConfiguration};
+use futures::{FutureExt, StreamExt};
+use serde_json::Value;
+use wasmrs_rx::Observer;
+use wick_interface_types::{OperationSignature, Type};
+use wick_packet::{Invocation, Packet, PacketStream, RuntimeConfig};
+
+use crate::BoxFuture;
+pub(crate) struct Op {}
+
+const INPUT: &str = "input";
+const OUTPUT: &str = "output";
+
+impl std::fmt::Debug for Op {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct(Op::ID).finish()
+  }
+}
+
+impl crate::graph::NodeDecorator for Op {
+  fn decorate(node: &mut crate::graph::types::Node) -> Result<(), String> {
+    node.add_input(INPUT);
+    node.add_output(OUTPUT);
+    Ok(())
+  }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub(crate) struct Config {
+  /// The number of elements in a window.
+  size: usize,
+  /// The number of elements a window moves forward after it's sent. Windows overlap when this is less than `size`.
+  slide: usize,
+  /// Whether elements left over at the end of the stream are sent as a final, shorter window.
+  partial: bool,
+  /// The type of the elements.
+  #[serde(rename = "type")]
+  ty: Type,
+}
+
+fn gen_signature(id: &str, config: Config) -> OperationSignature {
+  OperationSignature::new_named(id)
+    .add_input(INPUT, config.ty.clone())
+    .add_output(
+      OUTPUT,
+      Type::List {
+        ty: Box::new(config.ty),
+      },
+    )
+}
+
+impl Op {
+  pub(crate) const fn new() -> Self {
+    Self {}
+  }
+
+  pub(crate) fn gen_signature(id: &str, config: Config) -> OperationSignature {
+    gen_signature(id, config)
+  }
+}
+
+impl Operation for Op {
+  const ID: &'static str = "window";
+  type Config = Config;
+
+  fn handle(
+    &self,
+    invocation: Invocation,
+    context: Context<Self::Config>,
+  ) -> BoxFuture<Result<PacketStream, ComponentError>> {
+    let (tx, rx) = invocation.make_response();
+    let config = context.config;
+
+    tokio::spawn(async move {
+      let mut stream = invocation.into_stream();
+      let mut window: VecDeque<Value> = VecDeque::with_capacity(config.size);
+      // The number of elements in the window that haven't been sent yet.
+      let mut unsent = 0;
+
+      while let Some(next) = stream.next().await {
+        let packet = match next {
+          Ok(packet) => packet,
+          Err(e) => {
+            let _ = tx.send_result(Err(e));
+            continue;
+          }
+        };
+        if packet.port() != INPUT || packet.is_signal() {
+          continue;
+        }
+        if packet.is_error() {
+          let _ = tx.send(packet.set_port(OUTPUT));
+          continue;
+        }
+        match packet.decode_value() {
+          Ok(value) => {
+            window.push_back(value);
+            unsent += 1;
+          }
+          Err(e) => {
+            let _ = tx.send(Packet::err(OUTPUT, e.to_string()));
+            continue;
+          }
+        }
+        if window.len() == config.size {
+          let _ = tx.send(Packet::encode(OUTPUT, &window));
+          window.drain(..config.slide);
+          unsent = 0;
+        }
+      }
+
+      if config.partial && unsent > 0 {
+        let _ = tx.send(Packet::encode(OUTPUT, window));
+      }
+      let _ = tx.send(Packet::done(OUTPUT));
+    });
+
+    async move { Ok(rx) }.boxed()
+  }
+
+  fn get_signature(&self, _config: Option<&Self::Config>) -> &OperationSignature {
+    panic!("{} operation has a dynamic signature", Self::ID);
+  }
+
+  fn input_names(&self, _config: &Self::Config) -> Vec<String> {
+    vec![INPUT.to_owned()]
+  }
+}
+
+impl RenderConfiguration for Op {
+  type Config = Config;
+  type ConfigSource = RuntimeConfig;
+
+  fn decode_config(data: Option<Self::ConfigSource>) -> Result<Self::Config, ComponentError> {
+    let config =
+      data.ok_or_else(|| anyhow!("Window component requires configuration, please specify configuration."))?;
+
+    let size: usize = config.coerce_key("size")?;
+    let slide: usize = if config.has("slide") {
+      config.coerce_key("slide")?
+    } else {
+      size
+    };
+
+    if size == 0 {
+      return Err(anyhow!("Window size must be at least 1"));
+    }
+    if slide == 0 || slide > size {
+      return Err(anyhow!("Window slide must be between 1 and the window size ({})", size));
+    }
+
+    Ok(Self::Config {
+      size,
+      slide,
+      partial: if config.has("partial") {
+        config.coerce_key("partial")
+      } else {
+        Ok(false)
+      }?,
+      ty: if config.has("type") {
+        config.coerce_key("type")
+      } else {
+        Ok(Type::Object)
+      }?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use flow_component::panic_callback;
+  use serde_json::json;
+  use wick_packet::{Entity, InherentData};
+
+  use super::*;
+
+  async fn run(config: Value, input: &[u64]) -> Result<Vec<Packet>> {
+    let config = Op::decode_config(Some(RuntimeConfig::from_value(config)?))?;
+    let mut packets: Vec<_> = input.iter().map(|v| Packet::encode(INPUT, v)).collect();
+    packets.push(Packet::done(INPUT));
+    let inv = Invocation::test(file!(), Entity::test("window"), packets, None)?;
+    let packets = Op::new()
+      .handle(
+        inv,
+        Context::new(config, &InherentData::unsafe_default(), panic_callback()),
+      )
+      .await?
+      .collect::<Vec<_>>()
+      .await
+      .into_iter()
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(packets)
+  }
+
+  fn windows(packets: &[Packet]) -> Result<Vec<Vec<u64>>> {
+    Ok(
+      packets
+        .iter()
+        .filter(|p| p.has_data())
+        .map(|p| p.clone().decode())
+        .collect::<Result<_, _>>()?,
+    )
+  }
+
+  #[tokio::test]
+  async fn test_tumbling() -> Result<()> {
+    let packets = run(json!({"size": 2}), &[1, 2, 3, 4, 5]).await?;
+    assert_eq!(windows(&packets)?, vec![vec![1, 2], vec![3, 4]]);
+    assert_eq!(packets.last().unwrap(), &Packet::done(OUTPUT));
+
+    let packets = run(json!({"size": 2, "partial": true}), &[1, 2, 3, 4, 5]).await?;
+    assert_eq!(windows(&packets)?, vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_sliding() -> Result<()> {
+    let packets = run(json!({"size": 3, "slide": 1}), &[1, 2, 3, 4, 5]).await?;
+    assert_eq!(windows(&packets)?, vec![vec![1, 2, 3], vec![2, 3, 4], vec![3, 4, 5]]);
+
+    let packets = run(json!({"size": 3, "slide": 2, "partial": true}), &[1, 2, 3, 4, 5, 6]).await?;
+    assert_eq!(windows(&packets)?, vec![vec![1, 2, 3], vec![3, 4, 5], vec![5, 6]]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_config() -> Result<()> {
+    assert!(Op::decode_config(Some(RuntimeConfig::from_value(json!({}))?)).is_err());
+    assert!(Op::decode_config(Some(RuntimeConfig::from_value(json!({"size": 2, "slide": 3}))?)).is_err());
+
+    Ok(())
+  }
+}
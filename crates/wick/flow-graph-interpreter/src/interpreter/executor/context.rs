@@ -11,7 +11,7 @@ use uuid::Uuid;
 use wasmrs_rx::Observer;
 use wick_packet::{Entity, InvocationData, Packet, PacketError, PacketSender, PacketStream, RuntimeConfig};
 
-use self::operation::{FutureInvocation, InstanceHandler};
+use self::operation::{CaughtError, FutureInvocation, InstanceHandler};
 use super::error::ExecutionError;
 use crate::graph::types::*;
 use crate::interpreter::channel::InterpreterDispatchChannel;
@@ -336,17 +336,21 @@ impl ExecutionContext {
     let instance = self.instance(index);
 
     let graph = self.schematic();
+    let error_port = instance.error_port();
 
     for port in instance.outputs().refs() {
       let downport_name = graph.get_port_name(&port);
       let down_instance = self.instance(port.node_index());
-      accept_outputs(
-        self.id(),
-        port,
-        down_instance,
-        &self.channel,
-        vec![Packet::raw_err(downport_name, err.clone()), Packet::done(downport_name)],
-      );
+      // If the flow catches this instance's errors, the error only goes to the error port and every other port closes.
+      let packets = match error_port {
+        None => vec![Packet::raw_err(downport_name, err.clone()), Packet::done(downport_name)],
+        Some(error_port) if error_port == port => vec![
+          CaughtError::new(None, err.msg()).into_packet(),
+          Packet::done(downport_name),
+        ],
+        Some(_) => vec![Packet::done(downport_name)],
+      };
+      accept_outputs(self.id(), port, down_instance, &self.channel, packets);
     }
     Ok(())
   }
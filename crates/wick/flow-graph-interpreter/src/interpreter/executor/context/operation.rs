@@ -1,10 +1,11 @@
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use flow_component::{Component, ComponentError, RuntimeCallback};
-use flow_graph::{NodeIndex, PortReference};
+use flow_graph::{NodeIndex, PortReference, ERROR_PORT};
 use parking_lot::Mutex;
 use tokio::task::JoinHandle;
 use tokio_stream::StreamExt;
@@ -25,17 +26,21 @@ use wick_packet::{
 };
 
 use self::port::{InputPorts, OutputPorts, PortStatus};
+use self::retry::{Guard, InputReplay};
 use crate::graph::types::*;
-use crate::graph::Reference;
+use crate::graph::{is_error_port, Reference};
 use crate::interpreter::channel::InterpreterDispatchChannel;
+use crate::interpreter::circuit_breaker::Permit;
+use crate::interpreter::components::reconcile_op_id;
 use crate::interpreter::components::self_component::SelfComponent;
 use crate::interpreter::error::StateError;
 use crate::interpreter::executor::error::ExecutionError;
 use crate::utils::Bucket;
-use crate::{HandlerMap, InterpreterOptions};
+use crate::{HandlerMap, InterpreterOptions, SharedHandler};
 type Result<T> = std::result::Result<T, ExecutionError>;
 
 pub(crate) mod port;
+mod retry;
 
 pub(crate) struct FutureInvocation {
   origin: Entity,
@@ -85,6 +90,7 @@ pub(crate) struct InstanceHandler {
   sender: PacketSender,
   inputs: InputPorts,
   outputs: OutputPorts,
+  error_port: Option<PortReference>,
   schematic: Arc<Schematic>,
   pending: AtomicU32,
   components: Arc<HandlerMap>,
@@ -121,13 +127,26 @@ impl InstanceHandler {
     let inputs = op_node.inputs().to_vec();
     let outputs = op_node.outputs().to_vec();
     let reference: Reference = op_node.kind().cref().into();
+    let outputs = OutputPorts::new(op_node.id(), outputs);
+
+    // The error port is only reserved if the operation doesn't declare an output with the same name.
+    let error_port = outputs.find_ref(ERROR_PORT).filter(|_| {
+      let id = reconcile_op_id(reference.namespace(), reference.name(), schematic.name(), op_node.id());
+      let signature = if reference.namespace() == SelfComponent::ID {
+        self_component.signature().get_operation(&id)
+      } else {
+        components.get_op_signature(reference.namespace(), &id)
+      };
+      signature.map_or(false, |signature| is_error_port(signature, ERROR_PORT))
+    });
 
     // let span = info_span!(parent:&invocation.span,"interpreter:op:instance", entity = %invocation.target);
 
     Self {
       schematic,
       inputs: InputPorts::new(op_node.id(), inputs),
-      outputs: OutputPorts::new(op_node.id(), outputs),
+      outputs,
+      error_port,
       invocation: Bucket::new(invocation),
       reference,
       index: op_node.index(),
@@ -194,6 +213,29 @@ impl InstanceHandler {
     &self.outputs
   }
 
+  /// The reserved port this instance's errors are routed to, if the flow connects it.
+  pub(crate) const fn error_port(&self) -> Option<PortReference> {
+    self.error_port
+  }
+
+  /// Reroutes an error packet sent on one of the instance's outputs to its error port, if it has one.
+  pub(crate) fn route_output(&self, port: PortReference, packet: Packet) -> (PortReference, Packet) {
+    match (self.error_port, &packet.payload) {
+      (Some(error_port), PacketPayload::Err(e)) if error_port != port => {
+        (error_port, CaughtError::new(Some(packet.port()), e.msg()).into_packet())
+      }
+      _ => (port, packet),
+    }
+  }
+
+  /// Closes the error port when the operation completes, returning it so the close can be dispatched. Operations never
+  /// send on the error port themselves.
+  pub(crate) fn close_error_port(&self) -> Option<PortReference> {
+    let port = self.error_port?;
+    self.buffer_out(&port, Packet::done(ERROR_PORT));
+    Some(port)
+  }
+
   pub(crate) const fn inputs(&self) -> &InputPorts {
     &self.inputs
   }
@@ -252,6 +294,7 @@ impl InstanceHandler {
     Ok(())
   }
 
+  #[allow(clippy::too_many_lines)]
   pub(crate) async fn start(
     self: Arc<Self>,
     ctx_id: Uuid,
@@ -307,6 +350,49 @@ impl InstanceHandler {
     } else {
       PacketStream::new(Box::new(self.sender.take_rx().unwrap()))
     };
+
+    if let Some(guard) = Guard::new(associated_data.settings.as_ref()) {
+      let component: SharedHandler = if namespace == SelfComponent::ID {
+        Arc::new(Box::new(self.self_component.clone()))
+      } else {
+        self
+          .components
+          .get(&namespace)
+          .ok_or_else(|| ExecutionError::InvalidState(StateError::MissingComponent(namespace.clone())))?
+          .component
+          .clone()
+      };
+      let handler = retry::guarded_handler(
+        ctx_id,
+        self.clone(),
+        component,
+        guard,
+        invocation,
+        InputReplay::record(stream),
+        config,
+        callback,
+        channel,
+        timeout,
+        span.clone(),
+      );
+      self.task.start(self.clone(), handler, span);
+      return Ok(());
+    }
+
+    // Without a retry policy the output is streamed as usual and only its outcome is recorded.
+    let permit = match associated_data.settings.as_ref().and_then(|s| s.circuit_breaker) {
+      Some(breaker) => {
+        let Some(permit) = self.components.circuits().allow(&namespace, &breaker) else {
+          let msg = retry::circuit_open(&self, &namespace);
+          span.in_scope(|| warn!(%msg, "component error"));
+          channel.dispatch_op_err(ctx_id, self.index(), PacketPayload::fatal_error(msg));
+          return Ok(());
+        };
+        Some(permit)
+      }
+      None => None,
+    };
+
     let invocation = invocation.with_stream(stream);
     let cb = callback.clone();
 
@@ -358,9 +444,8 @@ impl InstanceHandler {
       }
     };
 
-    self
-      .task
-      .start(ctx_id, self.clone(), stream, channel, timeout, span.clone());
+    let handler = output_handler(ctx_id, self.clone(), stream, permit, channel, timeout, span.clone());
+    self.task.start(self.clone(), handler, span);
 
     Ok(())
   }
@@ -376,11 +461,8 @@ struct InstanceTask {
 impl InstanceTask {
   fn start(
     &self,
-    ctx_id: Uuid,
     instance: Arc<InstanceHandler>,
-    stream: PacketStream,
-    channel: InterpreterDispatchChannel,
-    timeout: Duration,
+    handler: impl Future<Output = Result<()>> + Send + 'static,
     span: Span,
   ) {
     if self.has_started() {
@@ -394,7 +476,7 @@ impl InstanceTask {
 
     span.in_scope(|| debug!(instance = instance.id(), "task:start"));
     let task = tokio::spawn(async move {
-      let result = output_handler(ctx_id, &instance, stream, channel, timeout, span.clone()).await;
+      let result = handler.await;
       if let Err(error) = &result {
         span.in_scope(|| error!(%error, "error in output handler"));
       }
@@ -431,8 +513,9 @@ impl InstanceTask {
 
 async fn output_handler(
   ctx_id: Uuid,
-  instance: &InstanceHandler,
+  instance: Arc<InstanceHandler>,
   mut stream: PacketStream,
+  permit: Option<Permit>,
   channel: InterpreterDispatchChannel,
   timeout: Duration,
   span: Span,
@@ -440,6 +523,7 @@ async fn output_handler(
   span.in_scope(|| trace!("starting output task"));
 
   let mut num_received = 0;
+  let mut errored = false;
   let reason = loop {
     let response = tokio::time::timeout(timeout, stream.next());
     let mut hanging = HashMap::new();
@@ -480,7 +564,9 @@ async fn output_handler(
         } else {
           hanging.insert(port, message.port().to_owned());
         }
+        errored |= message.is_error();
 
+        let (port, message) = instance.route_output(port, message);
         instance.buffer_out(&port, message);
         channel.dispatch_data(ctx_id, port);
       }
@@ -505,16 +591,40 @@ async fn output_handler(
           span.in_scope(|| debug!(%port,"auto-closing port"));
           instance.buffer_out(&portref, Packet::done(port));
         }
+        if let Some(port) = instance.close_error_port() {
+          channel.dispatch_data(ctx_id, port);
+        }
         span.in_scope(|| trace!("stream complete"));
         break CompletionStatus::Finished;
       }
     }
   };
+  if let Some(permit) = permit {
+    permit.record(matches!(reason, CompletionStatus::Finished) && !errored);
+  }
   instance.handle_stream_complete(reason)?;
   channel.dispatch_call_complete(ctx_id, instance.index());
   Ok(())
 }
 
+/// The data sent on an instance's error port for each error it catches.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct CaughtError<'a> {
+  /// The output the error was sent on, or `None` if the error ended the whole operation.
+  port: Option<&'a str>,
+  message: &'a str,
+}
+
+impl<'a> CaughtError<'a> {
+  pub(crate) const fn new(port: Option<&'a str>, message: &'a str) -> Self {
+    Self { port, message }
+  }
+
+  pub(crate) fn into_packet(self) -> Packet {
+    Packet::encode(ERROR_PORT, self)
+  }
+}
+
 #[derive(Clone, Copy)]
 pub(crate) enum CompletionStatus {
   Finished,
@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use flow_component::RuntimeCallback;
+use flow_graph::PortReference;
+use parking_lot::Mutex;
+use tokio_stream::StreamExt;
+use tracing::Span;
+use tracing_futures::Instrument;
+use uuid::Uuid;
+use wasmrs_rx::{FluxChannel, Observer};
+use wick_config::config::{CircuitBreakerConfig, ExecutionSettings, OperationRetry};
+use wick_packet::{InherentData, InvocationData, Packet, PacketPayload, PacketSender, PacketStream, RuntimeConfig};
+
+use super::{CompletionStatus, InstanceHandler, Result};
+use crate::interpreter::channel::InterpreterDispatchChannel;
+use crate::SharedHandler;
+
+/// The retry policy of an operation instance, and its circuit breaker if it has one.
+#[derive(Debug, Clone)]
+pub(super) struct Guard {
+  retry: OperationRetry,
+  circuit_breaker: Option<CircuitBreakerConfig>,
+}
+
+impl Guard {
+  /// The guard for an instance, if its settings configure a retry policy. Instances with only a circuit breaker
+  /// stream their output as usual.
+  pub(super) fn new(settings: Option<&ExecutionSettings>) -> Option<Self> {
+    let settings = settings?;
+    Some(Self {
+      retry: settings.retry.clone()?,
+      circuit_breaker: settings.circuit_breaker,
+    })
+  }
+
+  /// Whether the passed failure should be retried after `attempt` attempts.
+  fn should_retry(&self, attempt: u16, outcome: &Outcome) -> bool {
+    if attempt > self.retry.retries() {
+      return false;
+    }
+    match outcome {
+      Outcome::Success | Outcome::CircuitOpen(_) => false,
+      Outcome::Timeout(_) => self.retry.timeouts(),
+      Outcome::Fatal(msg) | Outcome::Errors(msg) => self.retry.retries_error(msg),
+    }
+  }
+
+  fn delay(&self, attempt: u16) -> Duration {
+    self.retry.backoff().delay_for(attempt)
+  }
+}
+
+/// Records the packets sent to an instance so they can be sent again to each new attempt.
+#[derive(Clone, Default)]
+pub(super) struct InputReplay {
+  state: Arc<Mutex<ReplayState>>,
+}
+
+#[derive(Default)]
+struct ReplayState {
+  packets: Vec<std::result::Result<Packet, wick_packet::Error>>,
+  listeners: Vec<PacketSender>,
+  complete: bool,
+}
+
+impl InputReplay {
+  /// Start recording the passed stream.
+  pub(super) fn record(mut stream: PacketStream) -> Self {
+    let replay = Self::default();
+    let state = replay.state.clone();
+    tokio::spawn(async move {
+      while let Some(packet) = stream.next().await {
+        let mut state = state.lock();
+        state.listeners.retain(|tx| match &packet {
+          Ok(p) => tx.send(p.clone()).is_ok(),
+          Err(e) => tx.error(e.clone()).is_ok(),
+        });
+        state.packets.push(packet);
+      }
+      let mut state = state.lock();
+      state.complete = true;
+      for tx in state.listeners.drain(..) {
+        tx.complete();
+      }
+    });
+    replay
+  }
+
+  /// A stream of every packet recorded so far followed by the packets still to come.
+  fn stream(&self) -> PacketStream {
+    let tx = FluxChannel::new();
+    let mut state = self.state.lock();
+    for packet in &state.packets {
+      let _ = match packet {
+        Ok(p) => tx.send(p.clone()),
+        Err(e) => tx.error(e.clone()),
+      };
+    }
+    if state.complete {
+      tx.complete();
+    } else {
+      state.listeners.push(tx.clone());
+    }
+    PacketStream::new(Box::new(tx.take_rx().unwrap()))
+  }
+}
+
+/// How an attempt ended.
+enum Outcome {
+  Success,
+  /// The operation could not be invoked or its stream ended in a component-wide error.
+  Fatal(String),
+  /// The operation sent error packets on its output ports.
+  Errors(String),
+  /// The operation didn't produce output in time.
+  Timeout(String),
+  /// The component's circuit is open, so the operation wasn't invoked.
+  CircuitOpen(String),
+}
+
+struct Attempt {
+  outcome: Outcome,
+  packets: Vec<(PortReference, Packet)>,
+}
+
+impl Attempt {
+  const fn failed(outcome: Outcome) -> Self {
+    Self {
+      outcome,
+      packets: Vec::new(),
+    }
+  }
+}
+
+/// Invokes a guarded instance, retrying failed attempts and tracking the component's circuit.
+///
+/// The output of each attempt is buffered until it completes, so only the output of the successful (or last) attempt
+/// reaches downstream operations.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn guarded_handler(
+  ctx_id: Uuid,
+  instance: Arc<InstanceHandler>,
+  component: SharedHandler,
+  guard: Guard,
+  invocation: InvocationData,
+  inputs: InputReplay,
+  config: Option<RuntimeConfig>,
+  callback: Arc<RuntimeCallback>,
+  channel: InterpreterDispatchChannel,
+  timeout: Duration,
+  span: Span,
+) -> Result<()> {
+  let namespace = instance.namespace().to_owned();
+  let circuits = instance.components.circuits().clone();
+  let mut invocation = invocation;
+  let mut attempt_num = 0_u16;
+
+  let attempt = loop {
+    attempt_num += 1;
+    let next_invocation = retry_invocation(&invocation);
+
+    let permit = guard
+      .circuit_breaker
+      .map(|breaker| circuits.allow(&namespace, &breaker));
+    let attempt = if matches!(permit, Some(None)) {
+      Attempt::failed(Outcome::CircuitOpen(circuit_open(&instance, &namespace)))
+    } else {
+      let invocation = invocation.with_stream(inputs.stream());
+      let attempt = run_attempt(
+        &instance,
+        component.clone(),
+        invocation,
+        config.clone(),
+        callback.clone(),
+        timeout,
+        &span,
+      )
+      .await?;
+      if let Some(Some(permit)) = permit {
+        permit.record(matches!(attempt.outcome, Outcome::Success));
+      }
+      attempt
+    };
+
+    if !guard.should_retry(attempt_num, &attempt.outcome) {
+      break attempt;
+    }
+    let delay = guard.delay(attempt_num);
+    span.in_scope(|| {
+      debug!(
+        instance = instance.id(),
+        attempt = attempt_num,
+        delay_ms = delay.as_millis(),
+        "operation failed, retrying"
+      );
+    });
+    tokio::time::sleep(delay).await;
+    invocation = next_invocation;
+  };
+
+  for (port, packet) in attempt.packets {
+    let (port, packet) = instance.route_output(port, packet);
+    instance.buffer_out(&port, packet);
+    channel.dispatch_data(ctx_id, port);
+  }
+  let status = match attempt.outcome {
+    Outcome::Success | Outcome::Errors(_) => {
+      if let Some(port) = instance.close_error_port() {
+        channel.dispatch_data(ctx_id, port);
+      }
+      CompletionStatus::Finished
+    }
+    Outcome::Fatal(msg) | Outcome::CircuitOpen(msg) => {
+      span.in_scope(|| warn!(error = %msg, "component-wide error"));
+      channel.dispatch_op_err(ctx_id, instance.index(), PacketPayload::fatal_error(msg));
+      CompletionStatus::Error
+    }
+    Outcome::Timeout(msg) => {
+      span.in_scope(|| warn!(error = %msg, "timeout"));
+      channel.dispatch_op_err(ctx_id, instance.index(), PacketPayload::fatal_error(msg));
+      CompletionStatus::Timeout
+    }
+  };
+  instance.handle_stream_complete(status)?;
+  channel.dispatch_call_complete(ctx_id, instance.index());
+  Ok(())
+}
+
+/// The error for an invocation that wasn't made because the component's circuit is open.
+pub(super) fn circuit_open(instance: &InstanceHandler, namespace: &str) -> String {
+  format!(
+    "Operation {} not invoked, the circuit for component '{}' is open",
+    instance.entity(),
+    namespace
+  )
+}
+
+/// A new invocation for the next attempt, in the same transaction as the passed one.
+fn retry_invocation(invocation: &InvocationData) -> InvocationData {
+  InvocationData::new_with_id(
+    invocation.tx_id,
+    invocation.origin.clone(),
+    invocation.target.clone(),
+    InherentData::new(invocation.inherent.seed, invocation.inherent.timestamp),
+    &invocation.span,
+  )
+}
+
+async fn run_attempt(
+  instance: &InstanceHandler,
+  component: SharedHandler,
+  invocation: wick_packet::Invocation,
+  config: Option<RuntimeConfig>,
+  callback: Arc<RuntimeCallback>,
+  timeout: Duration,
+  span: &Span,
+) -> Result<Attempt> {
+  let entity = instance.entity();
+  let result = tokio::spawn(async move { component.handle(invocation, config, callback).await })
+    .instrument(span.clone())
+    .await;
+
+  let stream = match result {
+    Ok(Ok(stream)) => stream,
+    Ok(Err(error)) => {
+      return Ok(Attempt::failed(Outcome::Fatal(format!(
+        "Operation {} failed: {}",
+        entity, error
+      ))))
+    }
+    Err(e) if e.is_panic() => {
+      return Ok(Attempt::failed(Outcome::Fatal(format!(
+        "Operation {} panicked",
+        entity
+      ))))
+    }
+    Err(_) => {
+      return Ok(Attempt::failed(Outcome::Fatal(format!(
+        "Operation {} cancelled",
+        entity
+      ))))
+    }
+  };
+
+  collect_output(instance, stream, timeout, span).await
+}
+
+/// Buffers an attempt's output until its stream completes.
+async fn collect_output(
+  instance: &InstanceHandler,
+  mut stream: PacketStream,
+  timeout: Duration,
+  span: &Span,
+) -> Result<Attempt> {
+  let mut packets = Vec::new();
+  let mut hanging = HashMap::new();
+  let mut error = None;
+  let mut num_received = 0;
+
+  let outcome = loop {
+    match tokio::time::timeout(timeout, stream.next()).await {
+      Ok(Some(Err(e))) => break Outcome::Fatal(e.to_string()),
+      Ok(Some(Ok(message))) => {
+        num_received += 1;
+        if message.is_fatal_error() {
+          break Outcome::Fatal(message.unwrap_err().msg().to_owned());
+        }
+        if message.is_noop() {
+          continue;
+        }
+        let port = match instance.find_output(message.port()) {
+          Ok(port) => port,
+          Err(e) => {
+            span.in_scope(|| warn!(error=?e,port=message.port(),data=?message.payload(),"invalid port name, this is likely due to a misconfigured or broken component"));
+            return Err(e);
+          }
+        };
+        if message.is_done() {
+          hanging.remove(&port);
+        } else {
+          hanging.insert(port, message.port().to_owned());
+        }
+        if let PacketPayload::Err(e) = message.payload() {
+          error.get_or_insert_with(|| e.msg().to_owned());
+        }
+        packets.push((port, message));
+      }
+      Ok(None) => {
+        if num_received == 0 && instance.outputs().len() > 0 {
+          break Outcome::Fatal("operation produced no output, likely due to a panic or misconfiguration".to_owned());
+        }
+        for (portref, port) in hanging.drain() {
+          packets.push((portref, Packet::done(port)));
+        }
+        break error.take().map_or(Outcome::Success, Outcome::Errors);
+      }
+      Err(_) => {
+        break Outcome::Timeout(format!(
+          "Execution timed out waiting for output from operation {} ({})",
+          instance.id(),
+          instance.entity()
+        ))
+      }
+    }
+  };
+
+  Ok(Attempt { outcome, packets })
+}
@@ -6,6 +6,7 @@ use wick_interface_types::{ComponentSignature, Field, OperationSignature, Type};
 use wick_packet::Entity;
 
 use crate::error::ValidationError;
+use crate::graph::is_error_port;
 use crate::graph::types::*;
 use crate::interpreter::components::self_component::SelfComponent;
 
@@ -202,6 +203,11 @@ fn get_signature(
             name: id.clone(),
           })?;
 
+      // Errors routed to the reserved error port are sent as objects describing each error.
+      if direction == PortDirection::Out && is_error_port(operation, name) {
+        return Ok(Some(Type::Object));
+      }
+
       let sig = match direction {
         PortDirection::In => operation
           .inputs
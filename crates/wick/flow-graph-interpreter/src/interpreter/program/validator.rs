@@ -1,8 +1,13 @@
+use std::collections::{HashMap, HashSet};
+
 use flow_graph::iterators::{SchematicWalker, WalkDirection};
-use flow_graph::NodeKind;
+use flow_graph::{NodeKind, PortReference};
 
 use self::error::{OperationInvalid, ValidationError};
 use super::Program;
+use crate::graph::is_error_port;
+use crate::graph::types::Schematic;
+use crate::interpreter::components::null::NullComponent;
 use crate::interpreter::components::reconcile_op_id;
 
 pub(crate) mod error;
@@ -18,6 +23,7 @@ impl Validator {
     let state = &program.state();
 
     let components = &state.components;
+    let mut circuit_breakers = HashMap::new();
     for schematic in state.network.schematics() {
       let schematic_name = schematic.name();
       for node in schematic.nodes() {
@@ -33,6 +39,16 @@ impl Validator {
           }
           let component = component.unwrap();
 
+          // A component has a single circuit, so every instance using it must agree on how it behaves.
+          if let Some(breaker) = node.data().settings.as_ref().and_then(|s| s.circuit_breaker) {
+            let first = circuit_breakers.entry(reference.component_id()).or_insert(breaker);
+            if *first != breaker {
+              validation_errors.push(ValidationError::ConflictingCircuitBreakers(
+                reference.component_id().to_owned(),
+              ));
+            }
+          }
+
           let id = reconcile_op_id(reference.component_id(), reference.name(), schematic_name, node.id());
 
           let operation_sig = component.get_operation(&id);
@@ -60,6 +76,16 @@ impl Validator {
           }
 
           for port in node.outputs() {
+            if is_error_port(operation_sig, port.name()) {
+              if !caught_errors_are_handled(schematic, port.detached()) {
+                validation_errors.push(ValidationError::UnhandledErrorPath {
+                  id: node.name.clone(),
+                  operation: reference.name().to_owned(),
+                  component: reference.component_id().to_owned(),
+                });
+              }
+              continue;
+            }
             let port_def = operation_sig.outputs.iter().find(|p| p.name == port.name());
             if port_def.is_none() {
               validation_errors.push(ValidationError::InvalidPort {
@@ -142,6 +168,49 @@ impl Validator {
   }
 }
 
+/// Whether every path leaving a connected error port ends in the flow's output, an explicit drop, or an operation that
+/// doesn't produce output. Errors that reach an operation whose outputs lead nowhere would otherwise disappear silently.
+///
+/// Only connected error ports are checked. An instance whose error port isn't connected doesn't catch its errors, so
+/// they travel downstream on the ports they were sent on like any other packet.
+fn caught_errors_are_handled(schematic: &Schematic, port: PortReference) -> bool {
+  let downstream = |port: &PortReference| {
+    schematic
+      .get_port(port)
+      .connections()
+      .iter()
+      .map(|index| schematic.connections()[*index].to().node_index())
+      .collect::<Vec<_>>()
+  };
+  let mut visited = HashSet::new();
+  let mut pending = downstream(&port);
+
+  while let Some(index) = pending.pop() {
+    if !visited.insert(index) {
+      continue;
+    }
+    let node = &schematic.nodes()[index];
+    match node.kind() {
+      NodeKind::Output(_) => continue,
+      NodeKind::External(reference) if reference.component_id() == NullComponent::ID => continue,
+      _ => {}
+    }
+    if node.outputs().is_empty() {
+      continue;
+    }
+    let next = node
+      .outputs()
+      .iter()
+      .flat_map(|port| downstream(&port.detached()))
+      .collect::<Vec<_>>();
+    if next.is_empty() {
+      return false;
+    }
+    pending.extend(next);
+  }
+  true
+}
+
 pub(crate) fn validate(program: &Program) -> Result {
   let validator = Validator {};
   validator.validate_external_components(program)?;
@@ -62,6 +62,18 @@ pub enum ValidationError {
     operation: String,
   },
 
+  #[error(
+    "Errors caught from operation '{id}' ('{component}::{operation}') are never handled, connect them to the flow's output, an operation, or drop"
+  )]
+  UnhandledErrorPath {
+    id: String,
+    component: String,
+    operation: String,
+  },
+
+  #[error("Operations using component '{0}' configure different circuit breakers")]
+  ConflictingCircuitBreakers(String),
+
   #[error("Unused output port '{port}' on operation '{id}' ('{component}::{operation}')")]
   UnusedOutput {
     port: String,
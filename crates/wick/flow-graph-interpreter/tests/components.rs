@@ -1,7 +1,7 @@
 mod test;
 
 use std::collections::HashMap;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use anyhow::Result;
 use flow_component::Component;
@@ -105,6 +105,217 @@ async fn test_timeout_fail() -> Result<()> {
   Ok(())
 }
 
+#[test_logger::test(tokio::test)]
+async fn test_retry_ok() -> Result<()> {
+  let (interpreter, mut outputs) = test::base_setup(
+    "./tests/manifests/v1/component-retry.yaml",
+    Entity::local("test"),
+    packets!(("input", "hello"), ("failures", 2)),
+    None,
+    None,
+  )
+  .await?;
+
+  assert_eq!(outputs.len(), 2);
+
+  let _wrapper = outputs.pop().unwrap(); //done signal
+  let wrapper = outputs.pop().unwrap();
+  assert_eq!(wrapper?, Packet::encode("output", "hello"));
+
+  interpreter.shutdown().await?;
+
+  Ok(())
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_retry_exhausted() -> Result<()> {
+  let (interpreter, mut outputs) = test::base_setup(
+    "./tests/manifests/v1/component-retry.yaml",
+    Entity::local("test"),
+    packets!(("input", "hello"), ("failures", 5)),
+    None,
+    None,
+  )
+  .await?;
+
+  assert_eq!(outputs.len(), 2);
+
+  let _wrapper = outputs.pop().unwrap(); //done signal
+  let packet = outputs.pop().unwrap()?;
+  assert_eq!(packet.unwrap_err().msg(), "flaky failure 3");
+
+  interpreter.shutdown().await?;
+
+  Ok(())
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_retry_unmatched_error() -> Result<()> {
+  let (interpreter, mut outputs) = test::base_setup(
+    "./tests/manifests/v1/component-retry.yaml",
+    Entity::local("filtered"),
+    packets!(("input", "hello"), ("failures", 1)),
+    None,
+    None,
+  )
+  .await?;
+
+  assert_eq!(outputs.len(), 2);
+
+  let _wrapper = outputs.pop().unwrap(); //done signal
+  let packet = outputs.pop().unwrap()?;
+  assert_eq!(packet.unwrap_err().msg(), "flaky failure 1");
+
+  interpreter.shutdown().await?;
+
+  Ok(())
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_circuit_breaker() -> Result<()> {
+  let entity = Entity::local("breaker");
+  let packets = || packets!(("input", "hello"), ("failures", 10));
+  let (interpreter, mut outputs) = test::base_setup(
+    "./tests/manifests/v1/component-retry.yaml",
+    entity.clone(),
+    packets(),
+    None,
+    None,
+  )
+  .await?;
+
+  let _wrapper = outputs.pop().unwrap(); //done signal
+  let packet = outputs.pop().unwrap()?;
+  assert_eq!(packet.unwrap_err().msg(), "flaky failure 1");
+
+  let mut outputs = test::invoke(&interpreter, entity.clone(), packets(), None).await?;
+  let _wrapper = outputs.pop().unwrap(); //done signal
+  let packet = outputs.pop().unwrap()?;
+  assert_eq!(packet.unwrap_err().msg(), "flaky failure 2");
+
+  // The second failure opened the circuit, so the operation isn't invoked again.
+  let mut outputs = test::invoke(&interpreter, entity, packets(), None).await?;
+  let _wrapper = outputs.pop().unwrap(); //done signal
+  let packet = outputs.pop().unwrap()?;
+  assert!(packet
+    .unwrap_err()
+    .msg()
+    .contains("circuit for component 'test' is open"));
+
+  interpreter.shutdown().await?;
+
+  Ok(())
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_circuit_breaker_trial_error() -> Result<()> {
+  let entity = Entity::local("breaker-error");
+  let packets = || packets!(("input", "hello"));
+  let error = |mut outputs: Vec<Result<Packet, wick_packet::Error>>| -> Result<String> {
+    let _wrapper = outputs.pop().unwrap(); //done signal
+    Ok(outputs.pop().unwrap()?.unwrap_err().msg().to_owned())
+  };
+  let (interpreter, outputs) = test::base_setup(
+    "./tests/manifests/v1/component-circuit-breaker.yaml",
+    entity.clone(),
+    packets(),
+    None,
+    None,
+  )
+  .await?;
+  assert!(error(outputs)?.contains("This operation always errors"));
+
+  let outputs = test::invoke(&interpreter, entity.clone(), packets(), None).await?;
+  assert!(error(outputs)?.contains("circuit for component 'test' is open"));
+
+  // The trial call after the reset fails, which opens the circuit again...
+  tokio::time::sleep(Duration::from_millis(150)).await;
+  let outputs = test::invoke(&interpreter, entity.clone(), packets(), None).await?;
+  assert!(error(outputs)?.contains("This operation always errors"));
+  let outputs = test::invoke(&interpreter, entity.clone(), packets(), None).await?;
+  assert!(error(outputs)?.contains("circuit for component 'test' is open"));
+
+  // ...rather than leaving it half-open, so another trial is let through after the next reset.
+  tokio::time::sleep(Duration::from_millis(150)).await;
+  let outputs = test::invoke(&interpreter, entity, packets(), None).await?;
+  assert!(error(outputs)?.contains("This operation always errors"));
+
+  interpreter.shutdown().await?;
+
+  Ok(())
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_error_port() -> Result<()> {
+  let (interpreter, outputs) = test::base_setup(
+    "./tests/manifests/v1/component-error-port.yaml",
+    Entity::local("test"),
+    packets!(("input", "hello"), ("failures", 1)),
+    None,
+    None,
+  )
+  .await?;
+
+  let outputs: Vec<_> = outputs.into_iter().collect::<Result<_, _>>()?;
+  let output: Vec<_> = outputs.iter().filter(|p| p.port() == "output").collect();
+  let caught: Vec<_> = outputs.iter().filter(|p| p.port() == "caught").collect();
+
+  // The error is caught by the flow, so the output port only closes...
+  assert_eq!(output, vec![&Packet::done("output")]);
+  // ...and the error is sent as data on the error port.
+  assert_eq!(
+    caught,
+    vec![&Packet::encode("caught", "flaky failure 1"), &Packet::done("caught")]
+  );
+
+  let outputs = test::invoke(
+    &interpreter,
+    Entity::local("test"),
+    packets!(("input", "hello"), ("failures", 0)),
+    None,
+  )
+  .await?;
+  let outputs: Vec<_> = outputs.into_iter().collect::<Result<_, _>>()?;
+  let output: Vec<_> = outputs.iter().filter(|p| p.port() == "output").collect();
+  let caught: Vec<_> = outputs.iter().filter(|p| p.port() == "caught").collect();
+
+  assert_eq!(
+    output,
+    vec![&Packet::encode("output", "hello"), &Packet::done("output")]
+  );
+  assert_eq!(caught, vec![&Packet::done("caught")]);
+
+  interpreter.shutdown().await?;
+
+  Ok(())
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_error_port_fatal() -> Result<()> {
+  let (interpreter, outputs) = test::base_setup(
+    "./tests/manifests/v1/component-error-port.yaml",
+    Entity::local("fatal"),
+    packets!(("input", "hello")),
+    None,
+    None,
+  )
+  .await?;
+
+  let outputs: Vec<_> = outputs.into_iter().collect::<Result<_, _>>()?;
+  let output: Vec<_> = outputs.iter().filter(|p| p.port() == "output").collect();
+  let caught: Vec<_> = outputs.iter().filter(|p| p.port() == "caught").collect();
+
+  assert_eq!(output, vec![&Packet::done("output")]);
+  assert_eq!(caught.len(), 2);
+  let error: serde_json::Value = caught[0].clone().decode()?;
+  assert_eq!(error, json!({"port": null, "message": "Oh no"}));
+  assert_eq!(caught[1], &Packet::done("caught"));
+
+  interpreter.shutdown().await?;
+
+  Ok(())
+}
+
 #[test_logger::test(tokio::test)]
 async fn test_context_passing() -> Result<()> {
   let (interpreter, mut outputs) = test::base_setup(
@@ -615,3 +615,131 @@ async fn test_switch_case_multi_input_streams() -> Result<()> {
   )
   .await
 }
+
+#[test_logger::test(tokio::test)]
+async fn test_map() -> Result<()> {
+  test_config(
+    "./tests/manifests/v1/core-map.yaml",
+    None,
+    None,
+    vec![
+      Packet::encode("input", json!(["hello", "world"])),
+      Packet::encode("input", "again"),
+      Packet::done("input"),
+    ],
+    vec![
+      Packet::encode("output", "HELLO"),
+      Packet::encode("output", "WORLD"),
+      Packet::encode("output", "AGAIN"),
+      Packet::done("output"),
+    ],
+  )
+  .await
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_map_collect() -> Result<()> {
+  let (interpreter, mut outputs) = test::common_setup(
+    "./tests/manifests/v1/core-map.yaml",
+    "collected",
+    vec![Packet::encode("input", json!(["a", "b", "c"])), Packet::done("input")],
+  )
+  .await?;
+
+  assert_eq!(outputs.len(), 2);
+
+  let _ = outputs.pop();
+  let actual = outputs.pop().unwrap().unwrap().decode_value()?;
+  assert_eq!(actual, json!(["A", "B", "C"]));
+  interpreter.shutdown().await?;
+
+  Ok(())
+}
+
+fn stream_of(values: &[serde_json::Value]) -> Vec<Packet> {
+  let mut packets: Vec<_> = values.iter().map(|v| Packet::encode("input", v)).collect();
+  packets.push(Packet::done("input"));
+  packets
+}
+
+async fn stream_op_test(op: &str, input: Vec<Packet>, expected: Vec<serde_json::Value>) -> Result<()> {
+  let (interpreter, outputs) = test::common_setup("./tests/manifests/v1/core-stream-ops.yaml", op, input).await?;
+
+  let actual = outputs
+    .into_iter()
+    .map(|p| p.unwrap())
+    .filter(|p| p.has_data())
+    .map(|p| p.decode_value())
+    .collect::<Result<Vec<_>, _>>()?;
+  assert_eq!(actual, expected);
+  interpreter.shutdown().await?;
+
+  Ok(())
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_batch() -> Result<()> {
+  stream_op_test(
+    "batch",
+    stream_of(&[json!(1), json!(2), json!(3)]),
+    vec![json!([1, 2]), json!([3])],
+  )
+  .await
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_window() -> Result<()> {
+  stream_op_test(
+    "window",
+    stream_of(&[json!(1), json!(2), json!(3)]),
+    vec![json!([1, 2]), json!([2, 3])],
+  )
+  .await
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_reduce_expression() -> Result<()> {
+  stream_op_test("sum", stream_of(&[json!(1), json!(2), json!(3)]), vec![json!(6)]).await
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_reduce_operation() -> Result<()> {
+  stream_op_test(
+    "concat",
+    stream_of(&[json!("a"), json!("b"), json!("c")]),
+    vec![json!("abc")],
+  )
+  .await
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_filter() -> Result<()> {
+  test_config(
+    "./tests/manifests/v1/core-expressions.yaml",
+    None,
+    None,
+    vec![
+      Packet::encode("input", json!({"name": "Ada", "age": 36})),
+      Packet::encode("input", json!({"name": "Tim", "age": 12})),
+      Packet::encode("input", json!({"name": "Bea", "age": 18})),
+      Packet::done("input"),
+    ],
+    vec![
+      Packet::encode("output", "Ada"),
+      Packet::encode("output", "Bea"),
+      Packet::done("output"),
+    ],
+  )
+  .await
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_expression_liquid() -> Result<()> {
+  first_packet_test_op(
+    "greet",
+    "./tests/manifests/v1/core-expressions.yaml",
+    packets!(("input", json!({"name": "world"}))),
+    "Hello, world",
+  )
+  .await
+}
@@ -37,8 +37,6 @@ pub async fn base_setup(
   config: Option<RuntimeConfig>,
 ) -> anyhow::Result<(Interpreter, Vec<Result<Packet, wick_packet::Error>>)> {
   use flow_graph_interpreter::{HandlerMap, InterpreterOptions, NamespaceHandler};
-  use tokio_stream::StreamExt;
-  use wick_packet::Invocation;
   let options = Some(InterpreterOptions::default());
   let mut def = wick_config::WickConfiguration::fetch(manifest, Default::default()).await?;
   def.set_root_config(component_config);
@@ -61,12 +59,24 @@ pub async fn base_setup(
   )?;
 
   interpreter.start(options, None).await;
+  let outputs = invoke(&interpreter, entity, packets, config).await?;
+  Ok((interpreter, outputs))
+}
+
+pub async fn invoke(
+  interpreter: &Interpreter,
+  entity: Entity,
+  packets: Vec<Packet>,
+  config: Option<RuntimeConfig>,
+) -> anyhow::Result<Vec<Result<Packet, wick_packet::Error>>> {
+  use tokio_stream::StreamExt;
+  use wick_packet::Invocation;
   let stream = wick_packet::PacketStream::new(Box::new(futures::stream::iter(packets.into_iter().map(Ok))));
   let invocation = Invocation::test("test", entity, stream, None)?;
   let stream = interpreter.invoke(invocation, config).await?;
   let outputs: Vec<_> = stream.collect().await;
   println!("{:#?}", outputs);
-  Ok((interpreter, outputs))
+  Ok(outputs)
 }
 
 pub fn from_packet_file(file: &str) -> Result<Vec<Packet>> {
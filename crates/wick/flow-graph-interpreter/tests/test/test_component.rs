@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
@@ -22,7 +23,7 @@ use wick_packet::{
   RuntimeConfig,
 };
 
-pub struct TestComponent(ComponentSignature);
+pub struct TestComponent(ComponentSignature, Arc<AtomicU64>);
 impl TestComponent {
   #[allow(dead_code)]
   pub fn new() -> Self {
@@ -137,13 +138,19 @@ impl TestComponent {
           .add_output("output", Type::String),
       )
       .add_operation(OperationSignature::new_named("no-inputs").add_output("output", Type::String))
+      .add_operation(
+        OperationSignature::new_named("flaky")
+          .add_input("input", Type::String)
+          .add_input("failures", Type::U64)
+          .add_output("output", Type::String),
+      )
       .add_operation(
         OperationSignature::new_named("render")
           .add_input("input", Type::String)
           .add_output("output", Type::String),
       );
 
-    Self(signature)
+    Self(signature, Default::default())
   }
 }
 
@@ -190,7 +197,13 @@ impl Component for TestComponent {
   ) -> BoxFuture<Result<PacketStream, ComponentError>> {
     let operation = invocation.target().operation_id();
     println!("got op {} in test collection", operation);
-    Box::pin(async move { handler(invocation, callback) })
+    // Counts calls to `flaky` so it can fail a set number of times before succeeding.
+    let call = if operation == "flaky" {
+      self.1.fetch_add(1, Ordering::SeqCst)
+    } else {
+      0
+    };
+    Box::pin(async move { handler(invocation, callback, call) })
   }
 
   fn signature(&self) -> &ComponentSignature {
@@ -198,7 +211,7 @@ impl Component for TestComponent {
   }
 }
 
-fn handler(invocation: Invocation, callback: Arc<RuntimeCallback>) -> anyhow::Result<PacketStream> {
+fn handler(invocation: Invocation, callback: Arc<RuntimeCallback>, call: u64) -> anyhow::Result<PacketStream> {
   let (invocation, mut payload_stream) = invocation.split();
   let operation = invocation.target.operation_id().to_owned();
   println!("handling {}", operation);
@@ -438,6 +451,24 @@ fn handler(invocation: Invocation, callback: Arc<RuntimeCallback>) -> anyhow::Re
       panic!();
     }
     "error" => Err(anyhow!("This operation always errors")),
+    "flaky" => {
+      let (mut input, mut failures) = fan_out!(payload_stream, "input", "failures");
+
+      let task = async move {
+        if let (Some(Ok(input)), Some(Ok(failures))) = (input.next().await, failures.next().await) {
+          let failures: u64 = failures.decode()?;
+          let output = if call < failures {
+            Packet::err("output", format!("flaky failure {}", call + 1))
+          } else {
+            input.set_port("output")
+          };
+          defer(vec![send(output), send(Packet::done("output"))]);
+        }
+        Ok::<_, wick_packet::Error>(())
+      };
+      defer(vec![task]);
+      Ok(stream)
+    }
     "noimpl" => Ok(stream),
     "component_error" => {
       send(Packet::component_error("Oh no"));
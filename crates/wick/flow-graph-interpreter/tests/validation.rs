@@ -137,3 +137,55 @@ async fn test_missing_port() -> Result<()> {
 
   Ok(())
 }
+
+#[test_logger::test(tokio::test)]
+async fn test_conflicting_circuit_breakers() -> Result<()> {
+  let signature = ComponentSignature::new_named("test")
+    .set_version("0.0.0")
+    .metadata(ComponentMetadata::default())
+    .add_operation(
+      OperationSignature::new_named("echo")
+        .add_input("input", Type::String)
+        .add_output("output", Type::String),
+    );
+
+  let result = interp("./tests/manifests/v1/component-circuit-conflict.yaml", signature).await;
+
+  let errors = vec![ValidationError::ConflictingCircuitBreakers("test".to_owned())];
+
+  if let Err(InterpreterError::ValidationError(e)) = result {
+    assert_eq!(e, vec![OperationInvalid::new("test".to_owned(), errors)]);
+  } else {
+    panic!("{:?}", result);
+  }
+
+  Ok(())
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_unhandled_error_path() -> Result<()> {
+  let signature = ComponentSignature::new_named("test")
+    .set_version("0.0.0")
+    .metadata(ComponentMetadata::default())
+    .add_operation(
+      OperationSignature::new_named("echo")
+        .add_input("input", Type::String)
+        .add_output("output", Type::String),
+    );
+
+  let result = interp("./tests/manifests/v1/component-error-unhandled.yaml", signature).await;
+
+  let errors = vec![ValidationError::UnhandledErrorPath {
+    id: "first".to_owned(),
+    component: "test".to_owned(),
+    operation: "echo".to_owned(),
+  }];
+
+  if let Err(InterpreterError::ValidationError(e)) = result {
+    assert_eq!(e, vec![OperationInvalid::new("test".to_owned(), errors)]);
+  } else {
+    panic!("{:?}", result);
+  }
+
+  Ok(())
+}
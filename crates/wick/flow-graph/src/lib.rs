@@ -121,6 +121,7 @@ mod port;
 mod schematic;
 
 pub use connection::Connection;
+pub use flow_expression_parser::parse::ERROR_PORT;
 pub use network::Network;
 pub use node::{Node, NodeKind, NodePort, NodeReference};
 pub use port::{PortDefinition, PortDirection, PortReference};
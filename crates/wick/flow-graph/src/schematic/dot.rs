@@ -1,4 +1,4 @@
-use crate::{Node, NodeKind, PortDirection, Schematic};
+use crate::{Node, NodeKind, PortDirection, Schematic, ERROR_PORT};
 
 pub(crate) fn render<DATA>(schematic: &Schematic<DATA>) -> String
 where
@@ -36,12 +36,19 @@ where
     let from_port = &from_node.outputs()[conn.from().port_index()];
     let to_node = &schematic.nodes()[conn.to().node_index()];
     let to_port = &to_node.inputs()[conn.to().port_index()];
+    // Connections that carry caught errors are drawn apart from the data flow.
+    let style = if from_port.name() == ERROR_PORT {
+      "[style=\"dashed\", color=\"red\"]"
+    } else {
+      ""
+    };
     lines.push(format!(
-      "\"{}.OUT.{}\" -> \"{}.IN.{}\"",
+      "\"{}.OUT.{}\" -> \"{}.IN.{}\"{}",
       from_node.id(),
       from_port.name(),
       to_node.id(),
       to_port.name(),
+      style,
     ));
   }
   lines
@@ -9,25 +9,56 @@ use crate::error::ManifestError;
 use crate::ExpandImports;
 mod cli;
 mod http;
+mod queue;
 mod time;
+mod watch;
 
 pub use cli::{CliConfig, CliConfigBuilder, CliConfigBuilderError};
 pub use http::{
+  ApiKeyMiddleware,
+  ApiKeyMiddlewareBuilder,
+  ApiKeyMiddlewareBuilderError,
+  BasicAuthMiddleware,
+  BasicAuthMiddlewareBuilder,
+  BasicAuthMiddlewareBuilderError,
+  BuiltinMiddleware,
+  CacheControlRule,
   Contact,
+  ContentEncoding,
+  ContentType,
+  CorsMiddleware,
+  CorsMiddlewareBuilder,
+  CorsMiddlewareBuilderError,
   Documentation,
+  FallbackRule,
+  HeaderRewrite,
+  HeaderRewriteBuilder,
+  HeaderRewriteBuilderError,
+  HealthCheck,
   HttpRouterConfig,
   HttpRouterKind,
+  HttpTlsConfig,
+  HttpTlsConfigBuilder,
+  HttpTlsConfigBuilderError,
   HttpTriggerConfig,
   HttpTriggerConfigBuilder,
   HttpTriggerConfigBuilderError,
   Info,
+  JwtMiddleware,
+  JwtMiddlewareBuilder,
+  JwtMiddlewareBuilderError,
   License,
+  LoadBalance,
   Middleware,
   MiddlewareBuilder,
   MiddlewareBuilderError,
   ProxyRouterConfig,
   ProxyRouterConfigBuilder,
   ProxyRouterConfigBuilderError,
+  ProxyUpstream,
+  RateLimitMiddleware,
+  RateLimitMiddlewareBuilder,
+  RateLimitMiddlewareBuilderError,
   RawRouterConfig,
   RawRouterConfigBuilder,
   RawRouterConfigBuilderError,
@@ -35,13 +66,24 @@ pub use http::{
   RestRouterConfig,
   RestRouterConfigBuilder,
   RestRouterConfigBuilderError,
+  StaticCompression,
   StaticRouterConfig,
   StaticRouterConfigBuilder,
   StaticRouterConfigBuilderError,
+  StreamRouterConfig,
+  StreamRouterConfigBuilder,
+  StreamRouterConfigBuilderError,
   Tools,
   WickRouter,
 };
+pub use queue::{QueueTriggerConfig, QueueTriggerConfigBuilder, QueueTriggerConfigBuilderError};
 pub use time::{
+  BackoffConfig,
+  BackoffConfigBuilder,
+  BackoffConfigBuilderError,
+  BackoffStrategy,
+  CatchUpPolicy,
+  ConcurrencyPolicy,
   ScheduleConfig,
   ScheduleConfigBuilder,
   ScheduleConfigBuilderError,
@@ -49,6 +91,7 @@ pub use time::{
   TimeTriggerConfigBuilder,
   TimeTriggerConfigBuilderError,
 };
+pub use watch::{WatchTriggerConfig, WatchTriggerConfigBuilder, WatchTriggerConfigBuilderError};
 
 use self::common::template_config::Renderable;
 use self::common::{Binding, ImportDefinition};
@@ -67,6 +110,10 @@ pub enum TriggerDefinition {
   Http(HttpTriggerConfig),
   /// A time trigger.
   Time(TimeTriggerConfig),
+  /// A queue trigger.
+  Queue(QueueTriggerConfig),
+  /// A filesystem watch trigger.
+  Watch(WatchTriggerConfig),
 }
 
 impl TriggerDefinition {
@@ -76,6 +123,8 @@ impl TriggerDefinition {
       TriggerDefinition::Cli(_) => TriggerKind::Cli,
       TriggerDefinition::Http(_) => TriggerKind::Http,
       TriggerDefinition::Time(_) => TriggerKind::Time,
+      TriggerDefinition::Queue(_) => TriggerKind::Queue,
+      TriggerDefinition::Watch(_) => TriggerKind::Watch,
     }
   }
 }
@@ -91,6 +140,8 @@ impl Renderable for TriggerDefinition {
       TriggerDefinition::Cli(v) => v.render_config(source, root_config, env),
       TriggerDefinition::Http(v) => v.render_config(source, root_config, env),
       TriggerDefinition::Time(v) => v.render_config(source, root_config, env),
+      TriggerDefinition::Queue(v) => v.render_config(source, root_config, env),
+      TriggerDefinition::Watch(v) => v.render_config(source, root_config, env),
     }
   }
 }
@@ -102,6 +153,8 @@ impl ExpandImports for TriggerDefinition {
       TriggerDefinition::Cli(c) => c.expand_imports(bindings, index),
       TriggerDefinition::Http(c) => c.expand_imports(bindings, index),
       TriggerDefinition::Time(c) => c.expand_imports(bindings, index),
+      TriggerDefinition::Queue(c) => c.expand_imports(bindings, index),
+      TriggerDefinition::Watch(c) => c.expand_imports(bindings, index),
     }
   }
 }
@@ -117,6 +170,10 @@ pub enum TriggerKind {
   Http,
   /// A time trigger.
   Time,
+  /// A queue trigger.
+  Queue,
+  /// A filesystem watch trigger.
+  Watch,
 }
 
 impl std::fmt::Display for TriggerKind {
@@ -125,6 +182,8 @@ impl std::fmt::Display for TriggerKind {
       TriggerKind::Cli => f.write_str("CLI"),
       TriggerKind::Http => f.write_str("HTTP"),
       TriggerKind::Time => f.write_str("TIME"),
+      TriggerKind::Queue => f.write_str("QUEUE"),
+      TriggerKind::Watch => f.write_str("WATCH"),
     }
   }
 }
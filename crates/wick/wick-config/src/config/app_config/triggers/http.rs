@@ -1,14 +1,45 @@
 use std::collections::HashMap;
 use std::path::Path;
 
-pub use middleware::{Middleware, MiddlewareBuilder, MiddlewareBuilderError};
+pub use middleware::{
+  ApiKeyMiddleware,
+  ApiKeyMiddlewareBuilder,
+  ApiKeyMiddlewareBuilderError,
+  BasicAuthMiddleware,
+  BasicAuthMiddlewareBuilder,
+  BasicAuthMiddlewareBuilderError,
+  BuiltinMiddleware,
+  CorsMiddleware,
+  CorsMiddlewareBuilder,
+  CorsMiddlewareBuilderError,
+  JwtMiddleware,
+  JwtMiddlewareBuilder,
+  JwtMiddlewareBuilderError,
+  Middleware,
+  MiddlewareBuilder,
+  MiddlewareBuilderError,
+  RateLimitMiddleware,
+  RateLimitMiddlewareBuilder,
+  RateLimitMiddlewareBuilderError,
+};
 use wick_asset_reference::AssetReference;
 use wick_packet::RuntimeConfig;
 
-pub use self::proxy_router::{ProxyRouterConfig, ProxyRouterConfigBuilder, ProxyRouterConfigBuilderError};
+pub use self::proxy_router::{
+  HeaderRewrite,
+  HeaderRewriteBuilder,
+  HeaderRewriteBuilderError,
+  HealthCheck,
+  LoadBalance,
+  ProxyRouterConfig,
+  ProxyRouterConfigBuilder,
+  ProxyRouterConfigBuilderError,
+  ProxyUpstream,
+};
 pub use self::raw_router::{RawRouterConfig, RawRouterConfigBuilder, RawRouterConfigBuilderError};
 pub use self::rest_router::{
   Contact,
+  ContentType,
   Documentation,
   Info,
   License,
@@ -18,7 +49,16 @@ pub use self::rest_router::{
   RestRouterConfigBuilderError,
   Tools,
 };
-pub use self::static_router::{StaticRouterConfig, StaticRouterConfigBuilder, StaticRouterConfigBuilderError};
+pub use self::static_router::{
+  CacheControlRule,
+  ContentEncoding,
+  FallbackRule,
+  StaticCompression,
+  StaticRouterConfig,
+  StaticRouterConfigBuilder,
+  StaticRouterConfigBuilderError,
+};
+pub use self::stream_router::{StreamRouterConfig, StreamRouterConfigBuilder, StreamRouterConfigBuilderError};
 use crate::config::common::template_config::Renderable;
 use crate::config::{Binding, ImportDefinition};
 use crate::error::ManifestError;
@@ -29,6 +69,7 @@ mod proxy_router;
 mod raw_router;
 mod rest_router;
 mod static_router;
+mod stream_router;
 
 fn index_to_router_id(trigger_index: usize, index: usize) -> String {
   format!("trigger_{}_router_{}", trigger_index, index)
@@ -43,11 +84,31 @@ fn index_to_router_id(trigger_index: usize, index: usize) -> String {
 pub struct HttpTriggerConfig {
   #[asset(skip)]
   pub(crate) resource: String,
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) tls: Option<HttpTlsConfig>,
   #[builder(default)]
   #[serde(skip_serializing_if = "Vec::is_empty")]
   pub(crate) routers: Vec<HttpRouterConfig>,
 }
 
+#[derive(Debug, Clone, PartialEq, property::Property, serde::Serialize, derive_builder::Builder)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+#[must_use]
+/// TLS settings for an HTTP trigger that serves HTTPS directly.
+pub struct HttpTlsConfig {
+  /// The resource that points to the PEM-encoded certificate chain.
+  pub(crate) cert: String,
+  /// The resource that points to the PEM-encoded private key.
+  pub(crate) key: String,
+  /// The resource that points to the PEM-encoded CA certificates used to verify client certificates.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) client_ca: Option<String>,
+}
+
 #[derive(Debug, Clone, derive_asset_container::AssetManager, serde::Serialize)]
 #[asset(asset(AssetReference))]
 #[must_use]
@@ -58,6 +119,7 @@ pub enum HttpRouterConfig {
   RestRouter(RestRouterConfig),
   StaticRouter(StaticRouterConfig),
   ProxyRouter(ProxyRouterConfig),
+  StreamRouter(StreamRouterConfig),
 }
 
 impl Renderable for HttpRouterConfig {
@@ -72,6 +134,7 @@ impl Renderable for HttpRouterConfig {
       HttpRouterConfig::RestRouter(v) => v.render_config(source, root_config, env),
       HttpRouterConfig::StaticRouter(v) => v.render_config(source, root_config, env),
       HttpRouterConfig::ProxyRouter(v) => v.render_config(source, root_config, env),
+      HttpRouterConfig::StreamRouter(v) => v.render_config(source, root_config, env),
     }
   }
 }
@@ -106,6 +169,9 @@ impl ExpandImports for HttpTriggerConfig {
         HttpRouterConfig::RestRouter(r) => {
           rest_router::process_runtime_config(trigger_index, router_index, r, bindings)?;
         }
+        HttpRouterConfig::StreamRouter(r) => {
+          stream_router::process_runtime_config(trigger_index, router_index, r, bindings)?;
+        }
       };
     }
 
@@ -121,6 +187,7 @@ impl HttpRouterConfig {
       Self::RestRouter(_) => HttpRouterKind::RestRouter,
       Self::StaticRouter(_) => HttpRouterKind::StaticRouter,
       Self::ProxyRouter(_) => HttpRouterKind::ProxyRouter,
+      Self::StreamRouter(_) => HttpRouterKind::StreamRouter,
     }
   }
 
@@ -131,6 +198,7 @@ impl HttpRouterConfig {
       Self::RestRouter(r) => r.path(),
       Self::StaticRouter(r) => r.path(),
       Self::ProxyRouter(r) => r.path(),
+      Self::StreamRouter(r) => r.path(),
     }
   }
 }
@@ -148,6 +216,7 @@ pub enum HttpRouterKind {
   RestRouter,
   StaticRouter,
   ProxyRouter,
+  StreamRouter,
 }
 
 impl std::fmt::Display for HttpRouterKind {
@@ -157,6 +226,7 @@ impl std::fmt::Display for HttpRouterKind {
       Self::RestRouter => write!(f, "rest"),
       Self::StaticRouter => write!(f, "static"),
       Self::ProxyRouter => write!(f, "proxy"),
+      Self::StreamRouter => write!(f, "stream"),
     }
   }
 }
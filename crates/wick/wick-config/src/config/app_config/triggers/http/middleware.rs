@@ -5,7 +5,7 @@ use wick_packet::RuntimeConfig;
 
 use super::WickRouter;
 use crate::config::template_config::Renderable;
-use crate::config::{self, Binding, ComponentOperationExpression, ImportDefinition};
+use crate::config::{self, Binding, ComponentOperationExpression, HttpMethod, ImportDefinition, TemplateConfig};
 use crate::error::ManifestError;
 
 #[derive(
@@ -27,6 +27,132 @@ pub struct Middleware {
   /// The middleware to apply to responses.
   #[serde(skip_serializing_if = "Vec::is_empty")]
   pub(crate) response: Vec<ComponentOperationExpression>,
+  /// Built-in middleware that runs, in order, before the request operations.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) builtin: Vec<BuiltinMiddleware>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[must_use]
+/// Middleware that runs natively in the HTTP trigger, without a component.
+pub enum BuiltinMiddleware {
+  /// A [CorsMiddleware] configuration.
+  Cors(CorsMiddleware),
+  /// A [RateLimitMiddleware] configuration.
+  RateLimit(RateLimitMiddleware),
+  /// An [ApiKeyMiddleware] configuration.
+  ApiKey(ApiKeyMiddleware),
+  /// A [BasicAuthMiddleware] configuration.
+  BasicAuth(BasicAuthMiddleware),
+  /// A [JwtMiddleware] configuration.
+  Jwt(JwtMiddleware),
+}
+
+impl Renderable for BuiltinMiddleware {
+  fn render_config(
+    &mut self,
+    source: Option<&Path>,
+    root_config: Option<&RuntimeConfig>,
+    env: Option<&HashMap<String, String>>,
+  ) -> Result<(), ManifestError> {
+    match self {
+      BuiltinMiddleware::ApiKey(v) => {
+        for key in &mut v.keys {
+          key.set_value(key.render(source, root_config, env)?);
+        }
+      }
+      BuiltinMiddleware::BasicAuth(v) => {
+        for password in v.users.values_mut() {
+          password.set_value(password.render(source, root_config, env)?);
+        }
+      }
+      BuiltinMiddleware::Cors(_) | BuiltinMiddleware::RateLimit(_) | BuiltinMiddleware::Jwt(_) => {}
+    }
+    Ok(())
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into), default)]
+#[property(get(public), set(private), mut(disable))]
+/// Adds Cross-Origin Resource Sharing (CORS) headers to responses and answers CORS preflight requests.
+pub struct CorsMiddleware {
+  /// The origins allowed to make cross-origin requests. Use `*` to allow any origin.
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) allowed_origins: Vec<String>,
+  /// The methods allowed in cross-origin requests. Any method is allowed if empty.
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) allowed_methods: Vec<HttpMethod>,
+  /// The headers allowed in cross-origin requests. Any requested header is allowed if empty.
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) allowed_headers: Vec<String>,
+  /// The response headers that browsers can expose to scripts.
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) exposed_headers: Vec<String>,
+  /// Whether browsers can send credentials with cross-origin requests. Can't be combined with the `*` origin.
+  pub(crate) allow_credentials: bool,
+  /// How long (in seconds) browsers can cache a preflight response.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) max_age: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+/// Limits how many requests each client IP address can make.
+pub struct RateLimitMiddleware {
+  /// The number of requests each client can make per window.
+  pub(crate) requests: u32,
+  /// The length of the window (in milliseconds).
+  pub(crate) window: u64,
+  /// A header that identifies clients instead of their IP address, like `x-forwarded-for`. The first comma-separated value is used. Only set this behind a proxy that sets the header.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) key_header: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+/// Requires requests to carry one of the configured API keys in a header.
+pub struct ApiKeyMiddleware {
+  /// The header that carries the key.
+  pub(crate) header: String,
+  /// The accepted keys.
+  pub(crate) keys: Vec<TemplateConfig<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+/// Requires requests to authenticate with HTTP Basic authentication.
+pub struct BasicAuthMiddleware {
+  /// The accepted usernames and their passwords.
+  pub(crate) users: HashMap<String, TemplateConfig<String>>,
+  /// The realm reported to clients that fail to authenticate.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) realm: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+/// Requires requests to carry a bearer JSON Web Token (JWT) signed by a key from a JSON Web Key Set (JWKS).
+pub struct JwtMiddleware {
+  /// The Url or Volume resource that points to the JWKS.
+  pub(crate) jwks: String,
+  /// The issuer (`iss` claim) that tokens must have.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) issuer: Option<String>,
+  /// The audiences (`aud` claim) that tokens can be issued for. Any audience is accepted if empty.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) audience: Vec<String>,
 }
 
 impl Renderable for Middleware {
@@ -37,7 +163,8 @@ impl Renderable for Middleware {
     env: Option<&HashMap<String, String>>,
   ) -> Result<(), ManifestError> {
     self.request.render_config(source, root_config, env)?;
-    self.response.render_config(source, root_config, env)
+    self.response.render_config(source, root_config, env)?;
+    self.builtin.render_config(source, root_config, env)
   }
 }
 
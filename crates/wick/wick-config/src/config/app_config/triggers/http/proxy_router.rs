@@ -25,12 +25,113 @@ pub struct ProxyRouterConfig {
   #[property(get(disable))]
   #[serde(skip_serializing_if = "Option::is_none")]
   pub(crate) middleware: Option<super::middleware::Middleware>,
-  /// The URL resource to proxy to.
+  /// The URL resource to proxy to. Required unless upstreams are configured.
   #[asset(skip)]
-  pub(crate) url: String,
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) url: Option<String>,
   /// Whether or not to strip the router's path from the proxied request.
   #[asset(skip)]
   pub(crate) strip_path: bool,
+  /// Additional URL resources to balance requests across.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) upstreams: Vec<ProxyUpstream>,
+  /// How to choose the upstream for each request.
+  #[asset(skip)]
+  #[builder(default)]
+  pub(crate) balance: LoadBalance,
+  /// Periodic checks that take unhealthy upstreams out of rotation.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) health_check: Option<HealthCheck>,
+  /// How long to wait for an upstream to respond (in milliseconds).
+  #[asset(skip)]
+  #[builder(default = "30000")]
+  pub(crate) timeout: u64,
+  /// How many times to retry a request on another upstream when an upstream can't be reached or, for
+  /// idempotent methods, times out.
+  #[asset(skip)]
+  #[builder(default)]
+  pub(crate) retries: u32,
+  /// Rules that rewrite the headers of proxied requests.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) request_headers: Option<HeaderRewrite>,
+  /// Rules that rewrite the headers of proxied responses.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) response_headers: Option<HeaderRewrite>,
+}
+
+#[derive(Debug, Clone, PartialEq, property::Property, serde::Serialize)]
+#[property(get(public), set(private), mut(disable))]
+/// An upstream the proxy router can forward requests to.
+pub struct ProxyUpstream {
+  /// The URL resource to proxy to.
+  pub(crate) url: String,
+  /// How long to wait for this upstream to respond (in milliseconds). Defaults to the router's timeout.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) timeout: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, property::Property, serde::Serialize)]
+#[property(get(public), set(private), mut(disable))]
+/// Periodic checks that take unhealthy upstreams out of rotation.
+pub struct HealthCheck {
+  /// The path to request from each upstream. Any 2xx or 3xx response is healthy.
+  pub(crate) path: String,
+  /// How often to check each upstream (in milliseconds).
+  pub(crate) interval: u64,
+  /// How long to wait for a check to respond (in milliseconds).
+  pub(crate) timeout: u64,
+  /// How many checks in a row must fail before an upstream is taken out of rotation.
+  pub(crate) failures: u32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(default, setter(into))]
+#[property(get(public), set(private), mut(disable))]
+/// Rules that rewrite HTTP headers. Headers are removed, then set, then added.
+pub struct HeaderRewrite {
+  /// Headers to remove.
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) remove: Vec<String>,
+  /// Headers to set, replacing any existing values.
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  pub(crate) set: HashMap<String, String>,
+  /// Headers to add alongside any existing values.
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  pub(crate) add: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+/// How the proxy router chooses an upstream for each request.
+#[serde(rename_all = "kebab-case")]
+pub enum LoadBalance {
+  /// Send requests to each upstream in turn.
+  RoundRobin = 0,
+  /// Send requests to the upstream with the fewest requests in flight.
+  LeastConnections = 1,
+}
+
+impl Default for LoadBalance {
+  fn default() -> Self {
+    Self::RoundRobin
+  }
+}
+
+impl std::fmt::Display for LoadBalance {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::RoundRobin => write!(f, "round-robin"),
+      Self::LeastConnections => write!(f, "least-connections"),
+    }
+  }
 }
 
 impl Renderable for ProxyRouterConfig {
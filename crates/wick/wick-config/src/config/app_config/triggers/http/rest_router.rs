@@ -186,6 +186,53 @@ pub struct RestRoute {
   #[asset(skip)]
   #[serde(skip_serializing_if = "Option::is_none")]
   pub(crate) summary: Option<String>,
+  /// The output port that carries the response body. Defaults to every output port, collected into a JSON object keyed by port name.
+  #[asset(skip)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) body_port: Option<String>,
+  /// The output port that carries the response status code.
+  #[asset(skip)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) status_port: Option<String>,
+  /// The output port that carries a map of response headers.
+  #[asset(skip)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) headers_port: Option<String>,
+  /// The content types this route can respond with, chosen by the request&#x27;s Accept header.
+  #[asset(skip)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) content_types: Vec<ContentType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+/// Content types a REST route can respond with.
+#[serde(rename_all = "kebab-case")]
+pub enum ContentType {
+  /// A single JSON document.
+  Json = 0,
+  /// Newline-delimited JSON, one line per output packet.
+  Ndjson = 1,
+  /// Comma-separated values, one or more rows per output packet.
+  Csv = 2,
+  /// The raw bytes of every output packet.
+  Raw = 3,
+}
+
+impl Default for ContentType {
+  fn default() -> Self {
+    Self::Json
+  }
+}
+
+impl std::fmt::Display for ContentType {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Json => write!(f, "json"),
+      Self::Ndjson => write!(f, "ndjson"),
+      Self::Csv => write!(f, "csv"),
+      Self::Raw => write!(f, "raw"),
+    }
+  }
 }
 
 pub(crate) fn process_runtime_config(
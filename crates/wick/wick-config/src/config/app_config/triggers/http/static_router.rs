@@ -31,6 +31,83 @@ pub struct StaticRouterConfig {
   pub(crate) fallback: Option<String>,
   #[asset(skip)]
   pub(crate) indexes: bool,
+  /// Files to serve when a request doesn't match a file, chosen by the first rule whose glob matches the request path. Checked before `fallback`.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) fallbacks: Vec<FallbackRule>,
+  /// How to compress the files this router serves.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) compression: Option<StaticCompression>,
+  /// Cache-Control headers to send, chosen by the first rule whose glob matches the served file.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) cache_control: Vec<CacheControlRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, property::Property, serde::Serialize)]
+#[property(get(public), set(private), mut(disable))]
+/// A file to serve when a request doesn't match a file.
+pub struct FallbackRule {
+  /// A glob matched against the request path, relative to the router's path (e.g. `app/**`).
+  pub(crate) glob: String,
+  /// The file to serve, relative to the volume.
+  pub(crate) file: String,
+  /// The status code to respond with.
+  pub(crate) status: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, property::Property, serde::Serialize)]
+#[property(get(public), set(private), mut(disable))]
+/// How the static router compresses the files it serves.
+pub struct StaticCompression {
+  /// Whether to serve a pre-compressed sibling of a file (e.g. `app.js.br` or `app.js.gz`) to clients that accept it.
+  pub(crate) precompressed: bool,
+  /// Whether to compress files on the fly when there is no pre-compressed sibling.
+  pub(crate) dynamic: bool,
+  /// The encodings to offer, in order of preference. Defaults to Brotli, then Gzip.
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) encodings: Vec<ContentEncoding>,
+  /// The smallest file (in bytes) to compress on the fly.
+  pub(crate) min_size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, property::Property, serde::Serialize)]
+#[property(get(public), set(private), mut(disable))]
+/// A Cache-Control header to send for matching files.
+pub struct CacheControlRule {
+  /// A glob matched against the served file's path, relative to the volume (e.g. `assets/**` or `**/*.html`).
+  pub(crate) glob: String,
+  /// The Cache-Control header value to send (e.g. `public, max-age=31536000, immutable`).
+  pub(crate) value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+/// Encodings the static router can compress files with.
+#[serde(rename_all = "kebab-case")]
+pub enum ContentEncoding {
+  /// Gzip compression.
+  Gzip = 0,
+  /// Brotli compression.
+  Brotli = 1,
+}
+
+impl Default for ContentEncoding {
+  fn default() -> Self {
+    Self::Gzip
+  }
+}
+
+impl std::fmt::Display for ContentEncoding {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Gzip => write!(f, "gzip"),
+      Self::Brotli => write!(f, "br"),
+    }
+  }
 }
 
 impl super::WickRouter for StaticRouterConfig {
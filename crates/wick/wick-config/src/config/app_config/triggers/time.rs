@@ -39,7 +39,11 @@ impl Renderable for TimeTriggerConfig {
     root_config: Option<&RuntimeConfig>,
     env: Option<&HashMap<String, String>>,
   ) -> Result<(), ManifestError> {
-    self.operation.render_config(source, root_config, env)
+    self.operation.render_config(source, root_config, env)?;
+    if let Some(dead_letter) = &mut self.schedule.dead_letter {
+      dead_letter.render_config(source, root_config, env)?;
+    }
+    Ok(())
   }
 }
 
@@ -62,6 +66,157 @@ pub struct ScheduleConfig {
   #[asset(skip)]
   #[builder(default)]
   pub(crate) repeat: u16,
+  #[asset(skip)]
+  #[builder(default)]
+  pub(crate) retries: u16,
+  #[asset(skip)]
+  #[builder(default)]
+  pub(crate) backoff: BackoffConfig,
+  #[asset(skip)]
+  #[builder(default)]
+  pub(crate) concurrency: ConcurrencyPolicy,
+  #[asset(skip)]
+  #[builder(default)]
+  pub(crate) jitter: u64,
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) dead_letter: Option<ComponentOperationExpression>,
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) state: Option<String>,
+  #[asset(skip)]
+  #[builder(default)]
+  pub(crate) catch_up: CatchUpPolicy,
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) timezone: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, property::Property, serde::Serialize, derive_builder::Builder)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+#[must_use]
+/// How long to wait between retries of a failed job.
+pub struct BackoffConfig {
+  #[builder(default)]
+  pub(crate) strategy: BackoffStrategy,
+  #[builder(default = "1000")]
+  pub(crate) delay: u64,
+  #[builder(default)]
+  pub(crate) max_delay: u64,
+}
+
+impl Default for BackoffConfig {
+  fn default() -> Self {
+    Self {
+      strategy: BackoffStrategy::default(),
+      delay: 1000,
+      max_delay: 0,
+    }
+  }
+}
+
+impl BackoffConfig {
+  /// The delay before the passed retry attempt (starting at 1), without jitter.
+  #[must_use]
+  pub fn delay_for(&self, attempt: u16) -> std::time::Duration {
+    let delay = match self.strategy {
+      BackoffStrategy::Fixed => self.delay,
+      BackoffStrategy::Exponential => {
+        let factor = 2u64.saturating_pow(u32::from(attempt.saturating_sub(1)));
+        self.delay.saturating_mul(factor)
+      }
+    };
+    let delay = if self.max_delay > 0 {
+      delay.min(self.max_delay)
+    } else {
+      delay
+    };
+    std::time::Duration::from_millis(delay)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+/// How delays between retries grow.
+#[serde(rename_all = "kebab-case")]
+pub enum BackoffStrategy {
+  /// Wait the same amount of time between every retry.
+  Fixed = 0,
+  /// Double the delay after every retry.
+  Exponential = 1,
+}
+
+impl Default for BackoffStrategy {
+  fn default() -> Self {
+    Self::Fixed
+  }
+}
+
+impl std::fmt::Display for BackoffStrategy {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Fixed => write!(f, "fixed"),
+      Self::Exponential => write!(f, "exponential"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+/// What to do when a scheduled run is due while a previous run is still executing.
+#[serde(rename_all = "kebab-case")]
+pub enum ConcurrencyPolicy {
+  /// Start the new run alongside the previous one.
+  Allow = 0,
+  /// Skip the new run.
+  Skip = 1,
+  /// Wait for the previous run to finish before starting the new one.
+  Queue = 2,
+}
+
+impl Default for ConcurrencyPolicy {
+  fn default() -> Self {
+    Self::Allow
+  }
+}
+
+impl std::fmt::Display for ConcurrencyPolicy {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Allow => write!(f, "allow"),
+      Self::Skip => write!(f, "skip"),
+      Self::Queue => write!(f, "queue"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+/// What to do with runs that were due while the schedule was not running.
+#[serde(rename_all = "kebab-case")]
+pub enum CatchUpPolicy {
+  /// Skip missed runs and wait for the next scheduled time.
+  None = 0,
+  /// Run once immediately if any runs were missed.
+  One = 1,
+  /// Run every missed run immediately.
+  All = 2,
+}
+
+impl Default for CatchUpPolicy {
+  fn default() -> Self {
+    Self::None
+  }
+}
+
+impl std::fmt::Display for CatchUpPolicy {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::None => write!(f, "none"),
+      Self::One => write!(f, "one"),
+      Self::All => write!(f, "all"),
+    }
+  }
 }
 
 impl ExpandImports for TimeTriggerConfig {
@@ -73,6 +228,9 @@ impl ExpandImports for TimeTriggerConfig {
   ) -> Result<(), Self::Error> {
     let id = format!("trigger_{}", trigger_index);
     self.operation_mut().maybe_import(&id, bindings);
+    if let Some(dead_letter) = &mut self.schedule.dead_letter {
+      dead_letter.maybe_import(&format!("{}_dead_letter", id), bindings);
+    }
     Ok(())
   }
 }
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use wick_asset_reference::AssetReference;
+use wick_packet::RuntimeConfig;
+
+use crate::config::template_config::Renderable;
+use crate::config::{Binding, ComponentOperationExpression, Glob, ImportDefinition};
+use crate::error::ManifestError;
+use crate::ExpandImports;
+
+#[derive(
+  Debug, Clone, derive_asset_container::AssetManager, property::Property, serde::Serialize, derive_builder::Builder,
+)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(public, suffix = "_mut"))]
+#[asset(asset(AssetReference))]
+/// Normalized representation of a Watch trigger configuration.
+pub struct WatchTriggerConfig {
+  #[asset(skip)]
+  pub(crate) resource: String,
+  pub(crate) operation: ComponentOperationExpression,
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) include: Vec<Glob>,
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) exclude: Vec<Glob>,
+  #[asset(skip)]
+  #[builder(default)]
+  pub(crate) contents: bool,
+}
+
+impl Renderable for WatchTriggerConfig {
+  fn render_config(
+    &mut self,
+    source: Option<&Path>,
+    root_config: Option<&RuntimeConfig>,
+    env: Option<&HashMap<String, String>>,
+  ) -> Result<(), ManifestError> {
+    self.operation.render_config(source, root_config, env)
+  }
+}
+
+impl ExpandImports for WatchTriggerConfig {
+  type Error = ManifestError;
+  fn expand_imports(
+    &mut self,
+    bindings: &mut Vec<Binding<ImportDefinition>>,
+    trigger_index: usize,
+  ) -> Result<(), Self::Error> {
+    let id = format!("trigger_{}", trigger_index);
+    self.operation_mut().maybe_import(&id, bindings);
+    Ok(())
+  }
+}
@@ -37,5 +37,13 @@ pub use self::metadata::{Metadata, MetadataBuilder};
 pub use self::operation_definition::{OperationDefinition, OperationDefinitionBuilder};
 pub use self::package_definition::{PackageConfig, PackageConfigBuilder, RegistryConfig, RegistryConfigBuilder};
 pub use self::resources::{ResourceDefinition, TcpPort, UdpPort, UrlResource, Volume};
-pub use self::settings::ExecutionSettings;
+pub use self::settings::{
+  CircuitBreakerConfig,
+  CircuitBreakerConfigBuilder,
+  CircuitBreakerConfigBuilderError,
+  ExecutionSettings,
+  OperationRetry,
+  OperationRetryBuilder,
+  OperationRetryBuilderError,
+};
 pub use self::template_config::TemplateConfig;
@@ -142,7 +142,6 @@ impl std::str::FromStr for ComponentOperationExpression {
 #[serde(rename_all = "kebab-case")]
 pub enum HighLevelComponent {
   /// A SQL Component.
-  #[asset(skip)]
   Sql(config::components::SqlComponentConfig),
   #[asset(skip)]
   /// An HTTP Client Component.
@@ -295,6 +294,9 @@ impl Renderable for ComponentDefinition {
       None
     };
     self.set_config(val);
+    if let ComponentDefinition::HighLevelComponent(HighLevelComponent::HttpClient(c)) = self {
+      c.render_config(source, root_config, env)?;
+    }
     Ok(())
   }
 }
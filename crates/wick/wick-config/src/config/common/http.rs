@@ -6,6 +6,25 @@ pub enum HttpMethod {
   Post = 1,
   Put = 2,
   Delete = 3,
+  Patch = 4,
+  Head = 5,
+  Options = 6,
+  Trace = 7,
+}
+
+impl std::fmt::Display for HttpMethod {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      HttpMethod::Get => write!(f, "GET"),
+      HttpMethod::Post => write!(f, "POST"),
+      HttpMethod::Put => write!(f, "PUT"),
+      HttpMethod::Delete => write!(f, "DELETE"),
+      HttpMethod::Patch => write!(f, "PATCH"),
+      HttpMethod::Head => write!(f, "HEAD"),
+      HttpMethod::Options => write!(f, "OPTIONS"),
+      HttpMethod::Trace => write!(f, "TRACE"),
+    }
+  }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
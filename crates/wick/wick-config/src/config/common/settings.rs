@@ -1,6 +1,8 @@
 #![allow(missing_docs)] // delete when we move away from the `property` crate.
 use std::time::Duration;
 
+use crate::config::BackoffConfig;
+
 #[derive(Debug, Clone, PartialEq, property::Property, serde::Serialize)]
 #[property(get(public), set(private), mut(disable))]
 #[allow(missing_copy_implementations)]
@@ -9,21 +11,27 @@ use std::time::Duration;
 pub struct ExecutionSettings {
   /// The timeout for the execution.
   pub timeout: Option<Duration>,
+  /// How the execution is retried when it fails.
+  pub retry: Option<OperationRetry>,
+  /// The circuit breaker guarding the executed component.
+  pub circuit_breaker: Option<CircuitBreakerConfig>,
 }
 
 impl ExecutionSettings {
   /// Create a new settings object.
   #[must_use]
   pub const fn new(timeout: Option<Duration>) -> Self {
-    Self { timeout }
+    Self {
+      timeout,
+      retry: None,
+      circuit_breaker: None,
+    }
   }
 
   /// Create a new settings object with a timeout from milliseconds.
   #[must_use]
   pub const fn from_timeout_millis(millis: u64) -> Self {
-    Self {
-      timeout: Some(Duration::from_millis(millis)),
-    }
+    Self::new(Some(Duration::from_millis(millis)))
   }
 
   /// Get the timeout duration as milliseconds if set.
@@ -32,3 +40,56 @@ impl ExecutionSettings {
     self.timeout.map(|d| d.as_millis() as _)
   }
 }
+
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+#[must_use]
+/// How a failed operation is retried. The operation is invoked again with the same inputs and its outputs are only
+/// passed on once an attempt succeeds or the last attempt fails.
+pub struct OperationRetry {
+  /// The number of times to retry a failed operation before giving up.
+  #[builder(default = "3")]
+  pub(crate) retries: u16,
+  /// How long to wait between retries.
+  #[builder(default)]
+  pub(crate) backoff: BackoffConfig,
+  /// Whether to retry operations that time out.
+  #[builder(default = "true")]
+  pub(crate) timeouts: bool,
+  /// Only retry errors whose message contains one of these strings. Every error is retried if empty.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) errors: Vec<String>,
+}
+
+impl OperationRetry {
+  /// Whether an error with the passed message should be retried.
+  #[must_use]
+  pub fn retries_error(&self, msg: &str) -> bool {
+    self.errors.is_empty() || self.errors.iter().any(|e| msg.contains(e.as_str()))
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+#[must_use]
+/// Fails calls to a component immediately after it fails repeatedly, giving it time to recover. The circuit is shared
+/// by every operation instance that uses the component.
+pub struct CircuitBreakerConfig {
+  /// The number of consecutive failures that open the circuit.
+  #[builder(default = "5")]
+  pub(crate) failures: u32,
+  /// How long (in milliseconds) the circuit stays open before a trial call is let through.
+  #[builder(default = "30000")]
+  pub(crate) reset_after: u64,
+}
+
+impl CircuitBreakerConfig {
+  /// How long the circuit stays open before a trial call is let through.
+  #[must_use]
+  pub const fn reset_duration(&self) -> Duration {
+    Duration::from_millis(self.reset_after)
+  }
+}
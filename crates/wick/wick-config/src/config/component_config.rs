@@ -270,7 +270,8 @@ impl ComponentConfiguration {
 
   /// Initialize the configuration.
   pub fn initialize(&mut self) -> Result<&Self> {
-    let root_config = self.root_config.as_ref();
+    let root_config = self.root_config.clone();
+    let root_config = root_config.as_ref();
     let source = self.source().map(std::path::Path::to_path_buf);
     trace!(
       source = ?source,
@@ -280,8 +281,7 @@ impl ComponentConfiguration {
       "initializing component"
     );
 
-    self.resources.render_config(source.as_deref(), root_config, None)?;
-    self.import.render_config(source.as_deref(), root_config, None)?;
+    self.render_config(source.as_deref(), root_config, None)?;
 
     Ok(self)
   }
@@ -307,6 +307,9 @@ impl Renderable for ComponentConfiguration {
   ) -> Result<()> {
     self.resources.render_config(source, root_config, env)?;
     self.import.render_config(source, root_config, env)?;
+    if let ComponentImplementation::HttpClient(c) = &mut self.component {
+      c.render_config(source, root_config, env)?;
+    }
     Ok(())
   }
 }
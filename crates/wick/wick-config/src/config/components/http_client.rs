@@ -1,11 +1,15 @@
 #![allow(missing_docs)] // delete when we move away from the `property` crate.
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::path::Path;
 
 use wick_interface_types::OperationSignatures;
+use wick_packet::RuntimeConfig;
 
 use super::{ComponentConfig, OperationConfig};
-use crate::config::{self, Codec, HttpMethod};
+use crate::config::template_config::Renderable;
+use crate::config::{self, BackoffConfig, Codec, HttpMethod, TemplateConfig};
+use crate::error::ManifestError;
 
 #[derive(
   Debug,
@@ -44,10 +48,50 @@ pub struct HttpClientComponentConfig {
   #[property(skip)]
   #[serde(skip_serializing_if = "Vec::is_empty")]
   pub(crate) operations: Vec<HttpClientOperationDefinition>,
+
+  /// The default time (in milliseconds) each request has to complete. Requests do not time out if omitted.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) timeout: Option<u64>,
+
+  /// The time (in milliseconds) allowed to establish a connection.
+  #[asset(skip)]
+  #[builder(default = "5000")]
+  pub(crate) connect_timeout: u64,
+
+  /// The User-Agent header to send with every request.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) user_agent: Option<String>,
+
+  /// How failed requests are retried. Can be overridden by individual operations.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) retry: Option<RetryPolicy>,
+
+  /// How requests authenticate with the server.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) auth: Option<HttpClientAuth>,
 }
 
 impl HttpClientComponentConfig {}
 
+impl Renderable for HttpClientComponentConfig {
+  fn render_config(
+    &mut self,
+    source: Option<&Path>,
+    root_config: Option<&RuntimeConfig>,
+    env: Option<&HashMap<String, String>>,
+  ) -> Result<(), ManifestError> {
+    self.auth.render_config(source, root_config, env)
+  }
+}
+
 impl OperationSignatures for HttpClientComponentConfig {
   fn operation_signatures(&self) -> Vec<wick_interface_types::OperationSignature> {
     let codec = self.codec;
@@ -89,38 +133,21 @@ impl OperationConfig for HttpClientOperationDefinition {
     Cow::Owned(vec![
       // TODO: support actual HTTP Response type.
       wick_interface_types::Field::new("response", wick_interface_types::Type::Object),
-      wick_interface_types::Field::new(
-        "body",
-        match self.codec {
-          Some(Codec::Json) => wick_interface_types::Type::Object,
-          Some(Codec::Raw) => wick_interface_types::Type::Bytes,
-          Some(Codec::FormData) => wick_interface_types::Type::Object,
-          Some(Codec::Text) => wick_interface_types::Type::Object,
-          None => wick_interface_types::Type::Object,
-        },
-      ),
+      wick_interface_types::Field::new("body", self.body_type()),
     ])
   }
 }
 
 impl From<HttpClientOperationDefinition> for wick_interface_types::OperationSignature {
   fn from(operation: HttpClientOperationDefinition) -> Self {
+    let body = operation.body_type();
     Self::new(
       operation.name,
       operation.inputs,
       vec![
         // TODO: support actual HTTP Response type.
         wick_interface_types::Field::new("response", wick_interface_types::Type::Object),
-        wick_interface_types::Field::new(
-          "body",
-          match operation.codec {
-            Some(Codec::Json) => wick_interface_types::Type::Object,
-            Some(Codec::Raw) => wick_interface_types::Type::Bytes,
-            Some(Codec::FormData) => wick_interface_types::Type::Object,
-            Some(Codec::Text) => wick_interface_types::Type::Object,
-            None => wick_interface_types::Type::Object,
-          },
-        ),
+        wick_interface_types::Field::new("body", body),
       ],
       operation.config,
     )
@@ -167,9 +194,43 @@ pub struct HttpClientOperationDefinition {
 
   /// The HTTP method to use.
   pub(crate) method: HttpMethod,
+
+  /// The time (in milliseconds) each request has to complete. Overrides the component's timeout.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) timeout: Option<u64>,
+
+  /// How failed requests are retried. Overrides the component's retry policy.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) retry: Option<RetryPolicy>,
+
+  /// How to follow paginated responses.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) pagination: Option<Pagination>,
+
+  /// The input whose packets are streamed as a chunked request body instead of rendering `body`.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) request_stream: Option<String>,
+
+  /// Emit the response body incrementally as it arrives instead of buffering it.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) response_stream: Option<ResponseStream>,
 }
 
 impl HttpClientOperationDefinition {
+  /// The type of the packets emitted on the `body` port.
+  const fn body_type(&self) -> wick_interface_types::Type {
+    match (self.response_stream, self.codec) {
+      (Some(ResponseStream::Chunks), _) | (None, Some(Codec::Raw)) => wick_interface_types::Type::Bytes,
+      (Some(ResponseStream::Lines), _) => wick_interface_types::Type::String,
+      (Some(ResponseStream::Ndjson | ResponseStream::Sse) | None, _) => wick_interface_types::Type::Object,
+    }
+  }
+
   /// Create a new GET operation.
   #[must_use]
   pub fn new_get(
@@ -228,6 +289,26 @@ impl HttpClientOperationDefinition {
     builder
   }
 
+  /// Create a new PATCH operation.
+  #[must_use]
+  pub fn new_patch(
+    name: &str,
+    path: &str,
+    inputs: Vec<wick_interface_types::Field>,
+    body: Option<liquid_json::LiquidJsonValue>,
+    headers: Option<HashMap<String, Vec<String>>>,
+  ) -> HttpClientOperationDefinitionBuilder {
+    let mut builder = HttpClientOperationDefinitionBuilder::default();
+    builder
+      .name(name)
+      .path(path)
+      .inputs(inputs)
+      .body(body)
+      .headers(headers)
+      .method(HttpMethod::Patch);
+    builder
+  }
+
   /// Create a new DELETE operation.
   #[must_use]
   pub fn new_delete(
@@ -248,3 +329,175 @@ impl HttpClientOperationDefinition {
     builder
   }
 }
+
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+#[must_use]
+/// How failed HTTP requests are retried. Only requests with idempotent methods are retried.
+pub struct RetryPolicy {
+  /// The number of times to retry a failed request before giving up.
+  #[builder(default = "3")]
+  pub(crate) retries: u16,
+  /// How long to wait between retries. A `Retry-After` header from the server takes precedence, up to the backoff's `max_delay` or 60 seconds without one.
+  #[builder(default)]
+  pub(crate) backoff: BackoffConfig,
+  /// The response statuses to retry. Defaults to 429, 502, 503 and 504 if empty.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) statuses: Vec<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[must_use]
+/// How an HTTP client component authenticates its requests.
+pub enum HttpClientAuth {
+  /// A [BearerAuth] configuration.
+  Bearer(BearerAuth),
+  /// A [BasicAuth] configuration.
+  Basic(BasicAuth),
+  /// An [OAuth2ClientCredentials] configuration.
+  OAuth2(OAuth2ClientCredentials),
+}
+
+impl Renderable for HttpClientAuth {
+  fn render_config(
+    &mut self,
+    source: Option<&Path>,
+    root_config: Option<&RuntimeConfig>,
+    env: Option<&HashMap<String, String>>,
+  ) -> Result<(), ManifestError> {
+    match self {
+      HttpClientAuth::Bearer(v) => {
+        v.token.set_value(v.token.render(source, root_config, env)?);
+      }
+      HttpClientAuth::Basic(v) => {
+        v.username.set_value(v.username.render(source, root_config, env)?);
+        if let Some(password) = &mut v.password {
+          password.set_value(password.render(source, root_config, env)?);
+        }
+      }
+      HttpClientAuth::OAuth2(v) => {
+        v.client_id.set_value(v.client_id.render(source, root_config, env)?);
+        v.client_secret
+          .set_value(v.client_secret.render(source, root_config, env)?);
+      }
+    }
+    Ok(())
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+/// Sends a static bearer token in the Authorization header.
+pub struct BearerAuth {
+  /// The token to send.
+  pub(crate) token: TemplateConfig<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+/// Sends a username and password with HTTP Basic authentication.
+pub struct BasicAuth {
+  /// The username to send.
+  pub(crate) username: TemplateConfig<String>,
+  /// The password to send.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) password: Option<TemplateConfig<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+/// Fetches a bearer token with the OAuth2 client credentials grant and reuses it until it expires.
+pub struct OAuth2ClientCredentials {
+  /// The URL of the token endpoint.
+  pub(crate) token_url: String,
+  /// The client ID to authenticate with.
+  pub(crate) client_id: TemplateConfig<String>,
+  /// The client secret to authenticate with.
+  pub(crate) client_secret: TemplateConfig<String>,
+  /// The scopes to request.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder, property::Property, serde::Serialize)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+#[must_use]
+/// How to follow paginated responses. Every page is emitted as a `response` packet and every item as a separate `body` packet.
+pub struct Pagination {
+  /// How the next page is found.
+  #[builder(default)]
+  pub(crate) mode: PaginationMode,
+  /// The dot-separated path to the array of items in each page. The whole page is treated as one item if omitted.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) items: Option<String>,
+  /// The dot-separated path to the next page's cursor in each page. Required for the `Cursor` mode.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) cursor: Option<String>,
+  /// The query parameter the cursor is sent in. Cursors that are absolute URLs are requested as-is.
+  #[builder(default = "\"cursor\".to_owned()")]
+  pub(crate) cursor_param: String,
+  /// The maximum number of pages to request. Use `0` for no limit.
+  #[builder(default)]
+  pub(crate) max_pages: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+/// How the next page of a paginated response is found.
+#[serde(rename_all = "kebab-case")]
+pub enum PaginationMode {
+  /// Follow the `next` URL in the response's `Link` header.
+  Link = 0,
+  /// Send the cursor found in the response body with the next request.
+  Cursor = 1,
+}
+
+impl Default for PaginationMode {
+  fn default() -> Self {
+    Self::Link
+  }
+}
+
+impl std::fmt::Display for PaginationMode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Link => write!(f, "link"),
+      Self::Cursor => write!(f, "cursor"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+/// How a streamed response body is split into `body` packets.
+#[serde(rename_all = "kebab-case")]
+pub enum ResponseStream {
+  /// Emit each chunk of bytes as it is received.
+  Chunks = 0,
+  /// Emit each line as a string.
+  Lines = 1,
+  /// Parse each line as JSON and emit it as an object.
+  Ndjson = 2,
+  /// Parse server-sent events and emit each event as an object with `event`, `data` and `id` fields.
+  Sse = 3,
+}
+
+impl std::fmt::Display for ResponseStream {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Chunks => write!(f, "chunks"),
+      Self::Lines => write!(f, "lines"),
+      Self::Ndjson => write!(f, "ndjson"),
+      Self::Sse => write!(f, "sse"),
+    }
+  }
+}
@@ -43,6 +43,11 @@ pub struct SqlComponentConfig {
   #[property(skip)]
   #[serde(skip_serializing_if = "Vec::is_empty")]
   pub(crate) operations: Vec<SqlOperationDefinition>,
+
+  /// A directory of versioned `.sql` migrations to apply when the component starts.
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) migrations: Option<config::AssetReference>,
 }
 
 impl SqlComponentConfig {}
@@ -186,10 +191,12 @@ impl OperationConfig for SqlExecOperationDefinition {
 
 impl From<SqlQueryOperationDefinition> for wick_interface_types::OperationSignature {
   fn from(operation: SqlQueryOperationDefinition) -> Self {
-    // TODO: Properly use configured outputs here.
-    // Forcing SQL components to have a single object output called "output" is a temporary
-    // limitation
-    let outputs = vec![Field::new("output", wick_interface_types::Type::Object)];
+    // Queries without declared outputs emit each row as an object on the "output" port.
+    let outputs = if operation.outputs.is_empty() {
+      vec![Field::new("output", wick_interface_types::Type::Object)]
+    } else {
+      operation.outputs
+    };
 
     Self::new(operation.name, operation.inputs, outputs, operation.config)
   }
@@ -310,4 +317,42 @@ pub struct SqlExecOperationDefinition {
   #[asset(skip)]
   #[builder(default)]
   pub(crate) on_error: ErrorBehavior,
+
+  /// How input rows are batched into a single statement, if at all.
+  #[asset(skip)]
+  #[builder(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) batch: Option<SqlBatchConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, property::Property, serde::Serialize, derive_builder::Builder)]
+#[builder(setter(into))]
+#[property(get(public), set(private), mut(disable))]
+#[must_use]
+/// How input rows are grouped into batches that run as a single statement.
+pub struct SqlBatchConfig {
+  /// The maximum number of input rows in a batch.
+  #[builder(default = "100")]
+  pub(crate) size: u32,
+  /// The maximum time (in milliseconds) to wait for a batch to fill. `0` waits until the batch is full or the input ends.
+  #[builder(default)]
+  pub(crate) window: u64,
+}
+
+impl Default for SqlBatchConfig {
+  fn default() -> Self {
+    Self { size: 100, window: 0 }
+  }
+}
+
+impl SqlBatchConfig {
+  /// The window as a [std::time::Duration], or [None] if batches are only bounded by size.
+  #[must_use]
+  pub const fn window_duration(&self) -> Option<std::time::Duration> {
+    if self.window == 0 {
+      None
+    } else {
+      Some(std::time::Duration::from_millis(self.window))
+    }
+  }
 }
@@ -273,6 +273,12 @@ pub enum TriggerDefinition {
   /// A variant representing a [TimeTrigger] type.
   #[serde(rename = "wick/trigger/time@v1")]
   TimeTrigger(TimeTrigger),
+  /// A variant representing a [QueueTrigger] type.
+  #[serde(rename = "wick/trigger/queue@v1")]
+  QueueTrigger(QueueTrigger),
+  /// A variant representing a [WatchTrigger] type.
+  #[serde(rename = "wick/trigger/watch@v1")]
+  WatchTrigger(WatchTrigger),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -303,6 +309,52 @@ pub struct TimeTrigger {
   pub payload: Vec<OperationInput>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// A trigger that consumes messages from a message broker and invokes an operation for each message.
+pub struct QueueTrigger {
+  /// The Url resource that points to the message broker.
+  pub resource: String,
+  /// The subject (queue, topic, or channel) to consume messages from.
+  pub subject: String,
+  /// The operation to invoke with each message&#x27;s payload and headers.
+
+  #[serde(serialize_with = "crate::v1::helpers::serialize_component_expression")]
+  #[serde(deserialize_with = "crate::v1::parse::component_operation_syntax")]
+  pub operation: ComponentOperationExpression,
+  /// Whether to requeue messages whose operation ended in an error. Requeued messages are retried with backoff and dead-lettered after five attempts.
+
+  #[serde(default)]
+  pub requeue: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// A trigger that invokes an operation whenever files within a Volume resource change. Changes to a file are reported once it has gone 200 milliseconds without changing.
+pub struct WatchTrigger {
+  /// The Volume resource to watch for changes.
+  pub resource: String,
+  /// The operation to invoke with the path, event, and contents of each changed file.
+
+  #[serde(serialize_with = "crate::v1::helpers::serialize_component_expression")]
+  #[serde(deserialize_with = "crate::v1::parse::component_operation_syntax")]
+  pub operation: ComponentOperationExpression,
+  /// Glob patterns, relative to the volume, of files to watch. Defaults to every file.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub include: Vec<crate::v1::helpers::Glob>,
+  /// Glob patterns, relative to the volume, of files to ignore.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub exclude: Vec<crate::v1::helpers::Glob>,
+  /// Whether to pass the contents of created and modified files to the operation.
+
+  #[serde(default)]
+  pub contents: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 /// Input to use when calling an operation
@@ -328,6 +380,192 @@ pub struct Schedule {
 
   #[serde(default = "SCHEDULE_REPEAT")]
   pub repeat: u16,
+  /// The number of times to retry a failed job before giving up.
+
+  #[serde(default)]
+  pub retries: u16,
+  /// How long to wait between retries of a failed job.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub backoff: Option<Backoff>,
+  /// What to do when a job is due while a previous run is still executing.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub concurrency: Option<ConcurrencyPolicy>,
+  /// The maximum random delay (in milliseconds) added before every run and retry.
+
+  #[serde(default)]
+  pub jitter: u64,
+  /// An operation invoked with the error when a job fails after exhausting its retries.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(serialize_with = "crate::v1::helpers::serialize_opt_component_expression")]
+  #[serde(deserialize_with = "crate::v1::parse::opt_component_operation_syntax")]
+  pub dead_letter: Option<ComponentOperationExpression>,
+  /// The Volume resource to persist the last run time and run count in. State is only kept in memory if omitted.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub state: Option<String>,
+  /// What to do with runs that were due while the schedule was not running. Requires &#x60;state&#x60;.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub catch_up: Option<CatchUpPolicy>,
+  /// The IANA timezone (e.g. &#x60;America/New_York&#x60;) to evaluate the cron expression in. Defaults to UTC.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timezone: Option<String>,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn BACKOFF_DELAY() -> u64 {
+  1000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// How long to wait between retries of a failed job.
+pub struct Backoff {
+  /// Whether the delay stays the same or doubles after every retry.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub strategy: Option<BackoffStrategy>,
+  /// The delay (in milliseconds) before the first retry.
+
+  #[serde(default = "BACKOFF_DELAY")]
+  pub delay: u64,
+  /// The maximum delay (in milliseconds) between retries. Use &#x60;0&#x60; for no limit.
+
+  #[serde(default)]
+  pub max_delay: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// How delays between retries grow.
+pub enum BackoffStrategy {
+  /// Wait the same amount of time between every retry.
+  Fixed = 0,
+  /// Double the delay after every retry.
+  Exponential = 1,
+}
+
+impl Default for BackoffStrategy {
+  fn default() -> Self {
+    Self::from_u16(0).unwrap()
+  }
+}
+
+impl FromPrimitive for BackoffStrategy {
+  fn from_i64(n: i64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Fixed,
+      1 => Self::Exponential,
+      _ => {
+        return None;
+      }
+    })
+  }
+
+  fn from_u64(n: u64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Fixed,
+      1 => Self::Exponential,
+      _ => {
+        return None;
+      }
+    })
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// What to do when a scheduled run is due while a previous run is still executing.
+pub enum ConcurrencyPolicy {
+  /// Start the new run alongside the previous one.
+  Allow = 0,
+  /// Skip the new run.
+  Skip = 1,
+  /// Wait for the previous run to finish before starting the new one.
+  Queue = 2,
+}
+
+impl Default for ConcurrencyPolicy {
+  fn default() -> Self {
+    Self::from_u16(0).unwrap()
+  }
+}
+
+impl FromPrimitive for ConcurrencyPolicy {
+  fn from_i64(n: i64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Allow,
+      1 => Self::Skip,
+      2 => Self::Queue,
+      _ => {
+        return None;
+      }
+    })
+  }
+
+  fn from_u64(n: u64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Allow,
+      1 => Self::Skip,
+      2 => Self::Queue,
+      _ => {
+        return None;
+      }
+    })
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// What to do with runs that were due while the schedule was not running.
+pub enum CatchUpPolicy {
+  /// Skip missed runs and wait for the next scheduled time.
+  None = 0,
+  /// Run once immediately if any runs were missed.
+  One = 1,
+  /// Run every missed run immediately.
+  All = 2,
+}
+
+impl Default for CatchUpPolicy {
+  fn default() -> Self {
+    Self::from_u16(0).unwrap()
+  }
+}
+
+impl FromPrimitive for CatchUpPolicy {
+  fn from_i64(n: i64) -> Option<Self> {
+    Some(match n {
+      0 => Self::None,
+      1 => Self::One,
+      2 => Self::All,
+      _ => {
+        return None;
+      }
+    })
+  }
+
+  fn from_u64(n: u64) -> Option<Self> {
+    Some(match n {
+      0 => Self::None,
+      1 => Self::One,
+      2 => Self::All,
+      _ => {
+        return None;
+      }
+    })
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -358,6 +596,11 @@ pub struct ComponentOperationExpression {
 pub struct HttpTrigger {
   /// The TcpPort resource to listen on for connections.
   pub resource: String,
+  /// TLS settings to serve HTTPS directly from this trigger.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub tls: Option<HttpTls>,
   /// The router to handle incoming requests
 
   #[serde(default)]
@@ -365,6 +608,21 @@ pub struct HttpTrigger {
   pub routers: Vec<HttpRouter>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// TLS settings for an HTTP trigger. Certificates are reloaded when their files change.
+pub struct HttpTls {
+  /// The Volume or file:// URL resource that points to the PEM-encoded certificate chain.
+  pub cert: String,
+  /// The Volume or file:// URL resource that points to the PEM-encoded private key.
+  pub key: String,
+  /// The Volume or file:// URL resource that points to PEM-encoded CA certificates. When set, clients must present a certificate signed by one of these CAs.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub client_ca: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 #[serde(tag = "kind")]
@@ -382,6 +640,9 @@ pub enum HttpRouter {
   /// A variant representing a [ProxyRouter] type.
   #[serde(rename = "wick/router/proxy@v1")]
   ProxyRouter(ProxyRouter),
+  /// A variant representing a [StreamRouter] type.
+  #[serde(rename = "wick/router/stream@v1")]
+  StreamRouter(StreamRouter),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -395,12 +656,130 @@ pub struct ProxyRouter {
   #[serde(default)]
   #[serde(skip_serializing_if = "Option::is_none")]
   pub middleware: Option<Middleware>,
-  /// The URL resource to proxy to.
-  pub url: String,
+  /// The URL resource to proxy to. Required unless upstreams are configured.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub url: Option<String>,
   /// Whether or not to strip the router&#x27;s path from the proxied request.
 
   #[serde(default)]
   pub strip_path: bool,
+  /// Additional URL resources to balance requests across.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub upstreams: Vec<ProxyUpstream>,
+  /// How to choose the upstream for each request.
+
+  #[serde(default)]
+  pub balance: LoadBalance,
+  /// Periodic checks that take unhealthy upstreams out of rotation.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub health_check: Option<HealthCheck>,
+  /// How long to wait for an upstream to respond (in milliseconds).
+
+  #[serde(default = "PROXY_ROUTER_TIMEOUT")]
+  pub timeout: u64,
+  /// How many times to retry a request on another upstream when an upstream can&#x27;t be reached or, for idempotent methods, times out.
+
+  #[serde(default)]
+  pub retries: u32,
+  /// Rules that rewrite the headers of proxied requests.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub request_headers: Option<HeaderRewrite>,
+  /// Rules that rewrite the headers of proxied responses.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub response_headers: Option<HeaderRewrite>,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn PROXY_ROUTER_TIMEOUT() -> u64 {
+  30000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// An upstream the proxy router can forward requests to.
+pub struct ProxyUpstream {
+  /// The URL resource to proxy to.
+  pub url: String,
+  /// How long to wait for this upstream to respond (in milliseconds). Defaults to the router&#x27;s timeout.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Periodic checks that take unhealthy upstreams out of rotation.
+pub struct HealthCheck {
+  /// The path to request from each upstream. Any 2xx or 3xx response is healthy.
+
+  #[serde(default = "HEALTH_CHECK_PATH")]
+  pub path: String,
+  /// How often to check each upstream (in milliseconds).
+
+  #[serde(default = "HEALTH_CHECK_INTERVAL")]
+  pub interval: u64,
+  /// How long to wait for a check to respond (in milliseconds).
+
+  #[serde(default = "HEALTH_CHECK_TIMEOUT")]
+  pub timeout: u64,
+  /// How many checks in a row must fail before an upstream is taken out of rotation.
+
+  #[serde(default = "HEALTH_CHECK_FAILURES")]
+  pub failures: u32,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn HEALTH_CHECK_PATH() -> String {
+  "/".to_owned()
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn HEALTH_CHECK_INTERVAL() -> u64 {
+  10000
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn HEALTH_CHECK_TIMEOUT() -> u64 {
+  2000
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn HEALTH_CHECK_FAILURES() -> u32 {
+  2
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Rules that rewrite HTTP headers. Headers are removed, then set, then added.
+pub struct HeaderRewrite {
+  /// Headers to remove.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub remove: Vec<String>,
+  /// Headers to set, replacing any existing values.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  #[serde(deserialize_with = "crate::helpers::kv_deserializer")]
+  pub set: HashMap<String, String>,
+  /// Headers to add alongside any existing values.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  #[serde(deserialize_with = "crate::helpers::kv_deserializer")]
+  pub add: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -464,6 +843,26 @@ pub struct Route {
   #[serde(default)]
   #[serde(skip_serializing_if = "Option::is_none")]
   pub summary: Option<String>,
+  /// The output port that carries the response body. Defaults to every output port, collected into a JSON object keyed by port name.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub body_port: Option<String>,
+  /// The output port that carries the response status code.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub status_port: Option<String>,
+  /// The output port that carries a map of response headers.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub headers_port: Option<String>,
+  /// The content types this route can respond with, chosen by the request&#x27;s Accept header.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub content_types: Vec<ContentType>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -588,6 +987,78 @@ pub struct StaticRouter {
 
   #[serde(default)]
   pub indexes: bool,
+  /// Files to serve when a request doesn&#x27;t match a file, chosen by the first rule whose glob matches the request path. Checked before &#x60;fallback&#x60;.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub fallbacks: Vec<FallbackRule>,
+  /// How to compress the files this router serves.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub compression: Option<StaticCompression>,
+  /// Cache-Control headers to send, chosen by the first rule whose glob matches the served file.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub cache_control: Vec<CacheControlRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// A file to serve when a request doesn&#x27;t match a file.
+pub struct FallbackRule {
+  /// A glob matched against the request path, relative to the router&#x27;s path (e.g. &#x60;app/**&#x60;).
+  pub glob: String,
+  /// The file to serve, relative to the volume.
+  pub file: String,
+  /// The status code to respond with.
+
+  #[serde(default = "FALLBACK_RULE_STATUS")]
+  pub status: u16,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn FALLBACK_RULE_STATUS() -> u16 {
+  200
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// How the static router compresses the files it serves.
+pub struct StaticCompression {
+  /// Whether to serve a pre-compressed sibling of a file (e.g. &#x60;app.js.br&#x60; or &#x60;app.js.gz&#x60;) to clients that accept it.
+
+  #[serde(default)]
+  pub precompressed: bool,
+  /// Whether to compress files on the fly when there is no pre-compressed sibling.
+
+  #[serde(default)]
+  pub dynamic: bool,
+  /// The encodings to offer, in order of preference. Defaults to Brotli, then Gzip.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub encodings: Vec<ContentEncoding>,
+  /// The smallest file (in bytes) to compress on the fly.
+
+  #[serde(default = "STATIC_COMPRESSION_MIN_SIZE")]
+  pub min_size: u64,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn STATIC_COMPRESSION_MIN_SIZE() -> u64 {
+  1024
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// A Cache-Control header to send for matching files.
+pub struct CacheControlRule {
+  /// A glob matched against the served file&#x27;s path, relative to the volume (e.g. &#x60;assets/**&#x60; or &#x60;**/*.html&#x60;).
+  pub glob: String,
+  /// The Cache-Control header value to send (e.g. &#x60;public, max-age=31536000, immutable&#x60;).
+  pub value: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -613,6 +1084,29 @@ pub struct RawRouter {
   pub operation: ComponentOperationExpression,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// A router that upgrades requests to WebSocket or Server-Sent Events connections and streams messages to and from the configured operation for the lifetime of the connection.
+pub struct StreamRouter {
+  /// The path that this router will trigger for.
+  pub path: String,
+  /// Middleware operations for this router.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub middleware: Option<Middleware>,
+  /// The codec to use when encoding/decoding messages.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub codec: Option<Codec>,
+  /// The operation that handles connections.
+
+  #[serde(serialize_with = "crate::v1::helpers::serialize_component_expression")]
+  #[serde(deserialize_with = "crate::v1::parse::component_operation_syntax")]
+  pub operation: ComponentOperationExpression,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 /// Request and response operations that run before and after the main operation.
@@ -629,6 +1123,145 @@ pub struct Middleware {
   #[serde(skip_serializing_if = "Vec::is_empty")]
   #[serde(deserialize_with = "crate::v1::parse::vec_component_operation")]
   pub response: Vec<ComponentOperationExpression>,
+  /// Built-in middleware that runs, in order, before the request operations.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub builtin: Vec<BuiltinMiddleware>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+#[serde(tag = "kind")]
+/// Middleware that runs natively in the HTTP trigger, without a component.
+pub enum BuiltinMiddleware {
+  /// A variant representing a [CorsMiddleware] type.
+  #[serde(rename = "wick/middleware/cors@v1")]
+  CorsMiddleware(CorsMiddleware),
+  /// A variant representing a [RateLimitMiddleware] type.
+  #[serde(rename = "wick/middleware/ratelimit@v1")]
+  RateLimitMiddleware(RateLimitMiddleware),
+  /// A variant representing a [ApiKeyMiddleware] type.
+  #[serde(rename = "wick/middleware/apikey@v1")]
+  ApiKeyMiddleware(ApiKeyMiddleware),
+  /// A variant representing a [BasicAuthMiddleware] type.
+  #[serde(rename = "wick/middleware/basicauth@v1")]
+  BasicAuthMiddleware(BasicAuthMiddleware),
+  /// A variant representing a [JwtMiddleware] type.
+  #[serde(rename = "wick/middleware/jwt@v1")]
+  JwtMiddleware(JwtMiddleware),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Adds Cross-Origin Resource Sharing (CORS) headers to responses and answers CORS preflight requests.
+pub struct CorsMiddleware {
+  /// The origins allowed to make cross-origin requests. Use &#x60;*&#x60; to allow any origin.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub allowed_origins: Vec<String>,
+  /// The methods allowed in cross-origin requests. Any method is allowed if empty.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub allowed_methods: Vec<HttpMethod>,
+  /// The headers allowed in cross-origin requests. Any requested header is allowed if empty.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub allowed_headers: Vec<String>,
+  /// The response headers that browsers can expose to scripts.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub exposed_headers: Vec<String>,
+  /// Whether browsers can send credentials with cross-origin requests. Can't be combined with the &#x60;*&#x60; origin.
+
+  #[serde(default)]
+  pub allow_credentials: bool,
+  /// How long (in seconds) browsers can cache a preflight response.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_age: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Limits how many requests each client IP address can make.
+pub struct RateLimitMiddleware {
+  /// The number of requests each client can make per window.
+  pub requests: u32,
+  /// The length of the window (in milliseconds).
+
+  #[serde(default = "RATE_LIMIT_MIDDLEWARE_WINDOW")]
+  pub window: u64,
+  /// A header that identifies clients instead of their IP address, like `x-forwarded-for`. The first comma-separated value is used. Only set this behind a proxy that sets the header.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub key_header: Option<String>,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn RATE_LIMIT_MIDDLEWARE_WINDOW() -> u64 {
+  1000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Requires requests to carry one of the configured API keys in a header.
+pub struct ApiKeyMiddleware {
+  /// The header that carries the key.
+
+  #[serde(default = "API_KEY_MIDDLEWARE_HEADER")]
+  pub header: String,
+  /// The accepted keys.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub keys: Vec<LiquidTemplate>,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn API_KEY_MIDDLEWARE_HEADER() -> String {
+  "x-api-key".to_owned()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Requires requests to authenticate with HTTP Basic authentication.
+pub struct BasicAuthMiddleware {
+  /// The accepted usernames and their passwords.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  #[serde(deserialize_with = "crate::helpers::kv_deserializer")]
+  pub users: HashMap<String, LiquidTemplate>,
+  /// The realm reported to clients that fail to authenticate.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub realm: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Requires requests to carry a bearer JSON Web Token (JWT) signed by a key from a JSON Web Key Set (JWKS).
+pub struct JwtMiddleware {
+  /// The Url or Volume resource that points to the JWKS.
+  pub jwks: String,
+  /// The issuer (&#x60;iss&#x60; claim) that tokens must have.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub issuer: Option<String>,
+  /// The audiences (&#x60;aud&#x60; claim) that tokens can be issued for. Any audience is accepted if empty.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub audience: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -944,6 +1577,9 @@ pub enum ImportDefinition {
   /// A variant representing a [ManifestComponent] type.
   #[serde(rename = "wick/component/manifest@v1")]
   ManifestComponent(ManifestComponent),
+  /// A variant representing a [GrpcUrlComponent] type.
+  #[serde(rename = "wick/component/grpc@v1")]
+  GrpcUrlComponent(GrpcUrlComponent),
   /// A variant representing a [SqlComponent] type.
   #[serde(rename = "wick/component/sql@v1")]
   SqlComponent(SqlComponent),
@@ -1489,19 +2125,87 @@ pub struct OperationInstance {
   pub name: String,
   /// The operation to bind to.
 
-  #[serde(serialize_with = "crate::v1::helpers::serialize_component_expression")]
-  #[serde(deserialize_with = "crate::v1::parse::component_operation_syntax")]
-  pub operation: ComponentOperationExpression,
-  /// Data to associate with the reference, if any.
+  #[serde(serialize_with = "crate::v1::helpers::serialize_component_expression")]
+  #[serde(deserialize_with = "crate::v1::parse::component_operation_syntax")]
+  pub operation: ComponentOperationExpression,
+  /// Data to associate with the reference, if any.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub with: Option<HashMap<String, liquid_json::LiquidJsonValue>>,
+  /// Timeout (in milliseconds) to wait for the operation to complete. Use 0 to wait indefinitely.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<u64>,
+  /// How the operation is retried when it fails.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub retry: Option<OperationRetry>,
+  /// Stop calling the operation&#x27;s component after repeated failures.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub circuit_breaker: Option<CircuitBreaker>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// How a failed operation is retried. The operation is invoked again with the same inputs and its outputs are only passed on once an attempt succeeds or the last attempt fails.
+pub struct OperationRetry {
+  /// The number of times to retry a failed operation before giving up.
+
+  #[serde(default = "OPERATION_RETRY_RETRIES")]
+  pub retries: u16,
+  /// How long to wait between retries.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub backoff: Option<Backoff>,
+  /// Whether to retry operations that time out.
+
+  #[serde(default = "OPERATION_RETRY_TIMEOUTS")]
+  pub timeouts: bool,
+  /// Only retry errors whose message contains one of these strings. Every error is retried if empty.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub errors: Vec<String>,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn OPERATION_RETRY_RETRIES() -> u16 {
+  3
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn OPERATION_RETRY_TIMEOUTS() -> bool {
+  true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Fails calls to a component immediately after it fails repeatedly, giving it time to recover. The circuit is shared by every operation instance that uses the component.
+pub struct CircuitBreaker {
+  /// The number of consecutive failures that open the circuit.
 
-  #[serde(default)]
-  #[serde(skip_serializing_if = "Option::is_none")]
-  pub with: Option<HashMap<String, liquid_json::LiquidJsonValue>>,
-  /// Timeout (in milliseconds) to wait for the operation to complete. Use 0 to wait indefinitely.
+  #[serde(default = "CIRCUIT_BREAKER_FAILURES")]
+  pub failures: u32,
+  /// How long (in milliseconds) the circuit stays open before a trial call is let through.
 
-  #[serde(default)]
-  #[serde(skip_serializing_if = "Option::is_none")]
-  pub timeout: Option<u64>,
+  #[serde(default = "CIRCUIT_BREAKER_RESET_AFTER")]
+  pub reset_after: u64,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn CIRCUIT_BREAKER_FAILURES() -> u32 {
+  5
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn CIRCUIT_BREAKER_RESET_AFTER() -> u64 {
+  30000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -1784,6 +2488,11 @@ pub struct SqlComponent {
   #[serde(default)]
   #[serde(skip_serializing_if = "Vec::is_empty")]
   pub operations: Vec<SqlQueryKind>,
+  /// A directory of versioned &#x60;.sql&#x60; migrations (e.g. &#x60;0001_create_users.sql&#x60;) to apply when the component starts.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub migrations: Option<crate::v1::helpers::LocationReference>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -1870,6 +2579,30 @@ pub struct SqlExecOperationDefinition {
   #[serde(default)]
   #[serde(skip_serializing_if = "Option::is_none")]
   pub on_error: Option<ErrorBehavior>,
+  /// Group input rows into batches that run as a single statement. Each batch outputs its total number of affected rows.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub batch: Option<SqlBatch>,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn SQL_BATCH_SIZE() -> u32 {
+  100
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// How input rows are grouped into batches that run as a single statement.
+pub struct SqlBatch {
+  /// The maximum number of input rows in a batch.
+
+  #[serde(default = "SQL_BATCH_SIZE")]
+  pub size: u32,
+  /// The maximum time (in milliseconds) to wait for a batch to fill before running it. Use &#x60;0&#x60; to wait until the batch is full or the input ends.
+
+  #[serde(default)]
+  pub window: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
@@ -1937,6 +2670,35 @@ pub struct HttpClientComponent {
   #[serde(default)]
   #[serde(skip_serializing_if = "Vec::is_empty")]
   pub operations: Vec<HttpClientOperationDefinition>,
+  /// The default time (in milliseconds) each request has to complete. Requests do not time out if omitted.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<u64>,
+  /// The time (in milliseconds) allowed to establish a connection.
+
+  #[serde(default = "HTTP_CLIENT_COMPONENT_CONNECT_TIMEOUT")]
+  pub connect_timeout: u64,
+  /// The User-Agent header to send with every request.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub user_agent: Option<String>,
+  /// How failed requests are retried. Can be overridden by individual operations.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub retry: Option<RetryPolicy>,
+  /// How requests authenticate with the server.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub auth: Option<HttpClientAuth>,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn HTTP_CLIENT_COMPONENT_CONNECT_TIMEOUT() -> u64 {
+  5000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -1980,6 +2742,227 @@ pub struct HttpClientOperationDefinition {
 
   #[serde(default)]
   pub path: String,
+  /// The time (in milliseconds) each request has to complete. Overrides the component&#x27;s timeout.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<u64>,
+  /// How failed requests are retried. Overrides the component&#x27;s retry policy.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub retry: Option<RetryPolicy>,
+  /// How to follow paginated responses.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub pagination: Option<Pagination>,
+  /// The input whose packets are streamed as a chunked request body instead of rendering &#x60;body&#x60;. The other inputs are read once to render the path and headers.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub request_stream: Option<String>,
+  /// Emit the response body incrementally as it arrives instead of buffering it.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub response_stream: Option<ResponseStream>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// How failed HTTP requests are retried. Only requests with idempotent methods are retried.
+pub struct RetryPolicy {
+  /// The number of times to retry a failed request before giving up.
+
+  #[serde(default = "RETRY_POLICY_RETRIES")]
+  pub retries: u16,
+  /// How long to wait between retries. A &#x60;Retry-After&#x60; header from the server takes precedence, up to the backoff&#x27;s &#x60;max_delay&#x60; or 60 seconds without one.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub backoff: Option<Backoff>,
+  /// The response statuses to retry. Defaults to 429, 502, 503 and 504 if empty.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub statuses: Vec<u16>,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn RETRY_POLICY_RETRIES() -> u16 {
+  3
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+#[serde(tag = "kind")]
+/// How an HTTP client component authenticates its requests.
+pub enum HttpClientAuth {
+  /// A variant representing a [BearerAuth] type.
+  #[serde(rename = "wick/auth/bearer@v1")]
+  BearerAuth(BearerAuth),
+  /// A variant representing a [BasicAuth] type.
+  #[serde(rename = "wick/auth/basic@v1")]
+  BasicAuth(BasicAuth),
+  /// A variant representing a [OAuth2ClientCredentials] type.
+  #[serde(rename = "wick/auth/oauth2@v1")]
+  OAuth2ClientCredentials(OAuth2ClientCredentials),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Sends a static bearer token in the Authorization header.
+pub struct BearerAuth {
+  /// The token to send.
+  pub token: LiquidTemplate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Sends a username and password with HTTP Basic authentication.
+pub struct BasicAuth {
+  /// The username to send.
+  pub username: LiquidTemplate,
+  /// The password to send.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub password: Option<LiquidTemplate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Fetches a bearer token with the OAuth2 client credentials grant and reuses it until it expires.
+pub struct OAuth2ClientCredentials {
+  /// The URL of the token endpoint.
+  pub token_url: String,
+  /// The client ID to authenticate with.
+  pub client_id: LiquidTemplate,
+  /// The client secret to authenticate with.
+  pub client_secret: LiquidTemplate,
+  /// The scopes to request.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// How to follow paginated responses. Every page is emitted as a &#x60;response&#x60; packet and every item as a separate &#x60;body&#x60; packet.
+pub struct Pagination {
+  /// How the next page is found.
+
+  #[serde(default)]
+  pub mode: PaginationMode,
+  /// The dot-separated path to the array of items in each page. The whole page is treated as one item if omitted.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub items: Option<String>,
+  /// The dot-separated path to the next page&#x27;s cursor in each page. Required for the &#x60;Cursor&#x60; mode.
+
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub cursor: Option<String>,
+  /// The query parameter the cursor is sent in. Cursors that are absolute URLs are requested as-is.
+
+  #[serde(default = "PAGINATION_CURSOR_PARAM")]
+  pub cursor_param: String,
+  /// The maximum number of pages to request. Use &#x60;0&#x60; for no limit.
+
+  #[serde(default)]
+  pub max_pages: u32,
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn PAGINATION_CURSOR_PARAM() -> String {
+  "cursor".to_owned()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// How the next page of a paginated response is found.
+pub enum PaginationMode {
+  /// Follow the &#x60;next&#x60; URL in the response&#x27;s &#x60;Link&#x60; header.
+  Link = 0,
+  /// Send the cursor found in the response body with the next request.
+  Cursor = 1,
+}
+
+impl Default for PaginationMode {
+  fn default() -> Self {
+    Self::from_u16(0).unwrap()
+  }
+}
+
+impl FromPrimitive for PaginationMode {
+  fn from_i64(n: i64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Link,
+      1 => Self::Cursor,
+      _ => {
+        return None;
+      }
+    })
+  }
+
+  fn from_u64(n: u64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Link,
+      1 => Self::Cursor,
+      _ => {
+        return None;
+      }
+    })
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// How a streamed response body is split into &#x60;body&#x60; packets.
+pub enum ResponseStream {
+  /// Emit each chunk of bytes as it is received.
+  Chunks = 0,
+  /// Emit each line as a string.
+  Lines = 1,
+  /// Parse each line as JSON and emit it as an object.
+  Ndjson = 2,
+  /// Parse server-sent events and emit each event as an object with &#x60;event&#x60;, &#x60;data&#x60; and &#x60;id&#x60; fields.
+  Sse = 3,
+}
+
+impl Default for ResponseStream {
+  fn default() -> Self {
+    Self::from_u16(0).unwrap()
+  }
+}
+
+impl FromPrimitive for ResponseStream {
+  fn from_i64(n: i64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Chunks,
+      1 => Self::Lines,
+      2 => Self::Ndjson,
+      3 => Self::Sse,
+      _ => {
+        return None;
+      }
+    })
+  }
+
+  fn from_u64(n: u64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Chunks,
+      1 => Self::Lines,
+      2 => Self::Ndjson,
+      3 => Self::Sse,
+      _ => {
+        return None;
+      }
+    })
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
@@ -2028,6 +3011,128 @@ impl FromPrimitive for Codec {
   }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Encodings the static router can compress files with.
+pub enum ContentEncoding {
+  /// Gzip compression.
+  Gzip = 0,
+  /// Brotli compression.
+  Brotli = 1,
+}
+
+impl Default for ContentEncoding {
+  fn default() -> Self {
+    Self::from_u16(0).unwrap()
+  }
+}
+
+impl FromPrimitive for ContentEncoding {
+  fn from_i64(n: i64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Gzip,
+      1 => Self::Brotli,
+      _ => {
+        return None;
+      }
+    })
+  }
+
+  fn from_u64(n: u64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Gzip,
+      1 => Self::Brotli,
+      _ => {
+        return None;
+      }
+    })
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// How the proxy router chooses an upstream for each request.
+pub enum LoadBalance {
+  /// Send requests to each upstream in turn.
+  RoundRobin = 0,
+  /// Send requests to the upstream with the fewest requests in flight.
+  LeastConnections = 1,
+}
+
+impl Default for LoadBalance {
+  fn default() -> Self {
+    Self::from_u16(0).unwrap()
+  }
+}
+
+impl FromPrimitive for LoadBalance {
+  fn from_i64(n: i64) -> Option<Self> {
+    Some(match n {
+      0 => Self::RoundRobin,
+      1 => Self::LeastConnections,
+      _ => {
+        return None;
+      }
+    })
+  }
+
+  fn from_u64(n: u64) -> Option<Self> {
+    Some(match n {
+      0 => Self::RoundRobin,
+      1 => Self::LeastConnections,
+      _ => {
+        return None;
+      }
+    })
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// Content types a REST route can respond with.
+pub enum ContentType {
+  /// A single JSON document.
+  Json = 0,
+  /// Newline-delimited JSON, one line per output packet.
+  Ndjson = 1,
+  /// Comma-separated values, one or more rows per output packet.
+  Csv = 2,
+  /// The raw bytes of every output packet.
+  Raw = 3,
+}
+
+impl Default for ContentType {
+  fn default() -> Self {
+    Self::from_u16(0).unwrap()
+  }
+}
+
+impl FromPrimitive for ContentType {
+  fn from_i64(n: i64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Json,
+      1 => Self::Ndjson,
+      2 => Self::Csv,
+      3 => Self::Raw,
+      _ => {
+        return None;
+      }
+    })
+  }
+
+  fn from_u64(n: u64) -> Option<Self> {
+    Some(match n {
+      0 => Self::Json,
+      1 => Self::Ndjson,
+      2 => Self::Csv,
+      3 => Self::Raw,
+      _ => {
+        return None;
+      }
+    })
+  }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq)]
 #[serde(deny_unknown_fields)]
 /// Supported HTTP methods
@@ -2040,6 +3145,14 @@ pub enum HttpMethod {
   Put = 2,
   /// DELETE method
   Delete = 3,
+  /// PATCH method
+  Patch = 4,
+  /// HEAD method
+  Head = 5,
+  /// OPTIONS method
+  Options = 6,
+  /// TRACE method
+  Trace = 7,
 }
 
 impl Default for HttpMethod {
@@ -2055,6 +3168,10 @@ impl FromPrimitive for HttpMethod {
       1 => Self::Post,
       2 => Self::Put,
       3 => Self::Delete,
+      4 => Self::Patch,
+      5 => Self::Head,
+      6 => Self::Options,
+      7 => Self::Trace,
       _ => {
         return None;
       }
@@ -2067,6 +3184,10 @@ impl FromPrimitive for HttpMethod {
       1 => Self::Post,
       2 => Self::Put,
       3 => Self::Delete,
+      4 => Self::Patch,
+      5 => Self::Head,
+      6 => Self::Options,
+      7 => Self::Trace,
       _ => {
         return None;
       }
@@ -25,17 +25,29 @@ use crate::config::{
 use crate::config::{
   AppConfiguration,
   Binding,
+  CacheControlRule,
   CliConfig,
+  ContentEncoding,
+  FallbackRule,
+  HeaderRewrite,
+  HealthCheck,
   HttpRouterConfig,
+  HttpTlsConfig,
   HttpTriggerConfig,
+  LoadBalance,
   ProxyRouterConfig,
+  ProxyUpstream,
+  QueueTriggerConfig,
   RawRouterConfig,
   RestRouterConfig,
+  StaticCompression,
   StaticRouterConfig,
+  StreamRouterConfig,
   TcpPort,
   TimeTriggerConfig,
   TriggerDefinition,
   UdpPort,
+  WatchTriggerConfig,
 };
 use crate::error::ManifestError;
 use crate::utils::{opt_str_to_ipv4addr, VecMapInto, VecTryMapInto};
@@ -280,6 +292,8 @@ impl TryFrom<TriggerDefinition> for v1::TriggerDefinition {
       TriggerDefinition::Http(v) => v1::TriggerDefinition::HttpTrigger(v.try_into()?),
       TriggerDefinition::Cli(v) => v1::TriggerDefinition::CliTrigger(v.try_into()?),
       TriggerDefinition::Time(v) => v1::TriggerDefinition::TimeTrigger(v.try_into()?),
+      TriggerDefinition::Queue(v) => v1::TriggerDefinition::QueueTrigger(v.try_into()?),
+      TriggerDefinition::Watch(v) => v1::TriggerDefinition::WatchTrigger(v.try_into()?),
     })
   }
 }
@@ -297,12 +311,45 @@ impl TryFrom<TimeTriggerConfig> for v1::TimeTrigger {
   }
 }
 
+impl TryFrom<QueueTriggerConfig> for v1::QueueTrigger {
+  type Error = ManifestError;
+  fn try_from(value: QueueTriggerConfig) -> Result<Self> {
+    Ok(Self {
+      resource: value.resource,
+      subject: value.subject,
+      operation: value.operation.try_into()?,
+      requeue: value.requeue,
+    })
+  }
+}
+
+impl TryFrom<WatchTriggerConfig> for v1::WatchTrigger {
+  type Error = ManifestError;
+  fn try_from(value: WatchTriggerConfig) -> Result<Self> {
+    Ok(Self {
+      resource: value.resource,
+      operation: value.operation.try_into()?,
+      include: value.include.try_map_into()?,
+      exclude: value.exclude.try_map_into()?,
+      contents: value.contents,
+    })
+  }
+}
+
 impl TryFrom<ScheduleConfig> for v1::Schedule {
   type Error = ManifestError;
   fn try_from(value: ScheduleConfig) -> Result<Self> {
     Ok(Self {
       cron: value.cron,
       repeat: value.repeat,
+      retries: value.retries,
+      backoff: Some(value.backoff.into()),
+      concurrency: Some(value.concurrency.into()),
+      jitter: value.jitter,
+      dead_letter: value.dead_letter.map(TryInto::try_into).transpose()?,
+      state: value.state,
+      catch_up: Some(value.catch_up.into()),
+      timezone: value.timezone,
     })
   }
 }
@@ -313,10 +360,96 @@ impl TryFrom<v1::Schedule> for ScheduleConfig {
     Ok(Self {
       cron: value.cron,
       repeat: value.repeat,
+      retries: value.retries,
+      backoff: value.backoff.map(Into::into).unwrap_or_default(),
+      concurrency: value.concurrency.unwrap_or_default().into(),
+      jitter: value.jitter,
+      dead_letter: value.dead_letter.map(TryInto::try_into).transpose()?,
+      state: value.state,
+      catch_up: value.catch_up.unwrap_or_default().into(),
+      timezone: value.timezone,
     })
   }
 }
 
+impl From<config::BackoffConfig> for v1::Backoff {
+  fn from(value: config::BackoffConfig) -> Self {
+    Self {
+      strategy: Some(value.strategy.into()),
+      delay: value.delay,
+      max_delay: value.max_delay,
+    }
+  }
+}
+
+impl From<v1::Backoff> for config::BackoffConfig {
+  fn from(value: v1::Backoff) -> Self {
+    Self {
+      strategy: value.strategy.unwrap_or_default().into(),
+      delay: value.delay,
+      max_delay: value.max_delay,
+    }
+  }
+}
+
+impl From<config::BackoffStrategy> for v1::BackoffStrategy {
+  fn from(value: config::BackoffStrategy) -> Self {
+    match value {
+      config::BackoffStrategy::Fixed => Self::Fixed,
+      config::BackoffStrategy::Exponential => Self::Exponential,
+    }
+  }
+}
+
+impl From<v1::BackoffStrategy> for config::BackoffStrategy {
+  fn from(value: v1::BackoffStrategy) -> Self {
+    match value {
+      v1::BackoffStrategy::Fixed => Self::Fixed,
+      v1::BackoffStrategy::Exponential => Self::Exponential,
+    }
+  }
+}
+
+impl From<config::CatchUpPolicy> for v1::CatchUpPolicy {
+  fn from(value: config::CatchUpPolicy) -> Self {
+    match value {
+      config::CatchUpPolicy::None => Self::None,
+      config::CatchUpPolicy::One => Self::One,
+      config::CatchUpPolicy::All => Self::All,
+    }
+  }
+}
+
+impl From<v1::CatchUpPolicy> for config::CatchUpPolicy {
+  fn from(value: v1::CatchUpPolicy) -> Self {
+    match value {
+      v1::CatchUpPolicy::None => Self::None,
+      v1::CatchUpPolicy::One => Self::One,
+      v1::CatchUpPolicy::All => Self::All,
+    }
+  }
+}
+
+impl From<config::ConcurrencyPolicy> for v1::ConcurrencyPolicy {
+  fn from(value: config::ConcurrencyPolicy) -> Self {
+    match value {
+      config::ConcurrencyPolicy::Allow => Self::Allow,
+      config::ConcurrencyPolicy::Skip => Self::Skip,
+      config::ConcurrencyPolicy::Queue => Self::Queue,
+    }
+  }
+}
+
+impl From<v1::ConcurrencyPolicy> for config::ConcurrencyPolicy {
+  fn from(value: v1::ConcurrencyPolicy) -> Self {
+    match value {
+      v1::ConcurrencyPolicy::Allow => Self::Allow,
+      v1::ConcurrencyPolicy::Skip => Self::Skip,
+      v1::ConcurrencyPolicy::Queue => Self::Queue,
+    }
+  }
+}
+
 // Implement conversion from OperationInputConfig to v1::OperationInput
 impl TryFrom<config::OperationInputConfig> for v1::OperationInput {
   type Error = ManifestError;
@@ -355,11 +488,32 @@ impl TryFrom<HttpTriggerConfig> for v1::HttpTrigger {
   fn try_from(value: HttpTriggerConfig) -> Result<Self> {
     Ok(Self {
       resource: value.resource,
+      tls: value.tls.map(Into::into),
       routers: value.routers.into_iter().map(|v| v.try_into()).collect::<Result<_>>()?,
     })
   }
 }
 
+impl From<HttpTlsConfig> for v1::HttpTls {
+  fn from(value: HttpTlsConfig) -> Self {
+    Self {
+      cert: value.cert,
+      key: value.key,
+      client_ca: value.client_ca,
+    }
+  }
+}
+
+impl From<v1::HttpTls> for HttpTlsConfig {
+  fn from(value: v1::HttpTls) -> Self {
+    Self {
+      cert: value.cert,
+      key: value.key,
+      client_ca: value.client_ca,
+    }
+  }
+}
+
 impl TryFrom<HttpRouterConfig> for v1::HttpRouter {
   type Error = ManifestError;
   fn try_from(value: HttpRouterConfig) -> Result<Self> {
@@ -368,6 +522,7 @@ impl TryFrom<HttpRouterConfig> for v1::HttpRouter {
       HttpRouterConfig::RestRouter(v) => v1::HttpRouter::RestRouter(v.try_into()?),
       HttpRouterConfig::StaticRouter(v) => v1::HttpRouter::StaticRouter(v.try_into()?),
       HttpRouterConfig::ProxyRouter(v) => v1::HttpRouter::ProxyRouter(v.try_into()?),
+      HttpRouterConfig::StreamRouter(v) => v1::HttpRouter::StreamRouter(v.try_into()?),
     })
   }
 }
@@ -380,10 +535,95 @@ impl TryFrom<ProxyRouterConfig> for v1::ProxyRouter {
       url: value.url,
       strip_path: value.strip_path,
       middleware: value.middleware.try_map_into()?,
+      upstreams: value.upstreams.map_into(),
+      balance: value.balance.into(),
+      health_check: value.health_check.map_into(),
+      timeout: value.timeout,
+      retries: value.retries,
+      request_headers: value.request_headers.map_into(),
+      response_headers: value.response_headers.map_into(),
     })
   }
 }
 
+impl From<ProxyUpstream> for v1::ProxyUpstream {
+  fn from(value: ProxyUpstream) -> Self {
+    Self {
+      url: value.url,
+      timeout: value.timeout,
+    }
+  }
+}
+
+impl From<v1::ProxyUpstream> for ProxyUpstream {
+  fn from(value: v1::ProxyUpstream) -> Self {
+    Self {
+      url: value.url,
+      timeout: value.timeout,
+    }
+  }
+}
+
+impl From<HealthCheck> for v1::HealthCheck {
+  fn from(value: HealthCheck) -> Self {
+    Self {
+      path: value.path,
+      interval: value.interval,
+      timeout: value.timeout,
+      failures: value.failures,
+    }
+  }
+}
+
+impl From<v1::HealthCheck> for HealthCheck {
+  fn from(value: v1::HealthCheck) -> Self {
+    Self {
+      path: value.path,
+      interval: value.interval,
+      timeout: value.timeout,
+      failures: value.failures,
+    }
+  }
+}
+
+impl From<HeaderRewrite> for v1::HeaderRewrite {
+  fn from(value: HeaderRewrite) -> Self {
+    Self {
+      remove: value.remove,
+      set: value.set,
+      add: value.add,
+    }
+  }
+}
+
+impl From<v1::HeaderRewrite> for HeaderRewrite {
+  fn from(value: v1::HeaderRewrite) -> Self {
+    Self {
+      remove: value.remove,
+      set: value.set,
+      add: value.add,
+    }
+  }
+}
+
+impl From<LoadBalance> for v1::LoadBalance {
+  fn from(value: LoadBalance) -> Self {
+    match value {
+      LoadBalance::RoundRobin => Self::RoundRobin,
+      LoadBalance::LeastConnections => Self::LeastConnections,
+    }
+  }
+}
+
+impl From<v1::LoadBalance> for LoadBalance {
+  fn from(value: v1::LoadBalance) -> Self {
+    match value {
+      v1::LoadBalance::RoundRobin => Self::RoundRobin,
+      v1::LoadBalance::LeastConnections => Self::LeastConnections,
+    }
+  }
+}
+
 impl TryFrom<StaticRouterConfig> for v1::StaticRouter {
   type Error = ManifestError;
   fn try_from(value: StaticRouterConfig) -> Result<Self> {
@@ -393,10 +633,91 @@ impl TryFrom<StaticRouterConfig> for v1::StaticRouter {
       fallback: value.fallback,
       middleware: value.middleware.try_map_into()?,
       indexes: value.indexes,
+      fallbacks: value.fallbacks.map_into(),
+      compression: value.compression.map_into(),
+      cache_control: value.cache_control.map_into(),
     })
   }
 }
 
+impl From<FallbackRule> for v1::FallbackRule {
+  fn from(value: FallbackRule) -> Self {
+    Self {
+      glob: value.glob,
+      file: value.file,
+      status: value.status,
+    }
+  }
+}
+
+impl From<v1::FallbackRule> for FallbackRule {
+  fn from(value: v1::FallbackRule) -> Self {
+    Self {
+      glob: value.glob,
+      file: value.file,
+      status: value.status,
+    }
+  }
+}
+
+impl From<StaticCompression> for v1::StaticCompression {
+  fn from(value: StaticCompression) -> Self {
+    Self {
+      precompressed: value.precompressed,
+      dynamic: value.dynamic,
+      encodings: value.encodings.map_into(),
+      min_size: value.min_size,
+    }
+  }
+}
+
+impl From<v1::StaticCompression> for StaticCompression {
+  fn from(value: v1::StaticCompression) -> Self {
+    Self {
+      precompressed: value.precompressed,
+      dynamic: value.dynamic,
+      encodings: value.encodings.map_into(),
+      min_size: value.min_size,
+    }
+  }
+}
+
+impl From<CacheControlRule> for v1::CacheControlRule {
+  fn from(value: CacheControlRule) -> Self {
+    Self {
+      glob: value.glob,
+      value: value.value,
+    }
+  }
+}
+
+impl From<v1::CacheControlRule> for CacheControlRule {
+  fn from(value: v1::CacheControlRule) -> Self {
+    Self {
+      glob: value.glob,
+      value: value.value,
+    }
+  }
+}
+
+impl From<ContentEncoding> for v1::ContentEncoding {
+  fn from(value: ContentEncoding) -> Self {
+    match value {
+      ContentEncoding::Gzip => Self::Gzip,
+      ContentEncoding::Brotli => Self::Brotli,
+    }
+  }
+}
+
+impl From<v1::ContentEncoding> for ContentEncoding {
+  fn from(value: v1::ContentEncoding) -> Self {
+    match value {
+      v1::ContentEncoding::Gzip => Self::Gzip,
+      v1::ContentEncoding::Brotli => Self::Brotli,
+    }
+  }
+}
+
 impl TryFrom<RawRouterConfig> for v1::RawRouter {
   type Error = ManifestError;
   fn try_from(value: RawRouterConfig) -> Result<Self> {
@@ -409,6 +730,18 @@ impl TryFrom<RawRouterConfig> for v1::RawRouter {
   }
 }
 
+impl TryFrom<StreamRouterConfig> for v1::StreamRouter {
+  type Error = ManifestError;
+  fn try_from(value: StreamRouterConfig) -> Result<Self> {
+    Ok(Self {
+      path: value.path,
+      codec: value.codec.map_into(),
+      operation: value.operation.try_into()?,
+      middleware: value.middleware.try_map_into()?,
+    })
+  }
+}
+
 impl TryFrom<RestRouterConfig> for v1::RestRouter {
   type Error = ManifestError;
   fn try_from(value: RestRouterConfig) -> Result<Self> {
@@ -429,6 +762,7 @@ impl TryFrom<config::Middleware> for v1::Middleware {
     Ok(Self {
       request: value.request.try_map_into()?,
       response: value.response.try_map_into()?,
+      builtin: value.builtin.try_map_into()?,
     })
   }
 }
@@ -440,10 +774,87 @@ impl TryFrom<v1::Middleware> for config::Middleware {
     Ok(Self {
       request: value.request.try_map_into()?,
       response: value.response.try_map_into()?,
+      builtin: value.builtin.map_into(),
     })
   }
 }
 
+impl TryFrom<config::BuiltinMiddleware> for v1::BuiltinMiddleware {
+  type Error = ManifestError;
+
+  fn try_from(value: config::BuiltinMiddleware) -> Result<Self> {
+    Ok(match value {
+      config::BuiltinMiddleware::Cors(v) => Self::CorsMiddleware(v1::CorsMiddleware {
+        allowed_origins: v.allowed_origins,
+        allowed_methods: v.allowed_methods.map_into(),
+        allowed_headers: v.allowed_headers,
+        exposed_headers: v.exposed_headers,
+        allow_credentials: v.allow_credentials,
+        max_age: v.max_age,
+      }),
+      config::BuiltinMiddleware::RateLimit(v) => Self::RateLimitMiddleware(v1::RateLimitMiddleware {
+        requests: v.requests,
+        window: v.window,
+        key_header: v.key_header,
+      }),
+      config::BuiltinMiddleware::ApiKey(v) => Self::ApiKeyMiddleware(v1::ApiKeyMiddleware {
+        header: v.header,
+        keys: v.keys.iter().map(TemplateConfig::unrender).collect::<Result<_>>()?,
+      }),
+      config::BuiltinMiddleware::BasicAuth(v) => Self::BasicAuthMiddleware(v1::BasicAuthMiddleware {
+        users: v
+          .users
+          .into_iter()
+          .map(|(user, password)| Ok((user, password.unrender()?)))
+          .collect::<Result<_>>()?,
+        realm: v.realm,
+      }),
+      config::BuiltinMiddleware::Jwt(v) => Self::JwtMiddleware(v1::JwtMiddleware {
+        jwks: v.jwks,
+        issuer: v.issuer,
+        audience: v.audience,
+      }),
+    })
+  }
+}
+
+impl From<v1::BuiltinMiddleware> for config::BuiltinMiddleware {
+  fn from(value: v1::BuiltinMiddleware) -> Self {
+    match value {
+      v1::BuiltinMiddleware::CorsMiddleware(v) => Self::Cors(config::CorsMiddleware {
+        allowed_origins: v.allowed_origins,
+        allowed_methods: v.allowed_methods.map_into(),
+        allowed_headers: v.allowed_headers,
+        exposed_headers: v.exposed_headers,
+        allow_credentials: v.allow_credentials,
+        max_age: v.max_age,
+      }),
+      v1::BuiltinMiddleware::RateLimitMiddleware(v) => Self::RateLimit(config::RateLimitMiddleware {
+        requests: v.requests,
+        window: v.window,
+        key_header: v.key_header,
+      }),
+      v1::BuiltinMiddleware::ApiKeyMiddleware(v) => Self::ApiKey(config::ApiKeyMiddleware {
+        header: v.header,
+        keys: v.keys.into_iter().map(TemplateConfig::new_template).collect(),
+      }),
+      v1::BuiltinMiddleware::BasicAuthMiddleware(v) => Self::BasicAuth(config::BasicAuthMiddleware {
+        users: v
+          .users
+          .into_iter()
+          .map(|(user, password)| (user, TemplateConfig::new_template(password)))
+          .collect(),
+        realm: v.realm,
+      }),
+      v1::BuiltinMiddleware::JwtMiddleware(v) => Self::Jwt(config::JwtMiddleware {
+        jwks: v.jwks,
+        issuer: v.issuer,
+        audience: v.audience,
+      }),
+    }
+  }
+}
+
 impl TryFrom<config::Tools> for v1::Tools {
   type Error = ManifestError;
 
@@ -471,6 +882,10 @@ impl TryFrom<v1::Route> for config::RestRoute {
       operation: value.operation.try_into()?,
       description: value.description,
       summary: value.summary,
+      body_port: value.body_port,
+      status_port: value.status_port,
+      headers_port: value.headers_port,
+      content_types: value.content_types.map_into(),
     })
   }
 }
@@ -486,10 +901,36 @@ impl TryFrom<config::RestRoute> for v1::Route {
       operation: value.operation.try_into()?,
       description: value.description,
       summary: value.summary,
+      body_port: value.body_port,
+      status_port: value.status_port,
+      headers_port: value.headers_port,
+      content_types: value.content_types.map_into(),
     })
   }
 }
 
+impl From<config::ContentType> for v1::ContentType {
+  fn from(value: config::ContentType) -> Self {
+    match value {
+      config::ContentType::Json => Self::Json,
+      config::ContentType::Ndjson => Self::Ndjson,
+      config::ContentType::Csv => Self::Csv,
+      config::ContentType::Raw => Self::Raw,
+    }
+  }
+}
+
+impl From<v1::ContentType> for config::ContentType {
+  fn from(value: v1::ContentType) -> Self {
+    match value {
+      v1::ContentType::Json => Self::Json,
+      v1::ContentType::Ndjson => Self::Ndjson,
+      v1::ContentType::Csv => Self::Csv,
+      v1::ContentType::Raw => Self::Raw,
+    }
+  }
+}
+
 impl TryFrom<v1::Info> for config::Info {
   type Error = ManifestError;
 
@@ -742,7 +1183,7 @@ impl TryFrom<config::ImportDefinition> for v1::ImportDefinition {
         #[allow(deprecated)]
         ComponentDefinition::Wasm(_) => unreachable!("Wasm components are not allowed in v1 imports"),
         ComponentDefinition::Reference(_) => unreachable!("Component references can't exist in v1 imports"),
-        ComponentDefinition::GrpcUrl(_) => unreachable!("GrpcUrl components are not allowed in v1 imports"),
+        ComponentDefinition::GrpcUrl(c) => v1::ImportDefinition::GrpcUrlComponent(c.into()),
         ComponentDefinition::Manifest(c) => v1::ImportDefinition::ManifestComponent(c.try_into()?),
         ComponentDefinition::HighLevelComponent(c) => match c {
           HighLevelComponent::Sql(c) => v1::ImportDefinition::SqlComponent(c.try_into()?),
@@ -830,10 +1271,140 @@ impl TryFrom<config::components::HttpClientComponentConfig> for v1::HttpClientCo
       codec: value.codec.map_into(),
       with: value.config.try_map_into()?,
       operations: value.operations.try_map_into()?,
+      timeout: value.timeout,
+      connect_timeout: value.connect_timeout,
+      user_agent: value.user_agent,
+      retry: value.retry.map_into(),
+      auth: value.auth.map(TryInto::try_into).transpose()?,
     })
   }
 }
 
+impl From<components::RetryPolicy> for v1::RetryPolicy {
+  fn from(value: components::RetryPolicy) -> Self {
+    Self {
+      retries: value.retries,
+      backoff: Some(value.backoff.into()),
+      statuses: value.statuses,
+    }
+  }
+}
+
+impl From<v1::RetryPolicy> for components::RetryPolicy {
+  fn from(value: v1::RetryPolicy) -> Self {
+    Self {
+      retries: value.retries,
+      backoff: value.backoff.map(Into::into).unwrap_or_default(),
+      statuses: value.statuses,
+    }
+  }
+}
+
+impl TryFrom<components::HttpClientAuth> for v1::HttpClientAuth {
+  type Error = ManifestError;
+  fn try_from(value: components::HttpClientAuth) -> Result<Self> {
+    Ok(match value {
+      components::HttpClientAuth::Bearer(v) => Self::BearerAuth(v1::BearerAuth {
+        token: v.token.unrender()?,
+      }),
+      components::HttpClientAuth::Basic(v) => Self::BasicAuth(v1::BasicAuth {
+        username: v.username.unrender()?,
+        password: v.password.as_ref().map(TemplateConfig::unrender).transpose()?,
+      }),
+      components::HttpClientAuth::OAuth2(v) => Self::OAuth2ClientCredentials(v1::OAuth2ClientCredentials {
+        token_url: v.token_url,
+        client_id: v.client_id.unrender()?,
+        client_secret: v.client_secret.unrender()?,
+        scopes: v.scopes,
+      }),
+    })
+  }
+}
+
+impl From<v1::HttpClientAuth> for components::HttpClientAuth {
+  fn from(value: v1::HttpClientAuth) -> Self {
+    match value {
+      v1::HttpClientAuth::BearerAuth(v) => Self::Bearer(components::BearerAuth {
+        token: TemplateConfig::new_template(v.token),
+      }),
+      v1::HttpClientAuth::BasicAuth(v) => Self::Basic(components::BasicAuth {
+        username: TemplateConfig::new_template(v.username),
+        password: v.password.map(TemplateConfig::new_template),
+      }),
+      v1::HttpClientAuth::OAuth2ClientCredentials(v) => Self::OAuth2(components::OAuth2ClientCredentials {
+        token_url: v.token_url,
+        client_id: TemplateConfig::new_template(v.client_id),
+        client_secret: TemplateConfig::new_template(v.client_secret),
+        scopes: v.scopes,
+      }),
+    }
+  }
+}
+
+impl From<components::Pagination> for v1::Pagination {
+  fn from(value: components::Pagination) -> Self {
+    Self {
+      mode: value.mode.into(),
+      items: value.items,
+      cursor: value.cursor,
+      cursor_param: value.cursor_param,
+      max_pages: value.max_pages,
+    }
+  }
+}
+
+impl From<v1::Pagination> for components::Pagination {
+  fn from(value: v1::Pagination) -> Self {
+    Self {
+      mode: value.mode.into(),
+      items: value.items,
+      cursor: value.cursor,
+      cursor_param: value.cursor_param,
+      max_pages: value.max_pages,
+    }
+  }
+}
+
+impl From<components::PaginationMode> for v1::PaginationMode {
+  fn from(value: components::PaginationMode) -> Self {
+    match value {
+      components::PaginationMode::Link => Self::Link,
+      components::PaginationMode::Cursor => Self::Cursor,
+    }
+  }
+}
+
+impl From<v1::PaginationMode> for components::PaginationMode {
+  fn from(value: v1::PaginationMode) -> Self {
+    match value {
+      v1::PaginationMode::Link => Self::Link,
+      v1::PaginationMode::Cursor => Self::Cursor,
+    }
+  }
+}
+
+impl From<components::ResponseStream> for v1::ResponseStream {
+  fn from(value: components::ResponseStream) -> Self {
+    match value {
+      components::ResponseStream::Chunks => Self::Chunks,
+      components::ResponseStream::Lines => Self::Lines,
+      components::ResponseStream::Ndjson => Self::Ndjson,
+      components::ResponseStream::Sse => Self::Sse,
+    }
+  }
+}
+
+impl From<v1::ResponseStream> for components::ResponseStream {
+  fn from(value: v1::ResponseStream) -> Self {
+    match value {
+      v1::ResponseStream::Chunks => Self::Chunks,
+      v1::ResponseStream::Lines => Self::Lines,
+      v1::ResponseStream::Ndjson => Self::Ndjson,
+      v1::ResponseStream::Sse => Self::Sse,
+    }
+  }
+}
+
 impl From<config::common::Codec> for v1::Codec {
   fn from(value: config::common::Codec) -> Self {
     match value {
@@ -887,11 +1458,53 @@ fn new_operation_instance(id: String, value: config::InstanceReference) -> v1::O
       with: None,
       timeout: None,
     },
-    timeout: value.settings.and_then(|v| v.timeout.map(|v| v.as_millis() as _)),
+    timeout: value.settings.as_ref().and_then(|v| v.timeout_millis()),
+    retry: value.settings.as_ref().and_then(|v| v.retry.clone()).map_into(),
+    circuit_breaker: value.settings.and_then(|v| v.circuit_breaker).map_into(),
     with: value.data.map_into(),
   }
 }
 
+impl From<config::OperationRetry> for v1::OperationRetry {
+  fn from(value: config::OperationRetry) -> Self {
+    Self {
+      retries: value.retries,
+      backoff: Some(value.backoff.into()),
+      timeouts: value.timeouts,
+      errors: value.errors,
+    }
+  }
+}
+
+impl From<v1::OperationRetry> for config::OperationRetry {
+  fn from(value: v1::OperationRetry) -> Self {
+    Self {
+      retries: value.retries,
+      backoff: value.backoff.map(Into::into).unwrap_or_default(),
+      timeouts: value.timeouts,
+      errors: value.errors,
+    }
+  }
+}
+
+impl From<config::CircuitBreakerConfig> for v1::CircuitBreaker {
+  fn from(value: config::CircuitBreakerConfig) -> Self {
+    Self {
+      failures: value.failures,
+      reset_after: value.reset_after,
+    }
+  }
+}
+
+impl From<v1::CircuitBreaker> for config::CircuitBreakerConfig {
+  fn from(value: v1::CircuitBreaker) -> Self {
+    Self {
+      failures: value.failures,
+      reset_after: value.reset_after,
+    }
+  }
+}
+
 impl TryFrom<crate::v1::ComponentDefinition> for ComponentDefinition {
   type Error = ManifestError;
   fn try_from(def: crate::v1::ComponentDefinition) -> Result<Self> {
@@ -927,7 +1540,13 @@ impl TryFrom<crate::v1::OperationInstance> for config::InstanceReference {
       component_id: ns.to_owned(),
       name,
       data: def.with.map_into(),
-      settings: def.timeout.map(ExecutionSettings::from_timeout_millis),
+      settings: (def.timeout.is_some() || def.retry.is_some() || def.circuit_breaker.is_some()).then(|| {
+        ExecutionSettings {
+          timeout: def.timeout.map(std::time::Duration::from_millis),
+          retry: def.retry.map_into(),
+          circuit_breaker: def.circuit_breaker.map_into(),
+        }
+      }),
     })
   }
 }
@@ -1044,6 +1663,7 @@ impl TryFrom<v1::TriggerDefinition> for TriggerDefinition {
       }),
       v1::TriggerDefinition::HttpTrigger(v) => Self::Http(HttpTriggerConfig {
         resource: v.resource,
+        tls: v.tls.map(Into::into),
         routers: v.routers.try_map_into()?,
       }),
       v1::TriggerDefinition::TimeTrigger(time) => Self::Time(TimeTriggerConfig {
@@ -1051,6 +1671,19 @@ impl TryFrom<v1::TriggerDefinition> for TriggerDefinition {
         operation: time.operation.try_into()?,
         payload: time.payload.try_map_into()?,
       }),
+      v1::TriggerDefinition::QueueTrigger(queue) => Self::Queue(QueueTriggerConfig {
+        resource: queue.resource,
+        subject: queue.subject,
+        operation: queue.operation.try_into()?,
+        requeue: queue.requeue,
+      }),
+      v1::TriggerDefinition::WatchTrigger(watch) => Self::Watch(WatchTriggerConfig {
+        resource: watch.resource,
+        operation: watch.operation.try_into()?,
+        include: watch.include.try_map_into()?,
+        exclude: watch.exclude.try_map_into()?,
+        contents: watch.contents,
+      }),
     };
     Ok(rv)
   }
@@ -1079,12 +1712,28 @@ impl TryFrom<v1::HttpRouter> for HttpRouterConfig {
         fallback: v.fallback,
         middleware: v.middleware.try_map_into()?,
         indexes: v.indexes,
+        fallbacks: v.fallbacks.map_into(),
+        compression: v.compression.map_into(),
+        cache_control: v.cache_control.map_into(),
       }),
       v1::HttpRouter::ProxyRouter(v) => Self::ProxyRouter(ProxyRouterConfig {
         path: v.path,
         url: v.url,
         strip_path: v.strip_path,
         middleware: v.middleware.try_map_into()?,
+        upstreams: v.upstreams.map_into(),
+        balance: v.balance.into(),
+        health_check: v.health_check.map_into(),
+        timeout: v.timeout,
+        retries: v.retries,
+        request_headers: v.request_headers.map_into(),
+        response_headers: v.response_headers.map_into(),
+      }),
+      v1::HttpRouter::StreamRouter(v) => Self::StreamRouter(StreamRouterConfig {
+        path: v.path,
+        codec: v.codec.map_into(),
+        operation: v.operation.try_into()?,
+        middleware: v.middleware.try_map_into()?,
       }),
     };
     Ok(rv)
@@ -1110,6 +1759,10 @@ impl TryFrom<v1::ImportDefinition> for config::ImportDefinition {
         let c = v1::ComponentDefinition::ManifestComponent(c);
         config::ImportDefinition::Component(c.try_into()?)
       }
+      v1::ImportDefinition::GrpcUrlComponent(c) => {
+        let c = v1::ComponentDefinition::GrpcUrlComponent(c);
+        config::ImportDefinition::Component(c.try_into()?)
+      }
       v1::ImportDefinition::SqlComponent(c) => config::ImportDefinition::Component(
         config::ComponentDefinition::HighLevelComponent(config::HighLevelComponent::Sql(c.try_into()?)),
       ),
@@ -1197,6 +1850,10 @@ impl TryFrom<v1::SqlComponent> for components::SqlComponentConfig {
         .into_iter()
         .map(TryInto::try_into)
         .collect::<Result<_>>()?,
+      migrations: match value.migrations {
+        Some(v) => Some(v.try_into()?),
+        None => None,
+      },
     })
   }
 }
@@ -1237,6 +1894,7 @@ impl TryFrom<v1::SqlExecOperationDefinition> for components::SqlExecOperationDef
       arguments: value.arguments,
       config: value.with.try_map_into()?,
       on_error: value.on_error.unwrap_or_default().try_into()?,
+      batch: value.batch.map(Into::into),
     })
   }
 }
@@ -1253,6 +1911,11 @@ impl TryFrom<v1::HttpClientOperationDefinition> for components::HttpClientOperat
       method: value.method.into(),
       config: value.with.try_map_into()?,
       headers: value.headers,
+      timeout: value.timeout,
+      retry: value.retry.map_into(),
+      pagination: value.pagination.map_into(),
+      request_stream: value.request_stream,
+      response_stream: value.response_stream.map_into(),
     })
   }
 }
@@ -1264,6 +1927,10 @@ impl From<v1::HttpMethod> for config::HttpMethod {
       v1::HttpMethod::Post => Self::Post,
       v1::HttpMethod::Put => Self::Put,
       v1::HttpMethod::Delete => Self::Delete,
+      v1::HttpMethod::Patch => Self::Patch,
+      v1::HttpMethod::Head => Self::Head,
+      v1::HttpMethod::Options => Self::Options,
+      v1::HttpMethod::Trace => Self::Trace,
     }
   }
 }
@@ -1275,6 +1942,10 @@ impl From<config::HttpMethod> for v1::HttpMethod {
       config::HttpMethod::Post => Self::Post,
       config::HttpMethod::Put => Self::Put,
       config::HttpMethod::Delete => Self::Delete,
+      config::HttpMethod::Patch => Self::Patch,
+      config::HttpMethod::Head => Self::Head,
+      config::HttpMethod::Options => Self::Options,
+      config::HttpMethod::Trace => Self::Trace,
     }
   }
 }
@@ -1287,6 +1958,11 @@ impl TryFrom<v1::HttpClientComponent> for components::HttpClientComponentConfig
       config: value.with.try_map_into()?,
       codec: value.codec.map_into(),
       operations: value.operations.try_map_into()?,
+      timeout: value.timeout,
+      connect_timeout: value.connect_timeout,
+      user_agent: value.user_agent,
+      retry: value.retry.map_into(),
+      auth: value.auth.map_into(),
     })
   }
 }
@@ -1299,6 +1975,7 @@ impl TryFrom<components::SqlComponentConfig> for v1::SqlComponent {
       with: value.config.try_map_into()?,
       tls: value.tls,
       operations: value.operations.try_map_into()?,
+      migrations: value.migrations.try_map_into()?,
     })
   }
 }
@@ -1339,11 +2016,30 @@ impl TryFrom<components::SqlExecOperationDefinition> for v1::SqlExecOperationDef
       exec: value.exec,
       arguments: value.arguments,
       on_error: Some(value.on_error.try_into()?),
+      batch: value.batch.map(Into::into),
       with: value.config.try_map_into()?,
     })
   }
 }
 
+impl From<v1::SqlBatch> for components::SqlBatchConfig {
+  fn from(value: v1::SqlBatch) -> Self {
+    Self {
+      size: value.size,
+      window: value.window,
+    }
+  }
+}
+
+impl From<components::SqlBatchConfig> for v1::SqlBatch {
+  fn from(value: components::SqlBatchConfig) -> Self {
+    Self {
+      size: value.size,
+      window: value.window,
+    }
+  }
+}
+
 impl TryFrom<config::ErrorBehavior> for v1::ErrorBehavior {
   type Error = crate::Error;
   fn try_from(value: config::ErrorBehavior) -> Result<Self> {
@@ -1378,6 +2074,11 @@ impl TryFrom<components::HttpClientOperationDefinition> for v1::HttpClientOperat
       method: value.method.into(),
       with: value.config.try_map_into()?,
       headers: value.headers,
+      timeout: value.timeout,
+      retry: value.retry.map_into(),
+      pagination: value.pagination.map_into(),
+      request_stream: value.request_stream,
+      response_stream: value.response_stream.map_into(),
     })
   }
 }
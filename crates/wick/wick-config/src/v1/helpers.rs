@@ -65,3 +65,16 @@ where
     m.end()
   }
 }
+
+pub(super) fn serialize_opt_component_expression<S>(
+  value: &Option<super::ComponentOperationExpression>,
+  s: S,
+) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  match value {
+    Some(value) => serialize_component_expression(value, s),
+    None => s.serialize_none(),
+  }
+}
@@ -123,6 +123,15 @@ where
   deserializer.deserialize_any(ComponentOperationExpressionVisitor)
 }
 
+pub(crate) fn opt_component_operation_syntax<'de, D>(
+  deserializer: D,
+) -> std::result::Result<Option<crate::v1::ComponentOperationExpression>, D::Error>
+where
+  D: serde::Deserializer<'de>,
+{
+  component_operation_syntax(deserializer).map(Some)
+}
+
 impl FromStr for v1::ComponentOperationExpression {
   type Err = Error;
 
@@ -102,3 +102,51 @@ async fn regression_issue_42() -> Result<(), ManifestError> {
   assert_eq!(value, expected);
   Ok(())
 }
+
+#[test_logger::test(tokio::test)]
+async fn test_grpc_import() -> Result<(), ManifestError> {
+  let component = load_app("./tests/manifests/v1/grpc-import.yaml").await?;
+  let import = component.import().get(0).unwrap();
+  let config::ImportDefinition::Component(config::ComponentDefinition::GrpcUrl(grpc)) = import.kind() else {
+    panic!("expected a grpc component import");
+  };
+  assert_eq!(grpc.url(), "http://127.0.0.1:8060");
+  let value: String = import.kind().config().unwrap().coerce_key("pwd").unwrap();
+  let expected = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+  assert_eq!(value, expected);
+  Ok(())
+}
+
+#[test_logger::test(tokio::test)]
+async fn test_http_client_import() -> Result<(), ManifestError> {
+  let component = load_app("./tests/manifests/v1/http-client-import.yaml").await?;
+  let import = component.import().get(0).unwrap();
+  let config::ImportDefinition::Component(config::ComponentDefinition::HighLevelComponent(
+    config::HighLevelComponent::HttpClient(http),
+  )) = import.kind()
+  else {
+    panic!("expected an http client component import");
+  };
+  assert_eq!(http.timeout(), Some(10000));
+  assert_eq!(http.connect_timeout(), 5000);
+  assert_eq!(http.retry().unwrap().retries(), 2);
+
+  let Some(config::components::HttpClientAuth::OAuth2(auth)) = http.auth() else {
+    panic!("expected oauth2 auth");
+  };
+  assert_eq!(auth.client_id().value_unchecked(), "wick-config");
+  let expected = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+  assert_eq!(auth.client_secret().value_unchecked(), &expected);
+
+  let op = http.operations().get(0).unwrap();
+  let pagination = op.pagination().unwrap();
+  assert_eq!(*pagination.mode(), config::components::PaginationMode::Cursor);
+  assert_eq!(pagination.cursor_param(), "cursor");
+  assert_eq!(pagination.max_pages(), 10);
+
+  let op = http.operations().get(1).unwrap();
+  assert_eq!(op.request_stream().map(String::as_str), Some("data"));
+  assert_eq!(op.response_stream(), Some(&config::components::ResponseStream::Ndjson));
+  Ok(())
+}
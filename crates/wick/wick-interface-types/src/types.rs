@@ -203,6 +203,146 @@ impl Type {
     };
     Ok(val)
   }
+
+  /// Coerce a JSON value into a value of this type, resolving named types against the passed type definitions.
+  ///
+  /// Returns the value that could not be coerced on failure.
+  #[cfg(feature = "value")]
+  pub fn coerce_value(
+    &self,
+    value: serde_json::Value,
+    types: &[TypeDefinition],
+  ) -> Result<serde_json::Value, serde_json::Value> {
+    use serde_json::Value;
+
+    let val = match self {
+      Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::U8 | Type::U16 | Type::U32 | Type::U64 => {
+        let n = match value {
+          Value::Number(ref n) => n
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| n.as_u64().map(i128::from))
+            .or_else(|| match n.as_f64() {
+              #[allow(clippy::cast_possible_truncation)]
+              Some(f) if f.fract() == 0.0 => Some(f as i128),
+              _ => None,
+            }),
+          Value::Bool(v) => Some(u8::from(v).into()),
+          Value::String(ref v) => v.parse::<i128>().ok(),
+          _ => None,
+        };
+        match n {
+          #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+          Some(n) if self.int_range().contains(&n) => {
+            i64::try_from(n).map_or_else(|_| Value::from(n as u64), Value::from)
+          }
+          _ => return Err(value),
+        }
+      }
+      Type::F32 | Type::F64 => match value {
+        Value::Number(_) => value,
+        Value::String(ref v) => match v.parse() {
+          Ok(n) => Value::Number(n),
+          Err(_) => return Err(value),
+        },
+        _ => return Err(value),
+      },
+      Type::Bool => match value {
+        Value::Bool(_) => value,
+        Value::Number(ref n) => match n.as_i64() {
+          Some(0) => Value::Bool(false),
+          Some(1) => Value::Bool(true),
+          _ => return Err(value),
+        },
+        Value::String(ref v) => match v.parse() {
+          Ok(b) => Value::Bool(b),
+          Err(_) => return Err(value),
+        },
+        _ => return Err(value),
+      },
+      Type::String => match value {
+        Value::Bool(v) => Value::String(v.to_string()),
+        Value::Number(v) => Value::String(v.to_string()),
+        Value::String(_) => value,
+        _ => return Err(value),
+      },
+      Type::Datetime => match value {
+        Value::Number(_) | Value::String(_) => value,
+        _ => return Err(value),
+      },
+      Type::Bytes => match value {
+        Value::String(_) | Value::Array(_) => value,
+        _ => return Err(value),
+      },
+      Type::Named(name) => match types.iter().find(|t| t.name() == name) {
+        Some(TypeDefinition::Struct(def)) => coerce_fields(&def.fields, value, types)?,
+        Some(_) => value,
+        None => return Err(value),
+      },
+      Type::AnonymousStruct(fields) => coerce_fields(fields, value, types)?,
+      Type::List { ty } => match value {
+        Value::Array(items) => Value::Array(
+          items
+            .into_iter()
+            .map(|v| ty.coerce_value(v, types))
+            .collect::<Result<_, _>>()?,
+        ),
+        _ => return Err(value),
+      },
+      Type::Optional { ty } => match value {
+        Value::Null => Value::Null,
+        _ => ty.coerce_value(value, types)?,
+      },
+      Type::Map { value: ty, .. } => match value {
+        Value::Object(map) => Value::Object(
+          map
+            .into_iter()
+            .map(|(k, v)| ty.coerce_value(v, types).map(|v| (k, v)))
+            .collect::<Result<_, _>>()?,
+        ),
+        _ => return Err(value),
+      },
+      Type::Link { .. } | Type::Object => value,
+    };
+    Ok(val)
+  }
+
+  /// The values an integer type can hold. Empty for every other type.
+  #[must_use]
+  pub fn int_range(&self) -> std::ops::RangeInclusive<i128> {
+    match self {
+      Type::I8 => i8::MIN.into()..=i8::MAX.into(),
+      Type::I16 => i16::MIN.into()..=i16::MAX.into(),
+      Type::I32 => i32::MIN.into()..=i32::MAX.into(),
+      Type::I64 => i64::MIN.into()..=i64::MAX.into(),
+      Type::U8 => 0..=u8::MAX.into(),
+      Type::U16 => 0..=u16::MAX.into(),
+      Type::U32 => 0..=u32::MAX.into(),
+      Type::U64 => 0..=u64::MAX.into(),
+      #[allow(clippy::reversed_empty_ranges)]
+      _ => 1..=0,
+    }
+  }
+}
+
+#[cfg(feature = "value")]
+fn coerce_fields(
+  fields: &[Field],
+  value: serde_json::Value,
+  types: &[TypeDefinition],
+) -> Result<serde_json::Value, serde_json::Value> {
+  let serde_json::Value::Object(mut map) = value else {
+    return Err(value);
+  };
+  let mut out = serde_json::Map::with_capacity(fields.len());
+  for field in fields {
+    let v = map
+      .remove(field.name())
+      .or_else(|| field.default.clone())
+      .unwrap_or_default();
+    out.insert(field.name.clone(), field.ty().coerce_value(v, types)?);
+  }
+  Ok(serde_json::Value::Object(out))
 }
 
 impl std::fmt::Display for Type {
@@ -353,4 +493,50 @@ mod test {
     assert_eq!(val, json);
     Ok(())
   }
+
+  #[cfg(feature = "value")]
+  #[rstest::rstest]
+  #[case(TS::U32, json!("48"), json!(48))]
+  #[case(TS::I64, json!(48.0), json!(48))]
+  #[case(TS::I8, json!(-128), json!(-128))]
+  #[case(TS::U64, json!(u64::MAX), json!(u64::MAX))]
+  #[case(TS::Bool, json!(1), json!(true))]
+  #[case(TS::String, json!(48), json!("48"))]
+  #[case(TS::Optional{ty:b(TS::U32)}, json!(null), json!(null))]
+  #[case(TS::List{ty:b(TS::F64)}, json!(["1.5", 2]), json!([1.5, 2]))]
+  #[case(TS::Named("Row".to_owned()), json!({"id":"1","name":"foo","extra":true}), json!({"id":1,"name":"foo","active":null}))]
+  fn test_coerce_value(
+    #[case] ty: Type,
+    #[case] value: serde_json::Value,
+    #[case] json: serde_json::Value,
+  ) -> Result<()> {
+    let types = [TypeDefinition::Struct(StructDefinition::new(
+      "Row",
+      vec![
+        Field::new("id", TS::I32),
+        Field::new("name", TS::String),
+        Field::new("active", TS::Optional { ty: b(TS::Bool) }),
+      ],
+      None,
+    ))];
+    let val = ty.coerce_value(value, &types).unwrap();
+
+    assert_eq!(val, json);
+    Ok(())
+  }
+
+  #[cfg(feature = "value")]
+  #[rstest::rstest]
+  #[case(TS::U32, json!("foo"))]
+  #[case(TS::Bool, json!(2))]
+  #[case(TS::U32, json!(-1))]
+  #[case(TS::U8, json!(256))]
+  #[case(TS::I8, json!("128"))]
+  #[case(TS::I64, json!(u64::MAX))]
+  #[case(TS::String, json!(null))]
+  #[case(TS::Named("Unknown".to_owned()), json!({}))]
+  fn test_coerce_value_err(#[case] ty: Type, #[case] value: serde_json::Value) -> Result<()> {
+    assert!(ty.coerce_value(value, &[]).is_err());
+    Ok(())
+  }
 }
@@ -7,7 +7,7 @@ use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 use tonic::{Response, Status};
-use wick_packet::PacketStream;
+use wick_packet::{PacketStream, RuntimeConfig};
 use wick_rpc::rpc::invocation_service_server::InvocationService;
 use wick_rpc::rpc::{InvocationRequest, ListResponse, Packet, StatsResponse};
 use wick_rpc::{rpc, DurationStatistics, Statistics};
@@ -106,11 +106,18 @@ impl InvocationService for InvocationServer {
     let (tx, rx) = mpsc::channel(4);
     let mut stream = request.into_inner();
     let first = stream.next().await;
-    let invocation: wick_packet::InvocationData = if let Some(Ok(inv)) = first {
-      if let Some(rpc::invocation_request::Data::Invocation(inv)) = inv.data {
-        inv
+    let (invocation, config): (wick_packet::InvocationData, Option<RuntimeConfig>) = if let Some(Ok(inv)) = first {
+      if let Some(rpc::invocation_request::Data::Invocation(mut inv)) = inv.data {
+        let config = inv
+          .config
+          .take()
+          .map(|config| serde_json::from_str(&config))
+          .transpose()
+          .map_err(|e| Status::invalid_argument(format!("Invalid invocation configuration: {}", e)))?;
+        let invocation = inv
           .try_into()
-          .map_err(|_| Status::invalid_argument("First message must be a valid invocation"))?
+          .map_err(|_| Status::invalid_argument("First message must be a valid invocation"))?;
+        (invocation, config)
       } else {
         return Err(Status::invalid_argument("First message must be an invocation"));
       }
@@ -123,10 +130,7 @@ impl InvocationService for InvocationServer {
 
     let op_id = invocation.target().operation_id().to_owned();
 
-    let result = self
-      .collection
-      .handle(invocation, Default::default(), panic_callback())
-      .await;
+    let result = self.collection.handle(invocation, config, panic_callback()).await;
     if let Err(e) = result {
       let message = e.to_string();
       error!("invocation failed: {}", message);
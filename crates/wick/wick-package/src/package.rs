@@ -43,6 +43,11 @@ fn process_assets(
       }
       seen_assets.insert(asset_path.clone());
 
+      if asset_path.is_dir() {
+        wick_files.extend(process_dir(&asset_path, &root_parent_dir)?);
+        continue;
+      }
+
       let relative_path = asset_path.strip_prefix(&root_parent_dir).unwrap_or(&asset_path);
 
       let options = wick_config::FetchOptions::default();
@@ -125,6 +130,36 @@ pub struct WickPackage {
   basedir: Option<PathBuf>,
 }
 
+/// Package every file within a directory asset, e.g. a SQL component's migrations.
+fn process_dir(dir: &Path, root_parent_dir: &Path) -> Result<Vec<PackageFile>, Error> {
+  let mut paths = std::fs::read_dir(dir)
+    .and_then(|entries| {
+      entries
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()
+    })
+    .map_err(|e| Error::ReadFile(dir.to_path_buf(), e))?;
+  paths.sort();
+
+  let mut wick_files = Vec::new();
+  for path in paths {
+    if path.is_dir() {
+      wick_files.extend(process_dir(&path, root_parent_dir)?);
+      continue;
+    }
+    let file_bytes = std::fs::read(&path).map_err(|e| Error::ReadFile(path.clone(), e))?;
+    let hash = format!("sha256:{}", digest(file_bytes.as_slice()));
+    let relative_path = path.strip_prefix(root_parent_dir).unwrap_or(&path);
+    wick_files.push(PackageFile::new(
+      relative_path.to_path_buf(),
+      hash,
+      media_types::OTHER.to_owned(),
+      file_bytes.into(),
+    ));
+  }
+  Ok(wick_files)
+}
+
 impl WickPackage {
   /// Creates a new WickPackage from the provided path.
   ///
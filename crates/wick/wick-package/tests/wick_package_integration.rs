@@ -105,4 +105,25 @@ mod integration_test {
     }
     Ok(())
   }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_package_directory_asset() -> Result<()> {
+    let package = WickPackage::from_path(None, Path::new("./tests/files/sql.wick")).await?;
+
+    let mut paths: Vec<_> = package
+      .list_files()
+      .iter()
+      .map(|file| file.package_path().to_string_lossy().to_string())
+      .collect();
+    paths.sort();
+    assert_eq!(
+      paths,
+      vec![
+        "migrations/0001_create_users.sql",
+        "migrations/0002_add_email.sql",
+        "sql.wick"
+      ]
+    );
+    Ok(())
+  }
 }
@@ -5,7 +5,7 @@ use std::time::Duration;
 use tokio_stream::{Stream, StreamExt};
 use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity, Uri};
 use tracing::debug;
-use wick_packet::{Invocation, Packet, PacketStream};
+use wick_packet::{Invocation, Packet, PacketStream, RuntimeConfig};
 
 use crate::error::RpcClientError;
 use crate::rpc::invocation_service_client::InvocationServiceClient;
@@ -141,12 +141,21 @@ impl RpcClient {
     Ok(stream)
   }
 
-  /// Send an invoke RPC command with an [Invocation] object.
-  pub async fn invoke(&mut self, invocation: Invocation) -> Result<PacketStream, RpcClientError> {
+  /// Send an invoke RPC command with an [Invocation] object and optional [RuntimeConfig].
+  pub async fn invoke(
+    &mut self,
+    invocation: Invocation,
+    config: Option<RuntimeConfig>,
+  ) -> Result<PacketStream, RpcClientError> {
     let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
     let (invocation, mut stream) = invocation.split();
+    let mut invocation: generated::wick::Invocation = invocation.into();
+    invocation.config = config
+      .map(|config| serde_json::to_string(&config))
+      .transpose()
+      .map_err(|e| RpcClientError::TypeConversion(e.to_string()))?;
     tx.send(InvocationRequest {
-      data: Some(generated::wick::invocation_request::Data::Invocation(invocation.into())),
+      data: Some(generated::wick::invocation_request::Data::Invocation(invocation)),
     })
     .map_err(|_e| RpcClientError::UnspecifiedConnectionError)?;
     tokio::spawn(async move {
@@ -28,6 +28,8 @@ pub struct Invocation {
   pub tx_id: ::prost::alloc::string::String,
   #[prost(message, optional, tag = "6")]
   pub inherent: ::core::option::Option<InherentData>,
+  #[prost(string, optional, tag = "7")]
+  pub config: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -167,6 +167,7 @@ impl From<wick_packet::InvocationData> for rpc::Invocation {
         seed: inv.inherent.seed,
         timestamp: inv.inherent.timestamp,
       }),
+      config: None,
     }
   }
 }
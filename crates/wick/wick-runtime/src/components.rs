@@ -1,5 +1,6 @@
 pub(crate) mod component_service;
 pub(crate) mod error;
+pub(crate) mod grpc_component;
 pub(crate) mod scope_component;
 pub(crate) mod validation;
 
@@ -14,9 +15,10 @@ use tracing::Instrument;
 use uuid::Uuid;
 use wick_component_wasm::component::{ComponentSetupBuilder, WasmComponent};
 use wick_component_wasm::error::LinkError;
-use wick_config::config::components::ManifestComponent;
+use wick_config::config::components::{GrpcUrlComponent, ManifestComponent};
 use wick_config::config::{Metadata, Permissions, PermissionsBuilder, WasmComponentImplementation};
 use wick_config::{AssetReference, FetchOptions, Resolver, WickConfiguration};
+use wick_interface_types::TypeDefinition;
 use wick_packet::validation::expect_configuration_matches;
 use wick_packet::{Entity, Invocation, RuntimeConfig};
 
@@ -146,13 +148,28 @@ pub(crate) async fn init_manifest_component(
   opts.rng_seed = rng.seed();
 
   let uuid = rng.uuid();
+  let operations = manifest.component().operation_signatures();
+  let types = manifest.types().unwrap_or_default();
   let _scope = init_child(uuid, manifest.clone(), id.clone(), opts).await?;
 
-  let component = Arc::new(scope_component::ScopeComponent::new(uuid));
+  let component = Arc::new(scope_component::ScopeComponent::new(uuid).with_declared_types(&operations, types));
   let service = NativeComponentService::new(component);
   Ok(NamespaceHandler::new(id, Box::new(service)))
 }
 
+pub(crate) async fn init_grpc_component(kind: &GrpcUrlComponent, id: String, opts: ChildInit) -> ComponentInitResult {
+  opts
+    .span
+    .in_scope(|| trace!(namespace = %id, url = kind.url(), ?opts, "registering grpc component"));
+
+  let component = grpc_component::GrpcComponent::new(kind.url(), opts.root_config)
+    .instrument(opts.span.clone())
+    .await
+    .map_err(|e| ScopeError::ComponentInit(id.clone(), e.to_string()))?;
+
+  Ok(NamespaceHandler::new(id, Box::new(component)))
+}
+
 pub(crate) async fn init_impl(
   manifest: &ComponentConfiguration,
   id: String,
@@ -208,6 +225,7 @@ pub(crate) async fn init_impl(
         opts.root_config.clone(),
         metadata.cloned(),
         wick_config::config::HighLevelComponent::Sql(c.clone()),
+        manifest.types()?,
         manifest.resolver(),
       )
       .await
@@ -218,6 +236,7 @@ pub(crate) async fn init_impl(
         opts.root_config.clone(),
         metadata.cloned(),
         wick_config::config::HighLevelComponent::HttpClient(c.clone()),
+        manifest.types()?,
         manifest.resolver(),
       )
       .await
@@ -237,11 +256,12 @@ pub(crate) async fn init_hlc_component(
   root_config: Option<RuntimeConfig>,
   metadata: Option<Metadata>,
   component: wick_config::config::HighLevelComponent,
+  types: Vec<TypeDefinition>,
   resolver: Box<Resolver>,
 ) -> ComponentInitResult {
   let comp: Box<dyn Component + Send + Sync> = match component {
     config::HighLevelComponent::Sql(comp) => {
-      Box::new(wick_sql::SqlComponent::new(comp, root_config, metadata, &resolver).await?)
+      Box::new(wick_sql::SqlComponent::new(comp, root_config, metadata, types, &resolver).await?)
     }
     config::HighLevelComponent::HttpClient(comp) => Box::new(wick_http_client::HttpClientComponent::new(
       comp,
@@ -0,0 +1,190 @@
+use wick_rpc::RpcClient;
+
+use crate::dev::prelude::*;
+
+/// A component backed by a remote wick host reachable over gRPC.
+#[derive(Debug, Clone)]
+pub(crate) struct GrpcComponent {
+  client: RpcClient,
+  signature: ComponentSignature,
+  config: Option<RuntimeConfig>,
+}
+
+impl GrpcComponent {
+  /// Connect to the remote host and fetch its signature via the `List` RPC.
+  pub(crate) async fn new(url: &str, config: Option<RuntimeConfig>) -> Result<Self, ComponentError> {
+    let mut client = wick_rpc::make_rpc_client(url.to_owned(), None, None, None, None)
+      .await
+      .map_err(|e| ComponentError::Downstream(Box::new(e)))?;
+
+    let signature = client
+      .list()
+      .await
+      .map_err(|e| ComponentError::Downstream(Box::new(e)))?
+      .into_iter()
+      .next()
+      .ok_or_else(|| ComponentError::ComponentNotFound(url.to_owned()))?;
+
+    Ok(Self {
+      client,
+      signature,
+      config,
+    })
+  }
+
+  /// Merge the component-level configuration with any operation-level configuration, the latter taking precedence.
+  fn merge_config(&self, op_config: Option<RuntimeConfig>) -> Option<RuntimeConfig> {
+    match (self.config.clone(), op_config) {
+      (Some(base), Some(op)) => {
+        let mut merged: HashMap<String, serde_json::Value> = base.into();
+        merged.extend(op);
+        Some(merged.into())
+      }
+      (base, op) => op.or(base),
+    }
+  }
+}
+
+impl Component for GrpcComponent {
+  fn handle(
+    &self,
+    invocation: Invocation,
+    config: Option<RuntimeConfig>,
+    _callback: Arc<RuntimeCallback>,
+  ) -> flow_component::BoxFuture<Result<PacketStream, flow_component::ComponentError>> {
+    let mut client = self.client.clone();
+    let config = self.merge_config(config);
+
+    invocation.trace(|| {
+      debug!(
+        target = %invocation.target(),
+        "grpc:invoke",
+      );
+    });
+
+    Box::pin(async move {
+      client
+        .invoke(invocation, config)
+        .await
+        .map_err(flow_component::ComponentError::new)
+    })
+  }
+
+  fn signature(&self) -> &ComponentSignature {
+    &self.signature
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use flow_component::panic_callback;
+  use futures::StreamExt;
+  use serde_json::json;
+  use wick_interface_types::component;
+  use wick_packet::{packet_stream, Entity, Packet};
+
+  use super::*;
+
+  struct EchoComponent {
+    signature: ComponentSignature,
+  }
+
+  impl EchoComponent {
+    fn new() -> Self {
+      Self {
+        signature: component! {
+          name: "echo",
+          version: Some("0.0.1"),
+          operations: {
+            "echo" => {
+              inputs: {
+                "input" => "string",
+              },
+              outputs: {
+                "output" => "string",
+              },
+            },
+          }
+        },
+      }
+    }
+  }
+
+  impl Component for EchoComponent {
+    fn handle(
+      &self,
+      invocation: Invocation,
+      config: Option<RuntimeConfig>,
+      _callback: Arc<RuntimeCallback>,
+    ) -> flow_component::BoxFuture<Result<PacketStream, flow_component::ComponentError>> {
+      Box::pin(async move {
+        let prefix = config
+          .and_then(|c| c.coerce_key::<String>("prefix").ok())
+          .unwrap_or_default();
+        let stream = invocation.into_stream().map(move |p| {
+          let p = p?;
+          if p.has_data() {
+            let value: String = p.decode()?;
+            Ok(Packet::encode("output", format!("{}{}", prefix, value)))
+          } else {
+            Ok(Packet::done("output"))
+          }
+        });
+        Ok(PacketStream::new(Box::new(stream)))
+      })
+    }
+
+    fn signature(&self) -> &ComponentSignature {
+      &self.signature
+    }
+  }
+
+  fn start_server() -> Result<String> {
+    let socket = wick_invocation_server::bind_new_socket()?;
+    let addr = socket.local_addr()?;
+    let _handle = wick_invocation_server::make_rpc_server(socket, Arc::new(EchoComponent::new()));
+    Ok(format!("http://{}", addr))
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_signature() -> Result<()> {
+    let url = start_server()?;
+    let component = GrpcComponent::new(&url, None).await?;
+    assert!(component.signature().get_operation("echo").is_some());
+
+    Ok(())
+  }
+
+  async fn invoke_echo(component: &GrpcComponent, config: Option<RuntimeConfig>) -> Result<Vec<Packet>> {
+    let invocation = Invocation::test(
+      "grpc",
+      Entity::operation("remote", "echo"),
+      packet_stream!(("input", "hello")),
+      None,
+    )?;
+    let stream = component.handle(invocation, config, panic_callback()).await?;
+    let packets: Vec<_> = stream.collect().await;
+    Ok(packets.into_iter().collect::<Result<Vec<_>, _>>()?)
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_invoke_with_config() -> Result<()> {
+    let url = start_server()?;
+    let config = RuntimeConfig::from([("prefix", json!("root:"))]);
+    let component = GrpcComponent::new(&url, Some(config)).await?;
+
+    let mut packets = invoke_echo(&component, None).await?;
+    assert_eq!(packets.len(), 2);
+    assert!(packets.pop().unwrap().is_done());
+    assert_eq!(packets.pop().unwrap().decode::<String>()?, "root:hello");
+
+    let op_config = RuntimeConfig::from([("prefix", json!("op:"))]);
+    let mut packets = invoke_echo(&component, Some(op_config)).await?;
+    assert_eq!(packets.len(), 2);
+    assert!(packets.pop().unwrap().is_done());
+    assert_eq!(packets.pop().unwrap().decode::<String>()?, "op:hello");
+
+    Ok(())
+  }
+}
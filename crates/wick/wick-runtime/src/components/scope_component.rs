@@ -1,4 +1,5 @@
 use uuid::Uuid;
+use wick_interface_types::{OperationSignature, Type, TypeDefinition};
 use wick_packet::Entity;
 
 use crate::dev::prelude::*;
@@ -21,6 +22,29 @@ impl ScopeComponent {
 
     Self { scope_id, signature }
   }
+
+  /// Use the types declared in a manifest for the ports of the scope's operations, which only
+  /// know their ports as `object`s. Ports the scope already knows the type of are left alone.
+  #[must_use]
+  pub fn with_declared_types(mut self, operations: &[OperationSignature], types: Vec<TypeDefinition>) -> Self {
+    for op in &mut self.signature.operations {
+      let Some(declared) = operations.iter().find(|declared| declared.name == op.name) else {
+        continue;
+      };
+      for (fields, declared) in [(&mut op.inputs, &declared.inputs), (&mut op.outputs, &declared.outputs)] {
+        for field in fields.iter_mut() {
+          if field.ty() != &Type::Object {
+            continue;
+          }
+          if let Some(declared) = declared.iter().find(|f| f.name() == field.name()) {
+            *field = declared.clone();
+          }
+        }
+      }
+    }
+    self.signature.types.extend(types);
+    self
+  }
 }
 
 impl Component for ScopeComponent {
@@ -80,6 +104,8 @@ mod tests {
 
   use flow_component::panic_callback;
   use futures::StreamExt;
+  use wick_config::WickConfiguration;
+  use wick_interface_types::Field;
   use wick_packet::{packet_stream, Entity, Packet};
 
   use super::*;
@@ -124,4 +150,67 @@ mod tests {
     assert_eq!(sig.operations.len(), 1);
     Ok(())
   }
+
+  async fn declarations(path: &str) -> Result<(Vec<OperationSignature>, Vec<TypeDefinition>)> {
+    let manifest = WickConfiguration::fetch(
+      &std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(path),
+      Default::default(),
+    )
+    .await?
+    .finish()?
+    .try_component_config()?;
+    Ok((manifest.component().operation_signatures(), manifest.types()?))
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_declared_types() -> Result<()> {
+    let path = "./manifests/v1/app_config/rest-router-validation/component.wick";
+    let (_, scope_id) = init_scope_from_yaml(path).await?;
+    let (operations, types) = declarations(path).await?;
+
+    let scope = ScopeComponent::new(scope_id);
+    let component = scope.clone().with_declared_types(&operations, types.clone());
+    let (before, after) = (scope.signature(), component.signature());
+
+    // Only the port types change, to the ones the manifest declares.
+    assert_eq!(after.operations, operations);
+    assert_eq!(after.types, types);
+    assert_eq!(before.operations.len(), after.operations.len());
+    for (before, after) in before.operations.iter().zip(&after.operations) {
+      assert_eq!(before.name, after.name);
+      assert_eq!(before.config, after.config);
+      let names = |fields: &[Field]| fields.iter().map(|f| f.name().to_owned()).collect::<Vec<_>>();
+      assert_eq!(names(&before.inputs), names(&after.inputs));
+      assert_eq!(names(&before.outputs), names(&after.outputs));
+    }
+    assert_eq!(before.name, after.name);
+    assert_eq!(before.config, after.config);
+
+    // Ports the scope already knows the type of aren't replaced.
+    let mut typed = scope.clone();
+    typed.signature.operations[0].inputs[0] = Field::new("input", Type::String);
+    let typed = typed.with_declared_types(&operations, Vec::new());
+    assert_eq!(
+      typed.signature.operations[0].inputs[0],
+      Field::new("input", Type::String)
+    );
+    assert_eq!(typed.signature.operations[0].outputs, operations[0].outputs);
+
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_undeclared_types() -> Result<()> {
+    let path = "./manifests/v0/simple.yaml";
+    let (_, scope_id) = init_scope_from_yaml(path).await?;
+    let (operations, types) = declarations(path).await?;
+    let scope = ScopeComponent::new(scope_id);
+    let component = scope.clone().with_declared_types(&operations, types);
+
+    assert_eq!(component.signature(), scope.signature());
+
+    Ok(())
+  }
 }
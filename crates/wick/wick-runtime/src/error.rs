@@ -94,6 +94,15 @@ pub enum RuntimeError {
   #[error("Error starting schedule: {0}")]
   ScheduleStartError(String),
 
+  #[error("Scheduled job failed: {0}")]
+  ScheduledJob(String),
+
+  #[error("Could not persist schedule state: {0}")]
+  ScheduleState(String),
+
+  #[error("Queue trigger error: {0}")]
+  Queue(String),
+
   #[error("Could not render configuration: {0}")]
   Configuration(String),
 
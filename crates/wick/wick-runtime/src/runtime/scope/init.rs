@@ -142,10 +142,14 @@ impl ScopeInit {
     extends: Option<&[String]>,
     mut components: HandlerMap,
   ) -> Result<HandlerMap, ScopeError> {
+    // Types are only resolved for fetched manifests. Those assembled in code, like an app's, don't declare any.
+    let types = self.manifest.types().unwrap_or_default();
     for binding in self.manifest.import() {
       let provided = generate_provides_handlers(binding.kind().provide(), &components)?;
       let component_init = self.child_init(binding.kind().config().cloned(), Some(provided));
-      if let Some(component) = instantiate_import(binding, component_init, self.manifest.resolver()).await? {
+      if let Some(component) =
+        instantiate_import(binding, component_init, self.manifest.resolver(), types.clone()).await?
+      {
         if let Some(extends) = extends {
           if extends.iter().any(|n| n == component.namespace()) {
             self.span.in_scope(|| {
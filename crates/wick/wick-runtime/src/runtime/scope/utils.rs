@@ -1,10 +1,11 @@
 use flow_graph_interpreter::{HandlerMap, NamespaceHandler};
 use wick_config::config::ComponentDefinition;
 use wick_config::Resolver;
+use wick_interface_types::TypeDefinition;
 
 use super::error::ConstraintFailure;
 use super::ChildInit;
-use crate::components::{init_hlc_component, init_manifest_component, init_wasm_component};
+use crate::components::{init_grpc_component, init_hlc_component, init_manifest_component, init_wasm_component};
 use crate::dev::prelude::*;
 use crate::runtime::RuntimeConstraint;
 
@@ -47,6 +48,7 @@ pub(crate) async fn instantiate_import(
   binding: &config::Binding<config::ImportDefinition>,
   opts: ChildInit,
   resolver: Box<Resolver>,
+  types: Vec<TypeDefinition>,
 ) -> Result<Option<NamespaceHandler>, ScopeError> {
   opts.span.in_scope(|| {
     debug!(id = binding.id(), ?opts, "init options");
@@ -58,7 +60,7 @@ pub(crate) async fn instantiate_import(
   let config::ImportDefinition::Component(config) = binding.kind() else {
     return Ok(None);
   };
-  let result = instantiate_imported_component(id, config, opts, resolver).await;
+  let result = instantiate_imported_component(id, config, opts, resolver, types).await;
   let end = std::time::Instant::now();
   span.in_scope(|| {
     info!(id = binding.id(), duration_ms = %end.duration_since(start).as_millis(), "initialized");
@@ -72,6 +74,7 @@ pub(crate) async fn instantiate_imported_component(
   kind: &ComponentDefinition,
   opts: ChildInit,
   resolver: Box<Resolver>,
+  types: Vec<TypeDefinition>,
 ) -> Result<Option<NamespaceHandler>, ScopeError> {
   match kind {
     #[allow(deprecated)]
@@ -89,9 +92,9 @@ pub(crate) async fn instantiate_imported_component(
     )),
     config::ComponentDefinition::Manifest(def) => Ok(Some(init_manifest_component(def, id, opts).await?)),
     config::ComponentDefinition::Reference(_) => unreachable!(),
-    config::ComponentDefinition::GrpcUrl(_) => todo!(), // CollectionKind::GrpcUrl(v) => initialize_grpc_collection(v, namespace).await,
+    config::ComponentDefinition::GrpcUrl(def) => Ok(Some(init_grpc_component(def, id, opts).await?)),
     config::ComponentDefinition::HighLevelComponent(hlc) => {
-      init_hlc_component(id, opts.root_config.clone(), None, hlc.clone(), resolver)
+      init_hlc_component(id, opts.root_config.clone(), None, hlc.clone(), types, resolver)
         .await
         .map(Some)
     }
@@ -1,7 +1,9 @@
 use std::convert::Infallible;
 mod cli;
 mod http;
+mod queue;
 mod time;
+mod watch;
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
@@ -90,6 +92,8 @@ static TRIGGER_LOADER_REGISTRY: Lazy<Mutex<HashMap<TriggerKind, TriggerLoader>>>
   m.insert(TriggerKind::Cli, Arc::new(cli::Cli::load));
   m.insert(TriggerKind::Http, Arc::new(http::Http::load));
   m.insert(TriggerKind::Time, Arc::new(time::Time::load));
+  m.insert(TriggerKind::Queue, Arc::new(queue::Queue::load));
+  m.insert(TriggerKind::Watch, Arc::new(watch::Watch::load));
   Mutex::new(m)
 });
 
@@ -6,6 +6,7 @@ mod error;
 mod middleware;
 mod routers;
 mod service_factory;
+mod tls;
 
 use std::collections::HashMap;
 use std::fmt;
@@ -20,6 +21,7 @@ use parking_lot::Mutex;
 use routers::{HttpRouter, RawRouterHandler, RouterOperation};
 use serde_json::json;
 use structured_output::StructuredOutput;
+use tokio::net::TcpListener;
 use tokio::task::JoinHandle;
 use tracing::Span;
 use wick_config::config::{AppConfiguration, TriggerDefinition};
@@ -28,6 +30,7 @@ use super::{Trigger, TriggerKind};
 use crate::dev::prelude::{RuntimeError, *};
 use crate::resources::{Resource, ResourceKind};
 use crate::triggers::http::service_factory::ServiceFactory;
+use crate::triggers::http::tls::{TlsFiles, TlsListener};
 use crate::Runtime;
 
 trait RawRouter {
@@ -51,34 +54,56 @@ struct HttpInstance {
 }
 
 impl HttpInstance {
-  async fn new(runtime: Runtime, routers: Vec<HttpRouter>, initiating_span: &Span, socket: &SocketAddr) -> Self {
-    let span = info_span!(parent:initiating_span,"http:server", %socket);
+  async fn new(
+    runtime: Runtime,
+    routers: Vec<HttpRouter>,
+    tls: Option<TlsListener>,
+    initiating_span: &Span,
+    socket: &SocketAddr,
+  ) -> Result<Self, RuntimeError> {
+    let span = info_span!(parent:initiating_span,"http:server", %socket, tls = tls.is_some());
 
     span.in_scope(|| trace!(%socket,"http server starting"));
     let (tx, rx) = tokio::sync::oneshot::channel::<()>();
     let (running_tx, running_rx) = tokio::sync::oneshot::channel::<()>();
-    let server = Server::bind(socket).serve(ServiceFactory::new(runtime, routers, span.id()));
+    let factory = ServiceFactory::new(runtime, routers, span.id());
     let shutdown_span = span.clone();
+    let shutdown = async move {
+      match rx.await {
+        Ok(_) => shutdown_span.in_scope(|| trace!("http server received shutdown signal")),
+        Err(_) => shutdown_span.in_scope(|| trace!("http server shutdown signal dropped")),
+      }
+      shutdown_span.in_scope(|| trace!("http server shutting down"));
+    };
+    let (server, addr): (BoxFuture<'static, Result<(), hyper::Error>>, SocketAddr) = match tls {
+      Some(tls) => {
+        let listener = TcpListener::bind(socket)
+          .await
+          .map_err(|e| RuntimeError::InitializationFailed(format!("could not bind to {}: {}", socket, e)))?;
+        let addr = listener.local_addr().unwrap_or(*socket);
+        let server = Server::builder(tls.incoming(listener, span.clone()))
+          .serve(factory)
+          .with_graceful_shutdown(shutdown);
+        (Box::pin(server), addr)
+      }
+      None => {
+        let server = Server::bind(socket).serve(factory);
+        let addr = server.local_addr();
+        (Box::pin(server.with_graceful_shutdown(shutdown)), addr)
+      }
+    };
     let handle = tokio::spawn(async move {
-      let _ = server
-        .with_graceful_shutdown(async move {
-          match rx.await {
-            Ok(_) => shutdown_span.in_scope(|| trace!("http server received shutdown signal")),
-            Err(_) => shutdown_span.in_scope(|| trace!("http server shutdown signal dropped")),
-          }
-          shutdown_span.in_scope(|| trace!("http server shutting down"));
-        })
-        .await;
+      let _ = server.await;
       let _ = running_tx.send(());
     });
     span.in_scope(|| trace!(%socket,"http server started"));
 
-    Self {
+    Ok(Self {
       handle,
       shutdown_tx: tx,
       running_rx: Some(running_rx),
-      addr: *socket,
-    }
+      addr,
+    })
   }
 
   async fn shutdown(self) -> Result<(), RuntimeError> {
@@ -149,13 +174,16 @@ impl Trigger for Http {
         info!(path = router.path(), kind = %router.kind(), "registering http router");
 
         let router = match router {
-          config::HttpRouterConfig::RawRouter(r) => routers::raw::register_raw_router(i, r)?,
+          config::HttpRouterConfig::RawRouter(r) => routers::raw::register_raw_router(i, resources.clone(), r)?,
           config::HttpRouterConfig::StaticRouter(r) => {
             routers::static_::register_static_router(i, resources.clone(), r)?
           }
           config::HttpRouterConfig::ProxyRouter(r) => routers::proxy::register_proxy_router(i, resources.clone(), r)?,
           config::HttpRouterConfig::RestRouter(r) => {
-            routers::rest::register_rest_router(i, resources.clone(), &app_config, r)?
+            routers::rest::register_rest_router(i, resources.clone(), &runtime, &app_config, r)?
+          }
+          config::HttpRouterConfig::StreamRouter(r) => {
+            routers::stream::register_stream_router(i, resources.clone(), r)?
           }
         };
 
@@ -165,10 +193,16 @@ impl Trigger for Http {
       Ok::<_, RuntimeError>(routers)
     })?;
 
-    let instance = HttpInstance::new(runtime, routers, &span, &socket).await;
+    let tls = config
+      .tls()
+      .map(|tls| TlsListener::new(TlsFiles::new(tls, &resources)?, &span))
+      .transpose()?;
+    let protocol = if tls.is_some() { "HTTPS" } else { "HTTP" };
+
+    let instance = HttpInstance::new(runtime, routers, tls, &span, &socket).await?;
 
     let output = StructuredOutput::new(
-      format!("HTTP Server started on {}", instance.addr),
+      format!("{} Server started on {}", protocol, instance.addr),
       json!({"ip": instance.addr.ip(),"port": instance.addr.port()}),
     );
 
@@ -285,6 +319,117 @@ mod test {
       Ok(())
     }
 
+    fn certs_dir() -> std::path::PathBuf {
+      std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/certs")
+    }
+
+    fn tls_client(ca: &str, identity: Option<&str>) -> Result<reqwest::Client> {
+      let ca = std::fs::read(certs_dir().join(format!("{}.pem", ca)))?;
+      let mut builder = reqwest::Client::builder()
+        .use_rustls_tls()
+        .add_root_certificate(reqwest::Certificate::from_pem(&ca)?);
+      if let Some(name) = identity {
+        let mut pem = std::fs::read(certs_dir().join(format!("{}.pem", name)))?;
+        pem.extend(std::fs::read(certs_dir().join(format!("{}.key", name)))?);
+        builder = builder.identity(reqwest::Identity::from_pem(&pem)?);
+      }
+      Ok(builder.build()?)
+    }
+
+    async fn start_tls_trigger(index: usize, dir: &std::path::Path) -> Result<Http> {
+      std::env::set_var("HTTP_PORT", PORT);
+      let app_config = load_test_manifest("app_config/app-https-server.wick")
+        .await?
+        .try_app_config()?;
+      let rt = build_trigger_runtime(&app_config, Span::current())?.build(None).await?;
+
+      let mut resources = HashMap::new();
+      for resource in app_config.resources() {
+        resources.insert(resource.id().to_owned(), Resource::new(resource.kind().clone())?);
+      }
+      // Serve copies of the certificates so the test can replace them.
+      resources.insert("CERT".to_owned(), Resource::Volume(dir.join("cert.pem")));
+      resources.insert("KEY".to_owned(), Resource::Volume(dir.join("key.pem")));
+      resources.insert("DIR".to_owned(), Resource::Volume(dir.to_path_buf()));
+
+      let trigger = Http::default();
+      let trigger_config = app_config.triggers()[index].clone();
+      trigger
+        .run(
+          "test".to_owned(),
+          rt,
+          app_config,
+          trigger_config,
+          Arc::new(resources),
+          Span::current(),
+        )
+        .await?;
+      Ok(trigger)
+    }
+
+    fn tls_dir(name: &str, cert: &str) -> Result<std::path::PathBuf> {
+      let dir = std::env::temp_dir().join(format!("wick-http-tls-{}-{}", name, std::process::id()));
+      let _ = std::fs::remove_dir_all(&dir);
+      std::fs::create_dir_all(&dir)?;
+      std::fs::write(dir.join("hello.txt"), "hello")?;
+      use_cert(&dir, cert)?;
+      Ok(dir)
+    }
+
+    fn use_cert(dir: &std::path::Path, cert: &str) -> Result<()> {
+      std::fs::copy(certs_dir().join(format!("{}.pem", cert)), dir.join("cert.pem"))?;
+      std::fs::copy(certs_dir().join(format!("{}.key", cert)), dir.join("key.pem"))?;
+      Ok(())
+    }
+
+    #[test_logger::test(tokio::test)]
+    async fn test_tls() -> Result<()> {
+      let dir = tls_dir("reload", "server")?;
+      let trigger = start_tls_trigger(0, &dir).await?;
+      let url = format!("https://localhost:{}/hello.txt", PORT);
+
+      let res = tls_client("ca", None)?.get(&url).send().await?.text().await?;
+      assert_eq!(res, "hello");
+
+      // Plain HTTP is not served on a TLS port.
+      assert!(get("/hello.txt").await.is_err());
+
+      // Replacing the certificate on disk takes effect without a restart.
+      use_cert(&dir, "server2")?;
+      let client = tls_client("ca2", None)?;
+      let mut reloaded = false;
+      for _ in 0..50 {
+        if client.get(&url).send().await.is_ok() {
+          reloaded = true;
+          break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+      }
+      assert!(reloaded, "certificate was not reloaded");
+
+      trigger.shutdown_gracefully().await?;
+      std::fs::remove_dir_all(&dir)?;
+
+      Ok(())
+    }
+
+    #[test_logger::test(tokio::test)]
+    async fn test_mtls() -> Result<()> {
+      let dir = tls_dir("mtls", "server")?;
+      let trigger = start_tls_trigger(1, &dir).await?;
+      let url = format!("https://localhost:{}/hello.txt", PORT);
+
+      assert!(tls_client("ca", None)?.get(&url).send().await.is_err());
+
+      let res = tls_client("ca", Some("client"))?.get(&url).send().await?.text().await?;
+      assert_eq!(res, "hello");
+
+      trigger.shutdown_gracefully().await?;
+      std::fs::remove_dir_all(&dir)?;
+
+      Ok(())
+    }
+
     #[test_logger::test(tokio::test)]
     async fn test_middleware() -> Result<()> {
       std::env::set_var("HTTP_PORT", PORT);
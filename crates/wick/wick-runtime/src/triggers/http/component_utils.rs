@@ -1,10 +1,8 @@
-use std::collections::HashMap;
-
 use hyper::header::CONTENT_LENGTH;
 use hyper::http::response::Builder;
 use hyper::http::{HeaderName, HeaderValue};
 use hyper::{Body, Response, StatusCode};
-use serde_json::{Map, Value};
+use serde_json::Value;
 use tokio_stream::StreamExt;
 use tracing::Span;
 use uuid::Uuid;
@@ -199,65 +197,3 @@ fn reset_header(mut builder: Builder, header: HeaderName, value: impl Into<Heade
   };
   builder
 }
-
-enum MapVal {
-  RawVal(Value),
-  RootArray(Vec<Value>),
-}
-
-pub(super) async fn stream_to_json(stream: PacketStream) -> Result<Value, HttpError> {
-  let mut stream = stream;
-  let mut map = HashMap::new();
-  while let Some(packet) = stream.next().await {
-    match packet {
-      Ok(p) => {
-        if let PacketPayload::Err(err) = p.payload() {
-          return Err(HttpError::OutputStream(p.port().to_owned(), err.msg().to_owned()));
-        }
-        if !p.has_data() {
-          continue;
-        }
-        let port = p.port().to_owned();
-        if let Some(val) = map.remove(p.port()) {
-          let val = match val {
-            MapVal::RawVal(v) => {
-              let response: Value = p
-                .decode_value()
-                .map_err(|e| HttpError::Codec(Codec::Json, e.to_string()))?;
-              MapVal::RootArray(vec![v, response])
-            }
-            MapVal::RootArray(mut v) => {
-              let response: Value = p
-                .decode_value()
-                .map_err(|e| HttpError::Codec(Codec::Json, e.to_string()))?;
-              v.push(response);
-              MapVal::RootArray(v)
-            }
-          };
-          map.insert(port, val);
-        } else {
-          let response: Value = p
-            .decode_value()
-            .map_err(|e| HttpError::Codec(Codec::Json, e.to_string()))?;
-          map.insert(port, MapVal::RawVal(response));
-        }
-      }
-      Err(e) => return Err(HttpError::OperationError(e.to_string())),
-    }
-  }
-  let json = Value::Object(
-    map
-      .into_iter()
-      .map(|(k, v)| {
-        (
-          k,
-          match v {
-            MapVal::RawVal(v) => v,
-            MapVal::RootArray(v) => Value::Array(v),
-          },
-        )
-      })
-      .collect::<Map<String, Value>>(),
-  );
-  Ok(json)
-}
@@ -46,6 +46,14 @@ pub(super) fn scheme_to_wick(scheme: Option<&hyper::http::uri::Scheme>) -> Resul
   })
 }
 
+/// The request's scheme, falling back to the scheme of the connection it arrived on.
+fn request_scheme<B>(req: &Request<B>) -> Option<&hyper::http::uri::Scheme> {
+  req
+    .uri()
+    .scheme()
+    .or_else(|| req.extensions().get::<hyper::http::uri::Scheme>())
+}
+
 pub(super) fn authority_to_wick(authority: Option<&hyper::http::uri::Authority>) -> Result<String, HttpError> {
   Ok(authority.map_or_else(String::default, |v| v.to_string()))
 }
@@ -99,7 +107,7 @@ where
   Ok((
     wick_http::HttpRequest {
       method: method_to_wick(req.method())?,
-      scheme: scheme_to_wick(req.uri().scheme())?,
+      scheme: scheme_to_wick(request_scheme(&req))?,
       authority: authority_to_wick(req.uri().authority())?,
       query_parameters: query_params_to_wick(req.uri().query())?,
       path: path_to_wick(req.uri().path())?,
@@ -118,7 +126,7 @@ where
 {
   Ok(wick_http::HttpRequest {
     method: method_to_wick(req.method())?,
-    scheme: scheme_to_wick(req.uri().scheme())?,
+    scheme: scheme_to_wick(request_scheme(req))?,
     authority: authority_to_wick(req.uri().authority())?,
     query_parameters: query_params_to_wick(req.uri().query())?,
     path: path_to_wick(req.uri().path())?,
@@ -204,4 +212,20 @@ mod test {
     assert!(params.contains_key("code"));
     Ok(())
   }
+
+  #[test]
+  fn test_connection_scheme() -> Result<()> {
+    let mut req = Request::builder().uri("/path").body(())?;
+    assert_eq!(
+      request_to_wick(&req, "127.0.0.1:80".parse()?)?.scheme,
+      wick_http::HttpScheme::Http
+    );
+
+    req.extensions_mut().insert(hyper::http::uri::Scheme::HTTPS);
+    assert_eq!(
+      request_to_wick(&req, "127.0.0.1:80".parse()?)?.scheme,
+      wick_http::HttpScheme::Https
+    );
+    Ok(())
+  }
 }
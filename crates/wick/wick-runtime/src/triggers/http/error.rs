@@ -21,9 +21,6 @@ pub(crate) enum HttpError {
   #[error("Missing query parameters: {}", .0.join(", "))]
   MissingQueryParameters(Vec<String>),
 
-  #[error("Could not decode body as JSON: {0}")]
-  InvalidBody(serde_json::Error),
-
   #[error("Invalid status code: {0}")]
   InvalidStatusCode(String),
 
@@ -62,6 +59,12 @@ pub(crate) enum HttpError {
 
   #[error("URI {0} could not be parsed: {1}")]
   RouteSyntax(String, String),
+
+  #[error("TLS configuration error: {0}")]
+  Tls(String),
+
+  #[error("Could not load JWKS: {0}")]
+  Jwks(String),
 }
 
 #[derive(Debug)]
@@ -1,28 +1,114 @@
-use wick_config::config::WickRouter;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::header::HeaderValue;
+use hyper::{Body, Request, Response};
+use tracing::Span;
+use wick_config::config::{BuiltinMiddleware as BuiltinMiddlewareConfig, WickRouter};
 use wick_packet::{Entity, RuntimeConfig};
 
+use super::HttpError;
 use crate::dev::prelude::RuntimeError;
+use crate::resources::Resource;
 use crate::triggers::ComponentId;
 
+mod auth;
+mod cors;
+mod rate_limit;
+
+#[derive(Debug)]
+pub(crate) enum BuiltinMiddleware {
+  Cors(cors::Cors),
+  RateLimit(rate_limit::RateLimiter),
+  ApiKey(auth::ApiKey),
+  BasicAuth(auth::BasicAuth),
+  Jwt(auth::Jwt),
+}
+
+impl BuiltinMiddleware {
+  fn new(config: &BuiltinMiddlewareConfig, resources: &HashMap<String, Resource>) -> Result<Self, RuntimeError> {
+    Ok(match config {
+      BuiltinMiddlewareConfig::Cors(config) => Self::Cors(cors::Cors::new(config)?),
+      BuiltinMiddlewareConfig::RateLimit(config) => Self::RateLimit(rate_limit::RateLimiter::new(config)?),
+      BuiltinMiddlewareConfig::ApiKey(config) => Self::ApiKey(auth::ApiKey::new(config)?),
+      BuiltinMiddlewareConfig::BasicAuth(config) => Self::BasicAuth(auth::BasicAuth::new(config)?),
+      BuiltinMiddlewareConfig::Jwt(config) => Self::Jwt(auth::Jwt::new(config, resources)?),
+    })
+  }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct RouterMiddleware {
+  pub(crate) builtin: Arc<Vec<BuiltinMiddleware>>,
   pub(crate) request: Vec<(Entity, Option<RuntimeConfig>)>,
   pub(crate) response: Vec<(Entity, Option<RuntimeConfig>)>,
 }
 
 impl RouterMiddleware {
   pub(crate) fn new(
+    builtin: Vec<BuiltinMiddleware>,
     request: Vec<(Entity, Option<RuntimeConfig>)>,
     response: Vec<(Entity, Option<RuntimeConfig>)>,
   ) -> Self {
-    Self { request, response }
+    Self {
+      builtin: Arc::new(builtin),
+      request,
+      response,
+    }
+  }
+
+  /// Run the built-in middleware, returning a response if one of them answers the request.
+  pub(super) async fn handle_builtin(
+    &self,
+    req: &Request<Body>,
+    remote_addr: SocketAddr,
+    span: &Span,
+  ) -> Result<Option<Response<Body>>, HttpError> {
+    // Preflight requests never carry credentials, so answer them before anything can reject them.
+    for middleware in self.builtin.iter() {
+      if let BuiltinMiddleware::Cors(cors) = middleware {
+        if let Some(response) = cors.preflight(req) {
+          return Ok(Some(response));
+        }
+      }
+    }
+    for middleware in self.builtin.iter() {
+      let response = match middleware {
+        BuiltinMiddleware::Cors(_) => None,
+        BuiltinMiddleware::RateLimit(limiter) => limiter.check(req, remote_addr.ip()),
+        BuiltinMiddleware::ApiKey(auth) => auth.check(req),
+        BuiltinMiddleware::BasicAuth(auth) => auth.check(req),
+        BuiltinMiddleware::Jwt(auth) => auth.check(req, span).await?,
+      };
+      if response.is_some() {
+        return Ok(response);
+      }
+    }
+    Ok(None)
+  }
+
+  /// Add the headers from the built-in middleware to the response for a request from `origin`.
+  pub(super) fn finish_builtin(&self, origin: Option<&HeaderValue>, response: &mut Response<Body>) {
+    for middleware in self.builtin.iter() {
+      if let BuiltinMiddleware::Cors(cors) = middleware {
+        cors.decorate(origin, response);
+      }
+    }
   }
 }
 
-pub(super) fn resolve_middleware_components(router: &impl WickRouter) -> Result<RouterMiddleware, RuntimeError> {
+pub(super) fn resolve_middleware_components(
+  router: &impl WickRouter,
+  resources: &HashMap<String, Resource>,
+) -> Result<RouterMiddleware, RuntimeError> {
+  let mut builtin = Vec::new();
   let mut request_operations = Vec::new();
   let mut response_operations = Vec::new();
   if let Some(middleware) = router.middleware() {
+    for config in middleware.builtin() {
+      builtin.push(BuiltinMiddleware::new(config, resources)?);
+    }
     for operation in middleware.request() {
       let component_id = operation.component_id()?;
       request_operations.push((
@@ -38,6 +124,6 @@ pub(super) fn resolve_middleware_components(router: &impl WickRouter) -> Result<
       ));
     }
   }
-  let middleware = RouterMiddleware::new(request_operations, response_operations);
+  let middleware = RouterMiddleware::new(builtin, request_operations, response_operations);
   Ok(middleware)
 }
@@ -0,0 +1,533 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use hyper::header::{HeaderName, HeaderValue, AUTHORIZATION, WWW_AUTHENTICATE};
+use hyper::{Body, Request, Response, StatusCode};
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use tokio::sync::RwLock;
+use tracing::Span;
+use url::Url;
+use wick_config::config::{ApiKeyMiddleware, BasicAuthMiddleware, JwtMiddleware, TemplateConfig};
+
+use crate::dev::prelude::*;
+use crate::resources::{Resource, ResourceKind};
+use crate::triggers::http::HttpError;
+use crate::triggers::TriggerKind;
+
+/// How long a fetched JWKS is trusted before it is fetched again.
+const JWKS_MAX_AGE: Duration = Duration::from_secs(600);
+
+/// The minimum time between fetches triggered by tokens signed with an unknown key.
+const JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long to wait before fetching again after the first failed fetch. Doubles with each failure after that.
+const JWKS_RETRY_MIN: Duration = Duration::from_secs(1);
+
+/// The longest to wait before fetching again after failed fetches.
+const JWKS_RETRY_MAX: Duration = Duration::from_secs(300);
+
+fn unauthorized(challenge: Option<HeaderValue>) -> Response<Body> {
+  let mut response = Response::builder()
+    .status(StatusCode::UNAUTHORIZED)
+    .body(Body::empty())
+    .unwrap();
+  if let Some(challenge) = challenge {
+    response.headers_mut().insert(WWW_AUTHENTICATE, challenge);
+  }
+  response
+}
+
+fn secure_eq(a: &[u8], b: &[u8]) -> bool {
+  ring::constant_time::verify_slices_are_equal(a, b).is_ok()
+}
+
+fn rendered(value: &TemplateConfig<String>) -> Result<String, RuntimeError> {
+  value
+    .value()
+    .cloned()
+    .ok_or_else(|| RuntimeError::InitializationFailed(format!("unrendered middleware configuration: {}", value)))
+}
+
+/// Requires one of a set of API keys in a request header.
+#[derive(Debug)]
+pub(crate) struct ApiKey {
+  header: HeaderName,
+  keys: Vec<String>,
+}
+
+impl ApiKey {
+  pub(crate) fn new(config: &ApiKeyMiddleware) -> Result<Self, RuntimeError> {
+    let header = HeaderName::from_bytes(config.header().as_bytes()).map_err(|e| {
+      RuntimeError::InitializationFailed(format!("invalid API key header '{}': {}", config.header(), e))
+    })?;
+    let keys = config.keys().iter().map(rendered).collect::<Result<_, _>>()?;
+    Ok(Self { header, keys })
+  }
+
+  pub(crate) fn check<B>(&self, req: &Request<B>) -> Option<Response<Body>> {
+    let authorized = req.headers().get(&self.header).map_or(false, |key| {
+      self.keys.iter().any(|k| secure_eq(key.as_bytes(), k.as_bytes()))
+    });
+    (!authorized).then(|| unauthorized(None))
+  }
+}
+
+/// Requires HTTP Basic credentials that match one of a set of users.
+#[derive(Debug)]
+pub(crate) struct BasicAuth {
+  users: HashMap<String, String>,
+  challenge: HeaderValue,
+}
+
+impl BasicAuth {
+  pub(crate) fn new(config: &BasicAuthMiddleware) -> Result<Self, RuntimeError> {
+    let users = config
+      .users()
+      .iter()
+      .map(|(user, password)| Ok((user.clone(), rendered(password)?)))
+      .collect::<Result<_, RuntimeError>>()?;
+    let realm = config.realm().map_or("wick", String::as_str);
+    let challenge = HeaderValue::from_str(&format!("Basic realm=\"{}\"", realm))
+      .map_err(|e| RuntimeError::InitializationFailed(format!("invalid basic auth realm '{}': {}", realm, e)))?;
+    Ok(Self { users, challenge })
+  }
+
+  fn authorized<B>(&self, req: &Request<B>) -> bool {
+    let Some(credentials) = authorization(req, "Basic") else {
+      return false;
+    };
+    let Ok(credentials) = base64::engine::general_purpose::STANDARD.decode(credentials) else {
+      return false;
+    };
+    let Ok(credentials) = String::from_utf8(credentials) else {
+      return false;
+    };
+    let Some((user, password)) = credentials.split_once(':') else {
+      return false;
+    };
+    self
+      .users
+      .get(user)
+      .map_or(false, |expected| secure_eq(password.as_bytes(), expected.as_bytes()))
+  }
+
+  pub(crate) fn check<B>(&self, req: &Request<B>) -> Option<Response<Body>> {
+    (!self.authorized(req)).then(|| unauthorized(Some(self.challenge.clone())))
+  }
+}
+
+/// The credentials of an `Authorization` header that uses `scheme`.
+fn authorization<'a, B>(req: &'a Request<B>, scheme: &str) -> Option<&'a str> {
+  let value = req.headers().get(AUTHORIZATION)?.to_str().ok()?;
+  let (name, credentials) = value.split_once(' ')?;
+  name.eq_ignore_ascii_case(scheme).then(|| credentials.trim())
+}
+
+#[derive(Debug)]
+enum JwksSource {
+  File(PathBuf),
+  Url(Url),
+}
+
+#[derive(Debug)]
+struct CachedJwks {
+  keys: JwkSet,
+  fetched: Instant,
+}
+
+#[derive(Debug, Default)]
+struct JwksCache {
+  /// The last key set that was fetched successfully.
+  jwks: Option<CachedJwks>,
+  /// When the last fetch failed and how many fetches in a row have failed.
+  failure: Option<(Instant, u32)>,
+}
+
+impl JwksCache {
+  /// Whether a fetch failed too recently to try again.
+  fn backing_off(&self) -> bool {
+    self
+      .failure
+      .map_or(false, |(at, failures)| at.elapsed() < retry_delay(failures))
+  }
+
+  /// Find the key for `kid` in the last good key set, if there is one.
+  fn last_good(&self, kid: Option<&str>) -> Result<Option<Jwk>, HttpError> {
+    self.jwks.as_ref().map_or_else(
+      || {
+        Err(HttpError::Jwks(
+          "no key set available, waiting to fetch again".to_owned(),
+        ))
+      },
+      |jwks| Ok(find(&jwks.keys, kid)),
+    )
+  }
+}
+
+fn retry_delay(failures: u32) -> Duration {
+  JWKS_RETRY_MIN
+    .saturating_mul(2_u32.saturating_pow(failures.saturating_sub(1)))
+    .min(JWKS_RETRY_MAX)
+}
+
+/// Requires a bearer JWT signed by a key from a JWKS.
+#[derive(Debug)]
+pub(crate) struct Jwt {
+  source: JwksSource,
+  issuer: Option<String>,
+  audience: Vec<String>,
+  jwks: RwLock<JwksCache>,
+}
+
+impl Jwt {
+  pub(crate) fn new(config: &JwtMiddleware, resources: &HashMap<String, Resource>) -> Result<Self, RuntimeError> {
+    let resource = resources
+      .get(config.jwks())
+      .ok_or_else(|| RuntimeError::ResourceNotFound(TriggerKind::Http.into(), config.jwks().to_owned()))?;
+    let source = match resource {
+      Resource::Volume(path) => JwksSource::File(path.clone()),
+      Resource::Url(url) if url.scheme() == "file" => JwksSource::File(
+        url
+          .to_file_path()
+          .map_err(|_| RuntimeError::InitializationFailed(format!("{} is not a valid file path", url)))?,
+      ),
+      Resource::Url(url) => JwksSource::Url(url.clone()),
+      _ => {
+        return Err(RuntimeError::InvalidResourceType(
+          TriggerKind::Http.into(),
+          ResourceKind::Url,
+          resource.kind(),
+        ))
+      }
+    };
+    Ok(Self {
+      source,
+      issuer: config.issuer().cloned(),
+      audience: config.audience().to_vec(),
+      jwks: RwLock::new(JwksCache::default()),
+    })
+  }
+
+  pub(crate) async fn check(&self, req: &Request<Body>, span: &Span) -> Result<Option<Response<Body>>, HttpError> {
+    let Some(token) = authorization(req, "Bearer") else {
+      return Ok(Some(unauthorized(Some(HeaderValue::from_static("Bearer")))));
+    };
+    match self.verify(token, span).await? {
+      Ok(()) => Ok(None),
+      Err(reason) => {
+        span.in_scope(|| debug!(reason, "rejected bearer token"));
+        Ok(Some(unauthorized(Some(HeaderValue::from_static(
+          "Bearer error=\"invalid_token\"",
+        )))))
+      }
+    }
+  }
+
+  /// Verify `token`, returning the reason it was rejected if it is invalid.
+  async fn verify(&self, token: &str, span: &Span) -> Result<Result<(), String>, HttpError> {
+    let header = match jsonwebtoken::decode_header(token) {
+      Ok(header) => header,
+      Err(e) => return Ok(Err(e.to_string())),
+    };
+    let Some(jwk) = self.find_key(header.kid.as_deref(), span).await? else {
+      return Ok(Err(format!("no key found for key id {:?}", header.kid)));
+    };
+    if !supports(&jwk, header.alg) {
+      return Ok(Err(format!("key does not support {:?}", header.alg)));
+    }
+    let key = match decoding_key(&jwk) {
+      Ok(key) => key,
+      Err(e) => return Ok(Err(e)),
+    };
+    let mut validation = Validation::new(header.alg);
+    if let Some(issuer) = &self.issuer {
+      validation.set_issuer(&[issuer]);
+    }
+    if !self.audience.is_empty() {
+      validation.set_audience(&self.audience);
+    }
+    Ok(
+      jsonwebtoken::decode::<serde_json::Value>(token, &key, &validation)
+        .map(|_| ())
+        .map_err(|e| e.to_string()),
+    )
+  }
+
+  /// Find the key for `kid`, fetching the JWKS again if it is stale or doesn't have the key. If the fetch fails, the
+  /// last good key set is used until a fetch succeeds.
+  async fn find_key(&self, kid: Option<&str>, span: &Span) -> Result<Option<Jwk>, HttpError> {
+    {
+      let cache = self.jwks.read().await;
+      if let Some(jwks) = cache.jwks.as_ref() {
+        let age = jwks.fetched.elapsed();
+        if age < JWKS_MAX_AGE {
+          let key = find(&jwks.keys, kid);
+          if key.is_some() || age < JWKS_REFRESH_INTERVAL {
+            return Ok(key);
+          }
+        }
+      }
+      if cache.backing_off() {
+        return cache.last_good(kid);
+      }
+    }
+
+    let mut cache = self.jwks.write().await;
+    // Another request may have refreshed the keys while we waited for the lock.
+    if let Some(jwks) = cache.jwks.as_ref() {
+      if jwks.fetched.elapsed() < JWKS_REFRESH_INTERVAL {
+        return Ok(find(&jwks.keys, kid));
+      }
+    }
+    if cache.backing_off() {
+      return cache.last_good(kid);
+    }
+    match self.fetch().await {
+      Ok(keys) => {
+        span.in_scope(|| debug!(keys = keys.keys.len(), "fetched jwks"));
+        let key = find(&keys, kid);
+        cache.jwks = Some(CachedJwks {
+          keys,
+          fetched: Instant::now(),
+        });
+        cache.failure = None;
+        Ok(key)
+      }
+      Err(e) => {
+        let failures = cache.failure.map_or(0, |(_, failures)| failures) + 1;
+        cache.failure = Some((Instant::now(), failures));
+        span.in_scope(|| warn!(error = %e, failures, "could not fetch jwks"));
+        cache.jwks.as_ref().map_or(Err(e), |jwks| Ok(find(&jwks.keys, kid)))
+      }
+    }
+  }
+
+  async fn fetch(&self) -> Result<JwkSet, HttpError> {
+    let bytes = match &self.source {
+      JwksSource::File(path) => tokio::fs::read(path)
+        .await
+        .map_err(|e| HttpError::Jwks(format!("could not read {}: {}", path.display(), e)))?,
+      JwksSource::Url(url) => reqwest::get(url.clone())
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| HttpError::Jwks(format!("could not fetch {}: {}", url, e)))?
+        .bytes()
+        .await
+        .map_err(|e| HttpError::Jwks(format!("could not fetch {}: {}", url, e)))?
+        .to_vec(),
+    };
+    serde_json::from_slice(&bytes).map_err(|e| HttpError::Jwks(e.to_string()))
+  }
+}
+
+/// The key to verify tokens with. `jsonwebtoken` reads the secret of an octet key as standard base64, but JWKs encode
+/// it as base64url.
+fn decoding_key(jwk: &Jwk) -> Result<DecodingKey, String> {
+  match &jwk.algorithm {
+    AlgorithmParameters::OctetKey(params) => base64::engine::general_purpose::URL_SAFE_NO_PAD
+      .decode(params.value.trim_end_matches('='))
+      .map(|secret| DecodingKey::from_secret(&secret))
+      .map_err(|e| e.to_string()),
+    _ => DecodingKey::from_jwk(jwk).map_err(|e| e.to_string()),
+  }
+}
+
+fn find(keys: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+  match kid {
+    Some(kid) => keys.find(kid).cloned(),
+    None if keys.keys.len() == 1 => keys.keys.first().cloned(),
+    None => None,
+  }
+}
+
+/// Whether `jwk` can verify tokens signed with `alg`, so a token can't choose how its key is used.
+fn supports(jwk: &Jwk, alg: Algorithm) -> bool {
+  if let Some(expected) = jwk.common.algorithm {
+    return expected == alg;
+  }
+  matches!(
+    (&jwk.algorithm, alg),
+    (
+      AlgorithmParameters::RSA(_),
+      Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512 | Algorithm::PS256 | Algorithm::PS384 | Algorithm::PS512
+    ) | (
+      AlgorithmParameters::EllipticCurve(_),
+      Algorithm::ES256 | Algorithm::ES384
+    ) | (AlgorithmParameters::OctetKeyPair(_), Algorithm::EdDSA)
+      | (
+        AlgorithmParameters::OctetKey(_),
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+      )
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use jsonwebtoken::{EncodingKey, Header};
+  use serde_json::json;
+  use wick_config::config::{ApiKeyMiddlewareBuilder, BasicAuthMiddlewareBuilder, JwtMiddlewareBuilder};
+
+  use super::*;
+
+  fn request(header: Option<(HeaderName, String)>) -> Request<Body> {
+    let mut builder = Request::builder().uri("/");
+    if let Some((name, value)) = header {
+      builder = builder.header(name, value);
+    }
+    builder.body(Body::empty()).unwrap()
+  }
+
+  #[test_logger::test]
+  fn test_api_key() -> Result<()> {
+    let auth = ApiKey::new(
+      &ApiKeyMiddlewareBuilder::default()
+        .header("x-api-key")
+        .keys(vec![TemplateConfig::new_value("secret".to_owned())])
+        .build()?,
+    )?;
+    let header = HeaderName::from_static("x-api-key");
+
+    assert!(auth
+      .check(&request(Some((header.clone(), "secret".to_owned()))))
+      .is_none());
+    assert!(auth.check(&request(Some((header, "wrong".to_owned())))).is_some());
+    assert!(auth.check(&request(None)).is_some());
+
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_basic_auth() -> Result<()> {
+    let auth = BasicAuth::new(
+      &BasicAuthMiddlewareBuilder::default()
+        .users(HashMap::from([(
+          "user".to_owned(),
+          TemplateConfig::new_value("pass".to_owned()),
+        )]))
+        .build()?,
+    )?;
+    let basic = |credentials: &str| {
+      Some((
+        AUTHORIZATION,
+        format!(
+          "Basic {}",
+          base64::engine::general_purpose::STANDARD.encode(credentials)
+        ),
+      ))
+    };
+
+    assert!(auth.check(&request(basic("user:pass"))).is_none());
+    assert!(auth.check(&request(basic("user:wrong"))).is_some());
+    assert!(auth.check(&request(basic("other:pass"))).is_some());
+    let response = auth.check(&request(None)).unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()[WWW_AUTHENTICATE], "Basic realm=\"wick\"");
+
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_jwt() -> Result<()> {
+    // The last bytes encode differently in base64url, which JWKs use, than in standard base64.
+    let secret = b"wick test secret, do not use!!\xfb\xff";
+    let dir = std::env::temp_dir().join(format!("wick-jwks-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    let jwks = dir.join("jwks.json");
+    std::fs::write(
+      &jwks,
+      json!({"keys": [{
+        "kty": "oct",
+        "kid": "test",
+        "k": base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(secret),
+      }]})
+      .to_string(),
+    )?;
+    let resources = HashMap::from([("JWKS".to_owned(), Resource::Volume(jwks))]);
+    let auth = Jwt::new(
+      &JwtMiddlewareBuilder::default()
+        .jwks("JWKS")
+        .issuer(Some("wick".to_owned()))
+        .build()?,
+      &resources,
+    )?;
+
+    let token = |kid: &str, claims: serde_json::Value| -> Result<String> {
+      let mut header = Header::new(Algorithm::HS256);
+      header.kid = Some(kid.to_owned());
+      Ok(jsonwebtoken::encode(
+        &header,
+        &claims,
+        &EncodingKey::from_secret(secret),
+      )?)
+    };
+    let bearer = |token: String| Some((AUTHORIZATION, format!("Bearer {}", token)));
+    let exp = jsonwebtoken::get_current_timestamp() + 60;
+    let span = Span::current();
+
+    let valid = token("test", json!({"iss": "wick", "exp": exp}))?;
+    assert!(auth.check(&request(bearer(valid)), &span).await?.is_none());
+
+    let wrong_issuer = token("test", json!({"iss": "other", "exp": exp}))?;
+    assert!(auth.check(&request(bearer(wrong_issuer)), &span).await?.is_some());
+
+    let expired = token("test", json!({"iss": "wick", "exp": exp - 3600}))?;
+    assert!(auth.check(&request(bearer(expired)), &span).await?.is_some());
+
+    let unknown_key = token("other", json!({"iss": "wick", "exp": exp}))?;
+    assert!(auth.check(&request(bearer(unknown_key)), &span).await?.is_some());
+
+    assert!(auth.check(&request(None), &span).await?.is_some());
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_jwks_unavailable() -> Result<()> {
+    let secret = b"wick test secret, do not use!!!!";
+    let jwks_json = |kid: &str| {
+      json!({"keys": [{
+        "kty": "oct",
+        "kid": kid,
+        "k": base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(secret),
+      }]})
+      .to_string()
+    };
+    let dir = std::env::temp_dir().join(format!("wick-jwks-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    let jwks = dir.join("jwks.json");
+    std::fs::write(&jwks, jwks_json("test"))?;
+    let resources = HashMap::from([("JWKS".to_owned(), Resource::Volume(jwks.clone()))]);
+    let auth = Jwt::new(&JwtMiddlewareBuilder::default().jwks("JWKS").build()?, &resources)?;
+    let span = Span::current();
+
+    assert!(auth.find_key(Some("test"), &span).await?.is_some());
+
+    // A stale key set is still used while the source is unavailable.
+    std::fs::remove_file(&jwks)?;
+    auth.jwks.write().await.jwks.as_mut().unwrap().fetched = Instant::now() - JWKS_MAX_AGE;
+    assert!(auth.find_key(Some("test"), &span).await?.is_some());
+    assert_eq!(auth.jwks.read().await.failure.map(|(_, failures)| failures), Some(1));
+
+    // Nothing is fetched again until the retry delay has passed.
+    std::fs::write(&jwks, jwks_json("other"))?;
+    assert!(auth.find_key(Some("other"), &span).await?.is_none());
+    auth.jwks.write().await.failure = Some((Instant::now() - JWKS_RETRY_MIN, 1));
+    assert!(auth.find_key(Some("other"), &span).await?.is_some());
+    assert!(auth.jwks.read().await.failure.is_none());
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_retry_delay() {
+    assert_eq!(retry_delay(1), JWKS_RETRY_MIN);
+    assert_eq!(retry_delay(3), JWKS_RETRY_MIN * 4);
+    assert_eq!(retry_delay(u32::MAX), JWKS_RETRY_MAX);
+  }
+}
@@ -0,0 +1,261 @@
+use hyper::header::{
+  HeaderValue,
+  ACCESS_CONTROL_ALLOW_CREDENTIALS,
+  ACCESS_CONTROL_ALLOW_HEADERS,
+  ACCESS_CONTROL_ALLOW_METHODS,
+  ACCESS_CONTROL_ALLOW_ORIGIN,
+  ACCESS_CONTROL_EXPOSE_HEADERS,
+  ACCESS_CONTROL_MAX_AGE,
+  ACCESS_CONTROL_REQUEST_HEADERS,
+  ACCESS_CONTROL_REQUEST_METHOD,
+  ORIGIN,
+  VARY,
+};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use wick_config::config::CorsMiddleware;
+
+use crate::dev::prelude::RuntimeError;
+
+/// Answers CORS preflight requests and adds CORS headers to responses.
+#[derive(Debug)]
+pub(crate) struct Cors {
+  any_origin: bool,
+  origins: Vec<String>,
+  methods: Vec<String>,
+  headers: Vec<String>,
+  allow_methods: Option<HeaderValue>,
+  allow_headers: Option<HeaderValue>,
+  exposed_headers: Option<HeaderValue>,
+  allow_credentials: bool,
+  max_age: Option<HeaderValue>,
+}
+
+impl Cors {
+  pub(crate) fn new(config: &CorsMiddleware) -> Result<Self, RuntimeError> {
+    let any_origin = config.allowed_origins().iter().any(|origin| origin == "*");
+    // Browsers reject a wildcard origin on credentialed requests, and echoing every origin instead would let any site
+    // make them.
+    if any_origin && config.allow_credentials() {
+      return Err(RuntimeError::InitializationFailed(
+        "CORS can't allow credentials from any origin, list the allowed origins instead of '*'".to_owned(),
+      ));
+    }
+    let methods: Vec<String> = config.allowed_methods().iter().map(ToString::to_string).collect();
+    let headers: Vec<String> = config
+      .allowed_headers()
+      .iter()
+      .map(|h| h.to_ascii_lowercase())
+      .collect();
+    let list = |values: &[String]| {
+      (!values.is_empty())
+        .then(|| header_value(&values.join(", ")))
+        .transpose()
+    };
+    Ok(Self {
+      any_origin,
+      origins: config.allowed_origins().to_vec(),
+      allow_methods: list(&methods)?,
+      allow_headers: list(&headers)?,
+      methods,
+      headers,
+      exposed_headers: list(config.exposed_headers())?,
+      allow_credentials: config.allow_credentials(),
+      max_age: config.max_age().map(HeaderValue::from),
+    })
+  }
+
+  /// The `Access-Control-Allow-Origin` value for `origin`, if it is allowed.
+  fn allowed_origin(&self, origin: &HeaderValue) -> Option<HeaderValue> {
+    if self.any_origin {
+      return Some(HeaderValue::from_static("*"));
+    }
+    let allowed = origin.to_str().map_or(false, |o| self.origins.iter().any(|a| a == o));
+    allowed.then(|| origin.clone())
+  }
+
+  fn allows_method(&self, method: &HeaderValue) -> bool {
+    self.methods.is_empty() || method.to_str().map_or(false, |m| self.methods.iter().any(|a| a == m))
+  }
+
+  fn allows_headers(&self, headers: Option<&HeaderValue>) -> bool {
+    if self.headers.is_empty() {
+      return true;
+    }
+    let Some(headers) = headers else { return true };
+    headers.to_str().map_or(false, |headers| {
+      headers
+        .split(',')
+        .map(|h| h.trim().to_ascii_lowercase())
+        .filter(|h| !h.is_empty())
+        .all(|h| self.headers.contains(&h))
+    })
+  }
+
+  /// Answer `req` if it is a CORS preflight request. Preflights for disallowed origins, methods,
+  /// or headers are answered without CORS headers so the browser blocks the actual request.
+  pub(crate) fn preflight<B>(&self, req: &Request<B>) -> Option<Response<Body>> {
+    if req.method() != Method::OPTIONS {
+      return None;
+    }
+    let origin = req.headers().get(ORIGIN)?;
+    let method = req.headers().get(ACCESS_CONTROL_REQUEST_METHOD)?;
+    let requested_headers = req.headers().get(ACCESS_CONTROL_REQUEST_HEADERS);
+
+    let mut response = Response::builder()
+      .status(StatusCode::NO_CONTENT)
+      .body(Body::empty())
+      .unwrap();
+    let headers = response.headers_mut();
+    headers.append(VARY, HeaderValue::from_static("Origin"));
+
+    let Some(allowed_origin) = self.allowed_origin(origin) else {
+      return Some(response);
+    };
+    if !self.allows_method(method) || !self.allows_headers(requested_headers) {
+      return Some(response);
+    }
+
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allowed_origin);
+    let methods = self.allow_methods.clone().unwrap_or_else(|| method.clone());
+    headers.insert(ACCESS_CONTROL_ALLOW_METHODS, methods);
+    if let Some(allowed_headers) = self.allow_headers.clone().or_else(|| requested_headers.cloned()) {
+      headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, allowed_headers);
+    }
+    if self.allow_credentials {
+      headers.insert(ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+    }
+    if let Some(max_age) = &self.max_age {
+      headers.insert(ACCESS_CONTROL_MAX_AGE, max_age.clone());
+    }
+    Some(response)
+  }
+
+  /// Add CORS headers to the response for a request from `origin`.
+  pub(crate) fn decorate(&self, origin: Option<&HeaderValue>, response: &mut Response<Body>) {
+    let Some(origin) = origin else { return };
+    let headers = response.headers_mut();
+    headers.append(VARY, HeaderValue::from_static("Origin"));
+    let Some(allowed_origin) = self.allowed_origin(origin) else {
+      return;
+    };
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allowed_origin);
+    if self.allow_credentials {
+      headers.insert(ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+    }
+    if let Some(exposed_headers) = &self.exposed_headers {
+      headers.insert(ACCESS_CONTROL_EXPOSE_HEADERS, exposed_headers.clone());
+    }
+  }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, RuntimeError> {
+  HeaderValue::from_str(value)
+    .map_err(|e| RuntimeError::InitializationFailed(format!("invalid CORS header value '{}': {}", value, e)))
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use wick_config::config::{CorsMiddlewareBuilder, HttpMethod};
+
+  use super::*;
+
+  fn preflight(origin: &str, method: &str, headers: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+      .method(Method::OPTIONS)
+      .uri("/")
+      .header(ORIGIN, origin)
+      .header(ACCESS_CONTROL_REQUEST_METHOD, method);
+    if let Some(headers) = headers {
+      builder = builder.header(ACCESS_CONTROL_REQUEST_HEADERS, headers);
+    }
+    builder.body(Body::empty()).unwrap()
+  }
+
+  #[test_logger::test]
+  fn test_preflight() -> Result<()> {
+    let cors = Cors::new(
+      &CorsMiddlewareBuilder::default()
+        .allowed_origins(vec!["https://example.com".to_owned()])
+        .allowed_methods(vec![HttpMethod::Get, HttpMethod::Post])
+        .allowed_headers(vec!["Content-Type".to_owned()])
+        .max_age(600)
+        .build()?,
+    )?;
+
+    let response = cors
+      .preflight(&preflight("https://example.com", "POST", Some("content-type")))
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let headers = response.headers();
+    assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+    assert_eq!(headers[ACCESS_CONTROL_ALLOW_METHODS], "GET, POST");
+    assert_eq!(headers[ACCESS_CONTROL_ALLOW_HEADERS], "content-type");
+    assert_eq!(headers[ACCESS_CONTROL_MAX_AGE], "600");
+
+    for request in [
+      preflight("https://evil.com", "POST", None),
+      preflight("https://example.com", "DELETE", None),
+      preflight("https://example.com", "POST", Some("x-secret")),
+    ] {
+      let response = cors.preflight(&request).unwrap();
+      assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    let request = Request::builder()
+      .method(Method::OPTIONS)
+      .uri("/")
+      .body(Body::empty())?;
+    assert!(cors.preflight(&request).is_none());
+
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_decorate() -> Result<()> {
+    let cors = Cors::new(
+      &CorsMiddlewareBuilder::default()
+        .allowed_origins(vec!["*".to_owned()])
+        .exposed_headers(vec!["x-request-id".to_owned()])
+        .build()?,
+    )?;
+    let mut response = Response::new(Body::empty());
+    cors.decorate(Some(&HeaderValue::from_static("https://example.com")), &mut response);
+    assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    assert_eq!(response.headers()[ACCESS_CONTROL_EXPOSE_HEADERS], "x-request-id");
+
+    let cors = Cors::new(
+      &CorsMiddlewareBuilder::default()
+        .allowed_origins(vec!["https://example.com".to_owned()])
+        .allow_credentials(true)
+        .build()?,
+    )?;
+    let mut response = Response::new(Body::empty());
+    cors.decorate(Some(&HeaderValue::from_static("https://example.com")), &mut response);
+    assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+    assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+
+    let mut response = Response::new(Body::empty());
+    cors.decorate(None, &mut response);
+    assert!(response.headers().is_empty());
+
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_invalid_config() -> Result<()> {
+    let credentials_from_anywhere = CorsMiddlewareBuilder::default()
+      .allowed_origins(vec!["*".to_owned()])
+      .allow_credentials(true)
+      .build()?;
+    assert!(Cors::new(&credentials_from_anywhere).is_err());
+
+    let invalid_header = CorsMiddlewareBuilder::default()
+      .allowed_origins(vec!["https://example.com".to_owned()])
+      .allowed_headers(vec!["x-bad\nheader".to_owned()])
+      .build()?;
+    assert!(Cors::new(&invalid_header).is_err());
+
+    Ok(())
+  }
+}
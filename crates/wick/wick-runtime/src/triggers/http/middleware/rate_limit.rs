@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use hyper::header::{HeaderName, HeaderValue, RETRY_AFTER};
+use hyper::{Body, Request, Response, StatusCode};
+use parking_lot::Mutex;
+use wick_config::config::RateLimitMiddleware;
+
+use crate::dev::prelude::RuntimeError;
+
+/// How many clients to track before forgetting the ones whose limits have fully reset.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Limits each client to a number of requests per window with a token bucket that refills
+/// continuously over the window. Clients are identified by their IP address or by the configured
+/// key header.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+  capacity: f64,
+  window: Duration,
+  key_header: Option<HeaderName>,
+  buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+  tokens: f64,
+  updated: Instant,
+}
+
+impl RateLimiter {
+  pub(crate) fn new(config: &RateLimitMiddleware) -> Result<Self, RuntimeError> {
+    let key_header = config
+      .key_header()
+      .map(|header| {
+        HeaderName::from_bytes(header.as_bytes())
+          .map_err(|e| RuntimeError::InitializationFailed(format!("invalid rate limit key header '{}': {}", header, e)))
+      })
+      .transpose()?;
+    Ok(Self {
+      capacity: f64::from(config.requests()),
+      window: Duration::from_millis(config.window().max(1)),
+      key_header,
+      buckets: Mutex::new(HashMap::new()),
+    })
+  }
+
+  /// Answer the request with a `429 Too Many Requests` if its client has exceeded its limit.
+  pub(crate) fn check<B>(&self, req: &Request<B>, ip: IpAddr) -> Option<Response<Body>> {
+    let wait = self.acquire(self.key(req, ip), Instant::now()).err()?;
+    let mut response = Response::builder()
+      .status(StatusCode::TOO_MANY_REQUESTS)
+      .body(Body::empty())
+      .unwrap();
+    let seconds = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(seconds));
+    Some(response)
+  }
+
+  /// The first value of the key header if the request has one, or else the client's IP address.
+  fn key<B>(&self, req: &Request<B>, ip: IpAddr) -> String {
+    self
+      .key_header
+      .as_ref()
+      .and_then(|header| req.headers().get(header)?.to_str().ok())
+      .and_then(|value| value.split(',').next())
+      .map(str::trim)
+      .filter(|key| !key.is_empty())
+      .map_or_else(|| ip.to_string(), ToOwned::to_owned)
+  }
+
+  /// Take a token for the client identified by `key`, or return how long until one is available.
+  fn acquire(&self, key: String, now: Instant) -> Result<(), Duration> {
+    let mut buckets = self.buckets.lock();
+    if buckets.len() >= PRUNE_THRESHOLD {
+      let window = self.window;
+      buckets.retain(|_, bucket| now.saturating_duration_since(bucket.updated) < window);
+    }
+    let bucket = buckets.entry(key).or_insert(Bucket {
+      tokens: self.capacity,
+      updated: now,
+    });
+    let refill = now.saturating_duration_since(bucket.updated).as_secs_f64() / self.window.as_secs_f64();
+    bucket.tokens = (bucket.tokens + refill * self.capacity).min(self.capacity);
+    bucket.updated = now;
+
+    if bucket.tokens >= 1.0 {
+      bucket.tokens -= 1.0;
+      return Ok(());
+    }
+    if self.capacity == 0.0 {
+      return Err(self.window);
+    }
+    Err(self.window.mul_f64((1.0 - bucket.tokens) / self.capacity))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use wick_config::config::RateLimitMiddlewareBuilder;
+
+  use super::*;
+
+  #[test_logger::test]
+  fn test_acquire() -> Result<()> {
+    let limiter = RateLimiter::new(
+      &RateLimitMiddlewareBuilder::default()
+        .requests(2u32)
+        .window(1000u64)
+        .build()?,
+    )?;
+    let client = || "10.0.0.1".to_owned();
+    let other = || "10.0.0.2".to_owned();
+    let start = Instant::now();
+
+    assert!(limiter.acquire(client(), start).is_ok());
+    assert!(limiter.acquire(client(), start).is_ok());
+    assert_eq!(limiter.acquire(client(), start), Err(Duration::from_millis(500)));
+    assert!(limiter.acquire(other(), start).is_ok());
+
+    assert!(limiter.acquire(client(), start + Duration::from_millis(500)).is_ok());
+    assert!(limiter.acquire(client(), start + Duration::from_millis(500)).is_err());
+    assert!(limiter.acquire(client(), start + Duration::from_millis(5000)).is_ok());
+    assert!(limiter.acquire(client(), start + Duration::from_millis(5000)).is_ok());
+    assert!(limiter.acquire(client(), start + Duration::from_millis(5000)).is_err());
+
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_check() -> Result<()> {
+    let limiter = RateLimiter::new(
+      &RateLimitMiddlewareBuilder::default()
+        .requests(1u32)
+        .window(60_000u64)
+        .build()?,
+    )?;
+    let client: IpAddr = "10.0.0.1".parse()?;
+    let req = Request::new(Body::empty());
+
+    assert!(limiter.check(&req, client).is_none());
+    let response = limiter.check(&req, client).unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers()[RETRY_AFTER], "60");
+
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_key_header() -> Result<()> {
+    let limiter = RateLimiter::new(
+      &RateLimitMiddlewareBuilder::default()
+        .requests(1u32)
+        .window(60_000u64)
+        .key_header(Some("x-forwarded-for".to_owned()))
+        .build()?,
+    )?;
+    let proxy: IpAddr = "10.0.0.1".parse()?;
+    let forwarded = |client: &str| {
+      Request::builder()
+        .header("x-forwarded-for", client)
+        .body(Body::empty())
+        .unwrap()
+    };
+
+    assert_eq!(limiter.key(&forwarded("203.0.113.1, 10.0.0.1"), proxy), "203.0.113.1");
+    assert_eq!(limiter.key(&Request::new(Body::empty()), proxy), "10.0.0.1");
+
+    assert!(limiter.check(&forwarded("203.0.113.1"), proxy).is_none());
+    assert!(limiter.check(&forwarded("203.0.113.2"), proxy).is_none());
+    assert!(limiter.check(&forwarded("203.0.113.1, 10.0.0.1"), proxy).is_some());
+    assert!(limiter.check(&Request::new(Body::empty()), proxy).is_none());
+
+    Ok(())
+  }
+}
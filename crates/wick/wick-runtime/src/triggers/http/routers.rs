@@ -10,6 +10,7 @@ pub(super) mod proxy;
 pub(super) mod raw;
 pub(super) mod rest;
 pub(super) mod static_;
+pub(super) mod stream;
 
 #[derive(Debug, Clone)]
 pub(crate) enum HttpRouter {
@@ -1,13 +1,19 @@
 use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use futures::future::BoxFuture;
-use hyper::{Body, Request, Response, StatusCode};
-use tracing::Span;
+use hyper::body::to_bytes;
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::http::request::Parts;
+use hyper::{Body, HeaderMap, Method, Request, Response, StatusCode};
+use hyper_reverse_proxy::ProxyError;
+use tracing::{Instrument, Span};
 use url::Url;
 use uuid::Uuid;
-use wick_config::config::{ProxyRouterConfig, TriggerKind, WickRouter};
+use wick_config::config::{HeaderRewrite, HealthCheck, LoadBalance, ProxyRouterConfig, TriggerKind, WickRouter};
 
 use super::super::{HttpError, HttpRouter, RawRouter};
 use crate::dev::prelude::RuntimeError;
@@ -16,19 +22,176 @@ use crate::triggers::http::middleware::resolve_middleware_components;
 use crate::triggers::http::RawRouterHandler;
 use crate::Runtime;
 
+/// A server the proxy router forwards requests to.
+#[derive(Debug)]
+struct Upstream {
+  url: String,
+  timeout: Duration,
+  healthy: AtomicBool,
+  /// The number of requests waiting on a response from this upstream.
+  active: AtomicUsize,
+}
+
+impl Upstream {
+  fn new(url: &Url, timeout: Duration) -> Self {
+    Self {
+      url: url.to_string().trim_end_matches('/').to_owned(),
+      timeout,
+      healthy: AtomicBool::new(true),
+      active: AtomicUsize::new(0),
+    }
+  }
+}
+
+/// Counts a request against an upstream for as long as it is alive.
+struct InFlight<'a>(&'a AtomicUsize);
+
+impl<'a> InFlight<'a> {
+  fn new(active: &'a AtomicUsize) -> Self {
+    active.fetch_add(1, Ordering::Relaxed);
+    Self(active)
+  }
+}
+
+impl Drop for InFlight<'_> {
+  fn drop(&mut self) {
+    self.0.fetch_sub(1, Ordering::Relaxed);
+  }
+}
+
+/// A [HeaderRewrite] with its header names and values parsed.
+#[derive(Debug, Default)]
+struct HeaderRules {
+  remove: Vec<HeaderName>,
+  set: Vec<(HeaderName, HeaderValue)>,
+  add: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl HeaderRules {
+  fn new(rewrite: &HeaderRewrite) -> Result<Self, RuntimeError> {
+    let name = |name: &String| {
+      HeaderName::from_bytes(name.as_bytes())
+        .map_err(|_| RuntimeError::InitializationFailed(format!("invalid header name '{}' in proxy router", name)))
+    };
+    let pair = |(k, v): (&String, &String)| {
+      let value = HeaderValue::from_str(v)
+        .map_err(|_| RuntimeError::InitializationFailed(format!("invalid value for header '{}' in proxy router", k)))?;
+      Ok::<_, RuntimeError>((name(k)?, value))
+    };
+    Ok(Self {
+      remove: rewrite.remove().iter().map(name).collect::<Result<_, _>>()?,
+      set: rewrite.set().iter().map(pair).collect::<Result<_, _>>()?,
+      add: rewrite.add().iter().map(pair).collect::<Result<_, _>>()?,
+    })
+  }
+
+  fn apply(&self, headers: &mut HeaderMap) {
+    for name in &self.remove {
+      headers.remove(name);
+    }
+    for (name, value) in &self.set {
+      headers.insert(name.clone(), value.clone());
+    }
+    for (name, value) in &self.add {
+      headers.append(name.clone(), value.clone());
+    }
+  }
+}
+
 #[derive()]
 #[must_use]
 pub(super) struct ProxyRouter {
-  url: String,
+  upstreams: Arc<Vec<Upstream>>,
+  balance: LoadBalance,
+  next: AtomicUsize,
+  retries: u32,
   strip: Option<String>,
+  request_headers: HeaderRules,
+  response_headers: HeaderRules,
 }
 
 impl ProxyRouter {
-  pub(super) fn new(url: Url, strip: Option<String>) -> Self {
-    let url = url.to_string();
-    let url = url.trim_end_matches('/').to_owned();
+  /// Choose the upstream for the next attempt at a request, preferring healthy upstreams that
+  /// haven't been tried yet.
+  fn select(&self, tried: &[usize]) -> Option<usize> {
+    let healthy: Vec<_> = (0..self.upstreams.len())
+      .filter(|i| self.upstreams[*i].healthy.load(Ordering::Relaxed))
+      .collect();
+    let untried: Vec<_> = healthy.iter().copied().filter(|i| !tried.contains(i)).collect();
+    let candidates = if untried.is_empty() { healthy } else { untried };
+    if candidates.is_empty() {
+      return None;
+    }
+    match self.balance {
+      LoadBalance::RoundRobin => Some(candidates[self.next.fetch_add(1, Ordering::Relaxed) % candidates.len()]),
+      LoadBalance::LeastConnections => candidates
+        .into_iter()
+        .min_by_key(|i| self.upstreams[*i].active.load(Ordering::Relaxed)),
+    }
+  }
+
+  async fn proxy(
+    &self,
+    client_ip: std::net::IpAddr,
+    parts: Parts,
+    body: Body,
+    span: &Span,
+  ) -> Result<Response<Body>, HttpError> {
+    // Requests are only buffered when they may need to be sent more than once.
+    let (mut body, buffered) = if self.retries > 0 {
+      let bytes = to_bytes(body).await.map_err(|e| HttpError::Bytes(e.to_string()))?;
+      (None, Some(bytes))
+    } else {
+      (Some(body), None)
+    };
+    let idempotent = is_idempotent(&parts.method);
+
+    let mut tried = Vec::new();
+    let mut status = StatusCode::SERVICE_UNAVAILABLE;
+    for _ in 0..=self.retries {
+      let Some(index) = self.select(&tried) else {
+        span.in_scope(|| warn!("http:trigger:proxy no healthy upstreams"));
+        break;
+      };
+      tried.push(index);
+      let upstream = &self.upstreams[index];
+      let body = buffered
+        .clone()
+        .map_or_else(|| body.take().unwrap_or_default(), Body::from);
+      let request = rebuild(&parts, body);
 
-    Self { strip, url }
+      let result = {
+        let _in_flight = InFlight::new(&upstream.active);
+        tokio::time::timeout(
+          upstream.timeout,
+          hyper_reverse_proxy::call(client_ip, &upstream.url, request),
+        )
+        .await
+      };
+      let retry = match result {
+        Ok(Ok(mut response)) => {
+          self.response_headers.apply(response.headers_mut());
+          return Ok(response);
+        }
+        Ok(Err(error)) => {
+          span.in_scope(|| warn!(upstream = upstream.url, ?error, "http:trigger:proxy upstream failed"));
+          status = StatusCode::BAD_GATEWAY;
+          // Requests that never reached the upstream are always safe to send again.
+          idempotent || matches!(&error, ProxyError::HyperError(e) if e.is_connect())
+        }
+        Err(_) => {
+          span.in_scope(
+            || warn!(upstream = upstream.url, timeout = ?upstream.timeout, "http:trigger:proxy upstream timed out"),
+          );
+          status = StatusCode::GATEWAY_TIMEOUT;
+          idempotent
+        }
+      };
+      if !retry || buffered.is_none() {
+        break;
+      }
+    }
+    Ok(Response::builder().status(status).body(Body::empty()).unwrap())
   }
 }
 
@@ -42,31 +205,96 @@ impl RawRouter for ProxyRouter {
     span: &Span,
   ) -> BoxFuture<Result<Response<Body>, HttpError>> {
     let span = info_span!(parent: span, "proxy");
-    let url = self.url.clone();
     let client_ip = remote_addr.ip();
     if let Some(to_strip) = &self.strip {
       let orig_path = request.uri().path_and_query().unwrap().as_str().to_owned();
       let path = orig_path.trim_start_matches(to_strip);
       *request.uri_mut() = path.parse().unwrap();
-      span.in_scope(|| trace!(to= url, orig = orig_path, uri = %request.uri(), "http:trigger:proxy proxying"));
+      span.in_scope(|| trace!(orig = orig_path, uri = %request.uri(), "http:trigger:proxy proxying"));
     } else {
-      span.in_scope(|| trace!(to= url, uri = %request.uri(), "http:trigger:proxy proxying"));
+      span.in_scope(|| trace!(uri = %request.uri(), "http:trigger:proxy proxying"));
     }
     // the proxy library does not set the appropriate host header, but if we delete
     // the header, it will get made correctly for us.
     request.headers_mut().remove("host");
-    let fut = async move {
-      match hyper_reverse_proxy::call(client_ip, &url, request).await {
-        Ok(response) => Ok(response),
-        Err(_error) => Ok(
-          Response::builder()
-            .status(StatusCode::INTERNAL_SERVER_ERROR)
-            .body(Body::empty())
-            .unwrap(),
-        ),
-      }
+    self.request_headers.apply(request.headers_mut());
+    let (parts, body) = request.into_parts();
+    Box::pin(async move { self.proxy(client_ip, parts, body, &span).await })
+  }
+}
+
+/// Methods whose requests can be sent more than once without changing their effect.
+const fn is_idempotent(method: &Method) -> bool {
+  matches!(
+    *method,
+    Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE | Method::PUT | Method::DELETE
+  )
+}
+
+fn rebuild(parts: &Parts, body: Body) -> Request<Body> {
+  let mut request = Request::new(body);
+  *request.method_mut() = parts.method.clone();
+  *request.uri_mut() = parts.uri.clone();
+  *request.version_mut() = parts.version;
+  *request.headers_mut() = parts.headers.clone();
+  request
+}
+
+/// Check every upstream on an interval until the router is dropped. Upstreams leave rotation after
+/// `failures` failed checks in a row and rejoin after one that passes.
+async fn check_health(upstreams: Weak<Vec<Upstream>>, check: HealthCheck) {
+  let client = match reqwest::Client::builder()
+    .redirect(reqwest::redirect::Policy::none())
+    .timeout(Duration::from_millis(check.timeout()))
+    .build()
+  {
+    Ok(client) => client,
+    Err(e) => {
+      error!(error = %e, "http:trigger:proxy could not create health check client");
+      return;
+    }
+  };
+  let mut interval = tokio::time::interval(Duration::from_millis(check.interval()));
+  let mut failures = HashMap::new();
+  loop {
+    interval.tick().await;
+    let Some(upstreams) = upstreams.upgrade() else {
+      break;
     };
-    Box::pin(fut)
+    for (i, upstream) in upstreams.iter().enumerate() {
+      let url = format!("{}/{}", upstream.url, check.path().trim_start_matches('/'));
+      let passed = client
+        .get(&url)
+        .send()
+        .await
+        .map_or(false, |res| res.status().is_success() || res.status().is_redirection());
+      let failed = failures.entry(i).or_insert(0);
+      if passed {
+        *failed = 0;
+        if !upstream.healthy.swap(true, Ordering::Relaxed) {
+          info!(upstream = upstream.url, "http:trigger:proxy upstream is healthy");
+        }
+      } else {
+        *failed += 1;
+        if *failed >= check.failures() && upstream.healthy.swap(false, Ordering::Relaxed) {
+          warn!(upstream = upstream.url, "http:trigger:proxy upstream is unhealthy");
+        }
+      }
+    }
+  }
+}
+
+fn resolve_url(resources: &HashMap<String, Resource>, name: &str) -> Result<Url, RuntimeError> {
+  let url = resources.get(name).ok_or_else(|| {
+    RuntimeError::ResourceNotFound(TriggerKind::Http.into(), format!("url resource {} not found", name))
+  })?;
+  match url {
+    Resource::Url(s) => Ok(s.clone()),
+    _ => Err(RuntimeError::InvalidResourceType(
+      TriggerKind::Http.into(),
+      ResourceKind::Url,
+      url.kind(),
+    )),
   }
 }
 
@@ -76,28 +304,219 @@ pub(crate) fn register_proxy_router(
   router_config: &ProxyRouterConfig,
 ) -> Result<HttpRouter, RuntimeError> {
   trace!(index, "registering proxy router");
-  let middleware = resolve_middleware_components(router_config)?;
-  let url = resources.get(router_config.url()).ok_or_else(|| {
-    RuntimeError::ResourceNotFound(
-      TriggerKind::Http.into(),
-      format!("url resource {} not found", router_config.url()),
-    )
-  })?;
-  let url = match url {
-    Resource::Url(s) => s.clone(),
-    _ => {
-      return Err(RuntimeError::InvalidResourceType(
-        TriggerKind::Http.into(),
-        ResourceKind::Url,
-        url.kind(),
-      ))
-    }
+  let middleware = resolve_middleware_components(router_config, &resources)?;
+  let timeout = Duration::from_millis(router_config.timeout());
+  let mut upstreams = Vec::new();
+  if let Some(url) = router_config.url() {
+    upstreams.push(Upstream::new(&resolve_url(&resources, url)?, timeout));
+  }
+  for upstream in router_config.upstreams() {
+    let timeout = upstream.timeout().map_or(timeout, Duration::from_millis);
+    upstreams.push(Upstream::new(&resolve_url(&resources, upstream.url())?, timeout));
+  }
+  if upstreams.is_empty() {
+    return Err(RuntimeError::InitializationFailed(format!(
+      "proxy router at {} needs a url or at least one upstream",
+      router_config.path()
+    )));
+  }
+  let upstreams = Arc::new(upstreams);
+  if let Some(check) = router_config.health_check() {
+    tokio::spawn(check_health(Arc::downgrade(&upstreams), check.clone()).instrument(Span::current()));
+  }
+
+  let rules = |rewrite: Option<&HeaderRewrite>| rewrite.map_or_else(|| Ok(HeaderRules::default()), HeaderRules::new);
+  let router = ProxyRouter {
+    upstreams,
+    balance: *router_config.balance(),
+    next: AtomicUsize::new(0),
+    retries: router_config.retries(),
+    strip: router_config.strip_path().then(|| router_config.path().to_owned()),
+    request_headers: rules(router_config.request_headers())?,
+    response_headers: rules(router_config.response_headers())?,
   };
-  let strip_path = router_config.strip_path().then(|| router_config.path().to_owned());
-  let router = ProxyRouter::new(url, strip_path);
   Ok(HttpRouter::Raw(RawRouterHandler {
     path: router_config.path().to_owned(),
     component: Arc::new(router),
     middleware,
   }))
 }
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use wick_config::config::HeaderRewriteBuilder;
+
+  use super::*;
+
+  fn router(balance: LoadBalance, count: usize) -> ProxyRouter {
+    let url: Url = "http://localhost:8080".parse().unwrap();
+    ProxyRouter {
+      upstreams: Arc::new(
+        (0..count)
+          .map(|_| Upstream::new(&url, Duration::from_secs(1)))
+          .collect(),
+      ),
+      balance,
+      next: AtomicUsize::new(0),
+      retries: 0,
+      strip: None,
+      request_headers: HeaderRules::default(),
+      response_headers: HeaderRules::default(),
+    }
+  }
+
+  #[test_logger::test]
+  fn test_round_robin() -> Result<()> {
+    let router = router(LoadBalance::RoundRobin, 3);
+    let picks: Vec<_> = (0..4).filter_map(|_| router.select(&[])).collect();
+    assert_eq!(picks, vec![0, 1, 2, 0]);
+
+    router.upstreams[1].healthy.store(false, Ordering::Relaxed);
+    let picks: Vec<_> = (0..2).filter_map(|_| router.select(&[])).collect();
+    assert_eq!(picks, vec![0, 2]);
+    assert_eq!(router.select(&[0]), Some(2));
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_least_connections() -> Result<()> {
+    let router = router(LoadBalance::LeastConnections, 3);
+    let first = InFlight::new(&router.upstreams[0].active);
+    let second = InFlight::new(&router.upstreams[1].active);
+    assert_eq!(router.select(&[]), Some(2));
+    assert_eq!(router.select(&[2]), Some(0));
+    drop(first);
+    let third = InFlight::new(&router.upstreams[0].active);
+    let fourth = InFlight::new(&router.upstreams[0].active);
+    assert_eq!(router.select(&[2]), Some(1));
+    drop((second, third, fourth));
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_no_healthy_upstreams() -> Result<()> {
+    let router = router(LoadBalance::RoundRobin, 2);
+    for upstream in router.upstreams.iter() {
+      upstream.healthy.store(false, Ordering::Relaxed);
+    }
+    assert_eq!(router.select(&[]), None);
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_header_rules() -> Result<()> {
+    let rewrite = HeaderRewriteBuilder::default()
+      .remove(vec!["x-remove".to_owned()])
+      .set(
+        [("x-set".to_owned(), "new".to_owned())]
+          .into_iter()
+          .collect::<HashMap<_, _>>(),
+      )
+      .add(
+        [("x-add".to_owned(), "two".to_owned())]
+          .into_iter()
+          .collect::<HashMap<_, _>>(),
+      )
+      .build()?;
+    let rules = HeaderRules::new(&rewrite)?;
+    let mut headers = HeaderMap::new();
+    headers.insert("x-remove", HeaderValue::from_static("gone"));
+    headers.insert("x-set", HeaderValue::from_static("old"));
+    headers.insert("x-add", HeaderValue::from_static("one"));
+    rules.apply(&mut headers);
+    assert!(headers.get("x-remove").is_none());
+    assert_eq!(headers["x-set"], "new");
+    let added: Vec<_> = headers.get_all("x-add").iter().collect();
+    assert_eq!(added, vec!["one", "two"]);
+    Ok(())
+  }
+
+  // "port_limited" tests are grouped together and run on a single thread to prevent port contention
+  mod port_limited {
+    use std::convert::Infallible;
+
+    use anyhow::Result;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::Server;
+
+    use super::super::*;
+    use crate::test::load_test_manifest;
+    use crate::triggers::http::Http;
+    use crate::{build_trigger_runtime, Trigger};
+
+    static PORT: &str = "9005";
+
+    /// Serve an upstream that answers every request after `delay`, reporting the request's
+    /// `x-forwarded-by` header.
+    fn serve_upstream(port: u16, delay: Duration) {
+      let make_service = make_service_fn(move |_| async move {
+        Ok::<_, Infallible>(service_fn(move |request: Request<Body>| async move {
+          tokio::time::sleep(delay).await;
+          let forwarded_by = request.headers().get("x-forwarded-by").cloned();
+          let mut response = Response::new(Body::from(request.uri().path().to_owned()));
+          response
+            .headers_mut()
+            .insert("x-internal", HeaderValue::from_static("secret"));
+          if let Some(value) = forwarded_by {
+            response.headers_mut().insert("x-forwarded-by", value);
+          }
+          Ok::<_, Infallible>(response)
+        }))
+      });
+      let server = Server::bind(&([127, 0, 0, 1], port).into()).serve(make_service);
+      tokio::spawn(server);
+    }
+
+    #[test_logger::test(tokio::test)]
+    async fn test_proxy_router() -> Result<()> {
+      std::env::set_var("HTTP_PORT", PORT);
+      serve_upstream(9011, Duration::ZERO);
+      serve_upstream(9013, Duration::from_secs(2));
+
+      let app_config = load_test_manifest("app_config/proxy-router.wick")
+        .await?
+        .try_app_config()?;
+      let rt = build_trigger_runtime(&app_config, Span::current())?.build(None).await?;
+      let mut resources = HashMap::new();
+      for resource in app_config.resources() {
+        resources.insert(resource.id().to_owned(), Resource::new(resource.kind().clone())?);
+      }
+      let trigger = Http::default();
+      let trigger_config = app_config.triggers()[0].clone();
+      trigger
+        .run(
+          "test".to_owned(),
+          rt,
+          app_config,
+          trigger_config,
+          Arc::new(resources),
+          Span::current(),
+        )
+        .await?;
+
+      let client = reqwest::Client::new();
+      // The first upstream can't be reached, so every request ends up on the live one.
+      for _ in 0..2 {
+        let res = client
+          .post(format!("http://0.0.0.0:{}/balanced/hello", PORT))
+          .body("hi")
+          .send()
+          .await?;
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers()["x-forwarded-by"], "wick");
+        assert!(res.headers().get("x-internal").is_none());
+        assert_eq!(res.text().await?, "/hello");
+      }
+
+      let res = client.get(format!("http://0.0.0.0:{}/dead", PORT)).send().await?;
+      assert_eq!(res.status(), 502);
+
+      let res = client.get(format!("http://0.0.0.0:{}/slow", PORT)).send().await?;
+      assert_eq!(res.status(), 504);
+
+      trigger.shutdown_gracefully().await?;
+      Ok(())
+    }
+  }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -14,6 +15,7 @@ use wick_config::config::{Codec, RawRouterConfig, WickRouter};
 use wick_packet::{packets, Base64Bytes, Entity, InherentData, Invocation, Observer, Packet, PacketStream};
 
 use crate::dev::prelude::RuntimeError;
+use crate::resources::Resource;
 use crate::triggers::http::component_utils::respond;
 use crate::triggers::http::conversions::request_and_body_to_wick;
 use crate::triggers::http::middleware::resolve_middleware_components;
@@ -170,9 +172,13 @@ impl Service<Request<Body>> for RawHandler {
   }
 }
 
-pub(crate) fn register_raw_router(index: usize, router_config: &RawRouterConfig) -> Result<HttpRouter, RuntimeError> {
+pub(crate) fn register_raw_router(
+  index: usize,
+  resources: Arc<HashMap<String, Resource>>,
+  router_config: &RawRouterConfig,
+) -> Result<HttpRouter, RuntimeError> {
   trace!(index, "registering raw router");
-  let middleware = resolve_middleware_components(router_config)?;
+  let middleware = resolve_middleware_components(router_config, &resources)?;
 
   let component_id = router_config.operation().component_id()?;
 
@@ -5,20 +5,31 @@ use std::task::Poll;
 
 use futures::future::BoxFuture;
 use hyper::body::to_bytes;
+use hyper::header::{HeaderValue, ACCEPT, ALLOW};
 use hyper::service::Service;
 use hyper::{Body, Request, Response, StatusCode};
 use tracing::{Instrument, Span};
 use uuid::Uuid;
-use wick_config::config::{AppConfiguration, ComponentOperationExpression, HttpMethod, RestRouterConfig, WickRouter};
+use wick_config::config::{
+  AppConfiguration,
+  ComponentOperationExpression,
+  ContentType,
+  HttpMethod,
+  RestRouterConfig,
+  WickRouter,
+};
+use wick_interface_types::{Field, Type};
 use wick_packet::{Entity, InherentData, Invocation, Packet};
 mod error;
 mod openapi;
+mod response;
 mod route;
+mod validation;
 
 use self::error::RestError;
+use self::validation::{BodySchema, Violation};
 use crate::dev::prelude::RuntimeError;
 use crate::resources::Resource;
-use crate::triggers::http::component_utils::stream_to_json;
 use crate::triggers::http::middleware::resolve_middleware_components;
 use crate::triggers::http::{HttpError, HttpRouter, RawRouter, RawRouterHandler};
 use crate::triggers::ComponentId;
@@ -125,21 +136,7 @@ impl RestHandler {
       span,
       tx_id,
     } = self;
-    let method = match *request.method() {
-      hyper::Method::GET => wick_config::config::HttpMethod::Get,
-      hyper::Method::POST => wick_config::config::HttpMethod::Post,
-      hyper::Method::PUT => wick_config::config::HttpMethod::Put,
-      hyper::Method::DELETE => wick_config::config::HttpMethod::Delete,
-      _ => {
-        return Ok(
-          Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .body(Body::empty())
-            .unwrap(),
-        )
-      }
-    };
-
+    let method = method_from_hyper(request.method());
     let path = request.uri().path().trim_start_matches(context.root.as_str());
     if let Some(openapi) = context.openapi.as_ref() {
       if path == OPENAPI_PATH {
@@ -155,77 +152,173 @@ impl RestHandler {
       }
     }
 
-    for route in &context.routes {
-      if !route.config.methods().is_empty() && !route.config.methods().contains(&method) {
-        continue;
-      }
-      let Some((path_params, query_params)) = route.route.compare(path, request.uri().query())? else {
-        continue;
-      };
-      let uri = request.uri().clone();
-      span.in_scope(
-        || trace!(route = %uri, path_params=?path_params, query_params=?query_params, "incoming http request"),
-      );
-      let mut packets: Vec<_> = path_params
+    // Routes are tried in order, a path parameter that doesn't fit one route's type falls through to the next.
+    let mut matched = None;
+    let mut invalid = None;
+    if let Some(method) = method {
+      for route in context
+        .routes
         .iter()
-        .chain(query_params.iter())
-        .map(|f| Packet::encode(f.name(), f.value()))
-        .collect();
-
-      let (_, body) = request.into_parts();
-
-      let body_bytes = to_bytes(body).await.unwrap_or_default();
-      let body = String::from_utf8_lossy(&body_bytes);
+        .filter(|route| route.allows(method) && route.route.matches_path(path))
+      {
+        match route.route.compare(path, request.uri().query()) {
+          Ok(Some(values)) => {
+            matched = Some((route, values));
+            break;
+          }
+          Ok(None) => {}
+          Err(e) => {
+            invalid.get_or_insert(e);
+          }
+        }
+      }
+    }
+    let Some((route, (path_params, query_params))) = matched else {
+      return invalid.map_or_else(|| Ok(unmatched(&context, path, method)), Err);
+    };
+    let method = method.unwrap_or(HttpMethod::Get);
+
+    let uri = request.uri().clone();
+    span
+      .in_scope(|| trace!(route = %uri, path_params=?path_params, query_params=?query_params, "incoming http request"));
+    let mut packets: Vec<_> = path_params
+      .iter()
+      .chain(query_params.iter())
+      .map(|f| Packet::encode(f.name(), f.value()))
+      .collect();
+
+    let accept = request.headers().get(ACCEPT).and_then(|accept| accept.to_str().ok());
+    let Some(content_type) = response::negotiate(accept, route.content_types()) else {
+      span.in_scope(|| debug!(route = %uri, ?accept, "no acceptable content type"));
+      return Ok(status_response(StatusCode::NOT_ACCEPTABLE));
+    };
 
-      span.in_scope(|| trace!(route = %uri, len=body_bytes.len(), "body"));
+    let (_, body) = request.into_parts();
 
-      if !matches!(method, HttpMethod::Get) {
-        let payload: Option<serde_json::Value> = if body.trim().is_empty() {
-          None
-        } else {
-          Some(serde_json::from_str(&body).map_err(HttpError::InvalidBody)?)
-        };
+    let body_bytes = to_bytes(body).await.unwrap_or_default();
+    let body = String::from_utf8_lossy(&body_bytes);
 
-        packets.push(Packet::encode("input", payload));
-      }
+    span.in_scope(|| trace!(route = %uri, len=body_bytes.len(), "body"));
 
-      let mut port_names: Vec<_> = packets.iter().map(|p| p.port().to_owned()).collect();
-      port_names.dedup();
-      for port in port_names {
-        packets.push(Packet::done(port));
+    if !matches!(method, HttpMethod::Get | HttpMethod::Head) {
+      let payload: Option<serde_json::Value> = if body.trim().is_empty() {
+        None
+      } else {
+        match serde_json::from_str(&body) {
+          Ok(payload) => payload,
+          Err(e) => return Ok(invalid_body(&[Violation::new("$", e.to_string())])),
+        }
+      };
+      if let Some(schema) = &route.body {
+        let violations = schema.validate(payload.as_ref());
+        if !violations.is_empty() {
+          span.in_scope(|| debug!(route = %uri, ?violations, "invalid request body"));
+          return Ok(invalid_body(&violations));
+        }
       }
 
-      let invocation = Invocation::new_with_id(
-        tx_id,
-        Entity::server("http"),
-        Entity::operation(&route.component, route.operation.name()),
-        packets,
-        InherentData::unsafe_default(),
-        &span,
-      );
-      let runtime_config = route.operation.config().and_then(|c| c.value().cloned());
+      packets.push(Packet::encode("input", payload));
+    }
 
-      let stream = runtime
-        .invoke(invocation, runtime_config)
-        .instrument(span)
-        .await
-        .map_err(|e| HttpError::OperationError(e.to_string()))?;
-      let json = stream_to_json(stream).await?;
-      return Response::builder()
-        .status(StatusCode::OK)
-        .header("Content-Type", "application/json")
-        .body(Body::from(json.to_string()))
-        .map_err(|e| HttpError::OperationError(e.to_string()));
+    let mut port_names: Vec<_> = packets.iter().map(|p| p.port().to_owned()).collect();
+    port_names.dedup();
+    for port in port_names {
+      packets.push(Packet::done(port));
     }
-    Ok(
-      Response::builder()
-        .status(StatusCode::NOT_FOUND)
-        .body(Body::empty())
-        .unwrap(),
-    )
+
+    let invocation = Invocation::new_with_id(
+      tx_id,
+      Entity::server("http"),
+      Entity::operation(&route.component, route.operation.name()),
+      packets,
+      InherentData::unsafe_default(),
+      &span,
+    );
+    let runtime_config = route.operation.config().and_then(|c| c.value().cloned());
+
+    let stream = runtime
+      .invoke(invocation, runtime_config)
+      .instrument(span.clone())
+      .await
+      .map_err(|e| HttpError::OperationError(e.to_string()))?;
+    response::respond(&route.config, content_type, stream, &span).await
   }
 }
 
+/// Every method in the order it is listed in `Allow` headers.
+const ALL_METHODS: [HttpMethod; 8] = [
+  HttpMethod::Get,
+  HttpMethod::Head,
+  HttpMethod::Post,
+  HttpMethod::Put,
+  HttpMethod::Patch,
+  HttpMethod::Delete,
+  HttpMethod::Options,
+  HttpMethod::Trace,
+];
+
+const fn method_from_hyper(method: &hyper::Method) -> Option<HttpMethod> {
+  match *method {
+    hyper::Method::GET => Some(HttpMethod::Get),
+    hyper::Method::POST => Some(HttpMethod::Post),
+    hyper::Method::PUT => Some(HttpMethod::Put),
+    hyper::Method::DELETE => Some(HttpMethod::Delete),
+    hyper::Method::PATCH => Some(HttpMethod::Patch),
+    hyper::Method::HEAD => Some(HttpMethod::Head),
+    hyper::Method::OPTIONS => Some(HttpMethod::Options),
+    hyper::Method::TRACE => Some(HttpMethod::Trace),
+    _ => None,
+  }
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+  Response::builder().status(status).body(Body::empty()).unwrap()
+}
+
+/// A `400 Bad Request` listing everything wrong with a request body.
+fn invalid_body(violations: &[Violation]) -> Response<Body> {
+  let body = serde_json::json!({
+    "error": "Invalid request body",
+    "violations": violations,
+  });
+  Response::builder()
+    .status(StatusCode::BAD_REQUEST)
+    .header("Content-Type", "application/json")
+    .body(Body::from(body.to_string()))
+    .unwrap()
+}
+
+/// Answer a request that no route accepts: a 404 if nothing serves the path, otherwise an
+/// automatic OPTIONS response or a 405 listing the allowed methods. CORS preflights are left to
+/// the cors middleware.
+fn unmatched(context: &Context, path: &str, method: Option<HttpMethod>) -> Response<Body> {
+  let allowed: Vec<_> = ALL_METHODS
+    .into_iter()
+    .filter(|method| {
+      *method == HttpMethod::Options
+        || context
+          .routes
+          .iter()
+          .any(|route| route.allows(*method) && route.route.matches_path(path))
+    })
+    .collect();
+  if allowed.len() == 1 {
+    return status_response(StatusCode::NOT_FOUND);
+  }
+  let allow = allowed.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+
+  let status = if method == Some(HttpMethod::Options) {
+    StatusCode::NO_CONTENT
+  } else {
+    StatusCode::METHOD_NOT_ALLOWED
+  };
+  let mut response = status_response(status);
+  response
+    .headers_mut()
+    .insert(ALLOW, HeaderValue::from_str(&allow).unwrap());
+  response
+}
+
 impl Service<Request<Body>> for RestHandler {
   type Response = Response<Body>;
   type Error = HttpError;
@@ -245,10 +338,37 @@ pub(crate) struct RestRoute {
   route: route::Route,
   component: String,
   operation: ComponentOperationExpression,
+  body: Option<BodySchema>,
+  response: Option<BodySchema>,
 }
 
 impl RestRoute {
-  pub(super) fn new(config: wick_config::config::RestRoute, component_id: String) -> Result<Self, HttpError> {
+  /// Whether this route handles `method`. Routes without methods handle everything except
+  /// OPTIONS, and routes that handle GET also handle HEAD.
+  fn allows(&self, method: HttpMethod) -> bool {
+    let methods = self.config.methods();
+    if methods.is_empty() {
+      return method != HttpMethod::Options;
+    }
+    methods.contains(&method) || (method == HttpMethod::Head && methods.contains(&HttpMethod::Get))
+  }
+
+  /// The content types this route responds with, in order of preference.
+  fn content_types(&self) -> &[ContentType] {
+    let content_types = self.config.content_types();
+    if content_types.is_empty() {
+      response::DEFAULT_CONTENT_TYPES
+    } else {
+      content_types
+    }
+  }
+
+  pub(super) fn new(
+    config: wick_config::config::RestRoute,
+    component_id: String,
+    body: Option<BodySchema>,
+    response: Option<BodySchema>,
+  ) -> Result<Self, HttpError> {
     let route = route::Route::parse(config.sub_path())
       .map_err(|e| HttpError::RouteSyntax(e.to_string(), config.sub_path().to_owned()))?;
     let operation = config.operation().clone();
@@ -258,6 +378,8 @@ impl RestRoute {
       route,
       component: component_id,
       operation,
+      body,
+      response,
     })
   }
 }
@@ -269,6 +391,13 @@ mod test {
   mod port_limited {
 
     use anyhow::Result;
+    use hyper::header::{
+      ACCESS_CONTROL_ALLOW_METHODS,
+      ACCESS_CONTROL_ALLOW_ORIGIN,
+      ACCESS_CONTROL_REQUEST_HEADERS,
+      ACCESS_CONTROL_REQUEST_METHOD,
+      ORIGIN,
+    };
 
     use super::super::*;
     use crate::resources::Resource;
@@ -316,24 +445,290 @@ mod test {
       trigger.shutdown_gracefully().await?;
       Ok(())
     }
+
+    #[test_logger::test(tokio::test)]
+    async fn rest_methods() -> Result<()> {
+      std::env::set_var("HTTP_PORT", PORT);
+      let app_config = load_test_manifest("app_config/rest-router-errors.wick")
+        .await?
+        .try_app_config()?;
+      let rt = build_trigger_runtime(&app_config, Span::current())?.build(None).await?;
+
+      let trigger = Http::default();
+      let resource = Resource::new(app_config.resources().get(0).as_ref().unwrap().kind().clone())?;
+      let resources = Arc::new([("http".to_owned(), resource)].iter().cloned().collect());
+      let trigger_config = app_config.triggers()[0].clone();
+      trigger
+        .run(
+          "test".to_owned(),
+          rt,
+          app_config,
+          trigger_config,
+          resources,
+          Span::current(),
+        )
+        .await?;
+
+      let client = reqwest::Client::new();
+      let res = client.get(format!("http://0.0.0.0:{}/bad_op", PORT)).send().await?;
+      assert_eq!(res.status(), 405);
+      assert_eq!(res.headers()[ALLOW], "POST, OPTIONS");
+
+      let res = client
+        .request(hyper::Method::OPTIONS, format!("http://0.0.0.0:{}/bad_op", PORT))
+        .header(ORIGIN, "http://example.com")
+        .header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .header(ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+        .send()
+        .await?;
+      assert_eq!(res.status(), 204);
+      assert_eq!(res.headers()[ALLOW], "POST, OPTIONS");
+      // Without the cors middleware, preflights aren't answered with CORS headers.
+      assert!(!res.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+
+      let res = client.get(format!("http://0.0.0.0:{}/missing", PORT)).send().await?;
+      assert_eq!(res.status(), 404);
+
+      // A parameter that doesn't fit the first route falls through to the next.
+      let res = client.get(format!("http://0.0.0.0:{}/items/5", PORT)).send().await?;
+      assert_eq!(res.json::<serde_json::Value>().await?, serde_json::json!({"output": 5}));
+      let res = client.get(format!("http://0.0.0.0:{}/items/abc", PORT)).send().await?;
+      assert_eq!(
+        res.json::<serde_json::Value>().await?,
+        serde_json::json!({"output": "abc"})
+      );
+      let res = client
+        .get(format!("http://0.0.0.0:{}/items/5/extra", PORT))
+        .send()
+        .await?;
+      assert_eq!(res.status(), 404);
+
+      trigger.shutdown_gracefully().await?;
+      Ok(())
+    }
+
+    #[test_logger::test(tokio::test)]
+    async fn rest_builtin_middleware() -> Result<()> {
+      std::env::set_var("HTTP_PORT", PORT);
+      let app_config = load_test_manifest("app_config/rest-router-builtin-middleware.wick")
+        .await?
+        .try_app_config()?;
+      let rt = build_trigger_runtime(&app_config, Span::current())?.build(None).await?;
+
+      let trigger = Http::default();
+      let resource = Resource::new(app_config.resources().get(0).as_ref().unwrap().kind().clone())?;
+      let resources = Arc::new([("http".to_owned(), resource)].iter().cloned().collect());
+      let trigger_config = app_config.triggers()[0].clone();
+      trigger
+        .run(
+          "test".to_owned(),
+          rt,
+          app_config,
+          trigger_config,
+          resources,
+          Span::current(),
+        )
+        .await?;
+
+      let client = reqwest::Client::new();
+      let res = client
+        .request(hyper::Method::OPTIONS, format!("http://0.0.0.0:{}/bad_op", PORT))
+        .header(ORIGIN, "http://example.com")
+        .header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .send()
+        .await?;
+      assert_eq!(res.status(), 204);
+      assert_eq!(res.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "http://example.com");
+      assert_eq!(res.headers()[ACCESS_CONTROL_ALLOW_METHODS], "POST");
+
+      let res = client
+        .post(format!("http://0.0.0.0:{}/bad_op", PORT))
+        .header(ORIGIN, "http://example.com")
+        .send()
+        .await?;
+      assert_eq!(res.status(), 401);
+      assert_eq!(res.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "http://example.com");
+
+      let res = client
+        .post(format!("http://0.0.0.0:{}/bad_op", PORT))
+        .header("x-api-key", "secret")
+        .body(r#"{"message": "my json message"}"#)
+        .send()
+        .await?;
+      assert_ne!(res.status(), 401);
+
+      trigger.shutdown_gracefully().await?;
+      Ok(())
+    }
+
+    #[test_logger::test(tokio::test)]
+    async fn rest_body_validation() -> Result<()> {
+      std::env::set_var("HTTP_PORT", PORT);
+      let app_config = load_test_manifest("app_config/rest-router-validation.wick")
+        .await?
+        .try_app_config()?;
+      let rt = build_trigger_runtime(&app_config, Span::current())?.build(None).await?;
+
+      let trigger = Http::default();
+      let resource = Resource::new(app_config.resources().get(0).as_ref().unwrap().kind().clone())?;
+      let resources = Arc::new([("http".to_owned(), resource)].iter().cloned().collect());
+      let trigger_config = app_config.triggers()[0].clone();
+      trigger
+        .run(
+          "test".to_owned(),
+          rt,
+          app_config,
+          trigger_config,
+          resources,
+          Span::current(),
+        )
+        .await?;
+
+      let client = reqwest::Client::new();
+      let res = client
+        .post(format!("http://0.0.0.0:{}/api/users", PORT))
+        .body(r#"{"name": "Jane", "age": 30, "role": "admin"}"#)
+        .send()
+        .await?;
+      assert_eq!(res.status(), 200);
+      let body: serde_json::Value = res.json().await?;
+      assert_eq!(body["output"]["name"], "Jane");
+
+      let res = client
+        .post(format!("http://0.0.0.0:{}/api/users", PORT))
+        .body(r#"{"age": -1, "role": "owner", "tags": [1]}"#)
+        .send()
+        .await?;
+      assert_eq!(res.status(), 400);
+      let body: serde_json::Value = res.json().await?;
+      let paths: Vec<_> = body["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["path"].as_str().unwrap().to_owned())
+        .collect();
+      assert_eq!(paths, vec!["$.name", "$.age", "$.role", "$.tags[0]"]);
+
+      let res = client
+        .post(format!("http://0.0.0.0:{}/api/users", PORT))
+        .body("{not json")
+        .send()
+        .await?;
+      assert_eq!(res.status(), 400);
+
+      let res = client
+        .get(format!("http://0.0.0.0:{}/api{}", PORT, OPENAPI_PATH))
+        .send()
+        .await?;
+      let openapi: serde_json::Value = res.json().await?;
+      let body = &openapi["paths"]["/users"]["post"]["requestBody"];
+      assert_eq!(body["required"], true);
+      assert_eq!(
+        body["content"]["application/json"]["schema"]["oneOf"][0]["$ref"],
+        "User"
+      );
+      assert!(openapi["components"]["schemas"]["Role"].is_object());
+
+      trigger.shutdown_gracefully().await?;
+      Ok(())
+    }
+
+    #[test_logger::test(tokio::test)]
+    async fn rest_response_shaping() -> Result<()> {
+      std::env::set_var("HTTP_PORT", PORT);
+      let app_config = load_test_manifest("app_config/rest-router-response.wick")
+        .await?
+        .try_app_config()?;
+      let rt = build_trigger_runtime(&app_config, Span::current())?.build(None).await?;
+
+      let trigger = Http::default();
+      let resource = Resource::new(app_config.resources().get(0).as_ref().unwrap().kind().clone())?;
+      let resources = Arc::new([("http".to_owned(), resource)].iter().cloned().collect());
+      let trigger_config = app_config.triggers()[0].clone();
+      trigger
+        .run(
+          "test".to_owned(),
+          rt,
+          app_config,
+          trigger_config,
+          resources,
+          Span::current(),
+        )
+        .await?;
+
+      let client = reqwest::Client::new();
+      let res = client
+        .post(format!("http://0.0.0.0:{}/api/users", PORT))
+        .body(r#"{"name": "Jane", "age": 30}"#)
+        .send()
+        .await?;
+      assert_eq!(res.status(), 201);
+      assert_eq!(res.headers()["location"], "/api/users/1");
+      let tags: Vec<_> = res.headers().get_all("x-tags").iter().collect();
+      assert_eq!(tags, vec!["new", "user"]);
+      assert_eq!(res.headers()["content-type"], "application/json");
+      let body: serde_json::Value = res.json().await?;
+      assert_eq!(body, serde_json::json!({"name": "Jane", "age": 30}));
+
+      let url = format!("http://0.0.0.0:{}/api/users/export", PORT);
+      let res = client.get(&url).header(ACCEPT, "text/csv").send().await?;
+      assert_eq!(res.status(), 200);
+      assert_eq!(res.headers()["content-type"], "text/csv");
+      assert_eq!(res.text().await?, "age,name\r\n30,Jane\r\n5,\"John, Jr.\"\r\n");
+
+      let res = client
+        .get(&url)
+        .header(ACCEPT, "application/json;q=0.5, application/x-ndjson")
+        .send()
+        .await?;
+      assert_eq!(res.headers()["content-type"], "application/x-ndjson");
+      let lines: Vec<serde_json::Value> = res
+        .text()
+        .await?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+      assert_eq!(lines.len(), 2);
+      assert_eq!(lines[1]["name"], "John, Jr.");
+
+      let res = client.get(&url).header(ACCEPT, "image/png").send().await?;
+      assert_eq!(res.status(), 406);
+
+      let res = client
+        .get(format!("http://0.0.0.0:{}/api{}", PORT, OPENAPI_PATH))
+        .send()
+        .await?;
+      let openapi: serde_json::Value = res.json().await?;
+      let responses = &openapi["paths"]["/users"]["post"]["responses"];
+      assert!(responses["default"]["content"]["application/json"].is_object());
+      assert!(responses["400"]["content"]["application/json"]["schema"]["properties"]["violations"].is_object());
+      let responses = &openapi["paths"]["/users/export"]["get"]["responses"];
+      assert!(responses["200"]["content"]["text/csv"].is_object());
+      assert!(responses["406"].is_object());
+
+      trigger.shutdown_gracefully().await?;
+      Ok(())
+    }
   }
 }
 
 pub(crate) fn register_rest_router(
   index: usize,
-  _resources: Arc<HashMap<String, Resource>>,
+  resources: Arc<HashMap<String, Resource>>,
+  runtime: &Runtime,
   app_config: &AppConfiguration,
   router_config: &RestRouterConfig,
 ) -> Result<HttpRouter, RuntimeError> {
   trace!(index, "registering rest router");
-  let middleware = resolve_middleware_components(router_config)?;
+  let middleware = resolve_middleware_components(router_config, &resources)?;
   let mut routes = Vec::new();
 
   for route in router_config.routes().iter() {
     info!(sub_path = route.sub_path(), "registering rest route");
 
     let component_id = route.operation().component_id()?;
-    let route = RestRoute::new(route.clone(), component_id.to_owned()).map_err(|e| {
+    let (body, response) = route_schemas(runtime, app_config, route, component_id);
+    let route = RestRoute::new(route.clone(), component_id.to_owned(), body, response).map_err(|e| {
       RuntimeError::InitializationFailed(format!(
         "could not intitialize rest router for route {}: {}",
         route.sub_path(),
@@ -351,3 +746,54 @@ pub(crate) fn register_rest_router(
     middleware,
   }))
 }
+
+/// The schemas for a route's request and response bodies. Requests are the type of its operation's
+/// `input` port and responses the type of its body port, or of every output that isn't a status or
+/// headers port when it doesn't name one.
+fn route_schemas(
+  runtime: &Runtime,
+  app_config: &AppConfiguration,
+  route: &wick_config::config::RestRoute,
+  component_id: &str,
+) -> (Option<BodySchema>, Option<BodySchema>) {
+  let operation = route.operation().name();
+  let signature = match runtime.deep_signature(None, Some(&Entity::operation(component_id, operation))) {
+    Ok(signature) => signature,
+    Err(e) => {
+      debug!(component_id, operation, error = %e, "could not find signature, request bodies will not be validated");
+      return (None, None);
+    }
+  };
+  let Some(op) = signature.get_operation(operation) else {
+    return (None, None);
+  };
+  let resolve = |name: &str| {
+    signature
+      .types
+      .iter()
+      .find(|ty| ty.name() == name)
+      .cloned()
+      .or_else(|| app_config.resolve_type(name))
+  };
+
+  let body = op
+    .inputs
+    .iter()
+    .find(|field| field.name() == "input")
+    .map(|field| BodySchema::new(field.clone(), resolve));
+
+  let response = route.body_port().map_or_else(
+    || {
+      let skipped = [route.status_port(), route.headers_port()];
+      let fields = op
+        .outputs
+        .iter()
+        .filter(|field| !skipped.iter().flatten().any(|port| *port == field.name()))
+        .cloned()
+        .collect();
+      Some(Field::new("output", Type::AnonymousStruct(fields)))
+    },
+    |port| op.outputs.iter().find(|field| field.name() == port).cloned(),
+  );
+  (body, response.map(|field| BodySchema::new(field, resolve)))
+}
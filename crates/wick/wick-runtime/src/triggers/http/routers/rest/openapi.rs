@@ -7,6 +7,7 @@ use openapiv3::{
   Info,
   IntegerType,
   License,
+  MediaType,
   NumberType,
   ObjectType,
   OpenAPI,
@@ -17,16 +18,19 @@ use openapiv3::{
   PathItem,
   Paths,
   ReferenceOr,
+  RequestBody,
+  Response,
   Responses,
   Schema,
   SchemaData,
   StringType,
   VariantOrUnknownOrEmpty,
 };
-use wick_config::config::{AppConfiguration, HttpMethod, WickRouter};
+use wick_config::config::{AppConfiguration, ContentType, HttpMethod, WickRouter};
 use wick_interface_types::{EnumDefinition, Field, StructDefinition, Type, TypeDefinition, UnionDefinition};
 
 use super::error::RestError;
+use super::response::media_type;
 use super::RestRoute;
 
 // Generates an OpenAPI spec from a RestRouterConfig
@@ -79,9 +83,9 @@ pub(crate) fn generate_openapi(
   openapi.paths.paths.extend(paths);
 
   let (mut schemas, mut named_types, mut new_named_types) =
-    resolve_named_types(app_config, HashMap::new(), named_types)?;
+    resolve_named_types(app_config, routes, HashMap::new(), named_types)?;
   while !new_named_types.is_empty() {
-    (schemas, named_types, new_named_types) = resolve_named_types(app_config, schemas, named_types)?;
+    (schemas, named_types, new_named_types) = resolve_named_types(app_config, routes, schemas, named_types)?;
   }
 
   if openapi.components.is_none() {
@@ -99,6 +103,7 @@ type SchemaMap = HashMap<String, ReferenceOr<Schema>>;
 
 fn resolve_named_types(
   app_config: &AppConfiguration,
+  routes: &[RestRoute],
   mut schemas: HashMap<String, ReferenceOr<Schema>>,
   mut named: HashSet<String>,
 ) -> Result<(SchemaMap, Seen, Seen), RestError> {
@@ -106,8 +111,13 @@ fn resolve_named_types(
 
   for name in &named {
     if !schemas.contains_key(name) {
-      let ty = app_config
-        .resolve_type(name)
+      // Types used by request and response bodies may come from the operation's component rather
+      // than the app.
+      let ty = routes
+        .iter()
+        .flat_map(|route| route.body.iter().chain(route.response.iter()))
+        .find_map(|schema| schema.resolve_type(name).cloned())
+        .or_else(|| app_config.resolve_type(name))
         .ok_or_else(|| RestError::TypeNotFound(name.clone()))?;
       let schema = typedef_to_schema(&ty, &mut new_named);
       schemas.insert(name.clone(), ReferenceOr::Item(schema));
@@ -154,7 +164,7 @@ fn route_to_path_item(route: &RestRoute, named: &mut HashSet<String>) -> PathIte
     external_docs: Default::default(),
     parameters: Default::default(),
     request_body: Default::default(),
-    responses: Responses::default(),
+    responses: responses(route, false, named),
     deprecated: Default::default(),
     security: Default::default(),
     servers: Default::default(),
@@ -163,18 +173,182 @@ fn route_to_path_item(route: &RestRoute, named: &mut HashSet<String>) -> PathIte
   if route.config.methods().is_empty() || route.config.methods().contains(&HttpMethod::Get) {
     path_item.get = Some(oapi_operation.clone());
   }
+  let body_operation = Operation {
+    request_body: route.body.as_ref().map(|body| {
+      let mut content = openapiv3::Content::default();
+      content.insert(
+        "application/json".to_owned(),
+        MediaType {
+          schema: Some(ReferenceOr::Item(field_to_schema(body.field(), named))),
+          ..Default::default()
+        },
+      );
+      ReferenceOr::Item(RequestBody {
+        description: body.field().description().map(|s| s.to_owned()),
+        content,
+        required: body.required(),
+        extensions: Default::default(),
+      })
+    }),
+    responses: responses(route, route.body.is_some(), named),
+    ..oapi_operation.clone()
+  };
   if route.config.methods().contains(&HttpMethod::Post) {
-    path_item.post = Some(oapi_operation.clone());
+    path_item.post = Some(body_operation.clone());
   }
   if route.config.methods().contains(&HttpMethod::Put) {
-    path_item.put = Some(oapi_operation.clone());
+    path_item.put = Some(body_operation.clone());
   }
   if route.config.methods().contains(&HttpMethod::Delete) {
-    path_item.delete = Some(oapi_operation);
+    path_item.delete = Some(body_operation.clone());
+  }
+  if route.config.methods().contains(&HttpMethod::Patch) {
+    path_item.patch = Some(body_operation);
+  }
+  if route.config.methods().contains(&HttpMethod::Head) {
+    path_item.head = Some(oapi_operation.clone());
+  }
+  if route.config.methods().contains(&HttpMethod::Options) {
+    path_item.options = Some(oapi_operation.clone());
+  }
+  if route.config.methods().contains(&HttpMethod::Trace) {
+    path_item.trace = Some(oapi_operation);
   }
   path_item
 }
 
+/// The responses a route can send: its operation's output in every content type it offers, and the
+/// errors the router answers with itself.
+fn responses(route: &RestRoute, validates_body: bool, named: &mut HashSet<String>) -> Responses {
+  let mut responses = Responses::default();
+
+  let mut content = openapiv3::Content::default();
+  for content_type in route.content_types() {
+    let schema = match (content_type, &route.response) {
+      (ContentType::Json, Some(response)) => Some(field_to_schema(response.field(), named)),
+      // Streams have a line for every output packet, which is a single port's value when the
+      // route doesn't name a body port.
+      (ContentType::Ndjson, Some(response)) => Some(match response.field().ty() {
+        Type::AnonymousStruct(fields) if route.config.body_port().is_none() => Schema {
+          schema_data: Default::default(),
+          schema_kind: openapiv3::SchemaKind::OneOf {
+            one_of: fields
+              .iter()
+              .map(|field| ReferenceOr::Item(field_to_schema(field, named)))
+              .collect(),
+          },
+        },
+        _ => field_to_schema(response.field(), named),
+      }),
+      (ContentType::Json | ContentType::Ndjson, None) => None,
+      (ContentType::Csv, _) => Some(string_schema(VariantOrUnknownOrEmpty::Empty)),
+      (ContentType::Raw, _) => Some(string_schema(VariantOrUnknownOrEmpty::Item(
+        openapiv3::StringFormat::Binary,
+      ))),
+    };
+    content.insert(
+      media_type(*content_type).to_owned(),
+      MediaType {
+        schema: schema.map(ReferenceOr::Item),
+        ..Default::default()
+      },
+    );
+  }
+  let success = ReferenceOr::Item(Response {
+    description: "The operation's output.".to_owned(),
+    content,
+    ..Default::default()
+  });
+  // Operations that set their own status can respond with anything.
+  if route.config.status_port().is_some() {
+    responses.default = Some(success);
+  } else {
+    responses.responses.insert(openapiv3::StatusCode::Code(200), success);
+  }
+
+  if validates_body {
+    let mut content = openapiv3::Content::default();
+    content.insert(
+      "application/json".to_owned(),
+      MediaType {
+        schema: Some(ReferenceOr::Item(invalid_body_schema())),
+        ..Default::default()
+      },
+    );
+    responses.responses.insert(
+      openapiv3::StatusCode::Code(400),
+      ReferenceOr::Item(Response {
+        description: "The request body does not match the operation's input.".to_owned(),
+        content,
+        ..Default::default()
+      }),
+    );
+  }
+  if route.content_types().len() > 1 {
+    responses.responses.insert(
+      openapiv3::StatusCode::Code(406),
+      ReferenceOr::Item(Response {
+        description: "The route can not respond with any content type the request accepts.".to_owned(),
+        ..Default::default()
+      }),
+    );
+  }
+  responses.responses.insert(
+    openapiv3::StatusCode::Code(500),
+    ReferenceOr::Item(Response {
+      description: "The operation failed.".to_owned(),
+      ..Default::default()
+    }),
+  );
+  responses
+}
+
+/// The body of a `400 Bad Request` sent for an invalid request body.
+fn invalid_body_schema() -> Schema {
+  let object = |properties: Vec<(&str, Schema)>| {
+    let mut obj = ObjectType::default();
+    for (name, schema) in properties {
+      obj
+        .properties
+        .insert(name.to_owned(), ReferenceOr::Item(Box::new(schema)));
+      obj.required.push(name.to_owned());
+    }
+    Schema {
+      schema_data: Default::default(),
+      schema_kind: openapiv3::SchemaKind::Type(openapiv3::Type::Object(obj)),
+    }
+  };
+  let violation = object(vec![
+    ("path", string_schema(VariantOrUnknownOrEmpty::Empty)),
+    ("message", string_schema(VariantOrUnknownOrEmpty::Empty)),
+  ]);
+  object(vec![
+    ("error", string_schema(VariantOrUnknownOrEmpty::Empty)),
+    (
+      "violations",
+      Schema {
+        schema_data: Default::default(),
+        schema_kind: openapiv3::SchemaKind::Type(openapiv3::Type::Array(ArrayType {
+          items: Some(ReferenceOr::Item(Box::new(violation))),
+          min_items: Default::default(),
+          max_items: Default::default(),
+          unique_items: Default::default(),
+        })),
+      },
+    ),
+  ])
+}
+
+fn string_schema(format: VariantOrUnknownOrEmpty<openapiv3::StringFormat>) -> Schema {
+  Schema {
+    schema_data: Default::default(),
+    schema_kind: openapiv3::SchemaKind::Type(openapiv3::Type::String(StringType {
+      format,
+      ..Default::default()
+    })),
+  }
+}
+
 fn convert_contact(contact: Option<&wick_config::config::Contact>) -> Option<Contact> {
   contact.map(|c| Contact {
     name: c.name().cloned(),
@@ -372,7 +546,19 @@ fn wick_type_to_schemakind(ty: &Type, named: &mut HashSet<String>) -> openapiv3:
       min_properties: Default::default(),
       max_properties: Default::default(),
     })),
-    Type::AnonymousStruct(_) => unimplemented!("Anonymous structs are not supported in OpenAPI config"),
+    Type::AnonymousStruct(fields) => {
+      let mut obj = ObjectType::default();
+      for field in fields {
+        obj.properties.insert(
+          field.name().to_owned(),
+          ReferenceOr::Item(Box::new(field_to_schema(field, named))),
+        );
+        if field.required() {
+          obj.required.push(field.name().to_owned());
+        }
+      }
+      openapiv3::SchemaKind::Type(openapiv3::Type::Object(obj))
+    }
     #[allow(deprecated)]
     Type::Link { .. } => unreachable!(),
   }
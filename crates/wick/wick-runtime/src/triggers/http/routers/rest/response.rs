@@ -0,0 +1,468 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use hyper::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use hyper::{Body, HeaderMap, Response, StatusCode};
+use serde_json::{Map, Value};
+use tokio_stream::StreamExt;
+use tracing::{Instrument, Span};
+use wick_config::config::{Codec, ContentType, RestRoute};
+use wick_packet::{Base64Bytes, Packet, PacketPayload, PacketStream};
+
+use crate::triggers::http::HttpError;
+
+/// The content types routes respond with when they don't configure any.
+pub(super) const DEFAULT_CONTENT_TYPES: &[ContentType] = &[ContentType::Json];
+
+/// The media types a content type is accepted as, the first of which it is served as.
+const fn media_types(content_type: ContentType) -> &'static [&'static str] {
+  match content_type {
+    ContentType::Json => &["application/json"],
+    ContentType::Ndjson => &["application/x-ndjson", "application/ndjson"],
+    ContentType::Csv => &["text/csv"],
+    ContentType::Raw => &["application/octet-stream"],
+  }
+}
+
+pub(super) const fn media_type(content_type: ContentType) -> &'static str {
+  media_types(content_type)[0]
+}
+
+/// Choose the content type to respond with from those a route offers. The quality of each is
+/// decided by the most specific range in `accept` that matches it, and ties go to the route's
+/// order. Requests without an `Accept` header get the route's first content type.
+pub(super) fn negotiate(accept: Option<&str>, offered: &[ContentType]) -> Option<ContentType> {
+  let Some(accept) = accept.filter(|accept| !accept.trim().is_empty()) else {
+    return offered.first().copied();
+  };
+  let ranges: Vec<(String, f32)> = accept
+    .split(',')
+    .filter_map(|range| {
+      let mut params = range.split(';');
+      let media = params.next()?.trim().to_ascii_lowercase();
+      let quality = params
+        .find_map(|param| param.trim().strip_prefix("q=")?.trim().parse().ok())
+        .unwrap_or(1.0);
+      (!media.is_empty()).then_some((media, quality))
+    })
+    .collect();
+
+  let mut best: Option<(ContentType, f32)> = None;
+  for content_type in offered {
+    let quality = ranges
+      .iter()
+      .filter_map(|(range, quality)| specificity(range, *content_type).map(|s| (s, *quality)))
+      .max_by_key(|(specificity, _)| *specificity)
+      .map(|(_, quality)| quality);
+    if let Some(quality) = quality {
+      if quality > 0.0 && best.map_or(true, |(_, best)| quality > best) {
+        best = Some((*content_type, quality));
+      }
+    }
+  }
+  best.map(|(content_type, _)| content_type)
+}
+
+/// How closely a media range matches a content type, if it matches at all.
+fn specificity(range: &str, content_type: ContentType) -> Option<u8> {
+  if range == "*/*" {
+    return Some(0);
+  }
+  media_types(content_type)
+    .iter()
+    .filter_map(|media| {
+      if *media == range {
+        Some(2)
+      } else {
+        let kind = range.strip_suffix("/*")?;
+        media.starts_with(&format!("{}/", kind)).then_some(1)
+      }
+    })
+    .max()
+}
+
+/// The output ports a route reads its response from.
+#[derive(Debug, Clone)]
+struct Ports {
+  body: Option<String>,
+  status: Option<String>,
+  headers: Option<String>,
+}
+
+impl Ports {
+  fn new(route: &RestRoute) -> Self {
+    Self {
+      body: route.body_port().cloned(),
+      status: route.status_port().cloned(),
+      headers: route.headers_port().cloned(),
+    }
+  }
+
+  fn is_body(&self, port: &str) -> bool {
+    self.body.as_deref().map_or_else(
+      || Some(port) != self.status.as_deref() && Some(port) != self.headers.as_deref(),
+      |body| port == body,
+    )
+  }
+}
+
+/// The status and headers of a response.
+struct Head {
+  status: StatusCode,
+  headers: HeaderMap,
+}
+
+impl Head {
+  /// Apply a packet from the status or headers port, returning whether it was one.
+  fn apply(&mut self, ports: &Ports, packet: &Packet) -> Result<bool, HttpError> {
+    if Some(packet.port()) == ports.status.as_deref() {
+      self.status = decode_status(packet)?;
+      Ok(true)
+    } else if Some(packet.port()) == ports.headers.as_deref() {
+      decode_headers(packet, &mut self.headers)?;
+      Ok(true)
+    } else {
+      Ok(false)
+    }
+  }
+
+  fn into_response(mut self, content_type: ContentType, body: Body) -> Response<Body> {
+    if !self.headers.contains_key(CONTENT_TYPE) {
+      self
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static(media_type(content_type)));
+    }
+    let mut response = Response::new(body);
+    *response.status_mut() = self.status;
+    *response.headers_mut() = self.headers;
+    response
+  }
+}
+
+/// Turn an operation's output into a response the way its route describes.
+///
+/// JSON responses are sent once the output ends. Other content types are streamed as the output
+/// is produced, so their status and headers must be sent before the first packet of the body.
+pub(super) async fn respond(
+  route: &RestRoute,
+  content_type: ContentType,
+  mut stream: PacketStream,
+  span: &Span,
+) -> Result<Response<Body>, HttpError> {
+  let ports = Ports::new(route);
+  let mut head = Head {
+    status: StatusCode::OK,
+    headers: HeaderMap::new(),
+  };
+
+  let mut first = None;
+  while let Some(packet) = next_packet(&mut stream).await? {
+    if !head.apply(&ports, &packet)? && ports.is_body(packet.port()) {
+      first = Some(packet);
+      break;
+    }
+  }
+
+  if content_type == ContentType::Json {
+    let mut body = JsonBody::new(ports.body.is_some());
+    if let Some(packet) = first {
+      body.push(packet)?;
+    }
+    while let Some(packet) = next_packet(&mut stream).await? {
+      if !head.apply(&ports, &packet)? && ports.is_body(packet.port()) {
+        body.push(packet)?;
+      }
+    }
+    return Ok(head.into_response(content_type, Body::from(body.finish().to_string())));
+  }
+
+  let (sender, body) = Body::channel();
+  let packets = tokio_stream::iter(first.map(Ok))
+    .chain(stream)
+    .filter(move |packet| packet.as_ref().map_or(true, |packet| ports.is_body(packet.port())));
+  tokio::spawn(stream_body(content_type, sender, Box::pin(packets)).instrument(span.clone()));
+  Ok(head.into_response(content_type, body))
+}
+
+/// The next packet with data, or an error if the operation failed.
+async fn next_packet(stream: &mut PacketStream) -> Result<Option<Packet>, HttpError> {
+  while let Some(packet) = stream.next().await {
+    let packet = packet.map_err(|e| HttpError::OperationError(e.to_string()))?;
+    if let PacketPayload::Err(err) = packet.payload() {
+      return Err(HttpError::OutputStream(packet.port().to_owned(), err.msg().to_owned()));
+    }
+    if packet.has_data() {
+      return Ok(Some(packet));
+    }
+  }
+  Ok(None)
+}
+
+fn decode_value(packet: Packet) -> Result<Value, HttpError> {
+  packet
+    .decode_value()
+    .map_err(|e| HttpError::Codec(Codec::Json, e.to_string()))
+}
+
+fn decode_status(packet: &Packet) -> Result<StatusCode, HttpError> {
+  let value = decode_value(packet.clone())?;
+  let code = match &value {
+    Value::Number(code) => code.as_u64().and_then(|code| u16::try_from(code).ok()),
+    Value::String(code) => code.parse().ok(),
+    _ => None,
+  };
+  code
+    .and_then(|code| StatusCode::from_u16(code).ok())
+    .ok_or_else(|| HttpError::InvalidStatusCode(value.to_string()))
+}
+
+fn decode_headers(packet: &Packet, headers: &mut HeaderMap) -> Result<(), HttpError> {
+  let Value::Object(map) = decode_value(packet.clone())? else {
+    return Err(HttpError::Deserialize(
+      packet.port().to_owned(),
+      "headers must be a map of header names to values".to_owned(),
+    ));
+  };
+  for (name, values) in map {
+    let header = HeaderName::from_bytes(name.as_bytes()).map_err(|_| HttpError::InvalidHeaderName(name.clone()))?;
+    let values = match values {
+      Value::Array(values) => values,
+      value => vec![value],
+    };
+    for value in values {
+      let value = match value {
+        Value::String(value) => value,
+        value => value.to_string(),
+      };
+      let value = HeaderValue::from_str(&value).map_err(|_| HttpError::InvalidHeaderValue(value.clone()))?;
+      headers.append(header.clone(), value);
+    }
+  }
+  Ok(())
+}
+
+/// A JSON response body: the values from the body port, or every port's values keyed by port.
+enum JsonBody {
+  Port(Vec<Value>),
+  Ports(HashMap<String, Vec<Value>>),
+}
+
+impl JsonBody {
+  fn new(has_body_port: bool) -> Self {
+    if has_body_port {
+      Self::Port(Vec::new())
+    } else {
+      Self::Ports(HashMap::new())
+    }
+  }
+
+  fn push(&mut self, packet: Packet) -> Result<(), HttpError> {
+    let port = packet.port().to_owned();
+    let value = decode_value(packet)?;
+    match self {
+      Self::Port(values) => values.push(value),
+      Self::Ports(ports) => ports.entry(port).or_default().push(value),
+    }
+    Ok(())
+  }
+
+  /// Single values are sent as they are, and ports with more than one value as arrays.
+  fn finish(self) -> Value {
+    fn collapse(mut values: Vec<Value>) -> Value {
+      match values.len() {
+        0 => Value::Null,
+        1 => values.remove(0),
+        _ => Value::Array(values),
+      }
+    }
+    match self {
+      Self::Port(values) => collapse(values),
+      Self::Ports(ports) => Value::Object(
+        ports
+          .into_iter()
+          .map(|(port, values)| (port, collapse(values)))
+          .collect::<Map<String, Value>>(),
+      ),
+    }
+  }
+}
+
+/// Write the body of a streamed response until the output ends or the client disconnects.
+async fn stream_body(
+  content_type: ContentType,
+  mut sender: hyper::body::Sender,
+  mut packets: impl tokio_stream::Stream<Item = Result<Packet, wick_packet::Error>> + Send + Unpin,
+) {
+  let mut csv = CsvEncoder::default();
+  while let Some(packet) = packets.next().await {
+    let chunk = packet
+      .map_err(|e| HttpError::OperationError(e.to_string()))
+      .and_then(|packet| {
+        if let PacketPayload::Err(err) = packet.payload() {
+          return Err(HttpError::OutputStream(packet.port().to_owned(), err.msg().to_owned()));
+        }
+        if !packet.has_data() {
+          return Ok(None);
+        }
+        let chunk = match content_type {
+          ContentType::Json => Bytes::from(format!("{}\n", decode_value(packet)?)),
+          ContentType::Ndjson => Bytes::from(ndjson(decode_value(packet)?)),
+          ContentType::Csv => Bytes::from(csv.encode(decode_value(packet)?)),
+          ContentType::Raw => {
+            let bytes: Base64Bytes = packet.decode().map_err(|e| HttpError::Bytes(e.to_string()))?;
+            Bytes::copy_from_slice(&bytes)
+          }
+        };
+        Ok(Some(chunk))
+      });
+    match chunk {
+      Ok(Some(chunk)) => {
+        if sender.send_data(chunk).await.is_err() {
+          debug!("client disconnected from streamed response");
+          return;
+        }
+      }
+      Ok(None) => {}
+      Err(e) => {
+        // The status has already been sent, so all that's left is to cut the response short.
+        warn!(error = %e, "streamed response failed");
+        sender.abort();
+        return;
+      }
+    }
+  }
+}
+
+/// Encode a value as NDJSON lines: a list is one line per item, anything else is a single line.
+fn ndjson(value: Value) -> String {
+  let lines = match value {
+    Value::Array(items) => items,
+    value => vec![value],
+  };
+  lines.into_iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Encodes values as CSV rows. The keys of the first object become the header row and the columns
+/// of every object after it.
+#[derive(Debug, Default)]
+struct CsvEncoder {
+  columns: Option<Vec<String>>,
+}
+
+impl CsvEncoder {
+  /// Encode a value as rows: a list of objects or lists is one row per item, anything else is a
+  /// single row.
+  fn encode(&mut self, value: Value) -> String {
+    let rows = match value {
+      Value::Array(items) if !items.is_empty() && items.iter().all(|i| i.is_object() || i.is_array()) => items,
+      value => vec![value],
+    };
+    let mut out = String::new();
+    for row in rows {
+      let cells = match row {
+        Value::Object(mut object) => {
+          let columns = self.columns.get_or_insert_with(|| {
+            let columns: Vec<_> = object.keys().cloned().collect();
+            out.push_str(&csv_row(columns.iter().map(|c| Value::String(c.clone()))));
+            columns
+          });
+          columns
+            .iter()
+            .map(|column| object.remove(column).unwrap_or(Value::Null))
+            .collect()
+        }
+        Value::Array(cells) => cells,
+        value => vec![value],
+      };
+      out.push_str(&csv_row(cells.into_iter()));
+    }
+    out
+  }
+}
+
+fn csv_row(cells: impl Iterator<Item = Value>) -> String {
+  let mut row = cells
+    .map(|cell| {
+      let cell = match cell {
+        Value::Null => String::new(),
+        Value::String(cell) => cell,
+        cell => cell.to_string(),
+      };
+      if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+      } else {
+        cell
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(",");
+  row.push_str("\r\n");
+  row
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test_logger::test]
+  fn test_negotiate() -> Result<()> {
+    let offered = [ContentType::Json, ContentType::Csv, ContentType::Ndjson];
+    assert_eq!(negotiate(None, &offered), Some(ContentType::Json));
+    assert_eq!(negotiate(Some("*/*"), &offered), Some(ContentType::Json));
+    assert_eq!(negotiate(Some("text/csv"), &offered), Some(ContentType::Csv));
+    assert_eq!(negotiate(Some("text/*"), &offered), Some(ContentType::Csv));
+    assert_eq!(
+      negotiate(Some("application/json;q=0.5, application/x-ndjson"), &offered),
+      Some(ContentType::Ndjson)
+    );
+    assert_eq!(
+      negotiate(Some("*/*;q=0.8, application/json;q=0"), &offered),
+      Some(ContentType::Csv)
+    );
+    assert_eq!(negotiate(Some("image/png"), &offered), None);
+    assert_eq!(negotiate(Some("image/png"), &[]), None);
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_csv() -> Result<()> {
+    let mut csv = CsvEncoder::default();
+    assert_eq!(
+      csv.encode(json!({"name": "Jane, Doe", "age": 30})),
+      "age,name\r\n30,\"Jane, Doe\"\r\n"
+    );
+    assert_eq!(
+      csv.encode(json!([{"name": "John", "note": "ignored"}, {"age": 5}])),
+      ",John\r\n5,\r\n"
+    );
+    assert_eq!(csv.encode(json!(["a", "say \"hi\""])), "a,\"say \"\"hi\"\"\"\r\n");
+    assert_eq!(csv.encode(json!(42)), "42\r\n");
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_ndjson() -> Result<()> {
+    assert_eq!(ndjson(json!({"name": "Jane"})), "{\"name\":\"Jane\"}\n");
+    assert_eq!(ndjson(json!([{"a": 1}, 2])), "{\"a\":1}\n2\n");
+    assert_eq!(ndjson(json!([])), "");
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_json_body() -> Result<()> {
+    let mut body = JsonBody::new(false);
+    body.push(Packet::encode("a", 1))?;
+    body.push(Packet::encode("b", 1))?;
+    body.push(Packet::encode("b", 2))?;
+    assert_eq!(body.finish(), json!({"a": 1, "b": [1, 2]}));
+
+    let mut body = JsonBody::new(true);
+    body.push(Packet::encode("output", json!({"a": 1})))?;
+    assert_eq!(body.finish(), json!({"a": 1}));
+    assert_eq!(JsonBody::new(true).finish(), Value::Null);
+    Ok(())
+  }
+}
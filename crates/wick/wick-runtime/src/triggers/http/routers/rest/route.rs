@@ -87,12 +87,29 @@ impl Route {
     })
   }
 
+  /// Whether `path` reaches this route, without coercing any of its parameters.
+  pub(super) fn matches_path(&self, path: &str) -> bool {
+    let path_parts: Vec<_> = path.trim_start_matches('/').split('/').collect();
+    path_parts.len() == self.path_parts.len()
+      && self
+        .path_parts
+        .iter()
+        .zip(path_parts)
+        .all(|(part, path_part)| match part {
+          PathPart::Literal(literal) => path_part == literal,
+          PathPart::Param(_) => true,
+        })
+  }
+
   pub(super) fn compare(&self, path: &str, query_string: Option<&str>) -> Result<Option<MatchedValues>, HttpError> {
     let mut path_params = Vec::new();
     let mut query_params = Vec::new();
 
     let path = path.trim_start_matches('/');
 
+    if !self.matches_path(path) {
+      return Ok(None);
+    }
     let mut path_parts = path.split('/').peekable();
     for part in &self.path_parts {
       match part {
@@ -243,6 +260,20 @@ mod test {
     Ok(())
   }
 
+  #[test_logger::test]
+  fn test_matches_path() -> Result<()> {
+    let route = Route::parse("/api/v1/users/{id:u32}?filter:string")?;
+
+    assert!(route.matches_path("/api/v1/users/123"));
+    assert!(route.matches_path("/api/v1/users/abc"));
+    assert!(!route.matches_path("/api/v1/users/123/posts"));
+    assert!(!route.matches_path("/api/v1/users/123/"));
+    assert!(!route.matches_path("/api/v1/users"));
+    assert!(!route.matches_path("/api/v2/users/123"));
+
+    Ok(())
+  }
+
   #[test_logger::test]
   fn test_match_array() -> Result<()> {
     let route = Route::parse("/api/v1/users/{id:u32}/posts/{post_id:string}?filter:string[]&sort:string")?;
@@ -270,6 +301,15 @@ mod test {
     Ok(())
   }
 
+  #[test_logger::test]
+  fn test_match_extra_segments() -> Result<()> {
+    let route = Route::parse("/api/v1/users/{id:u32}")?;
+
+    assert_eq!(route.compare("/api/v1/users/123/posts", None)?, None);
+
+    Ok(())
+  }
+
   #[test_logger::test]
   fn test_match() -> Result<()> {
     let route = Route::parse("/api/v1/users/{id:u32}/posts/{post_id:string}?filter:string&sort:string")?;
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+use wick_interface_types::{EnumDefinition, Field, Type, TypeDefinition};
+
+/// A part of a request body that does not match the type its operation expects.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub(crate) struct Violation {
+  /// Where the violation is in the body, e.g. `$.items[2].name`.
+  pub(crate) path: String,
+  pub(crate) message: String,
+}
+
+impl Violation {
+  pub(crate) fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+    Self {
+      path: path.into(),
+      message: message.into(),
+    }
+  }
+}
+
+/// The type a route's request body must have along with every named type it refers to.
+#[derive(Debug, Clone)]
+pub(crate) struct BodySchema {
+  field: Field,
+  types: HashMap<String, TypeDefinition>,
+}
+
+impl BodySchema {
+  /// Create a schema for `field`, looking up the named types it refers to with `resolve`. Named
+  /// types that can not be resolved accept any value.
+  pub(crate) fn new(field: Field, resolve: impl Fn(&str) -> Option<TypeDefinition>) -> Self {
+    let mut types = HashMap::new();
+    collect_types(field.ty(), &resolve, &mut types);
+    Self { field, types }
+  }
+
+  pub(crate) const fn field(&self) -> &Field {
+    &self.field
+  }
+
+  pub(crate) fn resolve_type(&self, name: &str) -> Option<&TypeDefinition> {
+    self.types.get(name)
+  }
+
+  /// Whether a request must have a body.
+  pub(crate) const fn required(&self) -> bool {
+    is_required(&self.field)
+  }
+
+  /// Check a request body, returning every part of it that does not match the schema.
+  pub(crate) fn validate(&self, body: Option<&Value>) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    match body {
+      Some(value) => self.check(value, self.field.ty(), "$", &mut violations),
+      None if self.required() => violations.push(Violation::new("$", "a request body is required")),
+      None => {}
+    }
+    violations
+  }
+
+  fn check(&self, value: &Value, ty: &Type, path: &str, violations: &mut Vec<Violation>) {
+    let mismatch = |violations: &mut Vec<Violation>| {
+      violations.push(Violation::new(
+        path,
+        format!("expected {}, found {}", describe(ty), kind(value)),
+      ));
+    };
+    match ty {
+      Type::Optional { ty } => {
+        if !value.is_null() {
+          self.check(value, ty, path, violations);
+        }
+      }
+      Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::U8 | Type::U16 | Type::U32 | Type::U64 => {
+        check_integer(value, ty, path, violations);
+      }
+      Type::F32 | Type::F64 => {
+        if !value.is_number() {
+          mismatch(violations);
+        }
+      }
+      Type::Bool => {
+        if !value.is_boolean() {
+          mismatch(violations);
+        }
+      }
+      Type::String | Type::Bytes => {
+        if !value.is_string() {
+          mismatch(violations);
+        }
+      }
+      // Datetimes are accepted as strings or as milliseconds since the epoch.
+      Type::Datetime => {
+        if !value.is_string() && !value.is_number() {
+          mismatch(violations);
+        }
+      }
+      Type::List { ty } => match value.as_array() {
+        Some(items) => {
+          for (i, item) in items.iter().enumerate() {
+            self.check(item, ty, &format!("{}[{}]", path, i), violations);
+          }
+        }
+        None => mismatch(violations),
+      },
+      Type::Map { value: ty, .. } => match value.as_object() {
+        Some(map) => {
+          for (key, item) in map {
+            self.check(item, ty, &member_path(path, key), violations);
+          }
+        }
+        None => mismatch(violations),
+      },
+      Type::AnonymousStruct(fields) => self.check_fields(value, fields, ty, path, violations),
+      Type::Named(name) => match self.types.get(name) {
+        Some(TypeDefinition::Struct(def)) => self.check_fields(value, &def.fields, ty, path, violations),
+        Some(TypeDefinition::Enum(def)) => check_enum(value, def, path, violations),
+        Some(TypeDefinition::Union(def)) => {
+          let matches = def.types.iter().any(|ty| {
+            let mut inner = Vec::new();
+            self.check(value, ty, path, &mut inner);
+            inner.is_empty()
+          });
+          if !matches {
+            mismatch(violations);
+          }
+        }
+        None => {}
+      },
+      #[allow(deprecated)]
+      Type::Object | Type::Link { .. } => {}
+    }
+  }
+
+  fn check_fields(&self, value: &Value, fields: &[Field], ty: &Type, path: &str, violations: &mut Vec<Violation>) {
+    let Some(object) = value.as_object() else {
+      violations.push(Violation::new(
+        path,
+        format!("expected {}, found {}", describe(ty), kind(value)),
+      ));
+      return;
+    };
+    for field in fields {
+      let field_path = member_path(path, field.name());
+      match object.get(field.name()) {
+        Some(value) => self.check(value, field.ty(), &field_path, violations),
+        None if is_required(field) => violations.push(Violation::new(field_path, "missing required field")),
+        None => {}
+      }
+    }
+  }
+}
+
+/// Fields are required when they are neither optional nor have a default.
+const fn is_required(field: &Field) -> bool {
+  !matches!(field.ty(), Type::Optional { .. }) && field.default().is_none()
+}
+
+fn collect_types(
+  ty: &Type,
+  resolve: &impl Fn(&str) -> Option<TypeDefinition>,
+  types: &mut HashMap<String, TypeDefinition>,
+) {
+  match ty {
+    Type::Named(name) => {
+      if types.contains_key(name) {
+        return;
+      }
+      let Some(def) = resolve(name) else { return };
+      types.insert(name.clone(), def.clone());
+      match &def {
+        TypeDefinition::Struct(def) => {
+          for field in &def.fields {
+            collect_types(field.ty(), resolve, types);
+          }
+        }
+        TypeDefinition::Union(def) => {
+          for ty in &def.types {
+            collect_types(ty, resolve, types);
+          }
+        }
+        TypeDefinition::Enum(_) => {}
+      }
+    }
+    Type::Optional { ty } | Type::List { ty } => collect_types(ty, resolve, types),
+    Type::Map { key, value } => {
+      collect_types(key, resolve, types);
+      collect_types(value, resolve, types);
+    }
+    Type::AnonymousStruct(fields) => {
+      for field in fields {
+        collect_types(field.ty(), resolve, types);
+      }
+    }
+    _ => {}
+  }
+}
+
+fn check_integer(value: &Value, ty: &Type, path: &str, violations: &mut Vec<Violation>) {
+  let range = ty.int_range();
+  let number = value
+    .as_i64()
+    .map(i128::from)
+    .or_else(|| value.as_u64().map(i128::from));
+  match number {
+    Some(n) if !range.contains(&n) => violations.push(Violation::new(
+      path,
+      format!(
+        "{} is out of range for {} ({} to {})",
+        n,
+        ty,
+        range.start(),
+        range.end()
+      ),
+    )),
+    Some(_) => {}
+    None if value.is_number() => violations.push(Violation::new(path, format!("expected {}, found a decimal", ty))),
+    None => violations.push(Violation::new(path, format!("expected {}, found {}", ty, kind(value)))),
+  }
+}
+
+/// Enums are accepted by the name or value of any of their variants.
+fn check_enum(value: &Value, def: &EnumDefinition, path: &str, violations: &mut Vec<Violation>) {
+  let given = match value {
+    Value::String(s) => Some(s.clone()),
+    Value::Number(n) => Some(n.to_string()),
+    _ => None,
+  };
+  let valid = given.map_or(false, |given| {
+    def
+      .variants
+      .iter()
+      .any(|v| v.name == given || v.value.as_ref() == Some(&given))
+  });
+  if !valid {
+    let names = def
+      .variants
+      .iter()
+      .map(|v| v.name.as_str())
+      .collect::<Vec<_>>()
+      .join(", ");
+    violations.push(Violation::new(path, format!("expected one of {}", names)));
+  }
+}
+
+fn member_path(path: &str, key: &str) -> String {
+  if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+    format!("{}.{}", path, key)
+  } else {
+    format!("{}[{}]", path, Value::String(key.to_owned()))
+  }
+}
+
+fn describe(ty: &Type) -> String {
+  match ty {
+    #[allow(deprecated)]
+    Type::AnonymousStruct(_) | Type::Link { .. } => "object".to_owned(),
+    Type::Optional { ty } => describe(ty),
+    Type::List { ty } => format!("a list of {}", describe(ty)),
+    Type::Map { value, .. } => format!("a map of {}", describe(value)),
+    ty => ty.to_string(),
+  }
+}
+
+const fn kind(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "boolean",
+    Value::Number(_) => "number",
+    Value::String(_) => "string",
+    Value::Array(_) => "array",
+    Value::Object(_) => "object",
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+  use serde_json::json;
+  use wick_interface_types::{EnumVariant, StructDefinition};
+
+  use super::*;
+
+  fn schema() -> BodySchema {
+    let user = TypeDefinition::Struct(StructDefinition::new(
+      "User",
+      vec![
+        Field::new("name", Type::String),
+        Field::new("age", Type::U8),
+        Field::new("role", Type::Named("Role".to_owned())),
+        Field::new(
+          "tags",
+          Type::Optional {
+            ty: Box::new(Type::List {
+              ty: Box::new(Type::String),
+            }),
+          },
+        ),
+      ],
+      None,
+    ));
+    let role = TypeDefinition::Enum(EnumDefinition::new(
+      "Role",
+      vec![
+        EnumVariant::new("Admin", Some(0), Some("admin".to_owned()), None),
+        EnumVariant::new("User", Some(1), None, None),
+      ],
+      None,
+    ));
+    BodySchema::new(Field::new("input", Type::Named("User".to_owned())), |name| {
+      [&user, &role].into_iter().find(|t| t.name() == name).cloned()
+    })
+  }
+
+  #[test_logger::test]
+  fn test_valid() -> Result<()> {
+    let schema = schema();
+    let body = json!({"name": "Jane", "age": 30, "role": "admin", "tags": ["a", "b"], "extra": true});
+    assert_eq!(schema.validate(Some(&body)), vec![]);
+    let body = json!({"name": "Jane", "age": 30, "role": "User", "tags": null});
+    assert_eq!(schema.validate(Some(&body)), vec![]);
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_violations() -> Result<()> {
+    let schema = schema();
+    let body = json!({"age": 300, "role": "Owner", "tags": ["a", 2]});
+    assert_eq!(
+      schema.validate(Some(&body)),
+      vec![
+        Violation::new("$.name", "missing required field"),
+        Violation::new("$.age", "300 is out of range for u8 (0 to 255)"),
+        Violation::new("$.role", "expected one of Admin, User"),
+        Violation::new("$.tags[1]", "expected string, found number"),
+      ]
+    );
+    assert_eq!(
+      schema.validate(Some(&json!([]))),
+      vec![Violation::new("$", "expected User, found array")]
+    );
+    assert_eq!(
+      schema.validate(None),
+      vec![Violation::new("$", "a request body is required")]
+    );
+    Ok(())
+  }
+
+  #[test_logger::test]
+  fn test_member_path() -> Result<()> {
+    assert_eq!(member_path("$", "name"), "$.name");
+    assert_eq!(member_path("$.a", "some key"), r#"$.a["some key"]"#);
+    Ok(())
+  }
+}
@@ -1,18 +1,24 @@
 use std::collections::HashMap;
+use std::fs::Metadata;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+mod compression;
 mod index_list;
 
 use futures::future::BoxFuture;
+use glob::{MatchOptions, Pattern};
+use hyper::header::{self, HeaderMap, HeaderValue};
 use hyper::service::Service;
-use hyper::{Body, Method, Request, Response};
-use hyper_staticfile::{resolve_path, ResolveResult, ResponseBuilder};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use hyper_staticfile::{resolve_path, FileResponseBuilder, ResolveResult, ResponseBuilder};
+use tokio::fs::File;
 use tracing::{Instrument, Span};
 use uuid::Uuid;
 use wick_config::config::{StaticRouterConfig, TriggerKind, WickRouter};
 
+use self::compression::Compression;
 use self::index_list::StaticError;
 use crate::dev::prelude::RuntimeError;
 use crate::resources::{Resource, ResourceKind};
@@ -20,6 +26,12 @@ use crate::triggers::http::middleware::resolve_middleware_components;
 use crate::triggers::http::{HttpError, HttpRouter, RawRouter, RawRouterHandler};
 use crate::Runtime;
 
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+  case_sensitive: true,
+  require_literal_separator: true,
+  require_literal_leading_dot: false,
+};
+
 #[derive()]
 #[must_use]
 pub(super) struct StaticRouter {
@@ -27,10 +39,10 @@ pub(super) struct StaticRouter {
 }
 
 impl StaticRouter {
-  pub(super) fn new(root: PathBuf, strip: Option<String>, indexes: bool, fallback: Option<String>) -> Self {
+  pub(super) fn new(root: PathBuf, strip: Option<String>, config: &StaticRouterConfig) -> Result<Self, RuntimeError> {
     debug!(directory = %root.display(), "http:static:serving");
-    let handler = Static::new(root, strip, indexes, fallback);
-    Self { handler }
+    let handler = Static::new(root, strip, config)?;
+    Ok(Self { handler })
   }
 }
 
@@ -69,23 +81,80 @@ where
       .expect("unable to build response"),
   )
 }
+
+fn compile_glob(glob: &str) -> Result<Pattern, RuntimeError> {
+  Pattern::new(glob.trim_start_matches('/'))
+    .map_err(|e| RuntimeError::InvalidConfig(TriggerKind::Http.into(), format!("invalid glob '{}': {}", glob, e)))
+}
+
+#[derive(Debug, Clone)]
+struct Fallback {
+  glob: Pattern,
+  file: String,
+  status: StatusCode,
+}
+
+#[derive(Debug, Clone)]
+struct CacheRule {
+  glob: Pattern,
+  value: HeaderValue,
+}
+
 #[derive(Clone)]
 struct Static {
   root: PathBuf,
   strip: Option<String>,
   fallback: Option<String>,
   indexes: bool,
+  fallbacks: Arc<Vec<Fallback>>,
+  cache_control: Arc<Vec<CacheRule>>,
+  compression: Option<Arc<Compression>>,
 }
 
 impl Static {
-  fn new(root: impl Into<PathBuf>, strip: Option<String>, indexes: bool, fallback: Option<String>) -> Self {
+  fn new(root: impl Into<PathBuf>, strip: Option<String>, config: &StaticRouterConfig) -> Result<Self, RuntimeError> {
     let root = root.into();
-    Static {
+    let fallbacks = config
+      .fallbacks()
+      .iter()
+      .map(|rule| {
+        Ok(Fallback {
+          glob: compile_glob(rule.glob())?,
+          file: rule.file().to_owned(),
+          status: StatusCode::from_u16(rule.status()).map_err(|_| {
+            RuntimeError::InvalidConfig(
+              TriggerKind::Http.into(),
+              format!("invalid status code {} for fallback '{}'", rule.status(), rule.glob()),
+            )
+          })?,
+        })
+      })
+      .collect::<Result<Vec<_>, RuntimeError>>()?;
+    let cache_control = config
+      .cache_control()
+      .iter()
+      .map(|rule| {
+        Ok(CacheRule {
+          glob: compile_glob(rule.glob())?,
+          value: HeaderValue::from_str(rule.value()).map_err(|_| {
+            RuntimeError::InvalidConfig(
+              TriggerKind::Http.into(),
+              format!("invalid Cache-Control value '{}'", rule.value()),
+            )
+          })?,
+        })
+      })
+      .collect::<Result<Vec<_>, RuntimeError>>()?;
+
+    Ok(Static {
       root,
       strip,
-      indexes,
-      fallback,
-    }
+      indexes: config.indexes(),
+      fallback: config.fallback().cloned(),
+      fallbacks: Arc::new(fallbacks),
+      cache_control: Arc::new(cache_control),
+      compression: config.compression().map(|c| Arc::new(Compression::new(c))),
+    })
   }
 
   /// Serve a request.
@@ -93,12 +162,6 @@ impl Static {
   where
     B: Send + Sync + 'static,
   {
-    let Self {
-      root,
-      strip,
-      indexes,
-      fallback,
-    } = self;
     // Handle only `GET`/`HEAD` and absolute paths.
     match *request.method() {
       Method::HEAD | Method::GET => {}
@@ -107,35 +170,222 @@ impl Static {
       }
     }
 
-    let path = strip.map_or_else(
+    let path = self.strip.as_ref().map_or_else(
       || request.uri().path(),
       |path| {
         if path.len() > 1 {
-          request.uri().path().trim_start_matches(&path)
+          request.uri().path().trim_start_matches(path)
         } else {
           request.uri().path()
         }
       },
     );
+    let relative = percent_encoding::percent_decode_str(path)
+      .decode_utf8_lossy()
+      .trim_start_matches('/')
+      .to_owned();
 
-    let result = resolve_path(root.clone(), path).await;
+    let result = resolve_path(self.root.clone(), path).await?;
 
     match result {
-      Ok(ResolveResult::Found(_, _, _)) => create_response(&request, result?),
-      Ok(ResolveResult::IsDirectory) if indexes => index_list::create(&root, &request).await,
-      Ok(ResolveResult::NotFound) if (indexes && request.uri().path() == "/") => {
-        index_list::create(&root, &request).await
+      ResolveResult::Found(file, metadata, mime) => {
+        let served = if relative.is_empty() || relative.ends_with('/') {
+          format!("{}index.html", relative)
+        } else {
+          relative
+        };
+        self
+          .serve_file(&request, &served, file, metadata, mime.to_string(), StatusCode::OK)
+          .await
       }
-      _ => {
-        if let Some(fb) = &fallback {
-          let fallback_result = resolve_path(root.clone(), fb).await;
-          create_response(&request, fallback_result?)
+      ResolveResult::IsDirectory if self.indexes => index_list::create(&self.root, &request).await,
+      ResolveResult::NotFound if (self.indexes && request.uri().path() == "/") => {
+        index_list::create(&self.root, &request).await
+      }
+      result => {
+        let rule = self
+          .fallbacks
+          .iter()
+          .find(|rule| rule.glob.matches_with(&relative, MATCH_OPTIONS));
+        if let Some(rule) = rule {
+          self.serve_fallback(&request, &rule.file, rule.status).await
+        } else if let Some(fb) = &self.fallback {
+          self.serve_fallback(&request, fb, StatusCode::OK).await
         } else {
-          create_response(&request, result?)
+          create_response(&request, result)
         }
       }
     }
   }
+
+  async fn serve_fallback<B>(
+    &self,
+    request: &Request<B>,
+    file: &str,
+    status: StatusCode,
+  ) -> Result<Response<Body>, StaticError>
+  where
+    B: Send + Sync + 'static,
+  {
+    match resolve_path(self.root.clone(), file).await? {
+      ResolveResult::Found(f, metadata, mime) => {
+        let served = file.trim_start_matches('/');
+        self
+          .serve_file(request, served, f, metadata, mime.to_string(), status)
+          .await
+      }
+      result => create_response(request, result),
+    }
+  }
+
+  /// Serve a file that has been found, compressing it and applying caching headers as configured.
+  async fn serve_file<B>(
+    &self,
+    request: &Request<B>,
+    served: &str,
+    file: File,
+    metadata: Metadata,
+    content_type: String,
+    status: StatusCode,
+  ) -> Result<Response<Body>, StaticError>
+  where
+    B: Send + Sync + 'static,
+  {
+    let mut headers = request.headers().clone();
+    if status != StatusCode::OK {
+      // A fallback with its own status isn't the resource that was asked for, so it can't be
+      // revalidated or requested in part.
+      for name in [
+        header::IF_NONE_MATCH,
+        header::IF_MODIFIED_SINCE,
+        header::RANGE,
+        header::IF_RANGE,
+      ] {
+        headers.remove(name);
+      }
+    } else if headers.contains_key(header::IF_NONE_MATCH) {
+      // If-None-Match takes precedence over If-Modified-Since.
+      headers.remove(header::IF_MODIFIED_SINCE);
+    }
+    let build = |file: File, metadata: Metadata| {
+      FileResponseBuilder::new()
+        .request_parts(request.method(), &headers)
+        .build(file, metadata, content_type.clone())
+    };
+
+    let accepted = self.compression.as_ref().map_or_else(Vec::new, |compression| {
+      compression.accepted(headers.get(header::ACCEPT_ENCODING))
+    });
+
+    let mut sibling = None;
+    if self.compression.as_ref().map_or(false, |c| c.precompressed) {
+      for candidate in &accepted {
+        let path = format!("{}.{}", served, compression::extension(*candidate));
+        if let ResolveResult::Found(f, m, _) = resolve_path(self.root.clone(), &path).await? {
+          sibling = Some((*candidate, f, m));
+          break;
+        }
+      }
+    }
+
+    let mut compress_with = None;
+    let (mut response, encoding) = match sibling {
+      Some((encoding, f, m)) => (build(f, m)?, Some(encoding)),
+      None => {
+        let len = metadata.len();
+        let response = build(file, metadata)?;
+        compress_with = self
+          .compression
+          .as_ref()
+          .filter(|c| response.status() == StatusCode::OK && c.should_compress(&content_type, len))
+          .and(accepted.first().copied());
+        (response, compress_with)
+      }
+    };
+
+    let res_headers = response.headers_mut();
+    if self.compression.is_some() {
+      res_headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+    }
+    if let Some(encoding) = encoding {
+      res_headers.insert(
+        header::CONTENT_ENCODING,
+        HeaderValue::from_static(compression::token(encoding)),
+      );
+    }
+    if let Some(encoding) = compress_with {
+      // The compressed bytes differ from the file's, so they need their own entity tag.
+      if let Some(etag) = res_headers.get(header::ETAG).and_then(|v| v.to_str().ok()) {
+        let tagged = format!("{}-{}\"", etag.trim_end_matches('"'), compression::extension(encoding));
+        if let Ok(tagged) = HeaderValue::from_str(&tagged) {
+          res_headers.insert(header::ETAG, tagged);
+        }
+      }
+    }
+    if let Some(rule) = self
+      .cache_control
+      .iter()
+      .find(|rule| rule.glob.matches_with(served, MATCH_OPTIONS))
+    {
+      res_headers.insert(header::CACHE_CONTROL, rule.value.clone());
+    }
+
+    if response.status() == StatusCode::OK {
+      if let (Some(if_none_match), Some(etag)) =
+        (headers.get(header::IF_NONE_MATCH), response.headers().get(header::ETAG))
+      {
+        if etag_matches(if_none_match, etag) {
+          return not_modified(response.headers());
+        }
+      }
+    }
+
+    if let Some(encoding) = compress_with {
+      let (mut parts, body) = response.into_parts();
+      parts.headers.remove(header::CONTENT_LENGTH);
+      let body = if request.method() == Method::HEAD {
+        Body::empty()
+      } else {
+        let bytes = hyper::body::to_bytes(body)
+          .await
+          .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let compressed = compression::compress(encoding, bytes).await?;
+        parts.headers.insert(header::CONTENT_LENGTH, compressed.len().into());
+        Body::from(compressed)
+      };
+      response = Response::from_parts(parts, body);
+    }
+
+    if status != StatusCode::OK && response.status() == StatusCode::OK {
+      *response.status_mut() = status;
+    }
+    Ok(response)
+  }
+}
+
+/// Whether an If-None-Match header matches an entity tag, using weak comparison.
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+  let Ok(if_none_match) = if_none_match.to_str() else {
+    return false;
+  };
+  let Ok(etag) = etag.to_str() else {
+    return false;
+  };
+  let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+  let etag = opaque(etag);
+  if_none_match
+    .split(',')
+    .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+fn not_modified(headers: &HeaderMap) -> Result<Response<Body>, StaticError> {
+  let mut response = Response::builder().status(StatusCode::NOT_MODIFIED);
+  for name in [header::ETAG, header::LAST_MODIFIED, header::CACHE_CONTROL, header::VARY] {
+    if let Some(value) = headers.get(&name) {
+      response = response.header(name, value);
+    }
+  }
+  Ok(response.body(Body::empty())?)
 }
 
 impl<B: Send + Sync + 'static> Service<Request<B>> for Static {
@@ -158,7 +408,7 @@ pub(crate) fn register_static_router(
   router_config: &StaticRouterConfig,
 ) -> Result<HttpRouter, RuntimeError> {
   trace!(index, "registering static router");
-  let middleware = resolve_middleware_components(router_config)?;
+  let middleware = resolve_middleware_components(router_config, &resources)?;
   let volume = resources.get(router_config.volume()).ok_or_else(|| {
     RuntimeError::ResourceNotFound(
       TriggerKind::Http.into(),
@@ -176,10 +426,7 @@ pub(crate) fn register_static_router(
     }
   };
 
-  let fallback = router_config.fallback().cloned();
-  let indexes = router_config.indexes();
-
-  let router = StaticRouter::new(volume, Some(router_config.path().to_owned()), indexes, fallback);
+  let router = StaticRouter::new(volume, Some(router_config.path().to_owned()), router_config)?;
 
   Ok(HttpRouter::Raw(RawRouterHandler {
     path: router_config.path().to_owned(),
@@ -187,3 +434,132 @@ pub(crate) fn register_static_router(
     middleware,
   }))
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_etag_matches() {
+    let etag = HeaderValue::from_static("W/\"abc-1\"");
+    let matches = |header: &'static str| etag_matches(&HeaderValue::from_static(header), &etag);
+    assert!(matches("W/\"abc-1\""));
+    assert!(matches("\"abc-1\""));
+    assert!(matches("\"other\", W/\"abc-1\""));
+    assert!(matches("*"));
+    assert!(!matches("W/\"abc-1-br\""));
+  }
+
+  // "port_limited" tests are grouped together and run on a single thread to prevent port contention
+  mod port_limited {
+    use std::io::Read;
+
+    use anyhow::Result;
+
+    use super::super::*;
+    use crate::test::load_test_manifest;
+    use crate::triggers::http::Http;
+    use crate::{build_trigger_runtime, Trigger};
+
+    static PORT: &str = "9005";
+
+    #[test_logger::test(tokio::test)]
+    async fn test_static_router() -> Result<()> {
+      std::env::set_var("HTTP_PORT", PORT);
+      let app_config = load_test_manifest("app_config/static-router.wick")
+        .await?
+        .try_app_config()?;
+      let rt = build_trigger_runtime(&app_config, Span::current())?.build(None).await?;
+      let mut resources = HashMap::new();
+      for resource in app_config.resources() {
+        resources.insert(resource.id().to_owned(), Resource::new(resource.kind().clone())?);
+      }
+      let trigger = Http::default();
+      let trigger_config = app_config.triggers()[0].clone();
+      trigger
+        .run(
+          "test".to_owned(),
+          rt,
+          app_config,
+          trigger_config,
+          Arc::new(resources),
+          Span::current(),
+        )
+        .await?;
+
+      let client = reqwest::Client::new();
+      let url = |path: &str| format!("http://0.0.0.0:{}{}", PORT, path);
+      let script = include_str!("../../../../tests/manifests/v1/app_config/static-router/app.js");
+
+      // Compressed on the fly.
+      let res = client
+        .get(url("/app.js"))
+        .header("accept-encoding", "gzip, br")
+        .send()
+        .await?;
+      assert_eq!(res.status(), 200);
+      assert_eq!(res.headers()["content-encoding"], "br");
+      assert_eq!(res.headers()["vary"], "accept-encoding");
+      let etag = res.headers()["etag"].clone();
+      assert!(etag.to_str()?.ends_with("-br\""));
+      let mut body = String::new();
+      brotli::Decompressor::new(res.bytes().await?.as_ref(), 4096).read_to_string(&mut body)?;
+      assert_eq!(body, script);
+
+      let res = client
+        .get(url("/app.js"))
+        .header("accept-encoding", "br")
+        .header("if-none-match", etag)
+        .send()
+        .await?;
+      assert_eq!(res.status(), 304);
+
+      // Ranges are served from the uncompressed file.
+      let res = client
+        .get(url("/app.js"))
+        .header("accept-encoding", "br")
+        .header("range", "bytes=0-9")
+        .send()
+        .await?;
+      assert_eq!(res.status(), 206);
+      assert!(res.headers().get("content-encoding").is_none());
+      assert_eq!(res.text().await?, &script[0..10]);
+
+      // Pre-compressed sibling.
+      let res = client
+        .get(url("/app.css"))
+        .header("accept-encoding", "gzip")
+        .send()
+        .await?;
+      assert_eq!(res.status(), 200);
+      assert_eq!(res.headers()["content-encoding"], "gzip");
+      assert_eq!(res.headers()["content-type"], "text/css");
+      let mut body = String::new();
+      flate2::read::GzDecoder::new(res.bytes().await?.as_ref()).read_to_string(&mut body)?;
+      assert_eq!(body, "body { margin: 0; padding: 0; }\n".repeat(20));
+
+      // Too small to compress, with its own caching rule.
+      let res = client
+        .get(url("/assets/logo.txt"))
+        .header("accept-encoding", "gzip")
+        .send()
+        .await?;
+      assert_eq!(res.status(), 200);
+      assert!(res.headers().get("content-encoding").is_none());
+      assert_eq!(res.headers()["cache-control"], "public, max-age=31536000, immutable");
+
+      // Fallbacks.
+      let res = client.get(url("/app/settings")).send().await?;
+      assert_eq!(res.status(), 200);
+      assert_eq!(res.headers()["cache-control"], "no-cache");
+      assert!(res.text().await?.contains("app"));
+
+      let res = client.get(url("/missing.js")).send().await?;
+      assert_eq!(res.status(), 404);
+      assert!(res.text().await?.contains("not found"));
+
+      trigger.shutdown_gracefully().await?;
+      Ok(())
+    }
+  }
+}
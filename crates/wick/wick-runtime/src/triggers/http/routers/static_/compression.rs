@@ -0,0 +1,188 @@
+use std::io::Write;
+
+use bytes::Bytes;
+use hyper::header::HeaderValue;
+use wick_config::config::{ContentEncoding, StaticCompression};
+
+/// The largest file that will be compressed on the fly. Compression happens in memory, so larger
+/// files are served as-is.
+const MAX_DYNAMIC_SIZE: u64 = 8 * 1024 * 1024;
+
+const DEFAULT_ENCODINGS: [ContentEncoding; 2] = [ContentEncoding::Brotli, ContentEncoding::Gzip];
+
+#[derive(Debug, Clone)]
+pub(super) struct Compression {
+  pub(super) precompressed: bool,
+  pub(super) dynamic: bool,
+  encodings: Vec<ContentEncoding>,
+  min_size: u64,
+}
+
+impl Compression {
+  pub(super) fn new(config: &StaticCompression) -> Self {
+    let encodings = if config.encodings().is_empty() {
+      DEFAULT_ENCODINGS.to_vec()
+    } else {
+      config.encodings().to_vec()
+    };
+    Self {
+      precompressed: config.precompressed(),
+      dynamic: config.dynamic(),
+      encodings,
+      min_size: config.min_size(),
+    }
+  }
+
+  /// The configured encodings the client accepts, in the order they should be tried.
+  pub(super) fn accepted(&self, accept_encoding: Option<&HeaderValue>) -> Vec<ContentEncoding> {
+    let Some(header) = accept_encoding.and_then(|v| v.to_str().ok()) else {
+      return Vec::new();
+    };
+    let preferences: Vec<_> = header.split(',').filter_map(preference).collect();
+    let quality = |encoding: &ContentEncoding| {
+      preferences
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(token(*encoding)))
+        .or_else(|| preferences.iter().find(|(n, _)| *n == "*"))
+        .map_or(0.0, |(_, q)| *q)
+    };
+
+    let mut accepted: Vec<_> = self
+      .encodings
+      .iter()
+      .map(|e| (*e, quality(e)))
+      .filter(|(_, q)| *q > 0.0)
+      .collect();
+    // The sort is stable, so encodings the client likes equally stay in the configured order.
+    accepted.sort_by(|a, b| b.1.total_cmp(&a.1));
+    accepted.into_iter().map(|(e, _)| e).collect()
+  }
+
+  /// Whether a file of this type and size should be compressed on the fly.
+  pub(super) fn should_compress(&self, content_type: &str, len: u64) -> bool {
+    self.dynamic && len >= self.min_size && len <= MAX_DYNAMIC_SIZE && is_compressible(content_type)
+  }
+}
+
+fn preference(part: &str) -> Option<(&str, f32)> {
+  let mut params = part.split(';');
+  let name = params.next()?.trim();
+  if name.is_empty() {
+    return None;
+  }
+  let quality = match params.find_map(|p| p.trim().strip_prefix("q=")) {
+    Some(q) => q.trim().parse().ok()?,
+    None => 1.0,
+  };
+  Some((name, quality))
+}
+
+/// The Content-Encoding token for the given encoding.
+pub(super) const fn token(encoding: ContentEncoding) -> &'static str {
+  match encoding {
+    ContentEncoding::Gzip => "gzip",
+    ContentEncoding::Brotli => "br",
+  }
+}
+
+/// The file extension of a pre-compressed sibling for the given encoding.
+pub(super) const fn extension(encoding: ContentEncoding) -> &'static str {
+  match encoding {
+    ContentEncoding::Gzip => "gz",
+    ContentEncoding::Brotli => "br",
+  }
+}
+
+fn is_compressible(content_type: &str) -> bool {
+  let essence = content_type.split(';').next().unwrap_or_default().trim();
+  essence.starts_with("text/")
+    || essence.ends_with("+json")
+    || essence.ends_with("+xml")
+    || matches!(
+      essence,
+      "application/javascript" | "application/json" | "application/xml" | "application/wasm" | "image/svg+xml"
+    )
+}
+
+pub(super) async fn compress(encoding: ContentEncoding, bytes: Bytes) -> std::io::Result<Vec<u8>> {
+  tokio::task::spawn_blocking(move || match encoding {
+    ContentEncoding::Gzip => {
+      let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+      encoder.write_all(&bytes)?;
+      encoder.finish()
+    }
+    ContentEncoding::Brotli => {
+      let mut out = Vec::new();
+      {
+        // Quality 5 trades a little size for speed, which matters when compressing per request.
+        let mut encoder = brotli::CompressorWriter::new(&mut out, 4096, 5, 22);
+        encoder.write_all(&bytes)?;
+      }
+      Ok(out)
+    }
+  })
+  .await
+  .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?
+}
+
+#[cfg(test)]
+mod test {
+  use std::io::Read;
+
+  use anyhow::Result;
+  use wick_config::config::ContentEncoding::{Brotli, Gzip};
+
+  use super::*;
+
+  const fn compression(encodings: Vec<ContentEncoding>) -> Compression {
+    Compression {
+      precompressed: true,
+      dynamic: true,
+      encodings,
+      min_size: 10,
+    }
+  }
+
+  #[test]
+  fn test_accepted() {
+    let c = compression(DEFAULT_ENCODINGS.to_vec());
+    let accepted = |header: &'static str| c.accepted(Some(&HeaderValue::from_static(header)));
+    assert_eq!(c.accepted(None), vec![]);
+    assert_eq!(accepted("gzip, deflate, br"), vec![Brotli, Gzip]);
+    assert_eq!(accepted("gzip"), vec![Gzip]);
+    assert_eq!(accepted("br;q=0.5, gzip"), vec![Gzip, Brotli]);
+    assert_eq!(accepted("br;q=0, *"), vec![Gzip]);
+    assert_eq!(accepted("identity"), vec![]);
+    assert_eq!(
+      compression(vec![Gzip]).accepted(Some(&HeaderValue::from_static("br, gzip"))),
+      vec![Gzip]
+    );
+  }
+
+  #[test]
+  fn test_should_compress() {
+    let c = compression(vec![Gzip]);
+    assert!(c.should_compress("text/html; charset=utf-8", 100));
+    assert!(c.should_compress("application/javascript", 100));
+    assert!(c.should_compress("application/ld+json", 100));
+    assert!(!c.should_compress("image/png", 100));
+    assert!(!c.should_compress("text/css", 5));
+    assert!(!c.should_compress("text/css", MAX_DYNAMIC_SIZE + 1));
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_compress() -> Result<()> {
+    let input = Bytes::from("hello hello hello hello hello hello");
+
+    let gzipped = compress(Gzip, input.clone()).await?;
+    let mut output = String::new();
+    flate2::read::GzDecoder::new(gzipped.as_slice()).read_to_string(&mut output)?;
+    assert_eq!(output, input);
+
+    let brotlied = compress(Brotli, input.clone()).await?;
+    let mut output = String::new();
+    brotli::Decompressor::new(brotlied.as_slice(), 4096).read_to_string(&mut output)?;
+    assert_eq!(output, input);
+    Ok(())
+  }
+}
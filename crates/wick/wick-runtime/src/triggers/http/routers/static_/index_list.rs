@@ -22,6 +22,7 @@ struct Entry {
 pub(super) enum StaticError {
   Template,
   Io(std::io::Error),
+  Http(hyper::http::Error),
 }
 
 impl From<std::io::Error> for StaticError {
@@ -30,11 +31,18 @@ impl From<std::io::Error> for StaticError {
   }
 }
 
+impl From<hyper::http::Error> for StaticError {
+  fn from(value: hyper::http::Error) -> Self {
+    Self::Http(value)
+  }
+}
+
 impl std::fmt::Display for StaticError {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
       Self::Template => write!(f, "Template error"),
       Self::Io(e) => e.fmt(f),
+      Self::Http(e) => e.fmt(f),
     }
   }
 }
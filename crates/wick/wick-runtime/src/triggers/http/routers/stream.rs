@@ -0,0 +1,533 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::Engine;
+use futures::future::BoxFuture;
+use futures::SinkExt;
+use hyper::header::{
+  HeaderValue,
+  ACCEPT,
+  CACHE_CONTROL,
+  CONNECTION,
+  CONTENT_TYPE,
+  SEC_WEBSOCKET_ACCEPT,
+  SEC_WEBSOCKET_KEY,
+  SEC_WEBSOCKET_VERSION,
+  UPGRADE,
+};
+use hyper::upgrade::Upgraded;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde_json::Value;
+use tokio_stream::StreamExt;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Role};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use tracing::{Instrument, Span};
+use uuid::Uuid;
+use wick_config::config::{Codec, StreamRouterConfig, WickRouter};
+use wick_packet::{
+  packets,
+  Base64Bytes,
+  Entity,
+  InherentData,
+  Invocation,
+  Observer,
+  Packet,
+  PacketPayload,
+  PacketSender,
+  PacketStream,
+};
+
+use crate::dev::prelude::RuntimeError;
+use crate::resources::Resource;
+use crate::triggers::http::conversions::request_to_wick;
+use crate::triggers::http::middleware::resolve_middleware_components;
+use crate::triggers::http::{HttpError, HttpRouter, RawRouter, RawRouterHandler, RouterOperation};
+use crate::triggers::ComponentId;
+use crate::Runtime;
+
+/// The port that carries messages to and from the operation.
+const MESSAGE_PORT: &str = "message";
+
+/// How long to wait for a client to acknowledge a close frame before dropping the connection.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The longest reason a WebSocket close frame can carry, in bytes.
+const MAX_CLOSE_REASON: usize = 123;
+
+#[must_use]
+pub(super) struct StreamComponentRouter {
+  config: Arc<RouterOperation>,
+}
+
+impl StreamComponentRouter {
+  pub(super) fn new(config: RouterOperation) -> Self {
+    Self {
+      config: Arc::new(config),
+    }
+  }
+}
+
+impl RawRouter for StreamComponentRouter {
+  fn handle(
+    &self,
+    tx_id: Uuid,
+    remote_addr: SocketAddr,
+    runtime: Runtime,
+    request: Request<Body>,
+    span: &Span,
+  ) -> BoxFuture<Result<Response<Body>, HttpError>> {
+    let config = self.config.clone();
+    let span = info_span!(parent: span, "stream");
+
+    Box::pin(serve(tx_id, config, runtime, remote_addr, request, span))
+  }
+}
+
+/// How a client asked to stream messages.
+enum Connection {
+  /// A WebSocket upgrade, with the `Sec-WebSocket-Accept` value that completes the handshake.
+  WebSocket(HeaderValue),
+  /// A Server-Sent Events (`text/event-stream`) request.
+  EventStream,
+}
+
+impl Connection {
+  fn from_request(req: &Request<Body>) -> Option<Self> {
+    let headers = req.headers();
+    let has_token = |name, token: &str| {
+      headers
+        .get_all(name)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|v| v.trim().eq_ignore_ascii_case(token))
+    };
+
+    if req.method() == Method::GET && has_token(CONNECTION, "upgrade") && has_token(UPGRADE, "websocket") {
+      let key = headers.get(SEC_WEBSOCKET_KEY)?;
+      if headers.get(SEC_WEBSOCKET_VERSION).map(HeaderValue::as_bytes) != Some(b"13") {
+        return None;
+      }
+      let accept = HeaderValue::from_str(&derive_accept_key(key.as_bytes())).ok()?;
+      return Some(Self::WebSocket(accept));
+    }
+    if has_token(ACCEPT, "text/event-stream") {
+      return Some(Self::EventStream);
+    }
+    None
+  }
+}
+
+async fn serve(
+  tx_id: Uuid,
+  config: Arc<RouterOperation>,
+  runtime: Runtime,
+  remote_addr: SocketAddr,
+  mut req: Request<Body>,
+  span: Span,
+) -> Result<Response<Body>, HttpError> {
+  let Some(connection) = Connection::from_request(&req) else {
+    return Ok(
+      Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(Body::from(
+          "Expected a WebSocket upgrade or a text/event-stream request",
+        ))
+        .unwrap(),
+    );
+  };
+
+  let mut wick_req = request_to_wick(&req, remote_addr)?;
+  wick_req.path = wick_req.path.trim_start_matches(&config.path).to_owned();
+
+  let (tx, rx) = PacketStream::new_channels();
+  let invocation = Invocation::new_with_id(
+    tx_id,
+    Entity::server("http_client"),
+    Entity::operation(&config.component, &config.operation),
+    rx,
+    InherentData::unsafe_default(),
+    &span,
+  );
+  let stream = runtime
+    .invoke(invocation, config.config.clone())
+    .await
+    .map_err(|e| HttpError::OperationError(e.to_string()))?;
+
+  for packet in packets!(("request", wick_req)) {
+    let _ = tx.send(packet);
+  }
+
+  let codec = config.codec;
+  match connection {
+    Connection::WebSocket(accept) => {
+      let upgrade = hyper::upgrade::on(&mut req);
+      tokio::spawn(
+        async move {
+          match upgrade.await {
+            Ok(upgraded) => {
+              let socket = WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+              websocket(codec, socket, tx, stream).await;
+            }
+            Err(e) => {
+              warn!(error = %e, "websocket upgrade failed");
+            }
+          }
+          trace!("http:stream:websocket:done");
+        }
+        .instrument(span),
+      );
+
+      Ok(
+        Response::builder()
+          .status(StatusCode::SWITCHING_PROTOCOLS)
+          .header(UPGRADE, "websocket")
+          .header(CONNECTION, "Upgrade")
+          .header(SEC_WEBSOCKET_ACCEPT, accept)
+          .body(Body::empty())
+          .unwrap(),
+      )
+    }
+    Connection::EventStream => {
+      // Event streams only flow to the client, so there are never any inbound messages.
+      let _ = tx.send(Packet::done(MESSAGE_PORT));
+      let (sender, body) = Body::channel();
+      tokio::spawn(
+        async move {
+          event_stream(codec, sender, stream).await;
+          trace!("http:stream:sse:done");
+        }
+        .instrument(span),
+      );
+
+      Ok(
+        Response::builder()
+          .status(StatusCode::OK)
+          .header(CONTENT_TYPE, "text/event-stream")
+          .header(CACHE_CONTROL, "no-cache")
+          .body(body)
+          .unwrap(),
+      )
+    }
+  }
+}
+
+/// Relay frames from `socket` to the operation's input and the operation's output back to
+/// `socket` until either side closes.
+async fn websocket(codec: Codec, mut socket: WebSocketStream<Upgraded>, tx: PacketSender, mut stream: PacketStream) {
+  let mut output_open = true;
+  let closing = tokio::time::sleep(Duration::MAX);
+  tokio::pin!(closing);
+
+  loop {
+    tokio::select! {
+      message = socket.next() => match message {
+        // Received close frames are answered by the next read, which then ends the stream.
+        Some(Ok(Message::Close(_))) => {}
+        Some(Ok(message)) => {
+          if let Some(packet) = message_to_packet(codec, message) {
+            let _ = tx.send(packet);
+          }
+        }
+        // Clients often drop connections without a closing handshake, so treat it like a close.
+        Some(Err(e)) => {
+          debug!(error = %e, "websocket read failed");
+          break;
+        }
+        None => break,
+      },
+      packet = stream.next(), if output_open => {
+        let message = match packet {
+          Some(Ok(packet)) => match packet_to_message(codec, packet) {
+            Ok(Some(message)) => message,
+            Ok(None) => continue,
+            Err(e) => close_message(CloseCode::Error, &e.to_string()),
+          },
+          Some(Err(e)) => close_message(CloseCode::Error, &e.to_string()),
+          None => close_message(CloseCode::Normal, ""),
+        };
+        let is_close = matches!(message, Message::Close(_));
+        if let Err(e) = socket.send(message).await {
+          debug!(error = %e, "websocket write failed");
+          break;
+        }
+        if is_close {
+          output_open = false;
+          closing.as_mut().reset(tokio::time::Instant::now() + CLOSE_TIMEOUT);
+        }
+      },
+      _ = &mut closing => break,
+    }
+  }
+  let _ = tx.send(Packet::done(MESSAGE_PORT));
+}
+
+/// Write the operation's output to `sender` as Server-Sent Events until the output ends or the
+/// client disconnects.
+async fn event_stream(codec: Codec, mut sender: hyper::body::Sender, mut stream: PacketStream) {
+  while let Some(packet) = stream.next().await {
+    let (event, last) = match packet {
+      Ok(packet) => match packet_to_event(codec, packet) {
+        Ok(Some(event)) => (event, false),
+        Ok(None) => continue,
+        Err(e) => (format_event(Some("error"), &e.to_string()), true),
+      },
+      Err(e) => (format_event(Some("error"), &e.to_string()), true),
+    };
+    if sender.send_data(event.into()).await.is_err() || last {
+      break;
+    }
+  }
+}
+
+fn message_to_packet(codec: Codec, message: Message) -> Option<Packet> {
+  let bytes = match message {
+    Message::Text(text) => text.into_bytes(),
+    Message::Binary(bytes) => bytes,
+    Message::Ping(_) | Message::Pong(_) | Message::Close(_) | Message::Frame(_) => return None,
+  };
+  let packet = match codec {
+    Codec::Json => serde_json::from_slice::<Value>(&bytes).map_or_else(
+      |e| Packet::err(MESSAGE_PORT, e.to_string()),
+      |value| Packet::encode(MESSAGE_PORT, value),
+    ),
+    Codec::Raw => Packet::encode(MESSAGE_PORT, Base64Bytes::new(bytes)),
+    Codec::Text | Codec::FormData => String::from_utf8(bytes).map_or_else(
+      |e| Packet::err(MESSAGE_PORT, e.to_string()),
+      |text| Packet::encode(MESSAGE_PORT, text),
+    ),
+  };
+  Some(packet)
+}
+
+/// Decode an output packet, returning `None` for packets that don't carry a message.
+fn decode_message(codec: Codec, packet: Packet) -> Result<Option<MessageData>, HttpError> {
+  if packet.port() != MESSAGE_PORT {
+    return Ok(None);
+  }
+  if let PacketPayload::Err(e) = packet.payload() {
+    return Err(HttpError::OutputStream(MESSAGE_PORT.to_owned(), e.msg().to_owned()));
+  }
+  if !packet.has_data() {
+    return Ok(None);
+  }
+  let data = match codec {
+    Codec::Json => MessageData::Text(
+      packet
+        .decode::<Value>()
+        .map_err(|e| HttpError::Codec(codec, e.to_string()))?
+        .to_string(),
+    ),
+    Codec::Raw => MessageData::Binary(packet.decode().map_err(|e| HttpError::Bytes(e.to_string()))?),
+    Codec::Text | Codec::FormData => {
+      MessageData::Text(packet.decode().map_err(|e| HttpError::Codec(codec, e.to_string()))?)
+    }
+  };
+  Ok(Some(data))
+}
+
+enum MessageData {
+  Text(String),
+  Binary(Base64Bytes),
+}
+
+fn packet_to_message(codec: Codec, packet: Packet) -> Result<Option<Message>, HttpError> {
+  Ok(decode_message(codec, packet)?.map(|data| match data {
+    MessageData::Text(text) => Message::Text(text),
+    MessageData::Binary(bytes) => Message::Binary(bytes.0.to_vec()),
+  }))
+}
+
+fn packet_to_event(codec: Codec, packet: Packet) -> Result<Option<String>, HttpError> {
+  Ok(decode_message(codec, packet)?.map(|data| match data {
+    MessageData::Text(text) => format_event(None, &text),
+    // Events can only carry text, so binary messages are sent base64-encoded.
+    MessageData::Binary(bytes) => format_event(None, &base64::engine::general_purpose::STANDARD.encode(bytes)),
+  }))
+}
+
+fn format_event(event: Option<&str>, data: &str) -> String {
+  let mut formatted = String::new();
+  if let Some(event) = event {
+    formatted.push_str("event: ");
+    formatted.push_str(event);
+    formatted.push('\n');
+  }
+  for line in data.split('\n') {
+    formatted.push_str("data: ");
+    formatted.push_str(line.trim_end_matches('\r'));
+    formatted.push('\n');
+  }
+  formatted.push('\n');
+  formatted
+}
+
+fn close_message(code: CloseCode, reason: &str) -> Message {
+  let mut end = reason.len().min(MAX_CLOSE_REASON);
+  while !reason.is_char_boundary(end) {
+    end -= 1;
+  }
+  Message::Close(Some(CloseFrame {
+    code,
+    reason: reason[..end].to_owned().into(),
+  }))
+}
+
+pub(crate) fn register_stream_router(
+  index: usize,
+  resources: Arc<HashMap<String, Resource>>,
+  router_config: &StreamRouterConfig,
+) -> Result<HttpRouter, RuntimeError> {
+  trace!(index, "registering stream router");
+  let middleware = resolve_middleware_components(router_config, &resources)?;
+
+  let component_id = router_config.operation().component_id()?;
+
+  let router = RouterOperation {
+    operation: router_config.operation().name().to_owned(),
+    component: component_id.to_owned(),
+    codec: router_config.codec().copied().unwrap_or_default(),
+    config: router_config.operation().config().and_then(|v| v.value().cloned()),
+    path: router_config.path().to_owned(),
+  };
+
+  let router = StreamComponentRouter::new(router);
+
+  Ok(HttpRouter::Raw(RawRouterHandler {
+    path: router_config.path().to_owned(),
+    component: Arc::new(router),
+    middleware,
+  }))
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+
+  use super::*;
+
+  #[test]
+  fn test_format_event() {
+    assert_eq!(format_event(None, r#"{"a":1}"#), "data: {\"a\":1}\n\n");
+    assert_eq!(
+      format_event(Some("error"), "first\r\nsecond"),
+      "event: error\ndata: first\ndata: second\n\n"
+    );
+  }
+
+  #[test]
+  fn test_close_message() {
+    let reason = "é".repeat(100);
+    let Message::Close(Some(frame)) = close_message(CloseCode::Error, &reason) else {
+      panic!("expected a close frame");
+    };
+    assert_eq!(frame.code, CloseCode::Error);
+    assert_eq!(frame.reason.len(), 122);
+  }
+
+  #[test]
+  fn test_message_to_packet() -> Result<()> {
+    let packet = message_to_packet(Codec::Json, Message::Text(r#"{"a":1}"#.to_owned())).unwrap();
+    assert_eq!(packet.decode::<Value>()?, serde_json::json!({"a": 1}));
+
+    let packet = message_to_packet(Codec::Json, Message::Text("not json".to_owned())).unwrap();
+    assert!(packet.is_error());
+
+    let packet = message_to_packet(Codec::Text, Message::Binary(b"hello".to_vec())).unwrap();
+    assert_eq!(packet.decode::<String>()?, "hello");
+
+    let packet = message_to_packet(Codec::Raw, Message::Text("hello".to_owned())).unwrap();
+    assert_eq!(packet.decode::<Base64Bytes>()?.as_ref(), b"hello");
+
+    assert!(message_to_packet(Codec::Json, Message::Ping(Vec::new())).is_none());
+    Ok(())
+  }
+
+  // "port_limited" tests are grouped together and run on a single thread to prevent port contention
+  mod port_limited {
+    use anyhow::Result;
+    use tokio_tungstenite::connect_async;
+
+    use super::super::*;
+    use crate::resources::Resource;
+    use crate::test::load_test_manifest;
+    use crate::triggers::http::Http;
+    use crate::{build_trigger_runtime, Trigger};
+
+    static PORT: &str = "9005";
+
+    async fn start_trigger() -> Result<Http> {
+      std::env::set_var("HTTP_PORT", PORT);
+      let app_config = load_test_manifest("app_config/stream-router.wick")
+        .await?
+        .try_app_config()?;
+      let rt = build_trigger_runtime(&app_config, Span::current())?.build(None).await?;
+
+      let trigger = Http::default();
+      let resource = Resource::new(app_config.resources().get(0).as_ref().unwrap().kind().clone())?;
+      let resources = Arc::new([("http".to_owned(), resource)].iter().cloned().collect());
+      let trigger_config = app_config.triggers()[0].clone();
+      trigger
+        .run(
+          "test".to_owned(),
+          rt,
+          app_config,
+          trigger_config,
+          resources,
+          Span::current(),
+        )
+        .await?;
+      Ok(trigger)
+    }
+
+    #[test_logger::test(tokio::test)]
+    async fn stream_websocket() -> Result<()> {
+      let trigger = start_trigger().await?;
+
+      let (mut socket, response) = connect_async(format!("ws://0.0.0.0:{}/ws", PORT)).await?;
+      assert_eq!(response.status(), 101);
+
+      socket.send(Message::Text(r#"{"hello":"world"}"#.to_owned())).await?;
+      let message = socket.next().await.unwrap()?;
+      assert_eq!(message, Message::Text(r#"{"hello":"world"}"#.to_owned()));
+
+      socket.send(Message::Text("[1,2,3]".to_owned())).await?;
+      let message = socket.next().await.unwrap()?;
+      assert_eq!(message, Message::Text("[1,2,3]".to_owned()));
+
+      socket.send(Message::Text("not json".to_owned())).await?;
+      let Message::Close(Some(frame)) = socket.next().await.unwrap()? else {
+        panic!("expected a close frame");
+      };
+      assert_eq!(frame.code, CloseCode::Error);
+
+      let res = reqwest::get(format!("http://0.0.0.0:{}/ws", PORT)).await?;
+      assert_eq!(res.status(), 400);
+
+      trigger.shutdown_gracefully().await?;
+      Ok(())
+    }
+
+    #[test_logger::test(tokio::test)]
+    async fn stream_event_stream() -> Result<()> {
+      let trigger = start_trigger().await?;
+
+      let res = reqwest::Client::new()
+        .get(format!("http://0.0.0.0:{}/sse/hello", PORT))
+        .header(ACCEPT, "text/event-stream")
+        .send()
+        .await?;
+      assert_eq!(res.status(), 200);
+      assert_eq!(res.headers()[CONTENT_TYPE], "text/event-stream");
+      assert_eq!(res.text().await?, "data: \"/hello\"\n\n");
+
+      trigger.shutdown_gracefully().await?;
+      Ok(())
+    }
+  }
+}
@@ -4,7 +4,9 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use futures::Future;
+use hyper::header::ORIGIN;
 use hyper::http::response::Builder;
+use hyper::http::uri::Scheme;
 use hyper::server::conn::AddrStream;
 use hyper::service::Service;
 use hyper::{Body, Request, Response, StatusCode};
@@ -16,9 +18,12 @@ use wick_packet::Invocation;
 use super::component_utils::{handle_request_middleware, handle_response_middleware};
 use super::conversions::{convert_response, convert_to_wick_response, merge_requests, request_to_wick};
 use super::error::InternalError;
+use super::tls::TlsConnection;
 use super::{HttpError, HttpRouter, RawRouterHandler};
 use crate::Runtime;
 
+type ServiceFuture = Pin<Box<dyn Future<Output = Result<ResponseService, hyper::Error>> + Send + Sync>>;
+
 pub(super) struct ServiceFactory {
   runtime: Runtime,
   routers: Arc<Vec<HttpRouter>>,
@@ -33,40 +38,64 @@ impl ServiceFactory {
       span,
     }
   }
+
+  fn make_service(&self, remote_addr: SocketAddr, scheme: Scheme) -> ServiceFuture {
+    let rt = self.runtime.clone();
+    let routers = self.routers.clone();
+    let span = self.span.clone();
+
+    let fut = async move { Ok(ResponseService::new(remote_addr, scheme, rt, routers, span)) };
+    Box::pin(fut)
+  }
 }
 
 impl Service<&AddrStream> for ServiceFactory {
   type Response = ResponseService;
   type Error = hyper::Error;
-  type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + Sync>>;
+  type Future = ServiceFuture;
 
   fn poll_ready(&mut self, _: &mut Context) -> Poll<Result<(), Self::Error>> {
     Poll::Ready(Ok(()))
   }
 
   fn call(&mut self, conn: &AddrStream) -> Self::Future {
-    let rt = self.runtime.clone();
-    let routers = self.routers.clone();
+    self.make_service(conn.remote_addr(), Scheme::HTTP)
+  }
+}
 
-    let remote_addr = conn.remote_addr();
-    let span = self.span.clone();
+impl Service<&TlsConnection> for ServiceFactory {
+  type Response = ResponseService;
+  type Error = hyper::Error;
+  type Future = ServiceFuture;
 
-    let fut = async move { Ok(ResponseService::new(remote_addr, rt, routers, span)) };
-    Box::pin(fut)
+  fn poll_ready(&mut self, _: &mut Context) -> Poll<Result<(), Self::Error>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn call(&mut self, conn: &TlsConnection) -> Self::Future {
+    self.make_service(conn.remote_addr(), Scheme::HTTPS)
   }
 }
 
 pub(super) struct ResponseService {
   remote_addr: SocketAddr,
+  scheme: Scheme,
   runtime: Runtime,
   routers: Arc<Vec<HttpRouter>>,
   span: Option<Id>,
 }
 
 impl ResponseService {
-  fn new(remote_addr: SocketAddr, runtime: Runtime, routers: Arc<Vec<HttpRouter>>, span: Option<Id>) -> Self {
+  fn new(
+    remote_addr: SocketAddr,
+    scheme: Scheme,
+    runtime: Runtime,
+    routers: Arc<Vec<HttpRouter>>,
+    span: Option<Id>,
+  ) -> Self {
     Self {
       remote_addr,
+      scheme,
       runtime,
       routers,
       span,
@@ -83,8 +112,10 @@ impl Service<Request<Body>> for ResponseService {
     Poll::Ready(Ok(()))
   }
 
-  fn call(&mut self, req: Request<Body>) -> Self::Future {
+  fn call(&mut self, mut req: Request<Body>) -> Self::Future {
     let remote_addr = self.remote_addr;
+    // Requests only carry a path, so record the scheme of the connection they arrived on.
+    req.extensions_mut().insert(self.scheme.clone());
     let time = chrono::Local::now().format("%d/%b/%Y:%H:%M:%S %z");
     let path = req.uri().path().to_owned();
     let tx_id = Invocation::new_tx_id();
@@ -155,10 +186,28 @@ async fn handle(
   runtime: Runtime,
   remote_addr: SocketAddr,
   span: &Span,
+) -> Result<Response<Body>, HttpError> {
+  let origin = req.headers().get(ORIGIN).cloned();
+  let builtin_span = info_span!(parent: span, "builtin-middleware");
+  let mut response = match r.middleware.handle_builtin(&req, remote_addr, &builtin_span).await? {
+    Some(response) => response,
+    None => handle_components(tx_id, req, &r, runtime, remote_addr, span).await?,
+  };
+  r.middleware.finish_builtin(origin.as_ref(), &mut response);
+  Ok(response)
+}
+
+async fn handle_components(
+  tx_id: Uuid,
+  req: Request<Body>,
+  r: &RawRouterHandler,
+  runtime: Runtime,
+  remote_addr: SocketAddr,
+  span: &Span,
 ) -> Result<Response<Body>, HttpError> {
   let pre_span = info_span!(parent: span, "pre-request", service.name="pre-request");
   let (wick_request_object, early_response) =
-    run_request_middleware(tx_id, &req, runtime.clone(), &r, remote_addr, &pre_span).await?;
+    run_request_middleware(tx_id, &req, runtime.clone(), r, remote_addr, &pre_span).await?;
   // if we have an early response, skip the main handler.
   let response = if let Some(response) = early_response {
     response
@@ -170,7 +219,7 @@ async fn handle(
       .await?
   };
   let post_span = info_span!(parent: span, "post-request");
-  run_response_middleware(tx_id, wick_request_object, response, runtime.clone(), &r, &post_span).await
+  run_response_middleware(tx_id, wick_request_object, response, runtime.clone(), r, &post_span).await
 }
 
 async fn run_request_middleware<B>(
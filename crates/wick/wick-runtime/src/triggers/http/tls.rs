@@ -0,0 +1,256 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use hyper::server::accept::Accept;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::RwLock;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::Span;
+use wick_config::config::HttpTlsConfig;
+
+use super::HttpError;
+use crate::dev::prelude::*;
+use crate::resources::{Resource, ResourceKind};
+use crate::triggers::TriggerKind;
+
+/// How long a client has to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many handshaken connections can wait for the server to pick them up.
+const ACCEPT_BACKLOG: usize = 128;
+
+/// The certificate, key, and optional client CA files for a TLS listener.
+#[derive(Debug, Clone)]
+pub(super) struct TlsFiles {
+  cert: PathBuf,
+  key: PathBuf,
+  client_ca: Option<PathBuf>,
+}
+
+impl TlsFiles {
+  pub(super) fn new(config: &HttpTlsConfig, resources: &HashMap<String, Resource>) -> Result<Self, RuntimeError> {
+    Ok(Self {
+      cert: file_path(resources, config.cert())?,
+      key: file_path(resources, config.key())?,
+      client_ca: config.client_ca().map(|name| file_path(resources, name)).transpose()?,
+    })
+  }
+
+  fn paths(&self) -> impl Iterator<Item = &Path> {
+    [Some(&self.cert), Some(&self.key), self.client_ca.as_ref()]
+      .into_iter()
+      .flatten()
+      .map(PathBuf::as_path)
+  }
+
+  /// Read the files into a new rustls [ServerConfig].
+  pub(super) fn load(&self) -> Result<Arc<ServerConfig>, HttpError> {
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let builder = match &self.client_ca {
+      Some(path) => {
+        let mut roots = RootCertStore::empty();
+        for cert in read_certs(path)? {
+          roots
+            .add(&cert)
+            .map_err(|e| HttpError::Tls(format!("invalid CA certificate in {}: {}", path.display(), e)))?;
+        }
+        builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+      }
+      None => builder.with_no_client_auth(),
+    };
+    let mut config = builder
+      .with_single_cert(read_certs(&self.cert)?, read_key(&self.key)?)
+      .map_err(|e| HttpError::Tls(e.to_string()))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+  }
+}
+
+fn file_path(resources: &HashMap<String, Resource>, name: &str) -> Result<PathBuf, RuntimeError> {
+  let resource = resources
+    .get(name)
+    .ok_or_else(|| RuntimeError::ResourceNotFound(TriggerKind::Http.into(), name.to_owned()))?;
+  match resource {
+    Resource::Volume(path) => Ok(path.clone()),
+    Resource::Url(url) if url.scheme() == "file" => url
+      .to_file_path()
+      .map_err(|_| RuntimeError::InitializationFailed(format!("{} is not a valid file path", url))),
+    _ => Err(RuntimeError::InvalidResourceType(
+      TriggerKind::Http.into(),
+      ResourceKind::Volume,
+      resource.kind(),
+    )),
+  }
+}
+
+fn read_pem(path: &Path) -> Result<Vec<rustls_pemfile::Item>, HttpError> {
+  let file = File::open(path).map_err(|e| HttpError::Tls(format!("could not open {}: {}", path.display(), e)))?;
+  rustls_pemfile::read_all(&mut BufReader::new(file))
+    .map_err(|e| HttpError::Tls(format!("could not read {}: {}", path.display(), e)))
+}
+
+fn read_certs(path: &Path) -> Result<Vec<Certificate>, HttpError> {
+  let certs: Vec<_> = read_pem(path)?
+    .into_iter()
+    .filter_map(|item| match item {
+      rustls_pemfile::Item::X509Certificate(cert) => Some(Certificate(cert)),
+      _ => None,
+    })
+    .collect();
+  if certs.is_empty() {
+    return Err(HttpError::Tls(format!("no certificates found in {}", path.display())));
+  }
+  Ok(certs)
+}
+
+fn read_key(path: &Path) -> Result<PrivateKey, HttpError> {
+  read_pem(path)?
+    .into_iter()
+    .find_map(|item| match item {
+      rustls_pemfile::Item::RSAKey(key) | rustls_pemfile::Item::PKCS8Key(key) | rustls_pemfile::Item::ECKey(key) => {
+        Some(PrivateKey(key))
+      }
+      _ => None,
+    })
+    .ok_or_else(|| HttpError::Tls(format!("no private key found in {}", path.display())))
+}
+
+/// Terminates TLS for incoming connections, reloading its certificates when their files change.
+pub(super) struct TlsListener {
+  config: Arc<RwLock<Arc<ServerConfig>>>,
+  watcher: RecommendedWatcher,
+}
+
+impl TlsListener {
+  pub(super) fn new(files: TlsFiles, span: &Span) -> Result<Self, RuntimeError> {
+    let config = Arc::new(RwLock::new(
+      files
+        .load()
+        .map_err(|e| RuntimeError::InitializationFailed(e.to_string()))?,
+    ));
+
+    // Watch the parent directories rather than the files themselves so that certificates replaced
+    // by a rename (or a symlink swap, as with mounted Kubernetes secrets) are still picked up.
+    let dirs: HashSet<PathBuf> = files
+      .paths()
+      .map(|path| {
+        let path = path
+          .canonicalize()
+          .map_err(|e| RuntimeError::InitializationFailed(format!("could not watch {}: {}", path.display(), e)))?;
+        Ok(path.parent().map_or_else(|| path.clone(), Path::to_path_buf))
+      })
+      .collect::<Result<_, RuntimeError>>()?;
+
+    let reload_config = config.clone();
+    let reload_span = span.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+      let Ok(event) = event else { return };
+      if event.kind.is_access() {
+        return;
+      }
+      // A certificate may be mid-write; keep serving the previous one until it loads cleanly.
+      match files.load() {
+        Ok(config) => {
+          *reload_config.write() = config;
+          reload_span.in_scope(|| info!("reloaded tls certificates"));
+        }
+        Err(e) => reload_span.in_scope(|| warn!(error = %e, "could not reload tls certificates")),
+      }
+    })
+    .map_err(|e| RuntimeError::InitializationFailed(format!("could not create file watcher: {}", e)))?;
+    for dir in &dirs {
+      watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| RuntimeError::InitializationFailed(format!("could not watch {}: {}", dir.display(), e)))?;
+    }
+
+    Ok(Self { config, watcher })
+  }
+
+  /// Accept connections from `listener` and complete their TLS handshakes in the background.
+  pub(super) fn incoming(
+    self,
+    listener: TcpListener,
+    span: Span,
+  ) -> impl Accept<Conn = TlsConnection, Error = std::io::Error> {
+    let (tx, rx) = tokio::sync::mpsc::channel(ACCEPT_BACKLOG);
+    let Self { config, watcher } = self;
+
+    tokio::spawn(async move {
+      // Keep watching for certificate changes until the server stops listening.
+      let _watcher = watcher;
+      loop {
+        let (stream, remote_addr) = tokio::select! {
+          _ = tx.closed() => break,
+          accepted = listener.accept() => match accepted {
+            Ok(accepted) => accepted,
+            Err(e) => {
+              span.in_scope(|| warn!(error = %e, "could not accept connection"));
+              tokio::time::sleep(Duration::from_millis(100)).await;
+              continue;
+            }
+          },
+        };
+        let acceptor = TlsAcceptor::from(config.read().clone());
+        let tx = tx.clone();
+        let span = span.clone();
+        tokio::spawn(async move {
+          match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+            Ok(Ok(stream)) => {
+              let _ = tx.send(Ok(TlsConnection { stream, remote_addr })).await;
+            }
+            Ok(Err(e)) => span.in_scope(|| debug!(%remote_addr, error = %e, "tls handshake failed")),
+            Err(_) => span.in_scope(|| debug!(%remote_addr, "tls handshake timed out")),
+          }
+        });
+      }
+    });
+
+    hyper::server::accept::from_stream(ReceiverStream::new(rx))
+  }
+}
+
+/// A client connection that has completed its TLS handshake.
+pub(super) struct TlsConnection {
+  stream: TlsStream<TcpStream>,
+  remote_addr: SocketAddr,
+}
+
+impl TlsConnection {
+  pub(super) const fn remote_addr(&self) -> SocketAddr {
+    self.remote_addr
+  }
+}
+
+impl AsyncRead for TlsConnection {
+  fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+    Pin::new(&mut self.stream).poll_read(cx, buf)
+  }
+}
+
+impl AsyncWrite for TlsConnection {
+  fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+    Pin::new(&mut self.stream).poll_write(cx, buf)
+  }
+
+  fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+    Pin::new(&mut self.stream).poll_flush(cx)
+  }
+
+  fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+    Pin::new(&mut self.stream).poll_shutdown(cx)
+  }
+}
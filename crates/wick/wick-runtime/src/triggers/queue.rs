@@ -0,0 +1,319 @@
+mod broker;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use config::{AppConfiguration, TriggerDefinition};
+use parking_lot::Mutex;
+use serde_json::json;
+use structured_output::StructuredOutput;
+use tokio::task::JoinHandle;
+use tokio_stream::StreamExt;
+use tracing::{Instrument, Span};
+use wick_packet::{packet_stream, Base64Bytes, Entity, InherentData, Invocation};
+
+use self::broker::{get_broker, Broker, Delivery};
+use super::{ComponentId, Trigger, TriggerKind};
+use crate::dev::prelude::*;
+use crate::resources::{Resource, ResourceKind};
+use crate::Runtime;
+
+/// Invoke the target operation with a delivery and report whether it completed without an error.
+async fn invoke_operation(runtime: &Runtime, target: Entity, delivery: &Delivery) -> Result<bool, RuntimeError> {
+  let packets = packet_stream!(
+    ("payload", Base64Bytes::new(delivery.message.payload.clone())),
+    ("headers", &delivery.message.headers)
+  );
+  let invocation = Invocation::new(
+    Entity::server("queue_client"),
+    target,
+    packets,
+    InherentData::unsafe_default(),
+    &Span::current(),
+  );
+
+  let mut response = runtime.invoke(invocation, Default::default()).await?;
+  let mut success = true;
+  while let Some(packet) = response.next().await {
+    trace!(?packet, "trigger:queue:response");
+    match packet {
+      Ok(p) if p.is_error() => {
+        warn!(error = %p.unwrap_err().msg(), "trigger:queue:operation error");
+        success = false;
+      }
+      Err(e) => {
+        warn!(error = %e, "trigger:queue:stream error");
+        success = false;
+      }
+      Ok(_) => {}
+    }
+  }
+  Ok(success)
+}
+
+/// The number of times a requeued delivery is attempted before it's dead-lettered.
+const MAX_ATTEMPTS: u32 = 5;
+const BASE_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Tracks failed attempts for the deliveries a consumer has seen.
+#[derive(Debug, Default)]
+struct Attempts(HashMap<String, u32>);
+
+impl Attempts {
+  /// Record a failed attempt and return how long to wait before requeueing, or `None` once the delivery is out of
+  /// attempts.
+  fn failed(&mut self, id: &str) -> Option<Duration> {
+    let attempts = self.0.entry(id.to_owned()).or_default();
+    *attempts += 1;
+    if *attempts >= MAX_ATTEMPTS {
+      self.0.remove(id);
+      return None;
+    }
+    Some(
+      BASE_BACKOFF
+        .saturating_mul(2_u32.saturating_pow(*attempts - 1))
+        .min(MAX_BACKOFF),
+    )
+  }
+
+  fn clear(&mut self, id: &str) {
+    self.0.remove(id);
+  }
+}
+
+async fn consume(
+  runtime: Runtime,
+  broker: Arc<dyn Broker>,
+  subject: String,
+  target: Entity,
+  requeue: bool,
+  mut shutdown_rx: tokio::sync::oneshot::Receiver<()>,
+) -> Result<(), RuntimeError> {
+  let mut attempts = Attempts::default();
+  loop {
+    let delivery = tokio::select! {
+      _ = &mut shutdown_rx => break,
+      delivery = broker.next(&subject) => delivery.map_err(|e| RuntimeError::Queue(e.to_string()))?,
+    };
+    let span = info_span!("trigger:queue:message", id = %delivery.id, redelivered = delivery.redelivered);
+
+    let result = invoke_operation(&runtime, target.clone(), &delivery)
+      .instrument(span.clone())
+      .await;
+    let success = match result {
+      Ok(success) => success,
+      Err(e) => {
+        span.in_scope(|| error!(error = %e, "error invoking operation"));
+        false
+      }
+    };
+    let mut shutdown = false;
+    let acked = if success {
+      attempts.clear(&delivery.id);
+      broker.ack(&delivery).await
+    } else if !requeue {
+      broker.nack(&delivery, false).await
+    } else if let Some(backoff) = attempts.failed(&delivery.id) {
+      span.in_scope(|| debug!(?backoff, "requeueing message"));
+      // Hold on to the delivery while backing off so it isn't picked straight back up.
+      tokio::select! {
+        _ = &mut shutdown_rx => shutdown = true,
+        _ = tokio::time::sleep(backoff) => {},
+      };
+      broker.nack(&delivery, true).await
+    } else {
+      span.in_scope(|| warn!(attempts = MAX_ATTEMPTS, "message failed too many times, dead-lettering"));
+      broker.nack(&delivery, false).await
+    };
+    acked.map_err(|e| RuntimeError::Queue(e.to_string()))?;
+    if shutdown {
+      break;
+    }
+  }
+  Ok(())
+}
+
+#[derive(Debug)]
+struct QueueInstance {
+  handle: JoinHandle<Result<(), RuntimeError>>,
+  shutdown_tx: tokio::sync::oneshot::Sender<()>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Queue {
+  instance: Arc<Mutex<Option<QueueInstance>>>,
+}
+
+impl Queue {
+  pub(crate) fn load() -> Result<Arc<dyn Trigger + Send + Sync>, RuntimeError> {
+    Ok(Arc::new(Self::default()))
+  }
+}
+
+#[async_trait]
+impl Trigger for Queue {
+  async fn run(
+    &self,
+    _name: String,
+    runtime: Runtime,
+    _app_config: AppConfiguration,
+    config: TriggerDefinition,
+    resources: Arc<HashMap<String, Resource>>,
+    span: Span,
+  ) -> Result<StructuredOutput, RuntimeError> {
+    span.in_scope(|| debug!(kind = %TriggerKind::Queue, "trigger:run"));
+    let TriggerDefinition::Queue(config) = config else {
+      return Err(RuntimeError::TriggerKind(Context::Trigger, TriggerKind::Queue));
+    };
+
+    let resource_name = config.resource();
+    let resource = resources
+      .get(resource_name)
+      .ok_or_else(|| RuntimeError::ResourceNotFound(TriggerKind::Queue.into(), resource_name.to_owned()))?;
+    let Resource::Url(url) = resource else {
+      return Err(RuntimeError::InvalidResourceType(
+        TriggerKind::Queue.into(),
+        ResourceKind::Url,
+        resource.kind(),
+      ));
+    };
+
+    let broker = get_broker(url).map_err(|e| RuntimeError::Queue(e.to_string()))?;
+    let target = Entity::operation(config.operation().component_id()?, config.operation().name());
+    let subject = config.subject().to_owned();
+
+    let span = info_span!(parent: &span, "trigger:queue", %url, %subject);
+    span.in_scope(|| info!(%target, "queue trigger consuming"));
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+    let handle =
+      tokio::spawn(consume(runtime, broker, subject.clone(), target, config.requeue(), shutdown_rx).instrument(span));
+
+    self.instance.lock().replace(QueueInstance { handle, shutdown_tx });
+
+    Ok(StructuredOutput::new(
+      format!("Queue trigger consuming '{}' from {}", subject, url),
+      json!({"url": url.to_string(), "subject": subject}),
+    ))
+  }
+
+  async fn shutdown_gracefully(self) -> Result<(), RuntimeError> {
+    let Some(instance) = self.instance.lock().take() else {
+      return Ok(());
+    };
+    let _ = instance.shutdown_tx.send(());
+    instance
+      .handle
+      .await
+      .map_err(|e| RuntimeError::ShutdownFailed(TriggerKind::Queue.into(), e.to_string()))?
+  }
+
+  async fn wait_for_done(&self) -> StructuredOutput {
+    let Some(instance) = self.instance.lock().take() else {
+      return StructuredOutput::new("queue trigger never ran", json!({"status": "queue trigger never ran"}));
+    };
+    match instance.handle.await {
+      Ok(Ok(())) => StructuredOutput::new("queue trigger complete", json!({"status": "queue trigger complete"})),
+      Ok(Err(e)) => {
+        let message = format!("queue trigger error: {}", e);
+        StructuredOutput::new(message.clone(), json!({ "error": message }))
+      }
+      Err(e) => {
+        let message = format!("queue trigger error: {}", e);
+        StructuredOutput::new(message.clone(), json!({ "error": message }))
+      }
+    }
+  }
+}
+
+impl fmt::Display for Queue {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Queue Trigger")
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+
+  use super::broker::{MemoryBroker, Message};
+  use super::*;
+  use crate::build_trigger_runtime;
+  use crate::test::load_example;
+
+  async fn wait_for<F: Fn() -> bool + Send + Sync>(condition: F) -> Result<()> {
+    tokio::time::timeout(Duration::from_secs(10), async {
+      while !condition() {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+      }
+    })
+    .await?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_attempts() {
+    let mut attempts = Attempts::default();
+    assert_eq!(attempts.failed("a"), Some(BASE_BACKOFF));
+    assert_eq!(attempts.failed("a"), Some(BASE_BACKOFF * 2));
+    assert_eq!(attempts.failed("b"), Some(BASE_BACKOFF));
+    assert_eq!(attempts.failed("a"), Some(BASE_BACKOFF * 4));
+    assert_eq!(attempts.failed("a"), Some(BASE_BACKOFF * 8));
+    assert_eq!(attempts.failed("a"), None);
+    // Running out of attempts starts the count over.
+    assert_eq!(attempts.failed("a"), Some(BASE_BACKOFF));
+    attempts.clear("b");
+    assert_eq!(attempts.failed("b"), Some(BASE_BACKOFF));
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_queue_example() -> Result<()> {
+    let app_config = load_example("queue/queue.wick").await?.try_app_config()?;
+    let rt = build_trigger_runtime(&app_config, Span::current())?.build(None).await?;
+    let mut resources = HashMap::new();
+    for res in app_config.resources() {
+      resources.insert(res.id().to_owned(), Resource::new(res.kind().clone())?);
+    }
+    let resources = Arc::new(resources);
+
+    let broker = MemoryBroker::named("queue-example");
+    broker
+      .publish("accept", Message::new("hello").with_header("source", "test"))
+      .await?;
+    broker.publish("reject", Message::new("goodbye")).await?;
+
+    let mut triggers = Vec::new();
+    for trigger_config in app_config.triggers() {
+      let trigger = Queue::default();
+      trigger
+        .run(
+          "test".to_owned(),
+          rt.clone(),
+          app_config.clone(),
+          trigger_config.clone(),
+          resources.clone(),
+          Span::current(),
+        )
+        .await?;
+      triggers.push(trigger);
+    }
+
+    wait_for(|| broker.dead_letters("reject").len() == 1).await?;
+    assert_eq!(broker.dead_letters("reject")[0].payload, "goodbye");
+
+    // The accepted message is acked and leaves nothing behind.
+    broker.publish("accept", Message::new("again")).await?;
+    wait_for(|| broker.outstanding("accept") == 0).await?;
+    assert!(broker.dead_letters("accept").is_empty());
+
+    for trigger in triggers {
+      trigger.shutdown_gracefully().await?;
+    }
+
+    Ok(())
+  }
+}
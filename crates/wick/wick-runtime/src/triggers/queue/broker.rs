@@ -0,0 +1,94 @@
+mod file;
+mod memory;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use url::Url;
+
+pub(crate) use self::file::FileBroker;
+pub(crate) use self::memory::MemoryBroker;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum BrokerError {
+  #[error("No message broker registered for scheme '{0}'")]
+  UnsupportedScheme(String),
+
+  #[error("Invalid broker URL '{0}': {1}")]
+  InvalidUrl(Url, String),
+
+  #[error("Unknown delivery '{1}' on subject '{0}'")]
+  UnknownDelivery(String, String),
+
+  #[error("Broker I/O error: {0}")]
+  Io(#[from] std::io::Error),
+}
+
+/// A message consumed from or published to a broker.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct Message {
+  pub(crate) payload: Bytes,
+  pub(crate) headers: HashMap<String, String>,
+}
+
+impl Message {
+  pub(crate) fn new(payload: impl Into<Bytes>) -> Self {
+    Self {
+      payload: payload.into(),
+      headers: HashMap::new(),
+    }
+  }
+
+  pub(crate) fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+    self.headers.insert(name.into(), value.into());
+    self
+  }
+}
+
+/// A message handed out by a broker that must be acked or nacked by the consumer.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Delivery {
+  pub(crate) id: String,
+  pub(crate) subject: String,
+  pub(crate) redelivered: bool,
+  pub(crate) message: Message,
+}
+
+/// The interface a message broker adapter must implement to back a queue trigger.
+#[async_trait]
+pub(crate) trait Broker: std::fmt::Debug + Send + Sync {
+  /// Publish a message to the passed subject.
+  async fn publish(&self, subject: &str, message: Message) -> Result<(), BrokerError>;
+
+  /// Wait for the next message on the passed subject.
+  async fn next(&self, subject: &str) -> Result<Delivery, BrokerError>;
+
+  /// Acknowledge a delivery, removing it from the broker.
+  async fn ack(&self, delivery: &Delivery) -> Result<(), BrokerError>;
+
+  /// Reject a delivery, either returning it to its subject or dead-lettering it.
+  async fn nack(&self, delivery: &Delivery, requeue: bool) -> Result<(), BrokerError>;
+}
+
+pub(crate) type BrokerLoader = Arc<dyn Fn(&Url) -> Result<Arc<dyn Broker>, BrokerError> + Send + Sync>;
+
+static BROKER_LOADER_REGISTRY: Lazy<Mutex<HashMap<&'static str, BrokerLoader>>> = Lazy::new(|| {
+  let mut m: HashMap<&'static str, BrokerLoader> = HashMap::new();
+  m.insert("memory", Arc::new(MemoryBroker::load));
+  m.insert("file", Arc::new(FileBroker::load));
+  Mutex::new(m)
+});
+
+/// Create a broker for the passed URL based on its scheme.
+pub(crate) fn get_broker(url: &Url) -> Result<Arc<dyn Broker>, BrokerError> {
+  let loader = BROKER_LOADER_REGISTRY
+    .lock()
+    .get(url.scheme())
+    .cloned()
+    .ok_or_else(|| BrokerError::UnsupportedScheme(url.scheme().to_owned()))?;
+  loader(url)
+}
@@ -0,0 +1,296 @@
+use std::collections::{HashMap, HashSet};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use url::Url;
+
+use super::{Broker, BrokerError, Delivery, Message};
+
+const IN_FLIGHT_DIR: &str = ".inflight";
+const DEAD_LETTER_DIR: &str = ".dead";
+const HEADERS_DIR: &str = ".headers";
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A broker backed by a directory, addressed by `file:///path/to/dir`.
+///
+/// Each subject is a subdirectory and each file within it is a message. Deliveries are claimed by moving the file into
+/// `.inflight/`, acks delete the file, and nacks either move it back or into `.dead/`. Headers are kept as JSON
+/// alongside, in `.headers/`, and follow a message into `.dead/.headers/` when it's dead-lettered.
+#[derive(Debug)]
+pub(crate) struct FileBroker {
+  root: PathBuf,
+  recovered: Mutex<HashSet<String>>,
+  redelivered: Mutex<HashSet<String>>,
+  next_id: AtomicU64,
+}
+
+impl FileBroker {
+  pub(crate) fn load(url: &Url) -> Result<Arc<dyn Broker>, BrokerError> {
+    let root = url
+      .to_file_path()
+      .map_err(|_| BrokerError::InvalidUrl(url.clone(), "file brokers require an absolute path".to_owned()))?;
+    Ok(Arc::new(Self::new(root)))
+  }
+
+  pub(crate) fn new(root: impl Into<PathBuf>) -> Self {
+    Self {
+      root: root.into(),
+      recovered: Default::default(),
+      redelivered: Default::default(),
+      next_id: AtomicU64::new(0),
+    }
+  }
+
+  fn subject_dir(&self, subject: &str) -> PathBuf {
+    self.root.join(subject)
+  }
+
+  fn headers_path(&self, subject: &str, name: &str) -> PathBuf {
+    self.subject_dir(subject).join(HEADERS_DIR).join(name)
+  }
+
+  /// Read the headers published with a message. Files dropped into a subject by hand have none.
+  async fn read_headers(&self, subject: &str, name: &str) -> Result<HashMap<String, String>, BrokerError> {
+    match tokio::fs::read(self.headers_path(subject, name)).await {
+      Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| BrokerError::Io(e.into())),
+      Err(e) if e.kind() == ErrorKind::NotFound => Ok(HashMap::new()),
+      Err(e) => Err(e.into()),
+    }
+  }
+
+  /// Return deliveries left in flight by a previous consumer to their subject.
+  async fn recover(&self, subject: &str) -> Result<(), BrokerError> {
+    if !self.recovered.lock().insert(subject.to_owned()) {
+      return Ok(());
+    }
+    let dir = self.subject_dir(subject);
+    let mut entries = match tokio::fs::read_dir(dir.join(IN_FLIGHT_DIR)).await {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+      Err(e) => return Err(e.into()),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+      let name = entry.file_name().to_string_lossy().to_string();
+      tokio::fs::rename(entry.path(), dir.join(&name)).await?;
+      self.redelivered.lock().insert(name);
+    }
+    Ok(())
+  }
+
+  /// Try to claim the oldest message on a subject.
+  async fn claim(&self, subject: &str) -> Result<Option<Delivery>, BrokerError> {
+    let dir = self.subject_dir(subject);
+    let mut entries = match tokio::fs::read_dir(&dir).await {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+      Err(e) => return Err(e.into()),
+    };
+
+    let mut names = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+      let name = entry.file_name().to_string_lossy().to_string();
+      if !name.starts_with('.') && !entry.file_type().await?.is_dir() {
+        names.push(name);
+      }
+    }
+    names.sort();
+
+    let in_flight = dir.join(IN_FLIGHT_DIR);
+    tokio::fs::create_dir_all(&in_flight).await?;
+
+    for name in names {
+      let claimed = in_flight.join(&name);
+      match tokio::fs::rename(dir.join(&name), &claimed).await {
+        Ok(_) => {}
+        // Another consumer claimed it first.
+        Err(e) if e.kind() == ErrorKind::NotFound => continue,
+        Err(e) => return Err(e.into()),
+      }
+      let payload = tokio::fs::read(&claimed).await?;
+      let mut message = Message::new(payload);
+      message.headers = self.read_headers(subject, &name).await?;
+      let redelivered = self.redelivered.lock().remove(&name);
+      return Ok(Some(Delivery {
+        id: name.clone(),
+        subject: subject.to_owned(),
+        redelivered,
+        message: message.with_header("filename", name),
+      }));
+    }
+    Ok(None)
+  }
+
+  fn in_flight_path(&self, delivery: &Delivery) -> PathBuf {
+    self
+      .subject_dir(&delivery.subject)
+      .join(IN_FLIGHT_DIR)
+      .join(&delivery.id)
+  }
+
+  async fn move_in_flight(&self, delivery: &Delivery, to: &Path) -> Result<(), BrokerError> {
+    match tokio::fs::rename(self.in_flight_path(delivery), to).await {
+      Err(e) if e.kind() == ErrorKind::NotFound => Err(BrokerError::UnknownDelivery(
+        delivery.subject.clone(),
+        delivery.id.clone(),
+      )),
+      result => Ok(result?),
+    }
+  }
+}
+
+#[async_trait]
+impl Broker for FileBroker {
+  async fn publish(&self, subject: &str, message: Message) -> Result<(), BrokerError> {
+    let dir = self.subject_dir(subject);
+    tokio::fs::create_dir_all(&dir).await?;
+    let nanos = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default()
+      .as_nanos();
+    let name = format!("{:024}-{:06}", nanos, self.next_id.fetch_add(1, Ordering::Relaxed));
+    if !message.headers.is_empty() {
+      let headers = self.headers_path(subject, &name);
+      tokio::fs::create_dir_all(dir.join(HEADERS_DIR)).await?;
+      let json = serde_json::to_vec(&message.headers).map_err(|e| BrokerError::Io(e.into()))?;
+      tokio::fs::write(headers, json).await?;
+    }
+    // Write to a hidden file first so consumers never see a partial message.
+    let tmp = dir.join(format!(".{}", name));
+    tokio::fs::write(&tmp, &message.payload).await?;
+    tokio::fs::rename(tmp, dir.join(name)).await?;
+    Ok(())
+  }
+
+  async fn next(&self, subject: &str) -> Result<Delivery, BrokerError> {
+    self.recover(subject).await?;
+    loop {
+      if let Some(delivery) = self.claim(subject).await? {
+        return Ok(delivery);
+      }
+      tokio::time::sleep(POLL_INTERVAL).await;
+    }
+  }
+
+  async fn ack(&self, delivery: &Delivery) -> Result<(), BrokerError> {
+    match tokio::fs::remove_file(self.in_flight_path(delivery)).await {
+      Err(e) if e.kind() == ErrorKind::NotFound => {
+        return Err(BrokerError::UnknownDelivery(
+          delivery.subject.clone(),
+          delivery.id.clone(),
+        ))
+      }
+      result => result?,
+    }
+    match tokio::fs::remove_file(self.headers_path(&delivery.subject, &delivery.id)).await {
+      Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+      result => Ok(result?),
+    }
+  }
+
+  async fn nack(&self, delivery: &Delivery, requeue: bool) -> Result<(), BrokerError> {
+    let dir = self.subject_dir(&delivery.subject);
+    if requeue {
+      self.move_in_flight(delivery, &dir.join(&delivery.id)).await?;
+      self.redelivered.lock().insert(delivery.id.clone());
+    } else {
+      let dead = dir.join(DEAD_LETTER_DIR);
+      tokio::fs::create_dir_all(&dead).await?;
+      self.move_in_flight(delivery, &dead.join(&delivery.id)).await?;
+      // Keep the headers with the dead letter, in the same layout as a subject.
+      let headers = self.headers_path(&delivery.subject, &delivery.id);
+      if tokio::fs::try_exists(&headers).await? {
+        let dead_headers = dead.join(HEADERS_DIR);
+        tokio::fs::create_dir_all(&dead_headers).await?;
+        tokio::fs::rename(headers, dead_headers.join(&delivery.id)).await?;
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+
+  use super::*;
+
+  #[test_logger::test(tokio::test)]
+  async fn test_file_ack_nack() -> Result<()> {
+    let root = std::env::temp_dir().join(format!("wick-file-broker-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let broker = FileBroker::new(&root);
+    broker.publish("jobs", Message::new("first")).await?;
+    broker.publish("jobs", Message::new("second")).await?;
+
+    let first = broker.next("jobs").await?;
+    assert_eq!(first.message.payload, "first");
+    broker.nack(&first, true).await?;
+
+    let first = broker.next("jobs").await?;
+    assert!(first.redelivered);
+    broker.nack(&first, false).await?;
+    assert!(root.join("jobs").join(DEAD_LETTER_DIR).join(&first.id).exists());
+
+    let second = broker.next("jobs").await?;
+    assert_eq!(second.message.payload, "second");
+    assert_eq!(second.message.headers.get("filename"), Some(&second.id));
+    broker.ack(&second).await?;
+    assert!(broker.ack(&second).await.is_err());
+
+    // A new consumer picks up deliveries that were left in flight.
+    broker.publish("jobs", Message::new("third")).await?;
+    let third = broker.next("jobs").await?;
+    let broker = FileBroker::new(&root);
+    let third_again = broker.next("jobs").await?;
+    assert_eq!(third_again.id, third.id);
+    assert!(third_again.redelivered);
+
+    std::fs::remove_dir_all(&root)?;
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_file_headers() -> Result<()> {
+    let root = std::env::temp_dir().join(format!("wick-file-broker-headers-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let broker = FileBroker::new(&root);
+    broker
+      .publish("jobs", Message::new("first").with_header("source", "test"))
+      .await?;
+
+    let first = broker.next("jobs").await?;
+    assert_eq!(first.message.headers.get("source").map(String::as_str), Some("test"));
+    assert_eq!(first.message.headers.get("filename"), Some(&first.id));
+    broker.nack(&first, true).await?;
+
+    let first = broker.next("jobs").await?;
+    assert_eq!(first.message.headers.get("source").map(String::as_str), Some("test"));
+    broker.ack(&first).await?;
+    assert!(!root.join("jobs").join(HEADERS_DIR).join(&first.id).exists());
+
+    // Dead letters keep their headers.
+    broker
+      .publish("jobs", Message::new("second").with_header("source", "test"))
+      .await?;
+    let second = broker.next("jobs").await?;
+    broker.nack(&second, false).await?;
+    let dead = root.join("jobs").join(DEAD_LETTER_DIR);
+    assert!(!root.join("jobs").join(HEADERS_DIR).join(&second.id).exists());
+    let headers: HashMap<String, String> =
+      serde_json::from_slice(&std::fs::read(dead.join(HEADERS_DIR).join(&second.id))?)?;
+    assert_eq!(headers.get("source").map(String::as_str), Some("test"));
+
+    // Files written by something other than the broker carry no headers.
+    std::fs::write(root.join("jobs").join("external"), "raw")?;
+    let raw = broker.next("jobs").await?;
+    assert_eq!(raw.message.headers.len(), 1);
+
+    std::fs::remove_dir_all(&root)?;
+    Ok(())
+  }
+}
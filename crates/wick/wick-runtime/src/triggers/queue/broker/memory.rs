@@ -0,0 +1,169 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+use url::Url;
+
+use super::{Broker, BrokerError, Delivery, Message};
+
+static MEMORY_BROKERS: Lazy<Mutex<HashMap<String, Arc<MemoryBroker>>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Default)]
+struct SubjectQueue {
+  pending: VecDeque<Delivery>,
+  in_flight: HashMap<String, Delivery>,
+  dead: Vec<Message>,
+}
+
+/// An in-process broker addressed by `memory://<name>`. Every URL with the same name shares the same broker.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryBroker {
+  subjects: Mutex<HashMap<String, SubjectQueue>>,
+  notify: Notify,
+  next_id: AtomicU64,
+}
+
+impl MemoryBroker {
+  pub(crate) fn load(url: &Url) -> Result<Arc<dyn Broker>, BrokerError> {
+    let name = url.host_str().ok_or_else(|| {
+      BrokerError::InvalidUrl(
+        url.clone(),
+        "memory brokers must be named, e.g. memory://my-broker".to_owned(),
+      )
+    })?;
+    Ok(Self::named(name))
+  }
+
+  /// Get or create the broker registered under the passed name.
+  pub(crate) fn named(name: &str) -> Arc<Self> {
+    MEMORY_BROKERS.lock().entry(name.to_owned()).or_default().clone()
+  }
+
+  /// The messages on a subject that were rejected without being requeued.
+  #[cfg(test)]
+  pub(crate) fn dead_letters(&self, subject: &str) -> Vec<Message> {
+    self
+      .subjects
+      .lock()
+      .get(subject)
+      .map(|q| q.dead.clone())
+      .unwrap_or_default()
+  }
+
+  /// The number of messages on a subject that are pending or in flight.
+  #[cfg(test)]
+  pub(crate) fn outstanding(&self, subject: &str) -> usize {
+    self
+      .subjects
+      .lock()
+      .get(subject)
+      .map_or(0, |q| q.pending.len() + q.in_flight.len())
+  }
+
+  fn enqueue(&self, delivery: Delivery) {
+    self
+      .subjects
+      .lock()
+      .entry(delivery.subject.clone())
+      .or_default()
+      .pending
+      .push_back(delivery);
+    self.notify.notify_waiters();
+  }
+
+  fn take(&self, delivery: &Delivery) -> Result<Delivery, BrokerError> {
+    self
+      .subjects
+      .lock()
+      .get_mut(&delivery.subject)
+      .and_then(|q| q.in_flight.remove(&delivery.id))
+      .ok_or_else(|| BrokerError::UnknownDelivery(delivery.subject.clone(), delivery.id.clone()))
+  }
+}
+
+#[async_trait]
+impl Broker for MemoryBroker {
+  async fn publish(&self, subject: &str, message: Message) -> Result<(), BrokerError> {
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    self.enqueue(Delivery {
+      id: id.to_string(),
+      subject: subject.to_owned(),
+      redelivered: false,
+      message,
+    });
+    Ok(())
+  }
+
+  async fn next(&self, subject: &str) -> Result<Delivery, BrokerError> {
+    loop {
+      // Register interest before checking the queue so a concurrent publish can't be missed.
+      let notified = self.notify.notified();
+      {
+        let mut subjects = self.subjects.lock();
+        let queue = subjects.entry(subject.to_owned()).or_default();
+        if let Some(delivery) = queue.pending.pop_front() {
+          queue.in_flight.insert(delivery.id.clone(), delivery.clone());
+          return Ok(delivery);
+        }
+      }
+      notified.await;
+    }
+  }
+
+  async fn ack(&self, delivery: &Delivery) -> Result<(), BrokerError> {
+    self.take(delivery)?;
+    Ok(())
+  }
+
+  async fn nack(&self, delivery: &Delivery, requeue: bool) -> Result<(), BrokerError> {
+    let mut delivery = self.take(delivery)?;
+    if requeue {
+      delivery.redelivered = true;
+      self.enqueue(delivery);
+    } else {
+      self
+        .subjects
+        .lock()
+        .entry(delivery.subject)
+        .or_default()
+        .dead
+        .push(delivery.message);
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use anyhow::Result;
+
+  use super::*;
+
+  #[test_logger::test(tokio::test)]
+  async fn test_memory_ack_nack() -> Result<()> {
+    let broker = MemoryBroker::default();
+    broker.publish("jobs", Message::new("first")).await?;
+    broker.publish("jobs", Message::new("second")).await?;
+
+    let first = broker.next("jobs").await?;
+    assert_eq!(first.message.payload, "first");
+    broker.nack(&first, true).await?;
+
+    let second = broker.next("jobs").await?;
+    assert_eq!(second.message.payload, "second");
+    broker.ack(&second).await?;
+
+    let first = broker.next("jobs").await?;
+    assert!(first.redelivered);
+    broker.nack(&first, false).await?;
+
+    assert_eq!(broker.dead_letters("jobs"), vec![Message::new("first")]);
+    assert!(broker.ack(&first).await.is_err());
+
+    Ok(())
+  }
+}
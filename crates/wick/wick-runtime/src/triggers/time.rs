@@ -1,36 +1,43 @@
+mod state;
+
 use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use chrono::Utc;
-use config::{AppConfiguration, TimeTriggerConfig, TriggerDefinition};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use config::{
+  AppConfiguration,
+  CatchUpPolicy,
+  ConcurrencyPolicy,
+  ScheduleConfig,
+  TimeTriggerConfig,
+  TriggerDefinition,
+};
 use cron::Schedule;
 use parking_lot::Mutex;
 use serde_json::json;
 use structured_output::StructuredOutput;
+use tokio::sync::Semaphore;
 use tokio::time::Duration;
 use tokio_stream::StreamExt;
 use tracing::Span;
 use wick_packet::{Entity, InherentData, Packet};
 
+use self::state::{ScheduleState, StateStore};
 use super::{ComponentId, Trigger, TriggerKind};
 use crate::dev::prelude::*;
-use crate::resources::Resource;
+use crate::resources::{Resource, ResourceKind};
 use crate::Runtime;
 
 async fn invoke_operation(
   runtime: Runtime,
   target: Entity,
-  payload: Arc<Vec<config::OperationInputConfig>>,
+  packets: Vec<Packet>,
   span: &Span,
 ) -> Result<(), RuntimeError> {
-  let packets: Vec<_> = payload
-    .iter()
-    .map(|packet| Packet::encode(packet.name(), packet.value()))
-    .collect();
-
   let invocation = Invocation::new(
     Entity::server("schedule_client"),
     target,
@@ -40,69 +47,247 @@ async fn invoke_operation(
   );
 
   let mut response = runtime.invoke(invocation, Default::default()).await?;
+  let mut failure = None;
   while let Some(packet) = response.next().await {
     trace!(?packet, "trigger:time:response");
+    match packet {
+      Ok(p) if p.is_error() => failure = Some(p.unwrap_err().msg().to_owned()),
+      Err(e) => failure = Some(e.to_string()),
+      Ok(_) => {}
+    }
   }
-  Ok(())
+  failure.map_or(Ok(()), |e| Err(RuntimeError::ScheduledJob(e)))
 }
 
-async fn create_schedule(
+/// A random delay between zero and `max` milliseconds.
+fn jitter(max: u64) -> Duration {
+  if max == 0 {
+    return Duration::ZERO;
+  }
+  Duration::from_millis(Random::new().gen::<u64>() % (max + 1))
+}
+
+#[derive(Debug, Clone)]
+struct Job {
   runtime: Runtime,
-  schedule: Schedule,
-  config: TimeTriggerConfig,
-  component_id: String,
+  target: Entity,
+  dead_letter: Option<Entity>,
+  payload: Arc<Vec<config::OperationInputConfig>>,
+  schedule: ScheduleConfig,
+}
+
+impl Job {
+  /// Run the job, retrying failures with backoff and dead-lettering the last error once retries are exhausted.
+  async fn run(self, span: Span) {
+    let retries = self.schedule.retries();
+    let mut attempt: u16 = 0;
+    loop {
+      attempt += 1;
+      let packets = self
+        .payload
+        .iter()
+        .map(|packet| Packet::encode(packet.name(), packet.value()))
+        .collect();
+      let error = match invoke_operation(self.runtime.clone(), self.target.clone(), packets, &span).await {
+        Ok(()) => return,
+        Err(e) => e,
+      };
+
+      if attempt > retries {
+        span.in_scope(|| error!(%error, attempts = attempt, "scheduled job failed"));
+        self.dead_letter(error, attempt, &span).await;
+        return;
+      }
+
+      let delay = self.schedule.backoff().delay_for(attempt) + jitter(self.schedule.jitter());
+      span.in_scope(|| warn!(%error, attempt, ?delay, "scheduled job failed, retrying"));
+      tokio::time::sleep(delay).await;
+    }
+  }
+
+  async fn dead_letter(&self, error: RuntimeError, attempts: u16, span: &Span) {
+    let Some(target) = self.dead_letter.clone() else {
+      return;
+    };
+    let packets = vec![
+      Packet::encode("error", error.to_string()),
+      Packet::encode("attempts", attempts),
+      Packet::done("error"),
+      Packet::done("attempts"),
+    ];
+    if let Err(e) = invoke_operation(self.runtime.clone(), target, packets, span).await {
+      span.in_scope(|| error!(error = %e, "error invoking dead letter operation"));
+    }
+  }
+}
+
+/// Dispatches runs of a job according to its concurrency policy and keeps track of its progress.
+struct Scheduler {
+  job: Job,
+  policy: ConcurrencyPolicy,
+  // Skip and Queue both limit the job to a single run at a time.
+  permits: Arc<Semaphore>,
+  running: Vec<tokio::task::JoinHandle<()>>,
+  state: ScheduleState,
+  store: Option<StateStore>,
   span: Span,
-) -> Result<tokio::task::JoinHandle<()>, RuntimeError> {
-  // Create a scheduler loop
-  let handle = tokio::spawn(async move {
-    let operation = Arc::new(config.operation().name().to_owned());
-    let payload = Arc::new(config.payload().to_vec());
+}
 
-    let mut current_count: u16 = 0;
+impl Scheduler {
+  fn is_done(&self) -> bool {
+    let repeat = self.job.schedule.repeat();
+    repeat > 0 && self.state.count() >= repeat
+  }
 
-    let (failure_tx, mut failure_rx) = tokio::sync::mpsc::channel::<()>(1);
+  /// Start a run scheduled for the passed time and record it, unless the concurrency policy skips it.
+  async fn fire(&mut self, at: DateTime<Utc>) {
+    self.running.retain(|handle| !handle.is_finished());
 
-    loop {
-      if config.schedule().repeat() > 0 && current_count >= config.schedule().repeat() {
-        break;
+    let job_span = info_span!("trigger:schedule:job", target = ?self.job.target);
+    job_span.follows_from(&self.span);
+
+    let permit = match self.policy {
+      ConcurrencyPolicy::Skip => match self.permits.clone().try_acquire_owned() {
+        Ok(permit) => Some(permit),
+        Err(_) => {
+          job_span.in_scope(|| warn!("previous run still executing, skipping"));
+          return;
+        }
+      },
+      ConcurrencyPolicy::Allow | ConcurrencyPolicy::Queue => None,
+    };
+
+    // Skipped runs don't count towards `repeat`, so a run is only recorded once it's going to happen.
+    self.state.record(at);
+    if let Some(store) = &self.store {
+      if let Err(e) = store.save(&self.state).await {
+        self
+          .span
+          .in_scope(|| error!(error = %e, "could not save schedule state"));
       }
+    }
+    let permits = (self.policy == ConcurrencyPolicy::Queue).then(|| self.permits.clone());
+    let job = self.job.clone();
+
+    self.running.push(tokio::spawn(async move {
+      // Queued runs wait for the previous run to release its permit, in order.
+      let _permit = match permits {
+        Some(permits) => permits.acquire_owned().await.ok(),
+        None => permit,
+      };
+      job.run(job_span).await;
+    }));
+  }
 
-      if failure_rx.try_recv().is_ok() {
-        span.in_scope(|| error!("scheduler task failed to execute"));
-        break;
+  /// Start the runs that were due while the schedule wasn't running, according to the catch-up policy.
+  async fn catch_up(&mut self, schedule: &Schedule, timezone: Tz) {
+    let Some(last_run) = self.state.last_run() else {
+      return;
+    };
+    let now = Utc::now();
+    let mut missed = schedule
+      .after(&last_run.with_timezone(&timezone))
+      .map(|t| t.with_timezone(&Utc))
+      .take_while(|t| *t <= now);
+
+    match self.job.schedule.catch_up() {
+      CatchUpPolicy::None => {}
+      CatchUpPolicy::One => {
+        if missed.next().is_some() {
+          self.span.in_scope(|| info!("catching up on missed run"));
+          self.fire(now).await;
+        }
+      }
+      CatchUpPolicy::All => {
+        for at in missed {
+          if self.is_done() {
+            break;
+          }
+          self
+            .span
+            .in_scope(|| info!(scheduled = %at, "catching up on missed run"));
+          self.fire(at).await;
+        }
       }
+    }
+  }
 
-      current_count += 1;
+  async fn run(mut self, schedule: Schedule, timezone: Tz) {
+    self.catch_up(&schedule, timezone).await;
+
+    loop {
+      if self.is_done() {
+        break;
+      }
 
       // Calculate the next scheduled time based on the current time
-      let next = schedule.upcoming(Utc).next().unwrap();
+      let Some(next) = schedule.upcoming(timezone).next() else {
+        self.span.in_scope(|| info!("schedule has no upcoming runs"));
+        break;
+      };
+      let next = next.with_timezone(&Utc);
 
       // Calculate the duration until the next scheduled time
       let duration = next.signed_duration_since(Utc::now());
-      span.in_scope(|| debug!("duration until next schedule: {:?}", duration));
+      self
+        .span
+        .in_scope(|| debug!("duration until next schedule: {:?}", duration));
 
-      tokio::time::sleep(Duration::from_millis(duration.num_milliseconds() as u64)).await;
+      let duration = duration.to_std().unwrap_or_default() + jitter(self.job.schedule.jitter());
+      tokio::time::sleep(duration).await;
 
-      span.in_scope(|| debug!("done sleeping"));
+      self.span.in_scope(|| debug!("done sleeping"));
 
-      let target = Entity::operation(&component_id, &*operation);
-      let job_span = info_span!("trigger:schedule:job", target = ?target);
-      job_span.follows_from(&span);
-      let rt = runtime.clone();
-      let payload = payload.clone();
+      self.fire(next).await;
+    }
 
-      let fail_tx = failure_tx.clone();
+    for handle in self.running {
+      let _ = handle.await;
+    }
+  }
+}
 
-      tokio::spawn(async move {
-        let fut = invoke_operation(rt, target, payload, &job_span);
-        if let Err(e) = fut.await {
-          job_span.in_scope(|| error!("error invoking operation: {}", e));
-          let _ = fail_tx.send(()).await;
-        }
-      });
+async fn create_schedule(
+  runtime: Runtime,
+  schedule: Schedule,
+  config: TimeTriggerConfig,
+  store: Option<StateStore>,
+  span: Span,
+) -> Result<tokio::task::JoinHandle<()>, RuntimeError> {
+  let timezone = match config.schedule().timezone() {
+    Some(tz) => {
+      Tz::from_str(tz).map_err(|e| RuntimeError::ScheduleStartError(format!("Invalid timezone '{}': {}", tz, e)))?
     }
-  });
-  Ok(handle)
+    None => Tz::UTC,
+  };
+  let dead_letter = match config.schedule().dead_letter() {
+    Some(operation) => Some(Entity::operation(operation.component_id()?, operation.name())),
+    None => None,
+  };
+  let state = match &store {
+    Some(store) => store.load().await?,
+    None => ScheduleState::default(),
+  };
+
+  let job = Job {
+    runtime,
+    target: Entity::operation(config.operation().component_id()?, config.operation().name()),
+    dead_letter,
+    payload: Arc::new(config.payload().to_vec()),
+    schedule: config.schedule().clone(),
+  };
+  let scheduler = Scheduler {
+    policy: *job.schedule.concurrency(),
+    job,
+    permits: Arc::new(Semaphore::new(1)),
+    running: Vec::new(),
+    state,
+    store,
+    span,
+  };
+
+  // Create a scheduler loop
+  Ok(tokio::spawn(scheduler.run(schedule, timezone)))
 }
 
 #[derive(Debug)]
@@ -129,11 +314,11 @@ impl Time {
 impl Trigger for Time {
   async fn run(
     &self,
-    _name: String,
+    name: String,
     runtime: Runtime,
     _app_config: AppConfiguration,
     config: TriggerDefinition,
-    _resources: Arc<HashMap<String, Resource>>,
+    resources: Arc<HashMap<String, Resource>>,
     _span: Span,
   ) -> Result<StructuredOutput, RuntimeError> {
     let TriggerDefinition::Time(config) = config else {
@@ -154,10 +339,32 @@ impl Trigger for Time {
       }
     };
 
+    let store = match config.schedule().state() {
+      Some(resource_name) => {
+        let resource = resources
+          .get(resource_name)
+          .ok_or_else(|| RuntimeError::ResourceNotFound(TriggerKind::Time.into(), resource_name.clone()))?;
+        let Resource::Volume(path) = resource else {
+          return Err(RuntimeError::InvalidResourceType(
+            TriggerKind::Time.into(),
+            ResourceKind::Volume,
+            resource.kind(),
+          ));
+        };
+        let key = format!(
+          "{}-{}-{}",
+          name,
+          config.operation().component_id()?,
+          config.operation().name()
+        );
+        Some(StateStore::new(path, &key))
+      }
+      None => None,
+    };
+
     let span = info_span!("trigger:schedule", schedule = cron);
-    let component_id = config.operation().component_id()?.to_owned();
 
-    let scheduler_task = create_schedule(runtime, schedule, config, component_id, span).await?;
+    let scheduler_task = create_schedule(runtime, schedule, config, store, span).await?;
 
     self.handler.lock().replace(scheduler_task);
 
@@ -200,13 +407,277 @@ impl fmt::Display for Time {
 #[cfg(test)]
 mod test {
 
-  use std::path::Path;
+  use std::path::{Path, PathBuf};
+  use std::sync::atomic::{AtomicU16, Ordering};
 
   use anyhow::Result;
+  use flow_graph_interpreter::NamespaceHandler;
+  use futures::stream;
+  use wick_config::config::components::ComponentReference;
+  use wick_config::config::{
+    BackoffConfig,
+    BackoffConfigBuilder,
+    ComponentDefinition,
+    ComponentOperationExpression,
+    ScheduleConfigBuilder,
+    TimeTriggerConfigBuilder,
+  };
+  use wick_interface_types::component;
 
   use super::*;
-  use crate::build_trigger_runtime;
   use crate::test::load_example;
+  use crate::{build_trigger_runtime, RuntimeBuilder};
+
+  /// A component whose `run` operation fails until it has been called `failures` times and whose `dead_letter`
+  /// operation records what it was passed.
+  struct Flaky {
+    signature: ComponentSignature,
+    failures: u16,
+    delay: Duration,
+    calls: Arc<AtomicU16>,
+    dead_letters: Arc<Mutex<Vec<(String, u16)>>>,
+  }
+
+  impl Component for Flaky {
+    fn handle(
+      &self,
+      invocation: Invocation,
+      _config: Option<RuntimeConfig>,
+      _callback: Arc<RuntimeCallback>,
+    ) -> flow_component::BoxFuture<Result<PacketStream, flow_component::ComponentError>> {
+      let failures = self.failures;
+      let delay = self.delay;
+      let calls = self.calls.clone();
+      let dead_letters = self.dead_letters.clone();
+      Box::pin(async move {
+        let operation = invocation.target().operation_id().to_owned();
+        let packets: Vec<_> = invocation.into_stream().collect().await;
+        let output = if operation == "dead_letter" {
+          let mut record: (String, u16) = Default::default();
+          for packet in packets.into_iter().flatten().filter(|p| p.has_data()) {
+            match packet.port() {
+              "error" => record.0 = packet.decode()?,
+              "attempts" => record.1 = packet.decode()?,
+              _ => {}
+            }
+          }
+          dead_letters.lock().push(record);
+          Packet::done("output")
+        } else {
+          tokio::time::sleep(delay).await;
+          if calls.fetch_add(1, Ordering::SeqCst) < failures {
+            Packet::err("output", "transient failure")
+          } else {
+            Packet::done("output")
+          }
+        };
+        Ok(PacketStream::new(Box::new(stream::iter(vec![Ok(output)]))))
+      })
+    }
+
+    fn signature(&self) -> &ComponentSignature {
+      &self.signature
+    }
+  }
+
+  async fn run_schedule(
+    schedule: ScheduleConfig,
+    resources: Arc<HashMap<String, Resource>>,
+    failures: u16,
+    delay: Duration,
+  ) -> Result<Flaky> {
+    let flaky = Flaky {
+      signature: component! {
+        name: "flaky",
+        version: Some("0.0.1"),
+        operations: {
+          "run" => {
+            inputs: {},
+            outputs: { "output" => "object" },
+          },
+          "dead_letter" => {
+            inputs: { "error" => "string", "attempts" => "u16" },
+            outputs: { "output" => "object" },
+          },
+        }
+      },
+      failures,
+      delay,
+      calls: Default::default(),
+      dead_letters: Default::default(),
+    };
+    let (calls, dead_letters) = (flaky.calls.clone(), flaky.dead_letters.clone());
+    let signature = flaky.signature.clone();
+
+    let mut builder = RuntimeBuilder::from_definition(Default::default());
+    builder.add_native_component(Box::new(move |_| {
+      Ok(NamespaceHandler::new(
+        "flaky",
+        Box::new(Flaky {
+          signature: signature.clone(),
+          failures,
+          delay,
+          calls: calls.clone(),
+          dead_letters: dead_letters.clone(),
+        }),
+      ))
+    }));
+    let rt = builder.build(None).await?;
+
+    let config = TimeTriggerConfigBuilder::default()
+      .schedule(schedule)
+      .operation(ComponentOperationExpression::new_default(
+        "run",
+        ComponentDefinition::Reference(ComponentReference::new("flaky")),
+      ))
+      .build()?;
+
+    let trigger = Time::new();
+    trigger
+      .run(
+        "test".to_owned(),
+        rt,
+        AppConfiguration::default(),
+        TriggerDefinition::Time(config),
+        resources,
+        Span::current(),
+      )
+      .await?;
+    tokio::time::timeout(Duration::from_secs(30), trigger.wait_for_done()).await?;
+    Ok(flaky)
+  }
+
+  fn fast_backoff() -> BackoffConfig {
+    BackoffConfigBuilder::default().delay(10_u64).build().unwrap()
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_retry_until_success() -> Result<()> {
+    let schedule = ScheduleConfigBuilder::default()
+      .cron("* * * * * *")
+      .repeat(1_u16)
+      .retries(3_u16)
+      .backoff(fast_backoff())
+      .dead_letter(Some(ComponentOperationExpression::new_default(
+        "dead_letter",
+        ComponentDefinition::Reference(ComponentReference::new("flaky")),
+      )))
+      .build()?;
+    let flaky = run_schedule(schedule, Default::default(), 2, Duration::ZERO).await?;
+
+    assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+    assert!(flaky.dead_letters.lock().is_empty());
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_dead_letter_after_retries() -> Result<()> {
+    let schedule = ScheduleConfigBuilder::default()
+      .cron("* * * * * *")
+      .repeat(2_u16)
+      .retries(1_u16)
+      .backoff(fast_backoff())
+      .dead_letter(Some(ComponentOperationExpression::new_default(
+        "dead_letter",
+        ComponentDefinition::Reference(ComponentReference::new("flaky")),
+      )))
+      .build()?;
+    let flaky = run_schedule(schedule, Default::default(), u16::MAX, Duration::ZERO).await?;
+
+    // A failed job no longer stops the schedule, so both runs execute.
+    assert_eq!(flaky.calls.load(Ordering::SeqCst), 4);
+    let dead_letters = flaky.dead_letters.lock().clone();
+    assert_eq!(dead_letters.len(), 2);
+    assert!(dead_letters[0].0.contains("transient failure"));
+    assert_eq!(dead_letters[0].1, 2);
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_skip_overlapping_runs() -> Result<()> {
+    let schedule = ScheduleConfigBuilder::default()
+      .cron("* * * * * *")
+      .repeat(3_u16)
+      .concurrency(ConcurrencyPolicy::Skip)
+      .build()?;
+    let start = std::time::Instant::now();
+    let flaky = run_schedule(schedule, Default::default(), 0, Duration::from_millis(1500)).await?;
+
+    // Each run outlasts the next tick, so every other tick is skipped and the skipped ticks don't count as runs.
+    assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+    assert!(start.elapsed() >= Duration::from_secs(5));
+    Ok(())
+  }
+
+  fn state_dir(name: &str) -> Result<(PathBuf, Arc<HashMap<String, Resource>>)> {
+    let dir = std::env::temp_dir().join(format!("wick-time-state-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+    let resources = Arc::new(HashMap::from([("STATE".to_owned(), Resource::Volume(dir.clone()))]));
+    Ok((dir, resources))
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_persisted_repeat() -> Result<()> {
+    let (dir, resources) = state_dir("repeat")?;
+    let schedule = ScheduleConfigBuilder::default()
+      .cron("* * * * * *")
+      .repeat(1_u16)
+      .state(Some("STATE".to_owned()))
+      .build()?;
+
+    let flaky = run_schedule(schedule.clone(), resources.clone(), 0, Duration::ZERO).await?;
+    assert_eq!(flaky.calls.load(Ordering::SeqCst), 1);
+    let state = StateStore::new(&dir, "test-flaky-run").load().await?;
+    assert_eq!(state.count(), 1);
+    assert!(state.last_run().is_some());
+
+    // A restarted schedule remembers that it has already run.
+    let flaky = run_schedule(schedule, resources, 0, Duration::ZERO).await?;
+    assert_eq!(flaky.calls.load(Ordering::SeqCst), 0);
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_catch_up() -> Result<()> {
+    for (policy, repeat, expected) in [(CatchUpPolicy::One, 2_u16, 1), (CatchUpPolicy::All, 4_u16, 3)] {
+      let (dir, resources) = state_dir(&policy.to_string())?;
+      // The last hourly run happened three hours ago, so three runs were missed.
+      let mut state = ScheduleState::default();
+      state.record(Utc::now() - chrono::Duration::hours(3));
+      StateStore::new(&dir, "test-flaky-run").save(&state).await?;
+
+      let schedule = ScheduleConfigBuilder::default()
+        .cron("0 0 * * * *")
+        .repeat(repeat)
+        .state(Some("STATE".to_owned()))
+        .catch_up(policy)
+        .build()?;
+      let flaky = run_schedule(schedule, resources, 0, Duration::ZERO).await?;
+      assert_eq!(
+        flaky.calls.load(Ordering::SeqCst),
+        expected,
+        "catch-up policy {}",
+        policy
+      );
+
+      std::fs::remove_dir_all(&dir)?;
+    }
+    Ok(())
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_invalid_timezone() -> Result<()> {
+    let schedule = ScheduleConfigBuilder::default()
+      .cron("* * * * * *")
+      .timezone(Some("Mars/Olympus_Mons".to_owned()))
+      .build()?;
+    let result = run_schedule(schedule, Default::default(), 0, Duration::ZERO).await;
+    assert!(result.is_err());
+    Ok(())
+  }
 
   fn remove_test_file(test_file: &Path) {
     match std::fs::remove_file(test_file) {
@@ -0,0 +1,72 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::dev::prelude::*;
+
+/// The progress of a schedule that survives restarts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub(super) struct ScheduleState {
+  /// When the last run was scheduled for, in milliseconds since the Unix epoch.
+  last_run: Option<i64>,
+  /// The number of runs started so far.
+  count: u16,
+}
+
+impl ScheduleState {
+  pub(super) fn last_run(&self) -> Option<DateTime<Utc>> {
+    self.last_run.and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+  }
+
+  pub(super) const fn count(&self) -> u16 {
+    self.count
+  }
+
+  /// Record a run scheduled for the passed time.
+  pub(super) fn record(&mut self, at: DateTime<Utc>) {
+    self.last_run = Some(at.timestamp_millis());
+    self.count = self.count.saturating_add(1);
+  }
+}
+
+/// A JSON file in a volume that a schedule's state is persisted to.
+#[derive(Debug, Clone)]
+pub(super) struct StateStore {
+  path: PathBuf,
+}
+
+impl StateStore {
+  /// Create a store in the passed directory, named after the passed key.
+  pub(super) fn new(dir: &Path, key: &str) -> Self {
+    let name: String = key
+      .chars()
+      .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+      .collect();
+    Self {
+      path: dir.join(format!("{}.schedule.json", name)),
+    }
+  }
+
+  pub(super) async fn load(&self) -> Result<ScheduleState, RuntimeError> {
+    let bytes = match tokio::fs::read(&self.path).await {
+      Ok(bytes) => bytes,
+      Err(e) if e.kind() == ErrorKind::NotFound => return Ok(ScheduleState::default()),
+      Err(e) => return Err(self.error(e)),
+    };
+    serde_json::from_slice(&bytes).map_err(|e| self.error(e))
+  }
+
+  pub(super) async fn save(&self, state: &ScheduleState) -> Result<(), RuntimeError> {
+    let bytes = serde_json::to_vec(state).map_err(|e| self.error(e))?;
+    // Write to a temporary file first so a crash can't leave a partial state behind.
+    let tmp = self.path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, bytes).await.map_err(|e| self.error(e))?;
+    tokio::fs::rename(&tmp, &self.path).await.map_err(|e| self.error(e))
+  }
+
+  fn error(&self, e: impl std::fmt::Display) -> RuntimeError {
+    RuntimeError::ScheduleState(format!("{}: {}", self.path.display(), e))
+  }
+}
@@ -0,0 +1,528 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use config::{AppConfiguration, TriggerDefinition};
+use glob::{MatchOptions, Pattern};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+use serde_json::json;
+use structured_output::StructuredOutput;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::task::JoinHandle;
+use tokio_stream::StreamExt;
+use tracing::{Instrument, Span};
+use wick_config::config::{Glob, WatchTriggerConfig};
+use wick_packet::{packets, Base64Bytes, Entity, InherentData, Invocation};
+
+use super::{ComponentId, Trigger, TriggerKind};
+use crate::dev::prelude::*;
+use crate::resources::{Resource, ResourceKind};
+use crate::Runtime;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+  case_sensitive: true,
+  require_literal_separator: true,
+  require_literal_leading_dot: false,
+};
+
+/// How long a file has to go without changing before its changes are reported, so files that are still being written
+/// aren't read part way through.
+const QUIET_PERIOD: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileEvent {
+  Created,
+  Modified,
+  Removed,
+}
+
+impl fmt::Display for FileEvent {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      FileEvent::Created => f.write_str("created"),
+      FileEvent::Modified => f.write_str("modified"),
+      FileEvent::Removed => f.write_str("removed"),
+    }
+  }
+}
+
+/// Translate a raw filesystem notification into the file events we report.
+fn classify(event: notify::Event) -> Vec<(PathBuf, FileEvent)> {
+  let kind = match event.kind {
+    EventKind::Create(_) => FileEvent::Created,
+    EventKind::Remove(_) => FileEvent::Removed,
+    EventKind::Modify(ModifyKind::Metadata(_)) => return Vec::new(),
+    EventKind::Modify(ModifyKind::Name(RenameMode::From)) => FileEvent::Removed,
+    EventKind::Modify(ModifyKind::Name(RenameMode::To)) => FileEvent::Created,
+    EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+      let mut paths = event.paths.into_iter();
+      return paths
+        .next()
+        .map(|from| (from, FileEvent::Removed))
+        .into_iter()
+        .chain(paths.map(|to| (to, FileEvent::Created)))
+        .collect();
+    }
+    EventKind::Modify(ModifyKind::Name(_)) => {
+      return event
+        .paths
+        .into_iter()
+        .map(|path| {
+          let kind = if path.exists() {
+            FileEvent::Created
+          } else {
+            FileEvent::Removed
+          };
+          (path, kind)
+        })
+        .collect();
+    }
+    EventKind::Modify(_) => FileEvent::Modified,
+    _ => return Vec::new(),
+  };
+  event.paths.into_iter().map(|path| (path, kind)).collect()
+}
+
+#[derive(Debug)]
+struct PathFilter {
+  include: Vec<Pattern>,
+  exclude: Vec<Pattern>,
+}
+
+impl PathFilter {
+  fn new(config: &WatchTriggerConfig) -> Result<Self, RuntimeError> {
+    let compile = |globs: &[Glob]| {
+      globs
+        .iter()
+        .map(|g| {
+          Pattern::new(g.glob()).map_err(|e| {
+            RuntimeError::InvalidConfig(TriggerKind::Watch.into(), format!("invalid glob '{}': {}", g.glob(), e))
+          })
+        })
+        .collect::<Result<Vec<_>, _>>()
+    };
+    Ok(Self {
+      include: compile(config.include())?,
+      exclude: compile(config.exclude())?,
+    })
+  }
+
+  fn matches(&self, path: &Path) -> bool {
+    (self.include.is_empty() || self.include.iter().any(|p| p.matches_path_with(path, MATCH_OPTIONS)))
+      && !self.exclude.iter().any(|p| p.matches_path_with(path, MATCH_OPTIONS))
+  }
+}
+
+async fn invoke_operation(
+  runtime: &Runtime,
+  target: Entity,
+  path: &Path,
+  event: FileEvent,
+  include_contents: bool,
+  contents: Option<Base64Bytes>,
+) -> Result<(), RuntimeError> {
+  let mut packets = packets!(("path", path.to_string_lossy()), ("event", event.to_string()));
+  if include_contents {
+    packets.extend(packets!(("contents", contents)));
+  }
+
+  let invocation = Invocation::new(
+    Entity::server("watch_client"),
+    target,
+    packets,
+    InherentData::unsafe_default(),
+    &Span::current(),
+  );
+
+  let mut response = runtime.invoke(invocation, Default::default()).await?;
+  while let Some(packet) = response.next().await {
+    trace!(?packet, "trigger:watch:response");
+    match packet {
+      Ok(p) if p.is_error() => warn!(error = %p.unwrap_err().msg(), "trigger:watch:operation error"),
+      Err(e) => warn!(error = %e, "trigger:watch:stream error"),
+      Ok(_) => {}
+    }
+  }
+  Ok(())
+}
+
+/// Combine a pending event for a path with a newer one. Returns `None` when the two cancel out.
+const fn coalesce(pending: FileEvent, next: FileEvent) -> Option<FileEvent> {
+  match (pending, next) {
+    (FileEvent::Created, FileEvent::Modified) => Some(FileEvent::Created),
+    // The operation never saw the file, so there's nothing to report.
+    (FileEvent::Created, FileEvent::Removed) => None,
+    (FileEvent::Removed, FileEvent::Created) => Some(FileEvent::Modified),
+    (_, next) => Some(next),
+  }
+}
+
+/// Events waiting for their paths to go quiet, in the order the paths first changed.
+#[derive(Debug, Default)]
+struct Pending {
+  events: Vec<(PathBuf, FileEvent, Instant)>,
+}
+
+impl Pending {
+  fn push(&mut self, path: PathBuf, kind: FileEvent) {
+    let now = Instant::now();
+    let Some(index) = self.events.iter().position(|(p, _, _)| *p == path) else {
+      self.events.push((path, kind, now));
+      return;
+    };
+    match coalesce(self.events[index].1, kind) {
+      Some(kind) => {
+        self.events[index].1 = kind;
+        self.events[index].2 = now;
+      }
+      None => {
+        self.events.remove(index);
+      }
+    }
+  }
+
+  /// When the next path goes quiet, if any are pending.
+  fn deadline(&self) -> Option<Instant> {
+    self.events.iter().map(|(_, _, at)| *at + QUIET_PERIOD).min()
+  }
+
+  /// Take the events whose paths have been quiet for [QUIET_PERIOD].
+  fn take_quiet(&mut self) -> Vec<(PathBuf, FileEvent)> {
+    let now = Instant::now();
+    let (quiet, waiting) = std::mem::take(&mut self.events)
+      .into_iter()
+      .partition(|(_, _, at)| now.duration_since(*at) >= QUIET_PERIOD);
+    self.events = waiting;
+    quiet.into_iter().map(|(path, kind, _)| (path, kind)).collect()
+  }
+}
+
+async fn dispatch(
+  runtime: Runtime,
+  root: PathBuf,
+  filter: PathFilter,
+  target: Entity,
+  include_contents: bool,
+  mut events: UnboundedReceiver<notify::Result<notify::Event>>,
+  mut shutdown_rx: tokio::sync::oneshot::Receiver<()>,
+) {
+  let mut pending = Pending::default();
+  loop {
+    let deadline = pending.deadline();
+    let event = tokio::select! {
+      _ = &mut shutdown_rx => break,
+      () = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now).into()), if deadline.is_some() => None,
+      event = events.recv() => match event {
+        Some(Ok(event)) => Some(event),
+        Some(Err(e)) => {
+          warn!(error = %e, "trigger:watch:watcher error");
+          continue;
+        }
+        None => break,
+      },
+    };
+
+    if let Some(event) = event {
+      for (path, kind) in classify(event) {
+        let Ok(relative) = path.strip_prefix(&root) else {
+          continue;
+        };
+        if !filter.matches(relative) || (kind != FileEvent::Removed && path.is_dir()) {
+          continue;
+        }
+        pending.push(path, kind);
+      }
+      continue;
+    }
+
+    for (path, kind) in pending.take_quiet() {
+      let Ok(relative) = path.strip_prefix(&root) else {
+        continue;
+      };
+      let contents = if include_contents && kind != FileEvent::Removed {
+        tokio::fs::read(&path).await.ok().map(Base64Bytes::new)
+      } else {
+        None
+      };
+
+      let span = info_span!("trigger:watch:event", path = %relative.display(), event = %kind);
+      if let Err(e) = invoke_operation(&runtime, target.clone(), relative, kind, include_contents, contents)
+        .instrument(span.clone())
+        .await
+      {
+        span.in_scope(|| error!(error = %e, "error invoking operation"));
+      }
+    }
+  }
+}
+
+struct WatchInstance {
+  handle: JoinHandle<()>,
+  shutdown_tx: tokio::sync::oneshot::Sender<()>,
+  // Dropping the watcher stops filesystem notifications.
+  _watcher: RecommendedWatcher,
+}
+
+#[derive(Default)]
+pub(crate) struct Watch {
+  instance: Arc<Mutex<Option<WatchInstance>>>,
+}
+
+impl Watch {
+  pub(crate) fn load() -> Result<Arc<dyn Trigger + Send + Sync>, RuntimeError> {
+    Ok(Arc::new(Self::default()))
+  }
+}
+
+#[async_trait]
+impl Trigger for Watch {
+  async fn run(
+    &self,
+    _name: String,
+    runtime: Runtime,
+    _app_config: AppConfiguration,
+    config: TriggerDefinition,
+    resources: Arc<HashMap<String, Resource>>,
+    span: Span,
+  ) -> Result<StructuredOutput, RuntimeError> {
+    span.in_scope(|| debug!(kind = %TriggerKind::Watch, "trigger:run"));
+    let TriggerDefinition::Watch(config) = config else {
+      return Err(RuntimeError::TriggerKind(Context::Trigger, TriggerKind::Watch));
+    };
+
+    let resource_name = config.resource();
+    let resource = resources
+      .get(resource_name)
+      .ok_or_else(|| RuntimeError::ResourceNotFound(TriggerKind::Watch.into(), resource_name.to_owned()))?;
+    let Resource::Volume(path) = resource else {
+      return Err(RuntimeError::InvalidResourceType(
+        TriggerKind::Watch.into(),
+        ResourceKind::Volume,
+        resource.kind(),
+      ));
+    };
+
+    // Watch the canonical path so event paths can be made relative to it.
+    let root = path
+      .canonicalize()
+      .map_err(|e| RuntimeError::InitializationFailed(format!("could not watch volume {}: {}", path.display(), e)))?;
+    let filter = PathFilter::new(&config)?;
+    let target = Entity::operation(config.operation().component_id()?, config.operation().name());
+
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+      let _ = tx.send(event);
+    })
+    .map_err(|e| RuntimeError::InitializationFailed(format!("could not create file watcher: {}", e)))?;
+    watcher
+      .watch(&root, RecursiveMode::Recursive)
+      .map_err(|e| RuntimeError::InitializationFailed(format!("could not watch {}: {}", root.display(), e)))?;
+
+    let span = info_span!(parent: &span, "trigger:watch", path = %root.display());
+    span.in_scope(|| info!(%target, "watch trigger started"));
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+    let handle = tokio::spawn(
+      dispatch(
+        runtime,
+        root.clone(),
+        filter,
+        target,
+        config.contents(),
+        rx,
+        shutdown_rx,
+      )
+      .instrument(span),
+    );
+
+    self.instance.lock().replace(WatchInstance {
+      handle,
+      shutdown_tx,
+      _watcher: watcher,
+    });
+
+    Ok(StructuredOutput::new(
+      format!("Watching {} for changes", root.display()),
+      json!({"path": root}),
+    ))
+  }
+
+  async fn shutdown_gracefully(self) -> Result<(), RuntimeError> {
+    let Some(instance) = self.instance.lock().take() else {
+      return Ok(());
+    };
+    let _ = instance.shutdown_tx.send(());
+    instance
+      .handle
+      .await
+      .map_err(|e| RuntimeError::ShutdownFailed(TriggerKind::Watch.into(), e.to_string()))
+  }
+
+  async fn wait_for_done(&self) -> StructuredOutput {
+    let Some(instance) = self.instance.lock().take() else {
+      return StructuredOutput::new("watch trigger never ran", json!({"status": "watch trigger never ran"}));
+    };
+    match instance.handle.await {
+      Ok(_) => StructuredOutput::new("watch trigger complete", json!({"status": "watch trigger complete"})),
+      Err(e) => {
+        let message = format!("watch trigger error: {}", e);
+        StructuredOutput::new(message.clone(), json!({ "error": message }))
+      }
+    }
+  }
+}
+
+impl fmt::Display for Watch {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Watch Trigger")
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::io::Write;
+
+  use anyhow::Result;
+  use flow_graph_interpreter::NamespaceHandler;
+  use futures::stream;
+  use wick_config::config::components::ComponentReference;
+  use wick_config::config::{ComponentDefinition, ComponentOperationExpression, WatchTriggerConfigBuilder};
+  use wick_interface_types::component;
+  use wick_packet::Packet;
+
+  use super::*;
+  use crate::RuntimeBuilder;
+
+  type Recorded = (String, String, Option<Vec<u8>>);
+
+  struct Recorder {
+    signature: ComponentSignature,
+    tx: tokio::sync::mpsc::UnboundedSender<Recorded>,
+  }
+
+  impl Component for Recorder {
+    fn handle(
+      &self,
+      invocation: Invocation,
+      _config: Option<RuntimeConfig>,
+      _callback: Arc<RuntimeCallback>,
+    ) -> flow_component::BoxFuture<Result<PacketStream, flow_component::ComponentError>> {
+      let tx = self.tx.clone();
+      Box::pin(async move {
+        let packets: Vec<_> = invocation.into_stream().collect().await;
+        let mut record: Recorded = Default::default();
+        for packet in packets.into_iter().flatten().filter(|p| p.has_data()) {
+          match packet.port() {
+            "path" => record.0 = packet.decode()?,
+            "event" => record.1 = packet.decode()?,
+            "contents" => record.2 = packet.decode::<Option<Base64Bytes>>()?.map(|b| b.to_vec()),
+            _ => {}
+          }
+        }
+        let _ = tx.send(record);
+        Ok(PacketStream::new(Box::new(stream::iter(vec![Ok(Packet::done(
+          "output",
+        ))]))))
+      })
+    }
+
+    fn signature(&self) -> &ComponentSignature {
+      &self.signature
+    }
+  }
+
+  async fn start_watch(dir: &Path, include: &[&str], exclude: &[&str]) -> Result<(Watch, UnboundedReceiver<Recorded>)> {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut builder = RuntimeBuilder::from_definition(Default::default());
+    builder.add_native_component(Box::new(move |_| {
+      let signature = component! {
+        name: "recorder",
+        version: Some("0.0.1"),
+        operations: {
+          "record" => {
+            inputs: { "path" => "string", "event" => "string", "contents" => "bytes?" },
+            outputs: { "output" => "object" },
+          },
+        }
+      };
+      Ok(NamespaceHandler::new(
+        "recorder",
+        Box::new(Recorder {
+          signature,
+          tx: tx.clone(),
+        }),
+      ))
+    }));
+    let rt = builder.build(None).await?;
+
+    let config = WatchTriggerConfigBuilder::default()
+      .resource("DIR")
+      .operation(ComponentOperationExpression::new_default(
+        "record",
+        ComponentDefinition::Reference(ComponentReference::new("recorder")),
+      ))
+      .include(include.iter().map(|g| Glob::new(*g)).collect::<Vec<_>>())
+      .exclude(exclude.iter().map(|g| Glob::new(*g)).collect::<Vec<_>>())
+      .contents(true)
+      .build()?;
+    let resources = Arc::new(HashMap::from([("DIR".to_owned(), Resource::Volume(dir.to_path_buf()))]));
+
+    let trigger = Watch::default();
+    trigger
+      .run(
+        "test".to_owned(),
+        rt,
+        AppConfiguration::default(),
+        TriggerDefinition::Watch(config),
+        resources,
+        Span::current(),
+      )
+      .await?;
+    Ok((trigger, rx))
+  }
+
+  async fn next_event(rx: &mut UnboundedReceiver<Recorded>) -> Result<Recorded> {
+    tokio::time::timeout(Duration::from_secs(10), rx.recv())
+      .await?
+      .ok_or_else(|| anyhow::anyhow!("recorder closed"))
+  }
+
+  #[test_logger::test(tokio::test)]
+  async fn test_watch_events() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("wick-watch-trigger-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    let (trigger, mut rx) = start_watch(&dir, &["*.csv"], &["ignored*"]).await?;
+
+    std::fs::write(dir.join("ignored.csv"), "skip")?;
+    std::fs::write(dir.join("notes.txt"), "skip")?;
+    // A file written in pieces is only reported once it's finished.
+    std::fs::write(dir.join("data.csv"), "a,")?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    std::fs::OpenOptions::new()
+      .append(true)
+      .open(dir.join("data.csv"))?
+      .write_all(b"b")?;
+
+    let (path, event, contents) = next_event(&mut rx).await?;
+    assert_eq!(path, "data.csv");
+    assert_eq!(event, "created");
+    assert_eq!(contents, Some(b"a,b".to_vec()));
+
+    std::fs::remove_file(dir.join("data.csv"))?;
+    let (path, event, contents) = next_event(&mut rx).await?;
+    assert_eq!(path, "data.csv");
+    assert_eq!(event, "removed");
+    assert_eq!(contents, None);
+
+    trigger.shutdown_gracefully().await?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+  }
+}
@@ -0,0 +1,40 @@
+console.log('wick static router line 0');
+console.log('wick static router line 1');
+console.log('wick static router line 2');
+console.log('wick static router line 3');
+console.log('wick static router line 4');
+console.log('wick static router line 5');
+console.log('wick static router line 6');
+console.log('wick static router line 7');
+console.log('wick static router line 8');
+console.log('wick static router line 9');
+console.log('wick static router line 10');
+console.log('wick static router line 11');
+console.log('wick static router line 12');
+console.log('wick static router line 13');
+console.log('wick static router line 14');
+console.log('wick static router line 15');
+console.log('wick static router line 16');
+console.log('wick static router line 17');
+console.log('wick static router line 18');
+console.log('wick static router line 19');
+console.log('wick static router line 20');
+console.log('wick static router line 21');
+console.log('wick static router line 22');
+console.log('wick static router line 23');
+console.log('wick static router line 24');
+console.log('wick static router line 25');
+console.log('wick static router line 26');
+console.log('wick static router line 27');
+console.log('wick static router line 28');
+console.log('wick static router line 29');
+console.log('wick static router line 30');
+console.log('wick static router line 31');
+console.log('wick static router line 32');
+console.log('wick static router line 33');
+console.log('wick static router line 34');
+console.log('wick static router line 35');
+console.log('wick static router line 36');
+console.log('wick static router line 37');
+console.log('wick static router line 38');
+console.log('wick static router line 39');
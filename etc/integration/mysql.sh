@@ -0,0 +1,45 @@
+#!/bin/bash
+
+export DOCKER_HOST=${DOCKER_HOST}
+
+container_name="wick_test_$(basename "${BASH_SOURCE[0]}")"
+
+username=root
+db=wick_test
+pw="${TEST_PASSWORD}"
+
+# Source utils
+script_dir="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+source $script_dir/utils.sh
+
+up() {
+  run_docker $container_name mysql:8 -p ${MYSQL_PORT}:3306 -e MYSQL_ROOT_PASSWORD="$pw"
+  local num_tries=10
+  local sleep_time=5
+  local i=0
+  while [ $i -lt $num_tries ]; do
+    echo "Waiting for database to be ready..."
+    sleep $sleep_time
+    docker exec -i $container_name mysql -u $username -p"$pw" -h 127.0.0.1 -e "SELECT 1" > /dev/null && break
+    i=$((i+1))
+  done
+}
+
+down() {
+  cleanup $container_name
+}
+
+init() {
+  echo "Dropping test db '$db'"
+  docker exec -i $container_name mysql -u $username -p"$pw" -h 127.0.0.1 -e "DROP DATABASE IF EXISTS $db;"
+  echo "Initializing database '$db'"
+  docker exec -i $container_name mysql -u $username -p"$pw" -h 127.0.0.1 -e "CREATE DATABASE $db;"
+  echo "Creating table 'users'"
+  docker exec -i $container_name mysql -u $username -p"$pw" -h 127.0.0.1 -D $db -e "CREATE TABLE users (id INT AUTO_INCREMENT PRIMARY KEY, name VARCHAR(255) NOT NULL, email VARCHAR(255) NOT NULL);"
+  echo "Creating user 'Test User'"
+  docker exec -i $container_name mysql -u $username -p"$pw" -h 127.0.0.1 -D $db -e "INSERT INTO users (name, email) VALUES ('Test User', 'test_users@example.com');"
+  echo "Creating table 'num_types'"
+  docker exec -i $container_name mysql -u $username -p"$pw" -h 127.0.0.1 -D $db -e "CREATE TABLE num_types (id INT AUTO_INCREMENT PRIMARY KEY, u8 TINYINT UNSIGNED, i16 SMALLINT, i32 INT, i64 BIGINT, db_decimal DECIMAL(19,4), f32 FLOAT, f64 DOUBLE);"
+}
+
+handle "$@"
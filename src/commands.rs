@@ -1,4 +1,5 @@
 pub(crate) mod config;
+pub(crate) mod db;
 pub(crate) mod install;
 pub(crate) mod invoke;
 pub(crate) mod key;
@@ -71,6 +72,10 @@ pub(crate) enum CliCommand {
   #[clap(subcommand, name = "config")]
   Config(config::SubCommands),
 
+  /// Commands to manage the databases behind SQL components.
+  #[clap(subcommand, name = "db")]
+  Db(db::SubCommands),
+
   /// Commands for WebAssembly component.
   #[clap(subcommand, name = "wasm")]
   Wasm(wasm::SubCommands),
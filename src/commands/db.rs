@@ -0,0 +1,48 @@
+use anyhow::{bail, Result};
+use clap::Subcommand;
+use wick_config::config::components::SqlComponentConfig;
+use wick_config::config::ComponentImplementation;
+use wick_config::Resolver;
+
+use crate::utils::{parse_config_string, reconcile_fetch_options};
+
+pub(crate) mod migrate;
+pub(crate) mod status;
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum SubCommands {
+  /// Apply pending migrations for a SQL component.
+  #[clap(name = "migrate")]
+  Migrate(migrate::Options),
+
+  /// Show the state of a SQL component's migrations.
+  #[clap(name = "status")]
+  Status(status::Options),
+}
+
+async fn fetch_sql_component(
+  oci: crate::options::oci::OciOptions,
+  component: &crate::options::component::ComponentOptions,
+  settings: &wick_settings::Settings,
+  span: &tracing::Span,
+) -> Result<(SqlComponentConfig, Box<Resolver>)> {
+  let runtime_config = parse_config_string(component.with.as_deref())?;
+  let fetch_opts = reconcile_fetch_options(&component.path, settings, oci, None);
+  let config = crate::utils::fetch_wick_config(&component.path, fetch_opts, runtime_config, span.clone())
+    .await?
+    .try_component_config()?;
+
+  let ComponentImplementation::Sql(sql) = config.component() else {
+    bail!("{} is not a SQL component", component.path);
+  };
+
+  Ok((sql.clone(), config.resolver()))
+}
+
+fn report(statuses: &[wick_sql::MigrationStatus]) -> String {
+  statuses
+    .iter()
+    .map(|s| format!("{:<8} {} {}", s.state, s.version, s.name))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
@@ -0,0 +1,29 @@
+use anyhow::Result;
+use clap::Args;
+use serde_json::json;
+use structured_output::StructuredOutput;
+
+#[derive(Debug, Clone, Args)]
+#[clap(rename_all = "kebab-case")]
+#[group(skip)]
+pub(crate) struct Options {
+  #[clap(flatten)]
+  pub(crate) oci: crate::options::oci::OciOptions,
+
+  #[clap(flatten)]
+  pub(crate) component: crate::options::component::ComponentOptions,
+}
+
+pub(crate) async fn handle(
+  opts: Options,
+  settings: wick_settings::Settings,
+  span: tracing::Span,
+) -> Result<StructuredOutput> {
+  let (config, resolver) = super::fetch_sql_component(opts.oci, &opts.component, &settings, &span).await?;
+  let statuses = wick_sql::migrate(config, &resolver).await?;
+
+  Ok(StructuredOutput::new(
+    super::report(&statuses),
+    json!({"migrations": statuses}),
+  ))
+}
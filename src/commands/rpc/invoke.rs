@@ -120,7 +120,7 @@ pub(crate) async fn handle(
 
     let invocation = Invocation::new(origin, target, stream, inherent_data, &span);
     span.in_scope(|| trace!("issuing invocation"));
-    let stream = client.invoke(invocation).await?;
+    let stream = client.invoke(invocation, None).await?;
     span.in_scope(|| trace!("server responsed"));
     utils::print_stream_json(stream, &opts.filter, opts.short, opts.raw).await?;
   }
@@ -246,6 +246,10 @@ async fn async_main(span: Span, cli: Cli, settings: wick_settings::Settings) ->
       commands::wasm::SubCommands::Sign(cmd) => commands::wasm::sign::handle(cmd, settings, span).await,
       commands::wasm::SubCommands::Inspect(cmd) => commands::wasm::inspect::handle(cmd, settings, span).await,
     },
+    CliCommand::Db(cmd) => match cmd {
+      commands::db::SubCommands::Migrate(cmd) => commands::db::migrate::handle(cmd, settings, span).await,
+      commands::db::SubCommands::Status(cmd) => commands::db::status::handle(cmd, settings, span).await,
+    },
     CliCommand::Key(cmd) => match cmd {
       commands::key::SubCommands::Get(cmd) => commands::key::get::handle(cmd, settings, span).await,
       commands::key::SubCommands::Gen(cmd) => commands::key::gen::handle(cmd, settings, span).await,